@@ -1,7 +1,55 @@
 use std::io::Result;
+use std::process::Command;
 
 fn main() -> Result<()> {
-    // Compile protobuf definitions
+    // Compile protobuf definitions. Declaring any rerun-if-changed below
+    // disables Cargo's default "rerun on any change", so list the proto too.
+    println!("cargo:rerun-if-changed=proto/geolocation.proto");
     prost_build::compile_protos(&["proto/geolocation.proto"], &["proto/"])?;
+
+    // Embed build metadata for the /version endpoint. GIT_SHA can be passed in
+    // explicitly (e.g. as a Docker build arg, where .git is not available).
+    println!("cargo:rerun-if-env-changed=GIT_SHA");
+    rerun_if_git_head_moves();
+
+    let git_sha = std::env::var("GIT_SHA")
+        .ok()
+        .filter(|sha| !sha.is_empty())
+        .or_else(|| {
+            let output = Command::new("git")
+                .args(["rev-parse", "--short", "HEAD"])
+                .output()
+                .ok()?;
+            output
+                .status
+                .success()
+                .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        });
+    if let Some(sha) = git_sha {
+        println!("cargo:rustc-env=GIT_SHA={}", sha);
+    }
+
+    let build_time = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+    println!("cargo:rustc-env=BUILD_TIME={}", build_time);
+
     Ok(())
 }
+
+/// Rebuild when HEAD moves, not just when it switches branches
+///
+/// A commit rewrites the branch ref HEAD points to (or `packed-refs` after
+/// `git gc`), leaving `.git/HEAD` untouched. Missing files are skipped, since
+/// Cargo treats a missing rerun-if-changed path as always changed.
+fn rerun_if_git_head_moves() {
+    let git_dir = std::path::Path::new(".git");
+    let head = git_dir.join("HEAD");
+    let mut watched = vec![head.clone(), git_dir.join("packed-refs")];
+    if let Ok(contents) = std::fs::read_to_string(&head) {
+        if let Some(reference) = contents.trim().strip_prefix("ref: ") {
+            watched.push(git_dir.join(reference));
+        }
+    }
+    for path in watched.iter().filter(|path| path.exists()) {
+        println!("cargo:rerun-if-changed={}", path.display());
+    }
+}
@@ -0,0 +1,187 @@
+//! Embedded IATA airport locations for `location.nearest_airport`
+//!
+//! Covers the main international airports of the countries in the country
+//! dataset; enough to give travel apps a sensible departure airport, not an
+//! exhaustive aviation database.
+
+/// Mean Earth radius in kilometres, as used by the haversine formula
+const EARTH_RADIUS_KM: f64 = 6371.0;
+
+/// IATA code, latitude, and longitude of each airport
+static AIRPORTS: &[(&str, f64, f64)] = &[
+    ("ATL", 33.6407, -84.4277),
+    ("LAX", 33.9416, -118.4085),
+    ("ORD", 41.9742, -87.9073),
+    ("DFW", 32.8998, -97.0403),
+    ("DEN", 39.8561, -104.6737),
+    ("JFK", 40.6413, -73.7781),
+    ("SFO", 37.6213, -122.379),
+    ("SEA", 47.4502, -122.3088),
+    ("LAS", 36.084, -115.1537),
+    ("MCO", 28.4312, -81.3081),
+    ("MIA", 25.7959, -80.287),
+    ("PHX", 33.4342, -112.0116),
+    ("IAH", 29.9902, -95.3368),
+    ("BOS", 42.3656, -71.0096),
+    ("MSP", 44.8848, -93.2223),
+    ("DTW", 42.2162, -83.3554),
+    ("PHL", 39.8744, -75.2424),
+    ("EWR", 40.6895, -74.1745),
+    ("IAD", 38.9531, -77.4565),
+    ("SJC", 37.3639, -121.9289),
+    ("SAN", 32.7338, -117.1933),
+    ("SLC", 40.7899, -111.9791),
+    ("HNL", 21.3187, -157.9225),
+    ("ANC", 61.1743, -149.9962),
+    ("YYZ", 43.6777, -79.6248),
+    ("YVR", 49.1967, -123.1815),
+    ("YUL", 45.4706, -73.7408),
+    ("YYC", 51.1215, -114.0076),
+    ("MEX", 19.4361, -99.0719),
+    ("CUN", 21.0365, -86.8771),
+    ("LHR", 51.47, -0.4543),
+    ("LGW", 51.1537, -0.1821),
+    ("MAN", 53.3537, -2.275),
+    ("EDI", 55.9508, -3.3615),
+    ("DUB", 53.4264, -6.2499),
+    ("CDG", 49.0097, 2.5479),
+    ("ORY", 48.7262, 2.3652),
+    ("NCE", 43.6584, 7.2159),
+    ("LYS", 45.7256, 5.0811),
+    ("AMS", 52.3105, 4.7683),
+    ("BRU", 50.901, 4.4856),
+    ("FRA", 50.0379, 8.5622),
+    ("MUC", 48.3537, 11.775),
+    ("BER", 52.3667, 13.5033),
+    ("HAM", 53.6304, 9.9882),
+    ("DUS", 51.2895, 6.7668),
+    ("ZRH", 47.4582, 8.5555),
+    ("GVA", 46.2381, 6.109),
+    ("VIE", 48.1103, 16.5697),
+    ("PRG", 50.1008, 14.26),
+    ("WAW", 52.1657, 20.9671),
+    ("BUD", 47.4385, 19.2523),
+    ("CPH", 55.618, 12.6508),
+    ("ARN", 59.6498, 17.9238),
+    ("GOT", 57.6628, 12.2798),
+    ("OSL", 60.1976, 11.1004),
+    ("HEL", 60.3172, 24.9633),
+    ("MAD", 40.4983, -3.5676),
+    ("BCN", 41.2974, 2.0833),
+    ("LIS", 38.7742, -9.1342),
+    ("OPO", 41.2481, -8.6814),
+    ("FCO", 41.8003, 12.2389),
+    ("MXP", 45.6301, 8.7231),
+    ("ATH", 37.9364, 23.9445),
+    ("IST", 41.2753, 28.7519),
+    ("OTP", 44.5711, 26.085),
+    ("SVO", 55.9726, 37.4146),
+    ("LED", 59.8003, 30.2625),
+    ("KBP", 50.345, 30.8947),
+    ("TLV", 32.0055, 34.8854),
+    ("DXB", 25.2532, 55.3657),
+    ("AUH", 24.433, 54.6511),
+    ("DOH", 25.2731, 51.6081),
+    ("RUH", 24.9576, 46.6988),
+    ("JED", 21.6796, 39.1565),
+    ("CAI", 30.1219, 31.4056),
+    ("JNB", -26.1392, 28.246),
+    ("CPT", -33.9715, 18.6021),
+    ("LOS", 6.5774, 3.3212),
+    ("ABV", 9.0068, 7.2632),
+    ("NBO", -1.3192, 36.9278),
+    ("ADD", 8.9779, 38.7993),
+    ("CMN", 33.3675, -7.5898),
+    ("DEL", 28.5562, 77.1),
+    ("BOM", 19.0896, 72.8656),
+    ("BLR", 13.1986, 77.7066),
+    ("MAA", 12.9941, 80.1709),
+    ("CCU", 22.6547, 88.4467),
+    ("HYD", 17.2403, 78.4294),
+    ("SIN", 1.3644, 103.9915),
+    ("KUL", 2.7456, 101.7072),
+    ("BKK", 13.69, 100.7501),
+    ("HKT", 8.1132, 98.3169),
+    ("CGK", -6.1256, 106.6559),
+    ("DPS", -8.7482, 115.1675),
+    ("MNL", 14.5086, 121.0194),
+    ("SGN", 10.8188, 106.652),
+    ("HAN", 21.2187, 105.8042),
+    ("HKG", 22.308, 113.9185),
+    ("TPE", 25.0797, 121.2342),
+    ("PEK", 40.0799, 116.6031),
+    ("PKX", 39.5098, 116.4105),
+    ("PVG", 31.1443, 121.8083),
+    ("CAN", 23.3924, 113.2988),
+    ("SZX", 22.6393, 113.8107),
+    ("CTU", 30.5785, 103.9471),
+    ("ICN", 37.4602, 126.4407),
+    ("GMP", 37.5583, 126.7906),
+    ("PUS", 35.1795, 128.9382),
+    ("NRT", 35.772, 140.3929),
+    ("HND", 35.5494, 139.7798),
+    ("KIX", 34.432, 135.2304),
+    ("NGO", 34.8584, 136.8054),
+    ("CTS", 42.7752, 141.6923),
+    ("FUK", 33.5859, 130.4507),
+    ("SYD", -33.9399, 151.1753),
+    ("MEL", -37.669, 144.841),
+    ("BNE", -27.3842, 153.1175),
+    ("PER", -31.9385, 115.9672),
+    ("ADL", -34.945, 138.5306),
+    ("AKL", -37.0082, 174.785),
+    ("WLG", -41.3272, 174.8053),
+    ("CHC", -43.4894, 172.532),
+    ("GRU", -23.4356, -46.4731),
+    ("GIG", -22.81, -43.2506),
+    ("BSB", -15.8697, -47.9208),
+    ("EZE", -34.8222, -58.5358),
+    ("AEP", -34.5592, -58.4156),
+    ("SCL", -33.393, -70.7858),
+    ("BOG", 4.7016, -74.1469),
+    ("LIM", -12.0219, -77.1143),
+    ("MDE", 6.1645, -75.4231),
+    ("PTY", 9.0714, -79.3835),
+];
+
+/// Great-circle distance between two coordinates in kilometres (haversine)
+pub fn haversine_km(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+    let d_lat = (lat2 - lat1).to_radians();
+    let d_lng = (lng2 - lng1).to_radians();
+    let a = (d_lat / 2.0).sin().powi(2)
+        + lat1.to_radians().cos() * lat2.to_radians().cos() * (d_lng / 2.0).sin().powi(2);
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
+
+/// Find the airport closest to a coordinate, with its distance in kilometres
+#[must_use]
+pub fn nearest_airport(lat: f64, lng: f64) -> Option<(&'static str, f64)> {
+    AIRPORTS
+        .iter()
+        .map(|(code, a_lat, a_lng)| (*code, haversine_km(lat, lng, *a_lat, *a_lng)))
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_haversine_km() {
+        // London Heathrow to New York JFK is about 5,540 km
+        let d = haversine_km(51.4700, -0.4543, 40.6413, -73.7781);
+        assert!((d - 5540.0).abs() < 20.0, "{d}");
+        assert_eq!(haversine_km(10.0, 20.0, 10.0, 20.0), 0.0);
+    }
+
+    #[test]
+    fn test_nearest_airport() {
+        // Central Stockholm
+        let (code, distance) = nearest_airport(59.3293, 18.0686).unwrap();
+        assert_eq!(code, "ARN");
+        assert!(distance > 30.0 && distance < 45.0, "{distance}");
+
+        // Mountain View is closer to San Jose than to San Francisco
+        assert_eq!(nearest_airport(37.386, -122.084).unwrap().0, "SJC");
+    }
+}
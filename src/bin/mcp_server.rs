@@ -26,10 +26,13 @@ use mcp_protocol_sdk::server::McpServer;
 use mcp_protocol_sdk::transport::StdioServerTransport;
 
 use ipgeolocation::geoip::{GeoIpReader, SharedGeoIpReader};
+use ipgeolocation::handlers::ResponseOptions;
 use ipgeolocation::mcp::{
-    schemas, GeoIpBulkLookupHandler, GeoIpLookupHandler, GeoIpLookupSelfHandler,
-    GeoIpResourceHandler, TimezoneLookupHandler,
+    parse_disabled_tools, schemas, BulkLimits, CountryInfoHandler, GeoIpBulkLookupHandler,
+    GeoIpLookupHandler, GeoIpLookupSelfHandler, GeoIpResourceHandler, TimezoneBulkLookupHandler,
+    TimezoneLookupHandler, BULK_LOOKUP_MAX_IPS, DEFAULT_MCP_BULK_CONCURRENCY,
 };
+use ipgeolocation::timezone::{SharedTimezoneProvider, TzfTimezoneProvider};
 
 /// Print usage information
 fn print_usage() {
@@ -115,6 +118,20 @@ fn parse_args() -> (String, String) {
     (transport, bind_addr)
 }
 
+/// Read a positive integer from the environment, exiting if it is invalid
+fn positive_env(name: &str, default: usize) -> usize {
+    match env::var(name) {
+        Ok(value) => match value.parse() {
+            Ok(n) if n >= 1 => n,
+            _ => {
+                eprintln!("Invalid {}: must be an integer >= 1, got '{}'", name, value);
+                process::exit(1);
+            }
+        },
+        Err(_) => default,
+    }
+}
+
 /// Initialize the GeoIP reader
 fn init_geoip() -> SharedGeoIpReader {
     let db_path =
@@ -143,8 +160,31 @@ async fn main() {
     let (transport, bind_addr) = parse_args();
     let _ = bind_addr; // Reserved for future SSE transport implementation
 
+    let limits = BulkLimits::new(
+        positive_env("BULK_MAX_IPS", BULK_LOOKUP_MAX_IPS),
+        positive_env("MCP_BULK_CONCURRENCY", DEFAULT_MCP_BULK_CONCURRENCY),
+    );
+    let disabled_tools = env::var("MCP_DISABLE_TOOLS")
+        .map(|spec| {
+            parse_disabled_tools(&spec).unwrap_or_else(|e| {
+                eprintln!("Invalid MCP_DISABLE_TOOLS: {}", e);
+                process::exit(1);
+            })
+        })
+        .unwrap_or_default();
+    let is_tool_enabled = |name: &str| !disabled_tools.iter().any(|tool| tool == name);
+
     // Initialize GeoIP reader
     let geoip = init_geoip();
+    let timezones: SharedTimezoneProvider = Arc::new(TzfTimezoneProvider);
+    // GeoLite2 attribution in geoip tool results; empty disables it
+    let options = Arc::new(match env::var("DATA_ATTRIBUTION") {
+        Ok(text) => ResponseOptions {
+            data_attribution: Some(text).filter(|text| !text.is_empty()),
+            ..ResponseOptions::default()
+        },
+        Err(_) => ResponseOptions::default(),
+    });
 
     // Create the MCP server (must be mutable to call start())
     let mut server = McpServer::new(
@@ -153,79 +193,140 @@ async fn main() {
     );
 
     // Register tools
-    if let Err(e) = server
-        .add_tool(
-            "geoip_lookup".to_string(),
-            Some(
-                "Look up geographic location for an IP address. Returns city, country, \
-                 coordinates, timezone, currency, and other location metadata."
-                    .to_string(),
-            ),
-            schemas::geoip_lookup_input_schema(),
-            GeoIpLookupHandler {
-                geoip: geoip.clone(),
-            },
-        )
-        .await
-    {
-        eprintln!("Failed to register geoip_lookup tool: {}", e);
-        process::exit(1);
+    if is_tool_enabled("geoip_lookup") {
+        if let Err(e) = server
+            .add_tool(
+                "geoip_lookup".to_string(),
+                Some(
+                    "Look up geographic location for an IP address. Returns city, country, \
+                     coordinates, timezone, currency, and other location metadata."
+                        .to_string(),
+                ),
+                schemas::geoip_lookup_input_schema(),
+                GeoIpLookupHandler {
+                    geoip: geoip.clone(),
+                    timezones: timezones.clone(),
+                    options: options.clone(),
+                },
+            )
+            .await
+        {
+            eprintln!("Failed to register geoip_lookup tool: {}", e);
+            process::exit(1);
+        }
     }
 
-    if let Err(e) = server
-        .add_tool(
-            "geoip_bulk_lookup".to_string(),
-            Some(
-                "Look up geographic locations for multiple IP addresses in a single request. \
-                 Maximum 100 IPs per request. Returns results and errors separately."
-                    .to_string(),
-            ),
-            schemas::geoip_bulk_lookup_input_schema(),
-            GeoIpBulkLookupHandler {
-                geoip: geoip.clone(),
-            },
-        )
-        .await
-    {
-        eprintln!("Failed to register geoip_bulk_lookup tool: {}", e);
-        process::exit(1);
+    if is_tool_enabled("geoip_bulk_lookup") {
+        if let Err(e) = server
+            .add_tool(
+                "geoip_bulk_lookup".to_string(),
+                Some(format!(
+                    "Look up geographic locations for multiple IP addresses in a single request. \
+                     Maximum {} IPs per request. Returns results and errors separately.",
+                    limits.max_items
+                )),
+                schemas::geoip_bulk_lookup_input_schema(limits.max_items),
+                GeoIpBulkLookupHandler {
+                    geoip: geoip.clone(),
+                    timezones: timezones.clone(),
+                    options: options.clone(),
+                    limits: limits.clone(),
+                },
+            )
+            .await
+        {
+            eprintln!("Failed to register geoip_bulk_lookup tool: {}", e);
+            process::exit(1);
+        }
     }
 
-    if let Err(e) = server
-        .add_tool(
-            "geoip_lookup_self".to_string(),
-            Some(
-                "Look up geographic location for the caller's IP address. \
-                 Only available via SSE transport - returns error on STDIO."
-                    .to_string(),
-            ),
-            schemas::geoip_lookup_self_input_schema(),
-            GeoIpLookupSelfHandler {
-                geoip: geoip.clone(),
-                caller_ip: None, // Will be set per-request in SSE transport
-            },
-        )
-        .await
-    {
-        eprintln!("Failed to register geoip_lookup_self tool: {}", e);
-        process::exit(1);
+    if is_tool_enabled("geoip_lookup_self") {
+        if let Err(e) = server
+            .add_tool(
+                "geoip_lookup_self".to_string(),
+                Some(
+                    "Look up geographic location for the caller's IP address. \
+                     Only available via SSE transport - returns error on STDIO."
+                        .to_string(),
+                ),
+                schemas::geoip_lookup_self_input_schema(),
+                GeoIpLookupSelfHandler {
+                    geoip: geoip.clone(),
+                    timezones: timezones.clone(),
+                    options: options.clone(),
+                    caller_ip: None, // Will be set per-request in SSE transport
+                },
+            )
+            .await
+        {
+            eprintln!("Failed to register geoip_lookup_self tool: {}", e);
+            process::exit(1);
+        }
     }
 
-    if let Err(e) = server
-        .add_tool(
-            "timezone_lookup".to_string(),
-            Some(
-                "Look up IANA timezone for geographic coordinates. Returns timezone name, \
-                 current offset, DST information, and current local time."
-                    .to_string(),
-            ),
-            schemas::timezone_lookup_input_schema(),
-            TimezoneLookupHandler,
-        )
-        .await
-    {
-        eprintln!("Failed to register timezone_lookup tool: {}", e);
-        process::exit(1);
+    if is_tool_enabled("timezone_lookup") {
+        if let Err(e) = server
+            .add_tool(
+                "timezone_lookup".to_string(),
+                Some(
+                    "Look up IANA timezone for geographic coordinates. Returns timezone name, \
+                     current offset, DST information, and current local time."
+                        .to_string(),
+                ),
+                schemas::timezone_lookup_input_schema(),
+                TimezoneLookupHandler {
+                    timezones: timezones.clone(),
+                    options: options.clone(),
+                },
+            )
+            .await
+        {
+            eprintln!("Failed to register timezone_lookup tool: {}", e);
+            process::exit(1);
+        }
+    }
+
+    if is_tool_enabled("timezone_bulk_lookup") {
+        if let Err(e) = server
+            .add_tool(
+                "timezone_bulk_lookup".to_string(),
+                Some(format!(
+                    "Look up IANA timezones for multiple coordinate pairs in a single request. \
+                     Maximum {} points per request. Returns one result per point in request order.",
+                    limits.max_items
+                )),
+                schemas::timezone_bulk_lookup_input_schema(limits.max_items),
+                TimezoneBulkLookupHandler {
+                    timezones,
+                    options,
+                    limits: limits.clone(),
+                },
+            )
+            .await
+        {
+            eprintln!("Failed to register timezone_bulk_lookup tool: {}", e);
+            process::exit(1);
+        }
+    }
+
+    if is_tool_enabled("country_info") {
+        if let Err(e) = server
+            .add_tool(
+                "country_info".to_string(),
+                Some(
+                    "Look up metadata for a country by ISO 3166-1 alpha-2 code: capital, \
+                     currency, languages, continent, EU membership, calling code, TLD, and \
+                     flag emoji."
+                        .to_string(),
+                ),
+                schemas::country_info_input_schema(),
+                CountryInfoHandler,
+            )
+            .await
+        {
+            eprintln!("Failed to register country_info tool: {}", e);
+            process::exit(1);
+        }
     }
 
     // Register resources
@@ -233,7 +334,9 @@ async fn main() {
         .add_resource(
             "geoip".to_string(),
             "geoip://".to_string(),
-            GeoIpResourceHandler,
+            GeoIpResourceHandler {
+                bulk_max_ips: limits.max_items,
+            },
         )
         .await
     {
@@ -0,0 +1,108 @@
+//! Reserved and special-purpose address ranges that are never geolocated
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+/// IPv4 bogon prefixes (network, prefix length)
+const BOGONS_V4: &[(Ipv4Addr, u8)] = &[
+    (Ipv4Addr::new(0, 0, 0, 0), 8),       // "This network" (RFC 791)
+    (Ipv4Addr::new(10, 0, 0, 0), 8),      // Private (RFC 1918)
+    (Ipv4Addr::new(100, 64, 0, 0), 10),   // CGNAT shared address space (RFC 6598)
+    (Ipv4Addr::new(127, 0, 0, 0), 8),     // Loopback
+    (Ipv4Addr::new(169, 254, 0, 0), 16),  // Link-local
+    (Ipv4Addr::new(172, 16, 0, 0), 12),   // Private (RFC 1918)
+    (Ipv4Addr::new(192, 0, 0, 0), 24),    // IETF protocol assignments (RFC 6890)
+    (Ipv4Addr::new(192, 0, 2, 0), 24),    // Documentation TEST-NET-1 (RFC 5737)
+    (Ipv4Addr::new(192, 168, 0, 0), 16),  // Private (RFC 1918)
+    (Ipv4Addr::new(198, 18, 0, 0), 15),   // Benchmarking (RFC 2544)
+    (Ipv4Addr::new(198, 51, 100, 0), 24), // Documentation TEST-NET-2 (RFC 5737)
+    (Ipv4Addr::new(203, 0, 113, 0), 24),  // Documentation TEST-NET-3 (RFC 5737)
+    (Ipv4Addr::new(224, 0, 0, 0), 4),     // Multicast
+    (Ipv4Addr::new(240, 0, 0, 0), 4),     // Reserved, including broadcast
+];
+
+/// IPv6 bogon prefixes (network, prefix length)
+const BOGONS_V6: &[(Ipv6Addr, u8)] = &[
+    (Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 0), 128), // Unspecified
+    (Ipv6Addr::new(0, 0, 0, 0, 0, 0, 0, 1), 128), // Loopback
+    (Ipv6Addr::new(0x100, 0, 0, 0, 0, 0, 0, 0), 64), // Discard-only (RFC 6666)
+    (Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 0), 32), // Documentation (RFC 3849)
+    (Ipv6Addr::new(0x3fff, 0, 0, 0, 0, 0, 0, 0), 20), // Documentation (RFC 9637)
+    (Ipv6Addr::new(0xfc00, 0, 0, 0, 0, 0, 0, 0), 7), // Unique local (RFC 4193)
+    (Ipv6Addr::new(0xfe80, 0, 0, 0, 0, 0, 0, 0), 10), // Link-local
+    (Ipv6Addr::new(0xff00, 0, 0, 0, 0, 0, 0, 0), 8), // Multicast
+];
+
+/// Whether `ip` falls in a reserved, private, or otherwise non-routable range
+///
+/// IPv4-mapped IPv6 addresses (`::ffff:a.b.c.d`) are checked against the IPv4 list.
+pub fn is_bogon(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_bogon_v4(*v4),
+        IpAddr::V6(v6) => match v6.to_ipv4_mapped() {
+            Some(v4) => is_bogon_v4(v4),
+            None => BOGONS_V6
+                .iter()
+                .any(|(net, len)| prefix_matches(u128::from(*v6), u128::from(*net), *len)),
+        },
+    }
+}
+
+fn is_bogon_v4(ip: Ipv4Addr) -> bool {
+    BOGONS_V4.iter().any(|(net, len)| {
+        prefix_matches(
+            u128::from(u32::from(ip)) << 96,
+            u128::from(u32::from(*net)) << 96,
+            *len,
+        )
+    })
+}
+
+/// Compare the top `len` bits of two left-aligned 128-bit addresses
+pub(crate) fn prefix_matches(addr: u128, net: u128, len: u8) -> bool {
+    let mask = u128::MAX.checked_shl(128 - u32::from(len)).unwrap_or(0);
+    addr & mask == net & mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bogon(ip: &str) -> bool {
+        is_bogon(&ip.parse().unwrap())
+    }
+
+    #[test]
+    fn test_is_bogon_cgnat() {
+        assert!(bogon("100.64.0.1"));
+        assert!(bogon("100.127.255.255"));
+        assert!(!bogon("100.128.0.1"));
+    }
+
+    #[test]
+    fn test_is_bogon_documentation() {
+        assert!(bogon("192.0.2.1"));
+        assert!(bogon("198.51.100.7"));
+        assert!(bogon("203.0.113.200"));
+        assert!(bogon("2001:db8::1"));
+        assert!(bogon("::ffff:192.0.2.1"));
+    }
+
+    #[test]
+    fn test_is_bogon_private_and_loopback() {
+        assert!(bogon("10.1.2.3"));
+        assert!(bogon("172.31.0.1"));
+        assert!(bogon("192.168.1.1"));
+        assert!(bogon("127.0.0.1"));
+        assert!(bogon("::1"));
+        assert!(bogon("fd00::1"));
+    }
+
+    #[test]
+    fn test_is_bogon_public() {
+        assert!(!bogon("8.8.8.8"));
+        assert!(!bogon("1.1.1.1"));
+        assert!(!bogon("172.32.0.1"));
+        assert!(!bogon("2001:4860:4860::8888"));
+        assert!(!bogon("::ffff:8.8.8.8"));
+    }
+}
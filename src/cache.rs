@@ -1,15 +1,20 @@
 use moka::sync::Cache;
+use moka::Expiry;
+use std::hash::{BuildHasher, RandomState};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
-use crate::models::IpGeoResponse;
+use crate::models::{IpGeoResponse, IpGeoResponseFull};
 
 /// Cache configuration
+#[derive(Clone)]
 pub struct CacheConfig {
     /// Maximum number of entries in the cache
     pub max_capacity: u64,
     /// Time-to-live for cache entries
     pub ttl: Duration,
+    /// Spread each entry's TTL by up to this percentage either way (0 disables)
+    pub ttl_jitter_pct: u8,
 }
 
 impl Default for CacheConfig {
@@ -17,34 +22,92 @@ impl Default for CacheConfig {
         Self {
             max_capacity: 10_000,
             ttl: Duration::from_secs(3600), // 1 hour
+            ttl_jitter_pct: 0,
         }
     }
 }
 
+/// Expiry policy giving each entry a TTL within ±`jitter_pct` of the base TTL
+///
+/// Entries inserted together during a traffic spike would otherwise all expire
+/// together and trigger a synchronized storm of re-lookups.
+struct JitteredTtl {
+    ttl: Duration,
+    jitter_pct: u8,
+    hasher: RandomState,
+}
+
+impl JitteredTtl {
+    fn new(ttl: Duration, jitter_pct: u8) -> Self {
+        Self {
+            ttl,
+            jitter_pct,
+            hasher: RandomState::new(),
+        }
+    }
+
+    /// TTL for an entry written at `written_at`
+    fn ttl_for(&self, key: &str, written_at: Instant) -> Duration {
+        // A randomly seeded hash of the write gives a uniform spread without an RNG
+        let unit = self.hasher.hash_one((key, written_at)) as f64 / u64::MAX as f64;
+        let factor = 1.0 + (unit * 2.0 - 1.0) * f64::from(self.jitter_pct) / 100.0;
+        self.ttl.mul_f64(factor)
+    }
+}
+
+impl<V> Expiry<String, V> for JitteredTtl {
+    fn expire_after_create(
+        &self,
+        key: &String,
+        _value: &V,
+        created_at: Instant,
+    ) -> Option<Duration> {
+        Some(self.ttl_for(key, created_at))
+    }
+
+    // Like `time_to_live`, overwriting an entry restarts its TTL
+    fn expire_after_update(
+        &self,
+        key: &String,
+        _value: &V,
+        updated_at: Instant,
+        _duration_until_expiry: Option<Duration>,
+    ) -> Option<Duration> {
+        Some(self.ttl_for(key, updated_at))
+    }
+}
+
 /// IP geolocation response cache
-pub struct GeoCache {
-    cache: Cache<String, IpGeoResponse>,
+///
+/// Holds `/api/v1` responses by default; `/v1/ipgeo` keeps full responses in a
+/// [`SharedFullGeoCache`] keyed by `handlers::canonical_cache_key`.
+pub struct GeoCache<V = IpGeoResponse> {
+    cache: Cache<String, V>,
 }
 
-impl GeoCache {
+impl<V: Clone + Send + Sync + 'static> GeoCache<V> {
     /// Create a new cache with the given configuration
     pub fn new(config: CacheConfig) -> Self {
-        let cache = Cache::builder()
-            .max_capacity(config.max_capacity)
-            .time_to_live(config.ttl)
-            .build();
+        let builder = Cache::builder().max_capacity(config.max_capacity);
+        let cache = if config.ttl_jitter_pct == 0 {
+            builder.time_to_live(config.ttl).build()
+        } else {
+            builder
+                .expire_after(JitteredTtl::new(config.ttl, config.ttl_jitter_pct))
+                .build()
+        };
 
         Self { cache }
     }
 
     /// Get a cached response for an IP address
     #[must_use]
-    pub fn get(&self, ip: &str) -> Option<IpGeoResponse> {
+    pub fn get(&self, ip: &str) -> Option<V> {
         self.cache.get(ip)
     }
 
     /// Insert a response into the cache
-    pub fn insert(&self, ip: String, response: IpGeoResponse) {
+    pub fn insert(&self, ip: String, response: V) {
         self.cache.insert(ip, response);
     }
 
@@ -59,11 +122,35 @@ impl GeoCache {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    /// Remove every entry from the cache, returning how many were evicted
+    pub fn clear(&self) -> u64 {
+        // Flush pending writes so the entry count is accurate before evicting
+        self.cache.run_pending_tasks();
+        let evicted = self.cache.entry_count();
+        self.cache.invalidate_all();
+        self.cache.run_pending_tasks();
+        evicted
+    }
+
+    /// Remove the cached entry for an IP address, returning whether one existed
+    pub fn invalidate(&self, ip: &str) -> bool {
+        self.cache.remove(ip).is_some()
+    }
+
+    /// Check whether an IP address is cached without counting it as an access
+    #[must_use]
+    pub fn contains_key(&self, ip: &str) -> bool {
+        self.cache.contains_key(ip)
+    }
 }
 
 /// Shared cache wrapped in Arc for thread-safe access
 pub type SharedGeoCache = Arc<GeoCache>;
 
+/// Shared cache of full `/v1/ipgeo` responses
+pub type SharedFullGeoCache = Arc<GeoCache<IpGeoResponseFull>>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -71,13 +158,13 @@ mod tests {
 
     #[test]
     fn test_cache_insert_and_get() {
-        let cache = GeoCache::new(CacheConfig::default());
+        let cache: GeoCache = GeoCache::new(CacheConfig::default());
 
         let response = IpGeoResponse {
             latitude: Some(37.751),
             longitude: Some(-97.822),
-            city: "Test City".to_string(),
-            country_name: "Test Country".to_string(),
+            city: Some("Test City".to_string()),
+            country_name: Some("Test Country".to_string()),
             time_zone: TimeZoneInfo {
                 name: "America/Chicago".to_string(),
             },
@@ -90,19 +177,19 @@ mod tests {
         assert!(cached.is_some());
 
         let cached = cached.unwrap();
-        assert_eq!(cached.city, "Test City");
+        assert_eq!(cached.city.as_deref(), Some("Test City"));
         assert_eq!(cached.latitude, Some(37.751));
     }
 
     #[test]
     fn test_cache_miss() {
-        let cache = GeoCache::new(CacheConfig::default());
+        let cache: GeoCache = GeoCache::new(CacheConfig::default());
         assert!(cache.get("1.2.3.4").is_none());
     }
 
     #[test]
     fn test_cache_len() {
-        let cache = GeoCache::new(CacheConfig::default());
+        let cache: GeoCache = GeoCache::new(CacheConfig::default());
 
         let response = IpGeoResponse::default();
         cache.insert("1.1.1.1".to_string(), response.clone());
@@ -118,6 +205,38 @@ mod tests {
         let config = CacheConfig::default();
         assert_eq!(config.max_capacity, 10_000);
         assert_eq!(config.ttl, Duration::from_secs(3600));
+        assert_eq!(config.ttl_jitter_pct, 0);
+    }
+
+    #[test]
+    fn test_jittered_ttl_within_band() {
+        let ttl = Duration::from_secs(3600);
+        let expiry = JitteredTtl::new(ttl, 10);
+        let now = Instant::now();
+
+        let ttls: Vec<Duration> = (0..1000)
+            .map(|i| {
+                let key = format!("10.0.{}.{}", i / 256, i % 256);
+                expiry
+                    .expire_after_create(&key, &IpGeoResponse::default(), now)
+                    .unwrap()
+            })
+            .collect();
+
+        let (low, high) = (ttl.mul_f64(0.9), ttl.mul_f64(1.1));
+        assert!(ttls.iter().all(|t| (low..=high).contains(t)));
+        // Entries written at the same instant are actually spread out
+        assert!(ttls.iter().any(|t| *t < ttl) && ttls.iter().any(|t| *t > ttl));
+    }
+
+    #[test]
+    fn test_cache_with_jitter() {
+        let cache: GeoCache = GeoCache::new(CacheConfig {
+            ttl_jitter_pct: 20,
+            ..Default::default()
+        });
+        cache.insert("8.8.8.8".to_string(), IpGeoResponse::default());
+        assert!(cache.get("8.8.8.8").is_some());
     }
 
     #[test]
@@ -125,6 +244,7 @@ mod tests {
         let config = CacheConfig {
             max_capacity: 5000,
             ttl: Duration::from_secs(1800),
+            ttl_jitter_pct: 10,
         };
         assert_eq!(config.max_capacity, 5000);
         assert_eq!(config.ttl, Duration::from_secs(1800));
@@ -132,14 +252,14 @@ mod tests {
 
     #[test]
     fn test_cache_overwrite() {
-        let cache = GeoCache::new(CacheConfig::default());
+        let cache: GeoCache = GeoCache::new(CacheConfig::default());
 
         let response1 = IpGeoResponse {
-            city: "City1".to_string(),
+            city: Some("City1".to_string()),
             ..Default::default()
         };
         let response2 = IpGeoResponse {
-            city: "City2".to_string(),
+            city: Some("City2".to_string()),
             ..Default::default()
         };
 
@@ -147,19 +267,19 @@ mod tests {
         cache.insert("1.1.1.1".to_string(), response2);
 
         let cached = cache.get("1.1.1.1").unwrap();
-        assert_eq!(cached.city, "City2");
+        assert_eq!(cached.city.as_deref(), Some("City2"));
     }
 
     #[test]
     fn test_cache_different_ips() {
-        let cache = GeoCache::new(CacheConfig::default());
+        let cache: GeoCache = GeoCache::new(CacheConfig::default());
 
         let response_us = IpGeoResponse {
-            country_name: "United States".to_string(),
+            country_name: Some("United States".to_string()),
             ..Default::default()
         };
         let response_uk = IpGeoResponse {
-            country_name: "United Kingdom".to_string(),
+            country_name: Some("United Kingdom".to_string()),
             ..Default::default()
         };
 
@@ -169,20 +289,20 @@ mod tests {
         let us = cache.get("8.8.8.8").unwrap();
         let uk = cache.get("1.1.1.1").unwrap();
 
-        assert_eq!(us.country_name, "United States");
-        assert_eq!(uk.country_name, "United Kingdom");
+        assert_eq!(us.country_name.as_deref(), Some("United States"));
+        assert_eq!(uk.country_name.as_deref(), Some("United Kingdom"));
     }
 
     #[test]
     fn test_cache_is_empty_on_new() {
-        let cache = GeoCache::new(CacheConfig::default());
+        let cache: GeoCache = GeoCache::new(CacheConfig::default());
         // New cache should have nothing retrievable
         assert!(cache.get("nonexistent").is_none());
     }
 
     #[test]
     fn test_cache_is_empty_method() {
-        let cache = GeoCache::new(CacheConfig::default());
+        let cache: GeoCache = GeoCache::new(CacheConfig::default());
         // Initially the cache reports no entries via get
         // Note: Moka's entry_count uses eventual consistency
         // so is_empty() may not be immediately accurate
@@ -190,9 +310,42 @@ mod tests {
         let _ = cache.is_empty();
     }
 
+    #[test]
+    fn test_cache_clear() {
+        let cache: GeoCache = GeoCache::new(CacheConfig::default());
+        cache.insert("1.1.1.1".to_string(), IpGeoResponse::default());
+        cache.insert("2.2.2.2".to_string(), IpGeoResponse::default());
+
+        assert_eq!(cache.clear(), 2);
+        assert!(cache.get("1.1.1.1").is_none());
+        assert!(cache.get("2.2.2.2").is_none());
+        assert_eq!(cache.clear(), 0);
+    }
+
+    #[test]
+    fn test_cache_invalidate() {
+        let cache: GeoCache = GeoCache::new(CacheConfig::default());
+        cache.insert("1.1.1.1".to_string(), IpGeoResponse::default());
+        cache.insert("2.2.2.2".to_string(), IpGeoResponse::default());
+
+        assert!(cache.invalidate("1.1.1.1"));
+        assert!(!cache.invalidate("1.1.1.1"));
+        assert!(cache.get("1.1.1.1").is_none());
+        assert!(cache.get("2.2.2.2").is_some());
+    }
+
+    #[test]
+    fn test_cache_contains_key() {
+        let cache: GeoCache = GeoCache::new(CacheConfig::default());
+        cache.insert("1.1.1.1".to_string(), IpGeoResponse::default());
+
+        assert!(cache.contains_key("1.1.1.1"));
+        assert!(!cache.contains_key("2.2.2.2"));
+    }
+
     #[test]
     fn test_cache_len_method() {
-        let cache = GeoCache::new(CacheConfig::default());
+        let cache: GeoCache = GeoCache::new(CacheConfig::default());
         // len() should return a u64
         let len = cache.len();
         assert!(len <= cache.len() + 1); // Just verify it returns a number
@@ -3,8 +3,22 @@
 use std::collections::HashMap;
 use std::sync::LazyLock;
 
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Where `country_name` in the full response comes from (`name_source` query parameter)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NameSource {
+    /// The GeoIP database's English name
+    #[default]
+    Database,
+    /// The embedded country table, consistent with `country_name_official`
+    Table,
+}
+
 /// Country metadata structure
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub struct CountryMetadata {
     pub name: &'static str,
     pub official_name: &'static str,
@@ -12,6 +26,10 @@ pub struct CountryMetadata {
     pub capital: &'static str,
     pub continent_code: &'static str,
     pub continent_name: &'static str,
+    /// UN M49 region code (e.g. "150" for Europe)
+    pub region_code: &'static str,
+    /// UN M49 subregion name (e.g. "Western Europe")
+    pub subregion: &'static str,
     pub calling_code: &'static str,
     pub tld: &'static str,
     pub currency_code: &'static str,
@@ -36,6 +54,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Washington, D.C.",
             continent_code: "NA",
             continent_name: "North America",
+            region_code: "019",
+            subregion: "Northern America",
             calling_code: "+1",
             tld: ".us",
             currency_code: "USD",
@@ -55,6 +75,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Ottawa",
             continent_code: "NA",
             continent_name: "North America",
+            region_code: "019",
+            subregion: "Northern America",
             calling_code: "+1",
             tld: ".ca",
             currency_code: "CAD",
@@ -74,6 +96,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Mexico City",
             continent_code: "NA",
             continent_name: "North America",
+            region_code: "019",
+            subregion: "Central America",
             calling_code: "+52",
             tld: ".mx",
             currency_code: "MXN",
@@ -95,6 +119,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "London",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Northern Europe",
             calling_code: "+44",
             tld: ".uk",
             currency_code: "GBP",
@@ -114,6 +140,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Berlin",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Western Europe",
             calling_code: "+49",
             tld: ".de",
             currency_code: "EUR",
@@ -133,6 +161,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Paris",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Western Europe",
             calling_code: "+33",
             tld: ".fr",
             currency_code: "EUR",
@@ -152,6 +182,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Rome",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Southern Europe",
             calling_code: "+39",
             tld: ".it",
             currency_code: "EUR",
@@ -171,6 +203,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Madrid",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Southern Europe",
             calling_code: "+34",
             tld: ".es",
             currency_code: "EUR",
@@ -190,6 +224,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Lisbon",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Southern Europe",
             calling_code: "+351",
             tld: ".pt",
             currency_code: "EUR",
@@ -209,6 +245,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Amsterdam",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Western Europe",
             calling_code: "+31",
             tld: ".nl",
             currency_code: "EUR",
@@ -228,6 +266,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Brussels",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Western Europe",
             calling_code: "+32",
             tld: ".be",
             currency_code: "EUR",
@@ -247,6 +287,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Vienna",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Western Europe",
             calling_code: "+43",
             tld: ".at",
             currency_code: "EUR",
@@ -266,6 +308,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Bern",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Western Europe",
             calling_code: "+41",
             tld: ".ch",
             currency_code: "CHF",
@@ -285,6 +329,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Stockholm",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Northern Europe",
             calling_code: "+46",
             tld: ".se",
             currency_code: "SEK",
@@ -304,6 +350,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Oslo",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Northern Europe",
             calling_code: "+47",
             tld: ".no",
             currency_code: "NOK",
@@ -323,6 +371,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Copenhagen",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Northern Europe",
             calling_code: "+45",
             tld: ".dk",
             currency_code: "DKK",
@@ -342,6 +392,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Helsinki",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Northern Europe",
             calling_code: "+358",
             tld: ".fi",
             currency_code: "EUR",
@@ -361,6 +413,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Warsaw",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Eastern Europe",
             calling_code: "+48",
             tld: ".pl",
             currency_code: "PLN",
@@ -380,6 +434,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Prague",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Eastern Europe",
             calling_code: "+420",
             tld: ".cz",
             currency_code: "CZK",
@@ -399,6 +455,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Athens",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Southern Europe",
             calling_code: "+30",
             tld: ".gr",
             currency_code: "EUR",
@@ -418,6 +476,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Dublin",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Northern Europe",
             calling_code: "+353",
             tld: ".ie",
             currency_code: "EUR",
@@ -437,6 +497,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Moscow",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Eastern Europe",
             calling_code: "+7",
             tld: ".ru",
             currency_code: "RUB",
@@ -456,6 +518,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Kyiv",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Eastern Europe",
             calling_code: "+380",
             tld: ".ua",
             currency_code: "UAH",
@@ -475,6 +539,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Bucharest",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Eastern Europe",
             calling_code: "+40",
             tld: ".ro",
             currency_code: "RON",
@@ -494,6 +560,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Budapest",
             continent_code: "EU",
             continent_name: "Europe",
+            region_code: "150",
+            subregion: "Eastern Europe",
             calling_code: "+36",
             tld: ".hu",
             currency_code: "HUF",
@@ -515,6 +583,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Tokyo",
             continent_code: "AS",
             continent_name: "Asia",
+            region_code: "142",
+            subregion: "Eastern Asia",
             calling_code: "+81",
             tld: ".jp",
             currency_code: "JPY",
@@ -534,6 +604,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Beijing",
             continent_code: "AS",
             continent_name: "Asia",
+            region_code: "142",
+            subregion: "Eastern Asia",
             calling_code: "+86",
             tld: ".cn",
             currency_code: "CNY",
@@ -553,6 +625,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Seoul",
             continent_code: "AS",
             continent_name: "Asia",
+            region_code: "142",
+            subregion: "Eastern Asia",
             calling_code: "+82",
             tld: ".kr",
             currency_code: "KRW",
@@ -572,6 +646,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "New Delhi",
             continent_code: "AS",
             continent_name: "Asia",
+            region_code: "142",
+            subregion: "Southern Asia",
             calling_code: "+91",
             tld: ".in",
             currency_code: "INR",
@@ -591,6 +667,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Singapore",
             continent_code: "AS",
             continent_name: "Asia",
+            region_code: "142",
+            subregion: "South-eastern Asia",
             calling_code: "+65",
             tld: ".sg",
             currency_code: "SGD",
@@ -610,6 +688,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Hong Kong",
             continent_code: "AS",
             continent_name: "Asia",
+            region_code: "142",
+            subregion: "Eastern Asia",
             calling_code: "+852",
             tld: ".hk",
             currency_code: "HKD",
@@ -629,6 +709,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Taipei",
             continent_code: "AS",
             continent_name: "Asia",
+            region_code: "142",
+            subregion: "Eastern Asia",
             calling_code: "+886",
             tld: ".tw",
             currency_code: "TWD",
@@ -648,6 +730,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Bangkok",
             continent_code: "AS",
             continent_name: "Asia",
+            region_code: "142",
+            subregion: "South-eastern Asia",
             calling_code: "+66",
             tld: ".th",
             currency_code: "THB",
@@ -667,6 +751,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Hanoi",
             continent_code: "AS",
             continent_name: "Asia",
+            region_code: "142",
+            subregion: "South-eastern Asia",
             calling_code: "+84",
             tld: ".vn",
             currency_code: "VND",
@@ -686,6 +772,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Jakarta",
             continent_code: "AS",
             continent_name: "Asia",
+            region_code: "142",
+            subregion: "South-eastern Asia",
             calling_code: "+62",
             tld: ".id",
             currency_code: "IDR",
@@ -705,6 +793,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Kuala Lumpur",
             continent_code: "AS",
             continent_name: "Asia",
+            region_code: "142",
+            subregion: "South-eastern Asia",
             calling_code: "+60",
             tld: ".my",
             currency_code: "MYR",
@@ -724,6 +814,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Manila",
             continent_code: "AS",
             continent_name: "Asia",
+            region_code: "142",
+            subregion: "South-eastern Asia",
             calling_code: "+63",
             tld: ".ph",
             currency_code: "PHP",
@@ -743,6 +835,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Abu Dhabi",
             continent_code: "AS",
             continent_name: "Asia",
+            region_code: "142",
+            subregion: "Western Asia",
             calling_code: "+971",
             tld: ".ae",
             currency_code: "AED",
@@ -762,6 +856,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Riyadh",
             continent_code: "AS",
             continent_name: "Asia",
+            region_code: "142",
+            subregion: "Western Asia",
             calling_code: "+966",
             tld: ".sa",
             currency_code: "SAR",
@@ -781,6 +877,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Jerusalem",
             continent_code: "AS",
             continent_name: "Asia",
+            region_code: "142",
+            subregion: "Western Asia",
             calling_code: "+972",
             tld: ".il",
             currency_code: "ILS",
@@ -800,6 +898,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Ankara",
             continent_code: "AS",
             continent_name: "Asia",
+            region_code: "142",
+            subregion: "Western Asia",
             calling_code: "+90",
             tld: ".tr",
             currency_code: "TRY",
@@ -821,6 +921,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Canberra",
             continent_code: "OC",
             continent_name: "Oceania",
+            region_code: "009",
+            subregion: "Australia and New Zealand",
             calling_code: "+61",
             tld: ".au",
             currency_code: "AUD",
@@ -840,6 +942,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Wellington",
             continent_code: "OC",
             continent_name: "Oceania",
+            region_code: "009",
+            subregion: "Australia and New Zealand",
             calling_code: "+64",
             tld: ".nz",
             currency_code: "NZD",
@@ -861,6 +965,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Brasília",
             continent_code: "SA",
             continent_name: "South America",
+            region_code: "019",
+            subregion: "South America",
             calling_code: "+55",
             tld: ".br",
             currency_code: "BRL",
@@ -880,6 +986,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Buenos Aires",
             continent_code: "SA",
             continent_name: "South America",
+            region_code: "019",
+            subregion: "South America",
             calling_code: "+54",
             tld: ".ar",
             currency_code: "ARS",
@@ -899,6 +1007,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Santiago",
             continent_code: "SA",
             continent_name: "South America",
+            region_code: "019",
+            subregion: "South America",
             calling_code: "+56",
             tld: ".cl",
             currency_code: "CLP",
@@ -918,6 +1028,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Bogotá",
             continent_code: "SA",
             continent_name: "South America",
+            region_code: "019",
+            subregion: "South America",
             calling_code: "+57",
             tld: ".co",
             currency_code: "COP",
@@ -939,6 +1051,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Pretoria",
             continent_code: "AF",
             continent_name: "Africa",
+            region_code: "002",
+            subregion: "Southern Africa",
             calling_code: "+27",
             tld: ".za",
             currency_code: "ZAR",
@@ -958,6 +1072,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Abuja",
             continent_code: "AF",
             continent_name: "Africa",
+            region_code: "002",
+            subregion: "Western Africa",
             calling_code: "+234",
             tld: ".ng",
             currency_code: "NGN",
@@ -977,6 +1093,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Cairo",
             continent_code: "AF",
             continent_name: "Africa",
+            region_code: "002",
+            subregion: "Northern Africa",
             calling_code: "+20",
             tld: ".eg",
             currency_code: "EGP",
@@ -996,6 +1114,8 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
             capital: "Nairobi",
             continent_code: "AF",
             continent_name: "Africa",
+            region_code: "002",
+            subregion: "Eastern Africa",
             calling_code: "+254",
             tld: ".ke",
             currency_code: "KES",
@@ -1010,6 +1130,472 @@ pub static COUNTRY_DATA: LazyLock<HashMap<&'static str, CountryMetadata>> = Lazy
     m
 });
 
+/// UN M49 region codes and names
+static M49_REGIONS: &[(&str, &str)] = &[
+    ("002", "Africa"),
+    ("009", "Oceania"),
+    ("019", "Americas"),
+    ("142", "Asia"),
+    ("150", "Europe"),
+];
+
+/// UN M49 subregion names and codes (intermediate regions for the Americas and
+/// Africa, matching the granularity most geo-routing tables use)
+static M49_SUBREGIONS: &[(&str, &str)] = &[
+    ("Australia and New Zealand", "053"),
+    ("Central America", "013"),
+    ("Eastern Africa", "014"),
+    ("Eastern Asia", "030"),
+    ("Eastern Europe", "151"),
+    ("Northern Africa", "015"),
+    ("Northern America", "021"),
+    ("Northern Europe", "154"),
+    ("South America", "005"),
+    ("South-eastern Asia", "035"),
+    ("Southern Africa", "018"),
+    ("Southern Asia", "034"),
+    ("Southern Europe", "039"),
+    ("Western Africa", "011"),
+    ("Western Asia", "145"),
+    ("Western Europe", "155"),
+];
+
+/// Approximate geographic centre (latitude, longitude) of each country, used
+/// when the database knows the country but not the coordinates
+static COUNTRY_CENTROIDS: &[(&str, f64, f64)] = &[
+    ("AE", 23.424076, 53.847818),
+    ("AR", -38.416097, -63.616672),
+    ("AT", 47.516231, 14.550072),
+    ("AU", -25.274398, 133.775136),
+    ("BE", 50.503887, 4.469936),
+    ("BR", -14.235004, -51.92528),
+    ("CA", 56.130366, -106.346771),
+    ("CH", 46.818188, 8.227512),
+    ("CL", -35.675147, -71.542969),
+    ("CN", 35.86166, 104.195397),
+    ("CO", 4.570868, -74.297333),
+    ("CZ", 49.817492, 15.472962),
+    ("DE", 51.165691, 10.451526),
+    ("DK", 56.26392, 9.501785),
+    ("EG", 26.820553, 30.802498),
+    ("ES", 40.463667, -3.74922),
+    ("FI", 61.92411, 25.748151),
+    ("FR", 46.227638, 2.213749),
+    ("GB", 55.378051, -3.435973),
+    ("GR", 39.074208, 21.824312),
+    ("HK", 22.396428, 114.109497),
+    ("HU", 47.162494, 19.503304),
+    ("ID", -0.789275, 113.921327),
+    ("IE", 53.41291, -8.24389),
+    ("IL", 31.046051, 34.851612),
+    ("IN", 20.593684, 78.96288),
+    ("IT", 41.87194, 12.56738),
+    ("JP", 36.204824, 138.252924),
+    ("KE", -0.023559, 37.906193),
+    ("KR", 35.907757, 127.766922),
+    ("MX", 23.634501, -102.552784),
+    ("MY", 4.210484, 101.975766),
+    ("NG", 9.081999, 8.675277),
+    ("NL", 52.132633, 5.291266),
+    ("NO", 60.472024, 8.468946),
+    ("NZ", -40.900557, 174.885971),
+    ("PH", 12.879721, 121.774017),
+    ("PL", 51.919438, 19.145136),
+    ("PT", 39.399872, -8.224454),
+    ("RO", 45.943161, 24.96676),
+    ("RU", 61.52401, 105.318756),
+    ("SA", 23.885942, 45.079162),
+    ("SE", 60.128161, 18.643501),
+    ("SG", 1.352083, 103.819836),
+    ("TH", 15.870032, 100.992541),
+    ("TR", 38.963745, 35.243322),
+    ("TW", 23.69781, 120.960515),
+    ("UA", 48.379433, 31.16558),
+    ("US", 37.09024, -95.712891),
+    ("VN", 14.058324, 108.277199),
+    ("ZA", -30.559482, 22.937506),
+];
+
+/// Currency details returned for a country
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CurrencyMetadata {
+    /// ISO 4217 code (e.g. "EUR")
+    pub code: &'static str,
+    pub name: &'static str,
+    pub symbol: &'static str,
+}
+
+/// ISO 4217 currencies used by the countries in `COUNTRY_CURRENCIES`
+static CURRENCIES: &[(&str, &str, &str)] = &[
+    ("AFN", "Afghan Afghani", "؋"),
+    ("ALL", "Albanian Lek", "L"),
+    ("AMD", "Armenian Dram", "֏"),
+    ("AOA", "Angolan Kwanza", "Kz"),
+    ("AUD", "Australian Dollar", "$"),
+    ("AWG", "Aruban Florin", "ƒ"),
+    ("AZN", "Azerbaijani Manat", "₼"),
+    ("BAM", "Convertible Mark", "KM"),
+    ("BBD", "Barbadian Dollar", "$"),
+    ("BDT", "Bangladeshi Taka", "৳"),
+    ("BHD", "Bahraini Dinar", ".د.ب"),
+    ("BIF", "Burundian Franc", "FBu"),
+    ("BMD", "Bermudian Dollar", "$"),
+    ("BND", "Brunei Dollar", "$"),
+    ("BOB", "Bolivian Boliviano", "Bs."),
+    ("BSD", "Bahamian Dollar", "$"),
+    ("BTN", "Bhutanese Ngultrum", "Nu."),
+    ("BWP", "Botswana Pula", "P"),
+    ("BYN", "Belarusian Ruble", "Br"),
+    ("BZD", "Belize Dollar", "$"),
+    ("CDF", "Congolese Franc", "FC"),
+    ("CHF", "Swiss Franc", "CHF"),
+    ("CRC", "Costa Rican Colón", "₡"),
+    ("CUP", "Cuban Peso", "$"),
+    ("CVE", "Cape Verdean Escudo", "$"),
+    ("DJF", "Djiboutian Franc", "Fdj"),
+    ("DKK", "Danish Krone", "kr"),
+    ("DOP", "Dominican Peso", "RD$"),
+    ("DZD", "Algerian Dinar", "د.ج"),
+    ("ERN", "Eritrean Nakfa", "Nfk"),
+    ("ETB", "Ethiopian Birr", "Br"),
+    ("EUR", "Euro", "€"),
+    ("FJD", "Fijian Dollar", "$"),
+    ("FKP", "Falkland Islands Pound", "£"),
+    ("GBP", "British Pound", "£"),
+    ("GEL", "Georgian Lari", "₾"),
+    ("GHS", "Ghanaian Cedi", "₵"),
+    ("GIP", "Gibraltar Pound", "£"),
+    ("GMD", "Gambian Dalasi", "D"),
+    ("GNF", "Guinean Franc", "FG"),
+    ("GTQ", "Guatemalan Quetzal", "Q"),
+    ("GYD", "Guyanese Dollar", "$"),
+    ("HNL", "Honduran Lempira", "L"),
+    ("HTG", "Haitian Gourde", "G"),
+    ("ILS", "Israeli Shekel", "₪"),
+    ("IQD", "Iraqi Dinar", "ع.د"),
+    ("IRR", "Iranian Rial", "﷼"),
+    ("ISK", "Icelandic Króna", "kr"),
+    ("JMD", "Jamaican Dollar", "$"),
+    ("JOD", "Jordanian Dinar", "د.ا"),
+    ("KGS", "Kyrgyzstani Som", "с"),
+    ("KHR", "Cambodian Riel", "៛"),
+    ("KMF", "Comorian Franc", "CF"),
+    ("KPW", "North Korean Won", "₩"),
+    ("KWD", "Kuwaiti Dinar", "د.ك"),
+    ("KYD", "Cayman Islands Dollar", "$"),
+    ("KZT", "Kazakhstani Tenge", "₸"),
+    ("LAK", "Lao Kip", "₭"),
+    ("LBP", "Lebanese Pound", "ل.ل"),
+    ("LKR", "Sri Lankan Rupee", "Rs"),
+    ("LRD", "Liberian Dollar", "$"),
+    ("LSL", "Lesotho Loti", "L"),
+    ("LYD", "Libyan Dinar", "ل.د"),
+    ("MAD", "Moroccan Dirham", "د.م."),
+    ("MDL", "Moldovan Leu", "L"),
+    ("MGA", "Malagasy Ariary", "Ar"),
+    ("MKD", "Macedonian Denar", "ден"),
+    ("MMK", "Myanmar Kyat", "K"),
+    ("MNT", "Mongolian Tögrög", "₮"),
+    ("MOP", "Macanese Pataca", "MOP$"),
+    ("MRU", "Mauritanian Ouguiya", "UM"),
+    ("MUR", "Mauritian Rupee", "₨"),
+    ("MVR", "Maldivian Rufiyaa", "Rf"),
+    ("MWK", "Malawian Kwacha", "MK"),
+    ("MZN", "Mozambican Metical", "MT"),
+    ("NAD", "Namibian Dollar", "$"),
+    ("NIO", "Nicaraguan Córdoba", "C$"),
+    ("NOK", "Norwegian Krone", "kr"),
+    ("NPR", "Nepalese Rupee", "Rs"),
+    ("NZD", "New Zealand Dollar", "$"),
+    ("OMR", "Omani Rial", "ر.ع."),
+    ("PAB", "Panamanian Balboa", "B/."),
+    ("PEN", "Peruvian Sol", "S/"),
+    ("PGK", "Papua New Guinean Kina", "K"),
+    ("PKR", "Pakistani Rupee", "Rs"),
+    ("PYG", "Paraguayan Guaraní", "₲"),
+    ("QAR", "Qatari Riyal", "ر.ق"),
+    ("RSD", "Serbian Dinar", "дин."),
+    ("RWF", "Rwandan Franc", "FRw"),
+    ("SBD", "Solomon Islands Dollar", "$"),
+    ("SCR", "Seychellois Rupee", "₨"),
+    ("SDG", "Sudanese Pound", "ج.س."),
+    ("SLE", "Sierra Leonean Leone", "Le"),
+    ("SOS", "Somali Shilling", "Sh"),
+    ("SRD", "Surinamese Dollar", "$"),
+    ("SSP", "South Sudanese Pound", "£"),
+    ("STN", "São Tomé and Príncipe Dobra", "Db"),
+    ("SYP", "Syrian Pound", "£S"),
+    ("SZL", "Swazi Lilangeni", "L"),
+    ("TJS", "Tajikistani Somoni", "SM"),
+    ("TMT", "Turkmenistan Manat", "m"),
+    ("TND", "Tunisian Dinar", "د.ت"),
+    ("TOP", "Tongan Paʻanga", "T$"),
+    ("TTD", "Trinidad and Tobago Dollar", "$"),
+    ("TZS", "Tanzanian Shilling", "TSh"),
+    ("UGX", "Ugandan Shilling", "USh"),
+    ("USD", "US Dollar", "$"),
+    ("UYU", "Uruguayan Peso", "$"),
+    ("UZS", "Uzbekistani Som", "soʻm"),
+    ("VES", "Venezuelan Bolívar", "Bs."),
+    ("VUV", "Vanuatu Vatu", "VT"),
+    ("WST", "Samoan Tālā", "T"),
+    ("XAF", "Central African CFA Franc", "FCFA"),
+    ("XCD", "East Caribbean Dollar", "$"),
+    ("XCG", "Caribbean Guilder", "Cg"),
+    ("XOF", "West African CFA Franc", "CFA"),
+    ("XPF", "CFP Franc", "₣"),
+    ("YER", "Yemeni Rial", "﷼"),
+    ("ZMW", "Zambian Kwacha", "ZK"),
+    ("ZWG", "Zimbabwe Gold", "ZiG"),
+];
+
+/// ISO 4217 currency of countries without full `COUNTRY_DATA` metadata, so
+/// `currency` can still be filled in for them
+static COUNTRY_CURRENCIES: &[(&str, &str)] = &[
+    ("AD", "EUR"),
+    ("AF", "AFN"),
+    ("AG", "XCD"),
+    ("AI", "XCD"),
+    ("AL", "ALL"),
+    ("AM", "AMD"),
+    ("AO", "AOA"),
+    ("AS", "USD"),
+    ("AW", "AWG"),
+    ("AX", "EUR"),
+    ("AZ", "AZN"),
+    ("BA", "BAM"),
+    ("BB", "BBD"),
+    ("BD", "BDT"),
+    ("BF", "XOF"),
+    ("BG", "EUR"),
+    ("BH", "BHD"),
+    ("BI", "BIF"),
+    ("BJ", "XOF"),
+    ("BL", "EUR"),
+    ("BM", "BMD"),
+    ("BN", "BND"),
+    ("BO", "BOB"),
+    ("BQ", "USD"),
+    ("BS", "BSD"),
+    ("BT", "BTN"),
+    ("BW", "BWP"),
+    ("BY", "BYN"),
+    ("BZ", "BZD"),
+    ("CC", "AUD"),
+    ("CD", "CDF"),
+    ("CF", "XAF"),
+    ("CG", "XAF"),
+    ("CI", "XOF"),
+    ("CK", "NZD"),
+    ("CM", "XAF"),
+    ("CR", "CRC"),
+    ("CU", "CUP"),
+    ("CV", "CVE"),
+    ("CW", "XCG"),
+    ("CX", "AUD"),
+    ("CY", "EUR"),
+    ("DJ", "DJF"),
+    ("DM", "XCD"),
+    ("DO", "DOP"),
+    ("DZ", "DZD"),
+    ("EC", "USD"),
+    ("EE", "EUR"),
+    ("EH", "MAD"),
+    ("ER", "ERN"),
+    ("ET", "ETB"),
+    ("FJ", "FJD"),
+    ("FK", "FKP"),
+    ("FM", "USD"),
+    ("FO", "DKK"),
+    ("GA", "XAF"),
+    ("GD", "XCD"),
+    ("GE", "GEL"),
+    ("GF", "EUR"),
+    ("GG", "GBP"),
+    ("GH", "GHS"),
+    ("GI", "GIP"),
+    ("GL", "DKK"),
+    ("GM", "GMD"),
+    ("GN", "GNF"),
+    ("GP", "EUR"),
+    ("GQ", "XAF"),
+    ("GT", "GTQ"),
+    ("GU", "USD"),
+    ("GW", "XOF"),
+    ("GY", "GYD"),
+    ("HN", "HNL"),
+    ("HR", "EUR"),
+    ("HT", "HTG"),
+    ("IM", "GBP"),
+    ("IO", "USD"),
+    ("IQ", "IQD"),
+    ("IR", "IRR"),
+    ("IS", "ISK"),
+    ("JE", "GBP"),
+    ("JM", "JMD"),
+    ("JO", "JOD"),
+    ("KG", "KGS"),
+    ("KH", "KHR"),
+    ("KI", "AUD"),
+    ("KM", "KMF"),
+    ("KN", "XCD"),
+    ("KP", "KPW"),
+    ("KW", "KWD"),
+    ("KY", "KYD"),
+    ("KZ", "KZT"),
+    ("LA", "LAK"),
+    ("LB", "LBP"),
+    ("LC", "XCD"),
+    ("LI", "CHF"),
+    ("LK", "LKR"),
+    ("LR", "LRD"),
+    ("LS", "LSL"),
+    ("LT", "EUR"),
+    ("LU", "EUR"),
+    ("LV", "EUR"),
+    ("LY", "LYD"),
+    ("MA", "MAD"),
+    ("MC", "EUR"),
+    ("MD", "MDL"),
+    ("ME", "EUR"),
+    ("MF", "EUR"),
+    ("MG", "MGA"),
+    ("MH", "USD"),
+    ("MK", "MKD"),
+    ("ML", "XOF"),
+    ("MM", "MMK"),
+    ("MN", "MNT"),
+    ("MO", "MOP"),
+    ("MP", "USD"),
+    ("MQ", "EUR"),
+    ("MR", "MRU"),
+    ("MS", "XCD"),
+    ("MT", "EUR"),
+    ("MU", "MUR"),
+    ("MV", "MVR"),
+    ("MW", "MWK"),
+    ("MZ", "MZN"),
+    ("NA", "NAD"),
+    ("NC", "XPF"),
+    ("NE", "XOF"),
+    ("NF", "AUD"),
+    ("NI", "NIO"),
+    ("NP", "NPR"),
+    ("NR", "AUD"),
+    ("NU", "NZD"),
+    ("OM", "OMR"),
+    ("PA", "PAB"),
+    ("PE", "PEN"),
+    ("PF", "XPF"),
+    ("PG", "PGK"),
+    ("PK", "PKR"),
+    ("PM", "EUR"),
+    ("PN", "NZD"),
+    ("PR", "USD"),
+    ("PS", "ILS"),
+    ("PW", "USD"),
+    ("PY", "PYG"),
+    ("QA", "QAR"),
+    ("RE", "EUR"),
+    ("RS", "RSD"),
+    ("RW", "RWF"),
+    ("SB", "SBD"),
+    ("SC", "SCR"),
+    ("SD", "SDG"),
+    ("SI", "EUR"),
+    ("SJ", "NOK"),
+    ("SK", "EUR"),
+    ("SL", "SLE"),
+    ("SM", "EUR"),
+    ("SN", "XOF"),
+    ("SO", "SOS"),
+    ("SR", "SRD"),
+    ("SS", "SSP"),
+    ("ST", "STN"),
+    ("SV", "USD"),
+    ("SX", "XCG"),
+    ("SY", "SYP"),
+    ("SZ", "SZL"),
+    ("TC", "USD"),
+    ("TD", "XAF"),
+    ("TG", "XOF"),
+    ("TJ", "TJS"),
+    ("TK", "NZD"),
+    ("TL", "USD"),
+    ("TM", "TMT"),
+    ("TN", "TND"),
+    ("TO", "TOP"),
+    ("TT", "TTD"),
+    ("TV", "AUD"),
+    ("TZ", "TZS"),
+    ("UG", "UGX"),
+    ("UY", "UYU"),
+    ("UZ", "UZS"),
+    ("VA", "EUR"),
+    ("VC", "XCD"),
+    ("VE", "VES"),
+    ("VG", "USD"),
+    ("VI", "USD"),
+    ("VU", "VUV"),
+    ("WF", "XPF"),
+    ("WS", "WST"),
+    ("XK", "EUR"),
+    ("YE", "YER"),
+    ("YT", "EUR"),
+    ("ZM", "ZMW"),
+    ("ZW", "ZWG"),
+];
+
+/// Get the currency of a country by ISO 3166-1 alpha-2 code
+///
+/// Uses the full metadata when the country has it, otherwise the broader
+/// `COUNTRY_CURRENCIES` table.
+#[must_use]
+pub fn currency_for_country(country_code: &str) -> Option<CurrencyMetadata> {
+    if let Some(meta) = get_country_metadata(Some(country_code)) {
+        return Some(CurrencyMetadata {
+            code: meta.currency_code,
+            name: meta.currency_name,
+            symbol: meta.currency_symbol,
+        });
+    }
+    let currency = COUNTRY_CURRENCIES
+        .iter()
+        .find(|(code, _)| code.eq_ignore_ascii_case(country_code))
+        .map(|(_, currency)| *currency)?;
+    CURRENCIES
+        .iter()
+        .find(|(code, _, _)| *code == currency)
+        .map(|(code, name, symbol)| CurrencyMetadata { code, name, symbol })
+}
+
+/// Get the approximate centre of a country as (latitude, longitude)
+#[must_use]
+pub fn get_country_centroid(country_code: &str) -> Option<(f64, f64)> {
+    COUNTRY_CENTROIDS
+        .iter()
+        .find(|(code, _, _)| code.eq_ignore_ascii_case(country_code))
+        .map(|(_, lat, lng)| (*lat, *lng))
+}
+
+/// Get the UN M49 region name for a region code (e.g. "150" -> "Europe")
+#[must_use]
+pub fn get_m49_region_name(region_code: &str) -> Option<&'static str> {
+    M49_REGIONS
+        .iter()
+        .find(|(code, _)| *code == region_code)
+        .map(|(_, name)| *name)
+}
+
+/// Get the UN M49 code for a subregion name (e.g. "Western Europe" -> "155")
+#[must_use]
+pub fn get_m49_subregion_code(subregion: &str) -> Option<&'static str> {
+    M49_SUBREGIONS
+        .iter()
+        .find(|(name, _)| *name == subregion)
+        .map(|(_, code)| *code)
+}
+
 /// Get country metadata by ISO 3166-1 alpha-2 code
 #[must_use]
 pub fn get_country_metadata(country_code: Option<&str>) -> Option<&'static CountryMetadata> {
@@ -1034,6 +1620,8 @@ pub fn get_country_metadata_or_fallback(country_code: Option<&str>) -> Option<Co
                     capital: "Unknown",
                     continent_code: "XX",
                     continent_name: "Unknown",
+                    region_code: "",
+                    subregion: "",
                     calling_code: "",
                     tld: "",
                     currency_code: "",
@@ -1049,18 +1637,106 @@ pub fn get_country_metadata_or_fallback(country_code: Option<&str>) -> Option<Co
     }
 }
 
+/// Country codes with a flag in flag-icons: ISO 3166-1 alpha-2, plus `EU` and
+/// `XK` (Kosovo), which MaxMind also reports
+static FLAG_CODES: &[&str] = &[
+    "AD", "AE", "AF", "AG", "AI", "AL", "AM", "AO", "AQ", "AR", "AS", "AT", "AU", "AW", "AX", "AZ",
+    "BA", "BB", "BD", "BE", "BF", "BG", "BH", "BI", "BJ", "BL", "BM", "BN", "BO", "BQ", "BR", "BS",
+    "BT", "BV", "BW", "BY", "BZ", "CA", "CC", "CD", "CF", "CG", "CH", "CI", "CK", "CL", "CM", "CN",
+    "CO", "CR", "CU", "CV", "CW", "CX", "CY", "CZ", "DE", "DJ", "DK", "DM", "DO", "DZ", "EC", "EE",
+    "EG", "EH", "ER", "ES", "ET", "EU", "FI", "FJ", "FK", "FM", "FO", "FR", "GA", "GB", "GD", "GE",
+    "GF", "GG", "GH", "GI", "GL", "GM", "GN", "GP", "GQ", "GR", "GS", "GT", "GU", "GW", "GY", "HK",
+    "HM", "HN", "HR", "HT", "HU", "ID", "IE", "IL", "IM", "IN", "IO", "IQ", "IR", "IS", "IT", "JE",
+    "JM", "JO", "JP", "KE", "KG", "KH", "KI", "KM", "KN", "KP", "KR", "KW", "KY", "KZ", "LA", "LB",
+    "LC", "LI", "LK", "LR", "LS", "LT", "LU", "LV", "LY", "MA", "MC", "MD", "ME", "MF", "MG", "MH",
+    "MK", "ML", "MM", "MN", "MO", "MP", "MQ", "MR", "MS", "MT", "MU", "MV", "MW", "MX", "MY", "MZ",
+    "NA", "NC", "NE", "NF", "NG", "NI", "NL", "NO", "NP", "NR", "NU", "NZ", "OM", "PA", "PE", "PF",
+    "PG", "PH", "PK", "PL", "PM", "PN", "PR", "PS", "PT", "PW", "PY", "QA", "RE", "RO", "RS", "RU",
+    "RW", "SA", "SB", "SC", "SD", "SE", "SG", "SH", "SI", "SJ", "SK", "SL", "SM", "SN", "SO", "SR",
+    "SS", "ST", "SV", "SX", "SY", "SZ", "TC", "TD", "TF", "TG", "TH", "TJ", "TK", "TL", "TM", "TN",
+    "TO", "TR", "TT", "TV", "TW", "TZ", "UA", "UG", "UM", "US", "UY", "UZ", "VA", "VC", "VE", "VG",
+    "VI", "VN", "VU", "WF", "WS", "XK", "YE", "YT", "ZA", "ZM", "ZW",
+];
+
+/// Flag served for codes missing from `FLAG_CODES` unless `DEFAULT_FLAG` is set
+pub const DEFAULT_FLAG: &str = "un";
+
+/// Parse a `DEFAULT_FLAG` value: a flag file name without `.svg`, e.g. `un` or `xx`
+pub fn parse_default_flag(value: &str) -> Result<String, String> {
+    let flag = value.trim().to_lowercase();
+    let valid = !flag.is_empty()
+        && flag
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(format!(
+            "DEFAULT_FLAG must be a flag name like 'un', got '{}'",
+            value
+        ));
+    }
+    Ok(flag)
+}
+
 /// Get flag path for a country code
 /// Returns a relative path suitable for static file serving
 /// Use with flag-icons (https://github.com/lipis/flag-icons) or similar
+///
+/// Codes without a flag (e.g. user-assigned codes like `ZZ`) get the
+/// `default_flag` path instead of one that would 404.
 #[must_use]
-pub fn get_flag_path(country_code: &str) -> String {
-    format!("/static/flags/{}.svg", country_code.to_lowercase())
+pub fn get_flag_path(country_code: &str, default_flag: &str) -> String {
+    let flag = if FLAG_CODES
+        .iter()
+        .any(|code| code.eq_ignore_ascii_case(country_code))
+    {
+        country_code.to_lowercase()
+    } else {
+        default_flag.to_string()
+    };
+    format!("/static/flags/{}.svg", flag)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_get_country_centroid() {
+        assert_eq!(get_country_centroid("de"), Some((51.165691, 10.451526)));
+        assert_eq!(get_country_centroid("XX"), None);
+        // Every country with metadata has a centroid
+        for code in COUNTRY_DATA.keys() {
+            assert!(get_country_centroid(code).is_some(), "{code}");
+        }
+    }
+
+    #[test]
+    fn test_currency_for_country() {
+        // Full metadata
+        assert_eq!(currency_for_country("de").unwrap().code, "EUR");
+        // Only in the currency table
+        assert_eq!(
+            currency_for_country("IS"),
+            Some(CurrencyMetadata {
+                code: "ISK",
+                name: "Icelandic Króna",
+                symbol: "kr",
+            })
+        );
+        assert_eq!(currency_for_country("HR").unwrap().symbol, "€");
+        assert_eq!(currency_for_country("XX"), None);
+
+        // The table only covers countries without metadata, and every
+        // currency it names is defined
+        for (country, currency) in COUNTRY_CURRENCIES {
+            assert!(!COUNTRY_DATA.contains_key(country), "{country}");
+            assert!(
+                CURRENCIES.iter().any(|(code, _, _)| code == currency),
+                "{currency}"
+            );
+        }
+    }
+
     #[test]
     fn test_get_country_metadata_us() {
         let meta = get_country_metadata(Some("US")).unwrap();
@@ -1078,6 +1754,20 @@ mod tests {
         assert_eq!(meta.currency_code, "EUR");
     }
 
+    #[test]
+    fn test_m49_regions() {
+        let meta = get_country_metadata(Some("DE")).unwrap();
+        assert_eq!(meta.subregion, "Western Europe");
+        assert_eq!(get_m49_region_name(meta.region_code), Some("Europe"));
+        assert_eq!(get_m49_subregion_code(meta.subregion), Some("155"));
+
+        // Every embedded country resolves through the M49 tables
+        for (code, meta) in COUNTRY_DATA.iter() {
+            assert!(get_m49_region_name(meta.region_code).is_some(), "{}", code);
+            assert!(get_m49_subregion_code(meta.subregion).is_some(), "{}", code);
+        }
+    }
+
     #[test]
     fn test_get_country_metadata_lowercase() {
         let meta = get_country_metadata(Some("se")).unwrap();
@@ -1116,6 +1806,19 @@ mod tests {
 
     #[test]
     fn test_get_flag_path() {
-        assert_eq!(get_flag_path("US"), "/static/flags/us.svg");
+        assert_eq!(get_flag_path("US", DEFAULT_FLAG), "/static/flags/us.svg");
+        assert_eq!(get_flag_path("xk", DEFAULT_FLAG), "/static/flags/xk.svg");
+        // Codes without a flag fall back to the default
+        assert_eq!(get_flag_path("ZZ", DEFAULT_FLAG), "/static/flags/un.svg");
+        assert_eq!(get_flag_path("", DEFAULT_FLAG), "/static/flags/un.svg");
+        assert_eq!(get_flag_path("ZZ", "xx"), "/static/flags/xx.svg");
+    }
+
+    #[test]
+    fn test_parse_default_flag() {
+        assert_eq!(parse_default_flag(" XX ").unwrap(), "xx");
+        assert_eq!(parse_default_flag("un-round").unwrap(), "un-round");
+        assert!(parse_default_flag("").is_err());
+        assert!(parse_default_flag("../etc").is_err());
     }
 }
@@ -0,0 +1,136 @@
+//! HTML page served on `/` to browsers
+//!
+//! Browsers send `Accept: text/html`, and a raw JSON body is an unfriendly
+//! answer to someone who just typed the URL. API clients (JSON, protobuf,
+//! `*/*`) keep getting the data formats.
+
+use crate::models::IpGeoResponse;
+
+/// Page template; `{{name}}` placeholders are replaced with escaped values
+const TEMPLATE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>Your location - IP Geolocation API</title>
+<style>
+body { font-family: system-ui, sans-serif; max-width: 36rem; margin: 3rem auto; padding: 0 1rem; color: #222; }
+h1 { font-size: 1.4rem; }
+dl { display: grid; grid-template-columns: max-content 1fr; gap: .4rem 1.2rem; }
+dt { color: #666; }
+dd { margin: 0; }
+code { background: #f3f3f3; padding: .1rem .3rem; border-radius: 3px; }
+</style>
+</head>
+<body>
+<h1>Your IP address is <code>{{ip}}</code></h1>
+<dl>
+<dt>City</dt><dd>{{city}}</dd>
+<dt>Country</dt><dd>{{country}}</dd>
+<dt>Coordinates</dt><dd>{{coordinates}}</dd>
+<dt>Timezone</dt><dd>{{timezone}}</dd>
+<dt>Languages</dt><dd>{{languages}}</dd>
+</dl>
+<p>Get this as JSON with <code>curl {{base_url}}/</code>, or see <a href="{{prefix}}/v1/ipgeo?ip={{ip}}">the full record</a>, the <a href="{{prefix}}/openapi.yaml">OpenAPI spec</a>, and <a href="{{prefix}}/llms.txt">llms.txt</a>.</p>
+</body>
+</html>
+"#;
+
+/// Shown for fields the database has no value for
+const UNKNOWN: &str = "Unknown";
+
+/// Whether the client prefers an HTML page (a browser navigating to the URL)
+///
+/// Only an explicit `text/html` counts; `*/*` and missing headers (curl,
+/// HTTP libraries) keep the API formats.
+pub fn accepts_html(accept: Option<&str>) -> bool {
+    accept.is_some_and(|a| a.contains("text/html"))
+}
+
+/// Escape text for an HTML element or double-quoted attribute
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Render the visitor's geolocation as an HTML page
+///
+/// Links are relative to `path_prefix`, the subpath the service is mounted under.
+pub fn render_self_page(
+    ip: &str,
+    response: &IpGeoResponse,
+    base_url: &str,
+    path_prefix: &str,
+) -> String {
+    let or_unknown =
+        |value: Option<&str>| escape(value.filter(|v| !v.is_empty()).unwrap_or(UNKNOWN));
+    let coordinates = match (response.latitude, response.longitude) {
+        (Some(lat), Some(lng)) => format!("{lat}, {lng}"),
+        _ => UNKNOWN.to_string(),
+    };
+
+    TEMPLATE
+        .replace("{{ip}}", &escape(ip))
+        .replace("{{city}}", &or_unknown(response.city.as_deref()))
+        .replace("{{country}}", &or_unknown(response.country_name.as_deref()))
+        .replace("{{coordinates}}", &coordinates)
+        .replace(
+            "{{timezone}}",
+            &or_unknown(Some(response.time_zone.name.as_str())),
+        )
+        .replace(
+            "{{languages}}",
+            &or_unknown(Some(response.languages.as_str())),
+        )
+        .replace("{{base_url}}", &escape(base_url))
+        .replace("{{prefix}}", &escape(path_prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::TimeZoneInfo;
+
+    #[test]
+    fn test_accepts_html() {
+        assert!(accepts_html(Some(
+            "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8"
+        )));
+        assert!(!accepts_html(Some("*/*")));
+        assert!(!accepts_html(Some("application/json")));
+        assert!(!accepts_html(None));
+    }
+
+    #[test]
+    fn test_render_self_page_escapes_values() {
+        let response = IpGeoResponse {
+            latitude: Some(59.3293),
+            longitude: Some(18.0686),
+            city: Some("<script>".to_string()),
+            country_name: None,
+            time_zone: TimeZoneInfo {
+                name: "Europe/Stockholm".to_string(),
+            },
+            languages: "sv-SE".to_string(),
+        };
+
+        let page = render_self_page("1.2.3.4", &response, "https://example.com/geoip", "/geoip");
+        assert!(page.contains("<code>1.2.3.4</code>"));
+        assert!(page.contains("&lt;script&gt;"));
+        assert!(!page.contains("<script>"));
+        assert!(page.contains("<dt>Country</dt><dd>Unknown</dd>"));
+        assert!(page.contains("59.3293, 18.0686"));
+        assert!(page.contains(r#"href="/geoip/openapi.yaml""#));
+        assert!(page.contains("Europe/Stockholm"));
+    }
+}
@@ -0,0 +1,3 @@
+//! Alternative renderings of lookup responses for non-API clients
+
+pub mod html;
@@ -1,9 +1,10 @@
 use maxminddb::{geoip2, Reader};
 use std::net::IpAddr;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex, RwLock};
 use thiserror::Error;
 
+use crate::bogons::prefix_matches;
 use crate::models::GeoData;
 
 #[derive(Error, Debug)]
@@ -16,22 +17,148 @@ pub enum GeoIpError {
     NotFound,
 }
 
+/// Metadata describing the loaded MaxMind database
+#[derive(Debug, Clone, Default)]
+pub struct DatabaseMetadata {
+    /// Database type (e.g., "GeoLite2-City")
+    pub database_type: String,
+    /// Unix timestamp when the database was built
+    pub build_epoch: u64,
+    /// Number of nodes in the search tree
+    pub node_count: u32,
+    /// IP version supported (4 or 6)
+    pub ip_version: u16,
+}
+
+impl DatabaseMetadata {
+    /// Whole days since the database was built
+    pub fn age_days(&self) -> u64 {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        now.saturating_sub(self.build_epoch) / 86_400
+    }
+}
+
 /// Trait for IP geolocation lookup
 pub trait GeoIpLookup: Send + Sync {
     /// Lookup geolocation data for an IP address string
     fn lookup(&self, ip_str: &str) -> Result<GeoData, GeoIpError>;
+
+    /// The full decoded database record for an IP, for debugging
+    fn lookup_raw(&self, ip_str: &str) -> Result<serde_json::Value, GeoIpError>;
+
+    /// Metadata of the underlying database
+    fn metadata(&self) -> DatabaseMetadata;
+
+    /// Whether a database is loaded; false only while running without one
+    fn is_loaded(&self) -> bool {
+        true
+    }
+
+    /// Whether the IP is answered from an operator override table rather than
+    /// a database, so even private and reserved ranges have a location
+    fn is_overridden(&self, _ip_str: &str) -> bool {
+        false
+    }
+}
+
+/// Locales MaxMind carries names in, in the order tried as a last resort
+pub const NAME_LOCALES: &[&str] = &["en", "de", "es", "fr", "ja", "pt-BR", "ru", "zh-CN"];
+
+/// Locale names are taken from unless `DEFAULT_NAME_LOCALE` is set
+pub const DEFAULT_NAME_LOCALE: &str = "en";
+
+/// Parse a `DEFAULT_NAME_LOCALE` value into one of `NAME_LOCALES`
+pub fn parse_name_locale(value: &str) -> Result<&'static str, String> {
+    NAME_LOCALES
+        .iter()
+        .copied()
+        .find(|l| l.eq_ignore_ascii_case(value.trim()))
+        .ok_or_else(|| {
+            format!(
+                "DEFAULT_NAME_LOCALE must be one of {}, got '{}'",
+                NAME_LOCALES.join(", "),
+                value
+            )
+        })
+}
+
+/// Name for one MaxMind locale; a bare language (`pt`, `zh`) matches its
+/// regional variant (`pt-BR`, `zh-CN`)
+fn localized_name<'a>(names: &geoip2::Names<'a>, locale: &str) -> Option<&'a str> {
+    let language = locale.split('-').next().unwrap_or(locale);
+    match language.to_ascii_lowercase().as_str() {
+        "en" => names.english,
+        "de" => names.german,
+        "es" => names.spanish,
+        "fr" => names.french,
+        "ja" => names.japanese,
+        "pt" => names.brazilian_portuguese,
+        "ru" => names.russian,
+        "zh" => names.simplified_chinese,
+        _ => None,
+    }
+}
+
+/// Pick a name from a MaxMind `names` map
+///
+/// Tries `default_locale`, then every locale in `NAME_LOCALES`, so a name is
+/// only `None` when the record has no name at all. Lookups are cached per IP,
+/// so names do not follow `Accept-Language`.
+pub fn select_name<'a>(names: &geoip2::Names<'a>, default_locale: &str) -> Option<&'a str> {
+    std::iter::once(default_locale)
+        .chain(NAME_LOCALES.iter().copied())
+        .find_map(|locale| localized_name(names, locale))
 }
 
 /// Wrapper around MaxMind database reader
 pub struct GeoIpReader {
     reader: Reader<Vec<u8>>,
+    /// Optional GeoIP2 Connection-Type database
+    connection_type: Option<Reader<Vec<u8>>>,
+    /// Locale names are taken from first (`DEFAULT_NAME_LOCALE`)
+    name_locale: &'static str,
 }
 
 impl GeoIpReader {
     /// Open a MaxMind database from the given path
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, GeoIpError> {
         let reader = Reader::open_readfile(path)?;
-        Ok(Self { reader })
+        Ok(Self {
+            reader,
+            connection_type: None,
+            name_locale: DEFAULT_NAME_LOCALE,
+        })
+    }
+
+    /// Take names from `locale` (one of `NAME_LOCALES`) before falling back
+    pub fn with_name_locale(mut self, locale: &'static str) -> Self {
+        self.name_locale = locale;
+        self
+    }
+
+    /// Open a City database together with a Connection-Type database
+    pub fn open_with_connection_type<P: AsRef<Path>, Q: AsRef<Path>>(
+        path: P,
+        connection_type_path: Q,
+    ) -> Result<Self, GeoIpError> {
+        let mut geoip = Self::open(path)?;
+        geoip.connection_type = Some(Reader::open_readfile(connection_type_path)?);
+        Ok(geoip)
+    }
+
+    /// Lookup the connection type, if that database is loaded and has the IP
+    fn lookup_connection_type(&self, ip: IpAddr) -> Option<String> {
+        let record: geoip2::ConnectionType = self
+            .connection_type
+            .as_ref()?
+            .lookup(ip)
+            .ok()?
+            .decode()
+            .ok()??;
+        record.connection_type.map(String::from)
     }
 
     /// Lookup geolocation data for an IP address
@@ -53,24 +180,38 @@ impl GeoIpReader {
         // but their fields are still Option
         let latitude = city.location.latitude;
         let longitude = city.location.longitude;
+        let time_zone = city.location.time_zone.map(String::from);
+        let metro_code = city.location.metro_code;
 
         // City name from the names struct
-        let city_name = city.city.names.english.map(String::from);
+        let city_name = select_name(&city.city.names, self.name_locale).map(String::from);
 
         // Country info
-        let country_name = city.country.names.english.map(String::from);
+        let country_name = select_name(&city.country.names, self.name_locale).map(String::from);
         let country_code = city.country.iso_code.map(String::from);
 
         // Extract subdivisions (state/province)
         let subdivision = city.subdivisions.first();
-        let state_prov = subdivision.and_then(|s| s.names.english).map(String::from);
+        let state_prov = subdivision
+            .and_then(|s| select_name(&s.names, self.name_locale))
+            .map(String::from);
         let state_code = subdivision.and_then(|s| s.iso_code).map(String::from);
 
         // Extract postal code
         let postal_code = city.postal.code.map(String::from);
 
-        // Extract geoname_id from city
+        // GeoNames IDs for the city, country, and first subdivision
         let geoname_id = city.city.geoname_id;
+        let country_geoname_id = city.country.geoname_id;
+        let state_geoname_id = subdivision.and_then(|s| s.geoname_id);
+
+        // Registered/represented country (differ from the located country for
+        // e.g. military bases, embassies, and some mobile/satellite ISPs)
+        let registered_country_code = city.registered_country.iso_code.map(String::from);
+        let represented_country_code = city.represented_country.iso_code.map(String::from);
+
+        // Most specific network in the database containing the IP
+        let network = lookup_result.network().ok().map(|n| n.to_string());
 
         Ok(GeoData {
             latitude,
@@ -82,6 +223,14 @@ impl GeoIpReader {
             state_code,
             postal_code,
             geoname_id,
+            country_geoname_id,
+            state_geoname_id,
+            registered_country_code,
+            represented_country_code,
+            connection_type: self.lookup_connection_type(ip),
+            metro_code,
+            network,
+            time_zone,
         })
     }
 }
@@ -91,11 +240,315 @@ impl GeoIpLookup for GeoIpReader {
         let ip: IpAddr = ip_str.parse()?;
         self.lookup_ip(ip)
     }
+
+    fn lookup_raw(&self, ip_str: &str) -> Result<serde_json::Value, GeoIpError> {
+        let ip: IpAddr = ip_str.parse()?;
+        let lookup_result = self.reader.lookup(ip)?;
+        if !lookup_result.has_data() {
+            return Err(GeoIpError::NotFound);
+        }
+        lookup_result.decode()?.ok_or(GeoIpError::NotFound)
+    }
+
+    fn metadata(&self) -> DatabaseMetadata {
+        let metadata = &self.reader.metadata;
+        DatabaseMetadata {
+            database_type: metadata.database_type.clone(),
+            build_epoch: metadata.build_epoch,
+            node_count: metadata.node_count,
+            ip_version: metadata.ip_version,
+        }
+    }
 }
 
 /// Shared GeoIP reader wrapped in Arc for thread-safe access
 pub type SharedGeoIpReader = Arc<dyn GeoIpLookup>;
 
+/// Ordered chain of readers consulted until one has the IP
+///
+/// Lets a commercial database be backed by GeoLite2 (or vice versa) for
+/// coverage. If no reader has the IP, the first error other than `NotFound` is
+/// returned so database failures are not masked; otherwise `NotFound`.
+pub struct ChainedGeoIpReader {
+    readers: Vec<SharedGeoIpReader>,
+}
+
+impl ChainedGeoIpReader {
+    /// Create a chain; `readers[0]` is the primary database
+    pub fn new(readers: Vec<SharedGeoIpReader>) -> Self {
+        Self { readers }
+    }
+
+    /// Run `lookup` against each reader in order, returning the first hit
+    fn first_found<T>(
+        &self,
+        lookup: impl Fn(&dyn GeoIpLookup) -> Result<T, GeoIpError>,
+    ) -> Result<T, GeoIpError> {
+        let mut first_error = None;
+        for reader in &self.readers {
+            match lookup(reader.as_ref()) {
+                Ok(found) => return Ok(found),
+                Err(GeoIpError::NotFound) => {}
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        Err(first_error.unwrap_or(GeoIpError::NotFound))
+    }
+}
+
+impl GeoIpLookup for ChainedGeoIpReader {
+    fn lookup(&self, ip_str: &str) -> Result<GeoData, GeoIpError> {
+        self.first_found(|reader| reader.lookup(ip_str))
+    }
+
+    fn lookup_raw(&self, ip_str: &str) -> Result<serde_json::Value, GeoIpError> {
+        self.first_found(|reader| reader.lookup_raw(ip_str))
+    }
+
+    /// Metadata of the primary database
+    fn metadata(&self) -> DatabaseMetadata {
+        self.readers
+            .first()
+            .map(|reader| reader.metadata())
+            .unwrap_or_default()
+    }
+}
+
+/// Placeholder used when `GEOIP_DB_OPTIONAL` lets the server start without a database
+///
+/// Every lookup is `NotFound` and `is_loaded` is false, so geolocation
+/// endpoints answer 503 until `POST /admin/reload` loads a real database.
+pub struct NullGeoIpReader;
+
+impl GeoIpLookup for NullGeoIpReader {
+    fn lookup(&self, _ip_str: &str) -> Result<GeoData, GeoIpError> {
+        Err(GeoIpError::NotFound)
+    }
+
+    fn lookup_raw(&self, _ip_str: &str) -> Result<serde_json::Value, GeoIpError> {
+        Err(GeoIpError::NotFound)
+    }
+
+    fn metadata(&self) -> DatabaseMetadata {
+        DatabaseMetadata::default()
+    }
+
+    fn is_loaded(&self) -> bool {
+        false
+    }
+}
+
+/// Reader that answers from a fixed `ip/cidr → GeoData` table before the database
+///
+/// Loaded from `GEOIP_OVERRIDES_PATH` to give office/VPN ranges (or test IPs)
+/// meaningful locations. The most specific matching entry wins; IPs outside
+/// every entry go to the wrapped reader.
+pub struct OverrideGeoIpReader {
+    /// (left-aligned network, prefix length on that scale, data), longest prefix first
+    overrides: Vec<(u128, u8, GeoData)>,
+    inner: SharedGeoIpReader,
+}
+
+impl OverrideGeoIpReader {
+    /// Read the table from a JSON file of `{"<ip or cidr>": {GeoData fields}}`
+    pub fn load(path: &str, inner: SharedGeoIpReader) -> Result<Self, String> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read GeoIP overrides at '{}': {}", path, e))?;
+        Self::from_json(&json, inner)
+            .map_err(|e| format!("Invalid GeoIP overrides at '{}': {}", path, e))
+    }
+
+    /// Parse the table from JSON; see [`OverrideGeoIpReader::load`]
+    pub fn from_json(json: &str, inner: SharedGeoIpReader) -> Result<Self, String> {
+        let entries: std::collections::HashMap<String, GeoData> =
+            serde_json::from_str(json).map_err(|e| e.to_string())?;
+        let mut overrides = entries
+            .into_iter()
+            .map(|(key, mut data)| {
+                let (network, len) = parse_cidr(&key)?;
+                data.network.get_or_insert(key);
+                Ok((network, len, data))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        overrides.sort_by_key(|(_, len, _)| std::cmp::Reverse(*len));
+        Ok(Self { overrides, inner })
+    }
+
+    /// Number of entries in the table
+    pub fn len(&self) -> usize {
+        self.overrides.len()
+    }
+
+    /// Whether the table has no entries
+    pub fn is_empty(&self) -> bool {
+        self.overrides.is_empty()
+    }
+
+    fn find(&self, ip_str: &str) -> Result<Option<&GeoData>, GeoIpError> {
+        let addr = left_aligned(ip_str.parse()?);
+        Ok(self
+            .overrides
+            .iter()
+            .find(|(network, len, _)| prefix_matches(addr, *network, *len))
+            .map(|(_, _, data)| data))
+    }
+}
+
+/// An address as a left-aligned 128-bit value; IPv4 sits in the mapped range
+/// so IPv4 and IPv4-mapped IPv6 addresses match the same entries
+fn left_aligned(ip: IpAddr) -> u128 {
+    match ip {
+        IpAddr::V4(v4) => u128::from(v4.to_ipv6_mapped()),
+        IpAddr::V6(v6) => u128::from(v6),
+    }
+}
+
+/// Parse `ip` or `ip/len` into a left-aligned network and a 128-bit-scale prefix length
+fn parse_cidr(value: &str) -> Result<(u128, u8), String> {
+    let (ip, len) = match value.split_once('/') {
+        Some((ip, len)) => (ip, Some(len)),
+        None => (value, None),
+    };
+    let ip: IpAddr = ip
+        .parse()
+        .map_err(|_| format!("'{}' is not an IP address or CIDR", value))?;
+    let max_len = if ip.is_ipv4() { 32 } else { 128 };
+    let len = match len {
+        Some(len) => len
+            .parse::<u8>()
+            .ok()
+            .filter(|len| *len <= max_len)
+            .ok_or_else(|| format!("'{}' has an invalid prefix length", value))?,
+        None => max_len,
+    };
+    // IPv4 prefixes are offset by the 96 bits of the mapped range
+    let len = if ip.is_ipv4() { len + 96 } else { len };
+    Ok((left_aligned(ip), len))
+}
+
+impl GeoIpLookup for OverrideGeoIpReader {
+    fn lookup(&self, ip_str: &str) -> Result<GeoData, GeoIpError> {
+        match self.find(ip_str)? {
+            Some(data) => Ok(data.clone()),
+            None => self.inner.lookup(ip_str),
+        }
+    }
+
+    /// Overridden IPs report the override entry in place of a database record
+    fn lookup_raw(&self, ip_str: &str) -> Result<serde_json::Value, GeoIpError> {
+        match self.find(ip_str)? {
+            Some(data) => Ok(serde_json::json!({ "override": data })),
+            None => self.inner.lookup_raw(ip_str),
+        }
+    }
+
+    fn metadata(&self) -> DatabaseMetadata {
+        self.inner.metadata()
+    }
+
+    fn is_loaded(&self) -> bool {
+        self.inner.is_loaded()
+    }
+
+    fn is_overridden(&self, ip_str: &str) -> bool {
+        matches!(self.find(ip_str), Ok(Some(_))) || self.inner.is_overridden(ip_str)
+    }
+}
+
+/// Paths of every database making up the served reader
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DatabasePaths {
+    /// Primary City database (`GEOIP_DB_PATH`)
+    pub city: String,
+    /// Optional Connection-Type database (`CONNECTION_TYPE_DB_PATH`)
+    pub connection_type: Option<String>,
+    /// Optional fallback City database (`GEOIP_DB_FALLBACK_PATH`)
+    pub fallback: Option<String>,
+}
+
+/// Opens a fresh reader, with every configured database, from the given paths
+pub type GeoIpLoader =
+    Box<dyn Fn(&DatabasePaths) -> Result<SharedGeoIpReader, String> + Send + Sync>;
+
+/// Reader that can be swapped for a freshly opened one at runtime
+///
+/// In-flight lookups keep using the reader they started with; the old one is
+/// dropped once they finish.
+pub struct ReloadableGeoIpReader {
+    current: RwLock<SharedGeoIpReader>,
+    /// Paths `current` was opened from; locked for the whole reload so
+    /// concurrent reloads cannot interleave
+    paths: Mutex<DatabasePaths>,
+    loader: GeoIpLoader,
+}
+
+impl ReloadableGeoIpReader {
+    /// Start with `initial`, opened from `paths`; `loader` opens the
+    /// replacement on each reload
+    pub fn new(initial: SharedGeoIpReader, paths: DatabasePaths, loader: GeoIpLoader) -> Self {
+        Self {
+            current: RwLock::new(initial),
+            paths: Mutex::new(paths),
+            loader,
+        }
+    }
+
+    /// Open the databases again and swap them in, keeping the current reader on failure
+    pub fn reload(&self) -> Result<DatabaseMetadata, String> {
+        self.reload_from(|paths| paths.clone())
+    }
+
+    /// Open the databases from new paths and swap them all in at once
+    ///
+    /// `update` derives the new paths from the current ones. Every database is
+    /// opened before anything is swapped, so if any of them fails the current
+    /// reader and paths stay in use unchanged.
+    pub fn reload_from(
+        &self,
+        update: impl FnOnce(&DatabasePaths) -> DatabasePaths,
+    ) -> Result<DatabaseMetadata, String> {
+        let mut paths = self.paths.lock().unwrap_or_else(|e| e.into_inner());
+        let new_paths = update(&paths);
+        let reader = (self.loader)(&new_paths)?;
+        let metadata = reader.metadata();
+        *self.current.write().unwrap_or_else(|e| e.into_inner()) = reader;
+        *paths = new_paths;
+        Ok(metadata)
+    }
+
+    /// Paths the served databases were opened from
+    pub fn paths(&self) -> DatabasePaths {
+        self.paths.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    fn current(&self) -> SharedGeoIpReader {
+        self.current
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+impl GeoIpLookup for ReloadableGeoIpReader {
+    fn lookup(&self, ip_str: &str) -> Result<GeoData, GeoIpError> {
+        self.current().lookup(ip_str)
+    }
+
+    fn lookup_raw(&self, ip_str: &str) -> Result<serde_json::Value, GeoIpError> {
+        self.current().lookup_raw(ip_str)
+    }
+
+    fn metadata(&self) -> DatabaseMetadata {
+        self.current().metadata()
+    }
+
+    fn is_loaded(&self) -> bool {
+        self.current().is_loaded()
+    }
+}
+
 /// Mock GeoIP reader for testing
 pub mod mock {
     use super::*;
@@ -103,6 +556,8 @@ pub mod mock {
     #[derive(Default)]
     pub struct MockGeoIpReader {
         pub responses: std::collections::HashMap<String, Result<GeoData, GeoIpError>>,
+        pub raw_records: std::collections::HashMap<String, serde_json::Value>,
+        pub metadata: DatabaseMetadata,
     }
 
     impl MockGeoIpReader {
@@ -114,16 +569,40 @@ pub mod mock {
             self.responses.insert(ip.to_string(), response);
             self
         }
+
+        pub fn with_metadata(mut self, metadata: DatabaseMetadata) -> Self {
+            self.metadata = metadata;
+            self
+        }
+
+        pub fn with_raw_record(mut self, ip: &str, record: serde_json::Value) -> Self {
+            self.raw_records.insert(ip.to_string(), record);
+            self
+        }
     }
 
     impl GeoIpLookup for MockGeoIpReader {
         fn lookup(&self, ip_str: &str) -> Result<GeoData, GeoIpError> {
             match self.responses.get(ip_str) {
                 Some(Ok(data)) => Ok(data.clone()),
-                Some(Err(_)) => Err(GeoIpError::NotFound),
-                None => Err(GeoIpError::NotFound),
+                Some(Err(GeoIpError::NotFound)) | None => Err(GeoIpError::NotFound),
+                // GeoIpError isn't Clone; surface other errors as database errors
+                Some(Err(e)) => Err(GeoIpError::DatabaseOpen(
+                    maxminddb::MaxMindDbError::invalid_database(e.to_string()),
+                )),
             }
         }
+
+        fn lookup_raw(&self, ip_str: &str) -> Result<serde_json::Value, GeoIpError> {
+            self.raw_records
+                .get(ip_str)
+                .cloned()
+                .ok_or(GeoIpError::NotFound)
+        }
+
+        fn metadata(&self) -> DatabaseMetadata {
+            self.metadata.clone()
+        }
     }
 }
 
@@ -132,6 +611,33 @@ mod tests {
     use super::mock::MockGeoIpReader;
     use super::*;
 
+    #[test]
+    fn test_select_name_fallback_chain() {
+        let names = geoip2::Names {
+            english: Some("Munich"),
+            german: Some("München"),
+            ..Default::default()
+        };
+        assert_eq!(select_name(&names, DEFAULT_NAME_LOCALE), Some("Munich"));
+        assert_eq!(select_name(&names, "de"), Some("München"));
+
+        // Without English, the first available locale is used
+        let names = geoip2::Names {
+            japanese: Some("ミュンヘン"),
+            russian: Some("Мюнхен"),
+            ..Default::default()
+        };
+        assert_eq!(select_name(&names, "de"), Some("ミュンヘン"));
+        assert_eq!(select_name(&geoip2::Names::default(), "en"), None);
+    }
+
+    #[test]
+    fn test_parse_name_locale() {
+        assert_eq!(parse_name_locale("de"), Ok("de"));
+        assert_eq!(parse_name_locale(" PT-br "), Ok("pt-BR"));
+        assert!(parse_name_locale("xx").is_err());
+    }
+
     #[test]
     fn test_invalid_ip() {
         let result = "not-an-ip".parse::<IpAddr>();
@@ -163,6 +669,7 @@ mod tests {
             state_code: Some("TS".to_string()),
             postal_code: Some("12345".to_string()),
             geoname_id: Some(123456),
+            ..Default::default()
         };
         assert_eq!(data.latitude, Some(37.751));
         assert_eq!(data.longitude, Some(-97.822));
@@ -183,6 +690,7 @@ mod tests {
             state_code: None,
             postal_code: None,
             geoname_id: None,
+            ..Default::default()
         };
         assert!(data.latitude.is_none());
         assert!(data.city.is_none());
@@ -215,6 +723,7 @@ mod tests {
                 state_code: Some("CA".to_string()),
                 postal_code: Some("94043".to_string()),
                 geoname_id: Some(5375480),
+                ..Default::default()
             }),
         );
 
@@ -232,6 +741,209 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_chained_reader_falls_back_on_not_found() {
+        let primary = MockGeoIpReader::new().with_response(
+            "8.8.8.8",
+            Ok(GeoData {
+                city: Some("Primary".to_string()),
+                ..Default::default()
+            }),
+        );
+        let fallback = MockGeoIpReader::new()
+            .with_response(
+                "8.8.8.8",
+                Ok(GeoData {
+                    city: Some("Fallback".to_string()),
+                    ..Default::default()
+                }),
+            )
+            .with_response(
+                "1.1.1.1",
+                Ok(GeoData {
+                    city: Some("Fallback".to_string()),
+                    ..Default::default()
+                }),
+            );
+        let chain = ChainedGeoIpReader::new(vec![Arc::new(primary), Arc::new(fallback)]);
+
+        // The primary wins when it has the IP
+        assert_eq!(
+            chain.lookup("8.8.8.8").unwrap().city.as_deref(),
+            Some("Primary")
+        );
+        // The fallback answers when the primary misses
+        assert_eq!(
+            chain.lookup("1.1.1.1").unwrap().city.as_deref(),
+            Some("Fallback")
+        );
+        // Neither has it
+        assert!(matches!(chain.lookup("9.9.9.9"), Err(GeoIpError::NotFound)));
+    }
+
+    #[test]
+    fn test_chained_reader_surfaces_database_errors() {
+        let primary = MockGeoIpReader::new().with_response("1.1.1.1", Err(GeoIpError::NotFound));
+        let broken = MockGeoIpReader::new().with_response(
+            "1.1.1.1",
+            Err(GeoIpError::InvalidIp("x".parse::<IpAddr>().unwrap_err())),
+        );
+        let chain = ChainedGeoIpReader::new(vec![Arc::new(primary), Arc::new(broken)]);
+
+        assert!(matches!(
+            chain.lookup("1.1.1.1"),
+            Err(GeoIpError::DatabaseOpen(_))
+        ));
+    }
+
+    #[test]
+    fn test_override_reader_wins_over_delegate() {
+        let delegate = MockGeoIpReader::new()
+            .with_response(
+                "10.1.2.3",
+                Ok(GeoData {
+                    city: Some("Database".to_string()),
+                    ..Default::default()
+                }),
+            )
+            .with_response(
+                "8.8.8.8",
+                Ok(GeoData {
+                    city: Some("Mountain View".to_string()),
+                    ..Default::default()
+                }),
+            );
+        let path =
+            std::env::temp_dir().join(format!("geoip-overrides-{}.json", std::process::id()));
+        std::fs::write(
+            &path,
+            r#"{
+                "10.0.0.0/8": {"city": "VPN", "country_code": "SE"},
+                "10.1.0.0/16": {"city": "Stockholm Office", "latitude": 59.33, "longitude": 18.07},
+                "2001:db8::1": {"city": "Lab"}
+            }"#,
+        )
+        .unwrap();
+        let reader = OverrideGeoIpReader::load(path.to_str().unwrap(), Arc::new(delegate)).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(reader.len(), 3);
+
+        // The most specific entry wins over both the broader one and the database
+        let data = reader.lookup("10.1.2.3").unwrap();
+        assert_eq!(data.city.as_deref(), Some("Stockholm Office"));
+        assert_eq!(data.network.as_deref(), Some("10.1.0.0/16"));
+        assert_eq!(
+            reader.lookup("10.200.0.1").unwrap().city.as_deref(),
+            Some("VPN")
+        );
+        assert_eq!(
+            reader.lookup("::ffff:10.200.0.1").unwrap().city.as_deref(),
+            Some("VPN")
+        );
+        assert_eq!(
+            reader.lookup("2001:db8::1").unwrap().city.as_deref(),
+            Some("Lab")
+        );
+        // Everything else goes to the delegate
+        assert_eq!(
+            reader.lookup("8.8.8.8").unwrap().city.as_deref(),
+            Some("Mountain View")
+        );
+        assert!(matches!(
+            reader.lookup("1.1.1.1"),
+            Err(GeoIpError::NotFound)
+        ));
+    }
+
+    #[test]
+    fn test_override_reader_rejects_bad_keys() {
+        let inner: SharedGeoIpReader = Arc::new(NullGeoIpReader);
+        assert!(OverrideGeoIpReader::from_json(r#"{"10.0.0.0/33": {}}"#, inner.clone()).is_err());
+        assert!(OverrideGeoIpReader::from_json(r#"{"office": {}}"#, inner).is_err());
+    }
+
+    #[test]
+    fn test_reloadable_reader_swaps_in_loaded_database() {
+        let reader = ReloadableGeoIpReader::new(
+            Arc::new(NullGeoIpReader),
+            DatabasePaths::default(),
+            Box::new(|_| {
+                Ok(Arc::new(MockGeoIpReader::new().with_response(
+                    "8.8.8.8",
+                    Ok(GeoData {
+                        city: Some("Mountain View".to_string()),
+                        ..Default::default()
+                    }),
+                )))
+            }),
+        );
+        assert!(!reader.is_loaded());
+        assert!(matches!(
+            reader.lookup("8.8.8.8"),
+            Err(GeoIpError::NotFound)
+        ));
+
+        reader.reload().unwrap();
+        assert!(reader.is_loaded());
+        assert_eq!(
+            reader.lookup("8.8.8.8").unwrap().city.as_deref(),
+            Some("Mountain View")
+        );
+    }
+
+    #[test]
+    fn test_reloadable_reader_keeps_current_on_failure() {
+        let reader = ReloadableGeoIpReader::new(
+            Arc::new(NullGeoIpReader),
+            DatabasePaths::default(),
+            Box::new(|_| Err("missing.mmdb: not found".to_string())),
+        );
+        assert_eq!(reader.reload().unwrap_err(), "missing.mmdb: not found");
+        assert!(!reader.is_loaded());
+    }
+
+    #[test]
+    fn test_reloadable_reader_swaps_paths_together() {
+        let initial = DatabasePaths {
+            city: "city-1.mmdb".to_string(),
+            connection_type: Some("conn-1.mmdb".to_string()),
+            fallback: None,
+        };
+        let reader = ReloadableGeoIpReader::new(
+            Arc::new(NullGeoIpReader),
+            initial.clone(),
+            Box::new(|paths| {
+                if paths.connection_type.as_deref() == Some("missing.mmdb") {
+                    return Err("missing.mmdb: not found".to_string());
+                }
+                Ok(Arc::new(MockGeoIpReader::new()))
+            }),
+        );
+
+        // One bad path rejects the whole set
+        let result = reader.reload_from(|paths| DatabasePaths {
+            city: "city-2.mmdb".to_string(),
+            connection_type: Some("missing.mmdb".to_string()),
+            ..paths.clone()
+        });
+        assert!(result.is_err());
+        assert!(!reader.is_loaded());
+        assert_eq!(reader.paths(), initial);
+
+        reader
+            .reload_from(|paths| DatabasePaths {
+                city: "city-2.mmdb".to_string(),
+                ..paths.clone()
+            })
+            .unwrap();
+        assert!(reader.is_loaded());
+        assert_eq!(reader.paths().city, "city-2.mmdb");
+        assert_eq!(
+            reader.paths().connection_type.as_deref(),
+            Some("conn-1.mmdb")
+        );
+    }
+
     #[test]
     fn test_mock_geoip_reader_with_error() {
         let mock = MockGeoIpReader::new().with_response("0.0.0.0", Err(GeoIpError::NotFound));
@@ -1,33 +1,61 @@
 use axum::{
     body::Body,
-    extract::{ConnectInfo, Query, State},
-    http::{header, HeaderMap, StatusCode},
-    response::{IntoResponse, Response},
-    Json,
+    extract::{ConnectInfo, Path, Query, Request, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode, Uri},
+    middleware::Next,
+    response::{
+        sse::{Event, KeepAlive},
+        IntoResponse, Response, Sse,
+    },
+    Extension, Json,
 };
+use chrono::{DateTime, Utc};
+use futures::Stream;
 use prost::Message;
-use std::net::{IpAddr, SocketAddr};
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::broadcast;
 use utoipa::OpenApi;
 
-use crate::cache::SharedGeoCache;
-use crate::country_data::{get_country_metadata, get_flag_path};
-use crate::geoip::SharedGeoIpReader;
-use crate::languages::get_languages;
+use crate::airports::nearest_airport;
+use crate::cache::{CacheConfig, GeoCache, SharedFullGeoCache, SharedGeoCache};
+use crate::country_data::{
+    currency_for_country, get_country_centroid, get_country_metadata, get_flag_path,
+    get_m49_region_name, get_m49_subregion_code, NameSource, COUNTRY_DATA, DEFAULT_FLAG,
+};
+use crate::formats::html::{accepts_html, render_self_page};
+use crate::geoip::{DatabasePaths, GeoIpError, ReloadableGeoIpReader, SharedGeoIpReader};
+use crate::ip_class::classify_ip;
+use crate::languages::get_languages_with;
+use crate::mcp::resources::RESOURCE_URI_PREFIX;
+use crate::mcp::{schemas, McpNotification, BULK_LOOKUP_MAX_IPS};
 use crate::models::{
-    ApiErrorResponse, CountryMetadataInfo, CurrencyInfo, GeoData, IpGeoQuery, IpGeoResponse,
-    IpGeoResponseFull, LocationInfo, TimeZoneInfo, TimeZoneInfoFull, TimezoneQuery,
-    TimezoneResponse, TimezoneResponseFull,
+    ApiErrorResponse, CountriesQuery, CountryEntry, CountryMetadataInfo, CurrencyInfo,
+    DatabaseHealthResponse, ExplainInfo, FlagResponse, FormatQuery, GeoData, IpGeoQuery,
+    IpGeoResponse, IpGeoResponseFull, LocationInfo, NearestAirportInfo, NetworkInfo,
+    RawRecordQuery, ReadinessResponse, RegionInfo, ReloadRequest, TimeZoneInfo, TimeZoneInfoFull,
+    TimezoneBatchRequest, TimezoneCompareEntry, TimezoneCompareQuery, TimezoneCompareResponse,
+    TimezoneListEntry, TimezonePoint, TimezoneQuery, TimezoneResponse, TimezoneResponseFull,
+    TimezonesQuery, VersionResponse,
+};
+use crate::proto::{accepts_protobuf, geolocation, PROTOBUF_CONTENT_TYPE, PROTO_DEFINITION};
+use crate::timezone::{
+    is_nautical_timezone, SharedTimezoneProvider, TimezoneProvider, TzfTimezoneProvider,
+};
+use crate::tz_utils::{
+    get_timezone_details, memoized_timezone_details_at, TimeFormat, TimezoneDetails,
 };
-use crate::proto::{accepts_protobuf, geolocation, PROTOBUF_CONTENT_TYPE};
-use crate::timezone::lookup_timezone;
-use crate::tz_utils::get_timezone_details;
 
 /// OpenAPI documentation for the IP Geolocation API
 #[derive(OpenApi)]
 #[openapi(
     info(
         title = "IP Geolocation API",
-        description = "A high-performance IP geolocation and timezone lookup API.\n\nProvides:\n- IP address to geographic location mapping\n- Coordinate-based timezone lookups\n- Country metadata (currency, languages, calling codes)\n\nAll responses are cached for 2 weeks (Cache-Control: public, max-age=1209600).",
+        description = "A high-performance IP geolocation and timezone lookup API.\n\nProvides:\n- IP address to geographic location mapping\n- Coordinate-based timezone lookups\n- Country metadata (currency, languages, calling codes)\n\nAll responses are cached for 2 weeks (Cache-Control: public, max-age=1209600), except /v1/ipgeo and full-format /ipgeo responses embedding the current time, which are cached for 60 seconds (pass live_time=false to omit it).",
         version = "1.0.0",
         license(name = "MIT", url = "https://opensource.org/licenses/MIT")
     ),
@@ -36,22 +64,45 @@ use crate::tz_utils::get_timezone_details;
     ),
     paths(
         root_handler,
+        self_stream_handler,
         ipgeo_handler,
         ipgeo_full_handler,
         timezone_handler,
         timezone_full_handler,
+        timezone_batch_handler,
+        timezone_compare_handler,
+        timezones_handler,
+        flag_handler,
+        countries_handler,
         health_handler,
+        health_database_handler,
+        health_ready_handler,
+        version_handler,
     ),
     components(schemas(
         IpGeoResponse,
         IpGeoResponseFull,
         TimezoneResponse,
         TimezoneResponseFull,
+        TimezoneBatchRequest,
+        TimezonePoint,
+        TimezoneCompareResponse,
+        TimezoneCompareEntry,
+        TimezoneListEntry,
+        CountryEntry,
         LocationInfo,
+        NearestAirportInfo,
         CountryMetadataInfo,
         CurrencyInfo,
+        NetworkInfo,
+        ExplainInfo,
+        RegionInfo,
         TimeZoneInfo,
         TimeZoneInfoFull,
+        FlagResponse,
+        VersionResponse,
+        DatabaseHealthResponse,
+        ReadinessResponse,
         ApiErrorResponse,
     ))
 )]
@@ -62,8 +113,124 @@ pub struct ApiDoc;
 pub struct AppState {
     pub geoip: SharedGeoIpReader,
     pub cache: SharedGeoCache,
+    /// Timezone lookups from coordinates (tzf-rs unless replaced)
+    pub timezones: SharedTimezoneProvider,
+    /// `/v1/ipgeo` responses, keyed by [`canonical_cache_key`]
+    pub full_cache: SharedFullGeoCache,
     /// Base URL for the API (used in OpenAPI spec, sitemap, etc.)
     pub base_url: String,
+    /// Bearer token guarding the /admin endpoints (disabled when unset)
+    pub admin_token: Option<String>,
+    /// Longest accepted `ip` query value in bytes (`MAX_QUERY_LENGTH`)
+    pub max_query_len: usize,
+    /// Default for `reject_private` on the REST lookups (`REJECT_PRIVATE_IPS`)
+    pub reject_private: bool,
+    /// Default for `empty_as_204` on the REST lookups (`EMPTY_AS_204`)
+    pub empty_as_204: bool,
+    /// Handle behind `geoip` for `POST /admin/reload` (unset when not reloadable)
+    pub reloader: Option<Arc<ReloadableGeoIpReader>>,
+    /// Honour `explain=true` on `/v1/ipgeo` (`ENABLE_EXPLAIN`)
+    pub enable_explain: bool,
+    /// Set by `run_http3_server` while it is listening (unset when HTTP/3 is disabled)
+    pub http3_running: Option<Arc<AtomicBool>>,
+    /// Serve an HTML page on `/` to browsers (off with `DISABLE_ROOT_HTML`)
+    pub root_html: bool,
+    /// `McpState::notification_tx`, told about database reloads for `/mcp/sse` clients
+    pub mcp_notifications: Option<broadcast::Sender<McpNotification>>,
+    /// Database age after which /health/database reports it stale (`MAX_DB_AGE_DAYS`)
+    pub max_db_age_days: u64,
+    /// Derive discovery URLs from the forwarded scheme and host (`DETECT_BASE_URL`)
+    pub detect_base_url: bool,
+    /// Reject query parameters an endpoint does not accept (`STRICT_QUERY`)
+    pub strict_query: bool,
+    /// Field omission, coordinate rounding, flags, path prefix and languages
+    pub response_options: Arc<ResponseOptions>,
+    /// Most IPs, points or zones one bulk request may carry (`BULK_MAX_IPS`)
+    pub bulk_max_ips: usize,
+}
+
+impl AppState {
+    /// Public URL the API is reached at: `base_url` plus any `PATH_PREFIX`
+    pub fn public_url(&self) -> String {
+        format!("{}{}", self.base_url, self.response_options.path_prefix)
+    }
+
+    /// Create state with the core dependencies and all optional features disabled
+    pub fn new(geoip: SharedGeoIpReader, cache: SharedGeoCache, base_url: String) -> Self {
+        Self {
+            geoip,
+            cache,
+            timezones: Arc::new(TzfTimezoneProvider),
+            full_cache: Arc::new(GeoCache::new(CacheConfig::default())),
+            base_url,
+            admin_token: None,
+            max_query_len: DEFAULT_MAX_QUERY_LEN,
+            reject_private: false,
+            empty_as_204: false,
+            reloader: None,
+            enable_explain: false,
+            http3_running: None,
+            root_html: false,
+            mcp_notifications: None,
+            max_db_age_days: DEFAULT_MAX_DB_AGE_DAYS,
+            detect_base_url: false,
+            strict_query: false,
+            response_options: Arc::new(ResponseOptions::default()),
+            bulk_max_ips: BULK_LOOKUP_MAX_IPS,
+        }
+    }
+
+    /// Public URL for a discovery document served in reply to `headers`
+    ///
+    /// With `detect_base_url`, the scheme comes from `X-Forwarded-Proto` and the
+    /// host from `X-Forwarded-Host` or `Host`, so one deployment can answer for
+    /// several hostnames. Falls back to [`public_url`](Self::public_url) when
+    /// detection is off or the headers are missing or malformed.
+    pub fn request_public_url(&self, headers: &HeaderMap) -> String {
+        let detected = self
+            .detect_base_url
+            .then(|| forwarded_base_url(headers, &self.base_url))
+            .flatten();
+        match detected {
+            Some(base_url) => format!("{}{}", base_url, self.response_options.path_prefix),
+            None => self.public_url(),
+        }
+    }
+}
+
+/// `scheme://host` from the forwarding headers, if they name a usable host
+///
+/// Without `X-Forwarded-Proto`, the scheme of `fallback` is kept. Only the
+/// first value of a comma-separated proxy chain is used.
+fn forwarded_base_url(headers: &HeaderMap, fallback: &str) -> Option<String> {
+    let first_value = |name: &str| {
+        headers
+            .get(name)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.split(',').next())
+            .map(str::trim)
+            .filter(|v| !v.is_empty())
+    };
+
+    let host = first_value("X-Forwarded-Host").or_else(|| first_value("Host"))?;
+    // Hostnames, IPv4/IPv6 literals and ports only; anything else would end up
+    // verbatim in the YAML and XML documents
+    let valid_host = host
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':' | '[' | ']'));
+    if !valid_host {
+        return None;
+    }
+
+    let scheme = match first_value("X-Forwarded-Proto") {
+        Some(proto) if proto.eq_ignore_ascii_case("https") => "https",
+        Some(proto) if proto.eq_ignore_ascii_case("http") => "http",
+        Some(_) => return None,
+        None => fallback
+            .split_once("://")
+            .map_or("https", |(scheme, _)| scheme),
+    };
+    Some(format!("{scheme}://{host}"))
 }
 
 /// API error response
@@ -71,94 +238,424 @@ pub struct AppState {
 pub struct ApiError {
     pub error: String,
     pub code: &'static str,
+    /// Request parameter that failed validation (e.g. "ip", "lat", "long")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<&'static str>,
+}
+
+impl ApiError {
+    /// HTTP status for this error: server-side failures are 503, everything
+    /// else is a client error
+    pub fn status(&self) -> StatusCode {
+        match self.code {
+            "DATABASE_ERROR" | "DATABASE_NOT_LOADED" => StatusCode::SERVICE_UNAVAILABLE,
+            "PRIVATE_IP" => StatusCode::UNPROCESSABLE_ENTITY,
+            _ => StatusCode::BAD_REQUEST,
+        }
+    }
 }
 
 /// Cache-Control header value for responses (2 weeks)
 /// IP geolocation data changes infrequently, so aggressive caching is safe
 const CACHE_CONTROL: &str = "public, max-age=1209600";
 
+/// Cache-Control header value for responses embedding the current time
+const CACHE_CONTROL_LIVE: &str = "public, max-age=60";
+
+/// Cache-Control header value for responses that depend on who is asking
+const CACHE_CONTROL_PRIVATE: &str = "private, no-store";
+
+/// Whether a response came from the lookup cache (`HIT` or `MISS`)
+const X_CACHE: HeaderName = HeaderName::from_static("x-cache");
+
+/// `ip` value on `/ipgeo` standing for the caller's own address
+const SELF_IP: &str = "self";
+
+/// `location.coordinate_source` when coordinates came from the country centre
+const COUNTRY_CENTROID_SOURCE: &str = "country_centroid";
+
+/// Default cap on the `ip` query value, roomy enough for 100 IPv6 addresses
+pub const DEFAULT_MAX_QUERY_LEN: usize = 8192;
+
+/// Default database age before warning; GeoLite2 is rebuilt weekly, so this
+/// allows a few missed updates
+pub const DEFAULT_MAX_DB_AGE_DAYS: u64 = 35;
+
+/// How often /v1/self/stream re-sends the client's location
+const SELF_STREAM_REFRESH: Duration = Duration::from_secs(300);
+
+/// Heartbeat comment interval for /v1/self/stream
+const SELF_STREAM_KEEP_ALIVE: Duration = Duration::from_secs(30);
+
 /// Helper to get Accept header value from HeaderMap
 fn get_accept_header(headers: &HeaderMap) -> Option<&str> {
     headers.get(header::ACCEPT).and_then(|v| v.to_str().ok())
 }
 
+/// Response encoding negotiated from the Accept header and `?pretty`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ResponseFormat {
+    Json,
+    PrettyJson,
+    Protobuf,
+}
+
+impl ResponseFormat {
+    /// Protobuf wins over `pretty`, which only affects JSON output
+    fn negotiate(headers: &HeaderMap, pretty: Option<bool>) -> Self {
+        if accepts_protobuf(get_accept_header(headers)) {
+            Self::Protobuf
+        } else if pretty.unwrap_or(false) {
+            Self::PrettyJson
+        } else {
+            Self::Json
+        }
+    }
+
+    fn is_protobuf(self) -> bool {
+        self == Self::Protobuf
+    }
+
+    /// Serialize a JSON body, indented when `?pretty=true` was requested
+    fn json_body<T: serde::Serialize>(self, value: &T) -> Vec<u8> {
+        if self == Self::PrettyJson {
+            serde_json::to_vec_pretty(value).unwrap()
+        } else {
+            serde_json::to_vec(value).unwrap()
+        }
+    }
+}
+
 /// Build OK response with content negotiation (JSON or Protobuf)
-fn build_response<T, P>(response: &T, proto_response: P, use_protobuf: bool) -> Response<Body>
+fn build_response<T, P>(response: &T, proto_response: P, format: ResponseFormat) -> Response<Body>
+where
+    T: serde::Serialize,
+    P: Message,
+{
+    build_response_with_cache_control(response, proto_response, format, CACHE_CONTROL)
+}
+
+/// Build OK response with content negotiation and an explicit Cache-Control value
+fn build_response_with_cache_control<T, P>(
+    response: &T,
+    proto_response: P,
+    format: ResponseFormat,
+    cache_control: &'static str,
+) -> Response<Body>
 where
     T: serde::Serialize,
     P: Message,
 {
-    if use_protobuf {
+    if format.is_protobuf() {
         let body = proto_response.encode_to_vec();
         Response::builder()
             .status(StatusCode::OK)
             .header(header::CONTENT_TYPE, PROTOBUF_CONTENT_TYPE)
-            .header(header::CACHE_CONTROL, CACHE_CONTROL)
+            .header(header::CACHE_CONTROL, cache_control)
             .body(Body::from(body))
             .unwrap()
     } else {
-        let body = serde_json::to_vec(response).unwrap();
+        let body = format.json_body(response);
         Response::builder()
             .status(StatusCode::OK)
             .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
-            .header(header::CACHE_CONTROL, CACHE_CONTROL)
+            .header(header::CACHE_CONTROL, cache_control)
             .body(Body::from(body))
             .unwrap()
     }
 }
 
+/// Add `X-Cache: HIT|MISS` to a response that consulted the lookup cache
+fn with_cache_status(mut response: Response<Body>, hit: bool) -> Response<Body> {
+    let status = if hit { "HIT" } else { "MISS" };
+    response
+        .headers_mut()
+        .insert(X_CACHE, HeaderValue::from_static(status));
+    response
+}
+
+/// 204 answer for `empty_as_204=true` lookups that found nothing
+///
+/// Cached like the empty 200 it replaces; there is no body to negotiate.
+fn no_content_response() -> Response<Body> {
+    Response::builder()
+        .status(StatusCode::NO_CONTENT)
+        .header(header::CACHE_CONTROL, CACHE_CONTROL)
+        .body(Body::empty())
+        .unwrap()
+}
+
 /// Build error response with content negotiation (JSON or Protobuf)
-fn build_error_response(error: &ApiError, use_protobuf: bool) -> Response<Body> {
+fn build_error_response(error: &ApiError, format: ResponseFormat) -> Response<Body> {
     let proto_error = geolocation::ApiError {
         error: error.error.clone(),
         code: error.code.to_string(),
+        field: error.field.map(String::from),
+    };
+
+    // Server-side failures are transient, so they must not be cached
+    let status = error.status();
+    let cache_control = if status.is_server_error() {
+        "no-store"
+    } else {
+        CACHE_CONTROL
     };
 
-    if use_protobuf {
+    if format.is_protobuf() {
         let body = proto_error.encode_to_vec();
         Response::builder()
-            .status(StatusCode::BAD_REQUEST)
+            .status(status)
             .header(header::CONTENT_TYPE, PROTOBUF_CONTENT_TYPE)
-            .header(header::CACHE_CONTROL, CACHE_CONTROL)
+            .header(header::CACHE_CONTROL, cache_control)
             .body(Body::from(body))
             .unwrap()
     } else {
-        let body = serde_json::to_vec(error).unwrap();
+        let body = format.json_body(error);
         Response::builder()
-            .status(StatusCode::BAD_REQUEST)
+            .status(status)
             .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
-            .header(header::CACHE_CONTROL, CACHE_CONTROL)
+            .header(header::CACHE_CONTROL, cache_control)
             .body(Body::from(body))
             .unwrap()
     }
 }
 
+/// Check the `Authorization: Bearer <token>` header against the configured admin token
+fn check_admin_token(state: &AppState, headers: &HeaderMap) -> Result<(), (StatusCode, ApiError)> {
+    let Some(expected) = state.admin_token.as_deref() else {
+        return Err((
+            StatusCode::FORBIDDEN,
+            ApiError {
+                error: "Admin endpoints are disabled (ADMIN_TOKEN is not set)".to_string(),
+                code: "ADMIN_DISABLED",
+                field: None,
+            },
+        ));
+    };
+
+    let provided = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+
+    // Constant-time so response timing doesn't reveal how much of a guess matched
+    let authorized = provided
+        .is_some_and(|token| crate::signing::secrets_match(token.as_bytes(), expected.as_bytes()));
+    if !authorized {
+        return Err((
+            StatusCode::UNAUTHORIZED,
+            ApiError {
+                error: "Missing or invalid admin token".to_string(),
+                code: "UNAUTHORIZED",
+                field: None,
+            },
+        ));
+    }
+
+    Ok(())
+}
+
 /// Validate IP address format
 fn validate_ip(ip: &str) -> Result<(), ApiError> {
     ip.parse::<IpAddr>().map_err(|_| ApiError {
         error: format!("Invalid IP address: {}", ip),
         code: "INVALID_IP",
+        field: Some("ip"),
     })?;
     Ok(())
 }
 
+/// Query parameter names given more than once, in order of first repeat
+///
+/// Which value the `Query` extractor would use for a repeated name depends on
+/// the target type, so such requests are refused rather than guessed at.
+pub fn duplicate_query_params(uri: &Uri) -> Vec<String> {
+    let Ok(Query(pairs)) = Query::<Vec<(String, String)>>::try_from_uri(uri) else {
+        return Vec::new();
+    };
+    let mut seen = std::collections::HashSet::new();
+    let mut duplicates = Vec::new();
+    for (name, _) in pairs {
+        if !seen.insert(name.clone()) && !duplicates.contains(&name) {
+            duplicates.push(name);
+        }
+    }
+    duplicates
+}
+
+/// 400 `DUPLICATE_PARAM` naming the repeated query parameters, if there are any
+pub fn duplicate_params_response(headers: &HeaderMap, uri: &Uri) -> Option<Response<Body>> {
+    let duplicates = duplicate_query_params(uri);
+    if duplicates.is_empty() {
+        return None;
+    }
+    let error = ApiError {
+        error: format!("Duplicate query parameter: {}", duplicates.join(", ")),
+        code: "DUPLICATE_PARAM",
+        field: None,
+    };
+    Some(build_error_response(
+        &error,
+        ResponseFormat::negotiate(headers, None),
+    ))
+}
+
+/// Middleware rejecting requests that repeat a query parameter (`?ip=a&ip=b`)
+pub async fn reject_duplicate_params(request: Request, next: Next) -> Response<Body> {
+    match duplicate_params_response(request.headers(), request.uri()) {
+        Some(response) => response,
+        None => next.run(request).await,
+    }
+}
+
+/// Cache-busting parameter (`?_=1712345678`) accepted on every endpoint
+const CACHE_BUSTER_PARAM: &str = "_";
+
+/// Query parameters accepted on `path`, or `None` for endpoints without a query
+fn known_query_params(path: &str) -> Option<&'static [&'static str]> {
+    Some(match path {
+        "/" | "/v1/timezone/batch" => FormatQuery::PARAMS,
+        "/ipgeo" | "/v1/ipgeo" | "/v1/flag" => IpGeoQuery::PARAMS,
+        "/timezone" | "/v1/timezone" => TimezoneQuery::PARAMS,
+        "/v1/timezone/compare" => TimezoneCompareQuery::PARAMS,
+        "/v1/timezones" => TimezonesQuery::PARAMS,
+        "/v1/countries" => CountriesQuery::PARAMS,
+        "/admin/raw" | "/admin/all" => RawRecordQuery::PARAMS,
+        _ => return None,
+    })
+}
+
+/// Query parameter names `path` does not accept, in order of first appearance
+///
+/// Paths outside the lookup API (static files, MCP, discovery documents) are
+/// not checked. The `_` cache-buster is always accepted.
+pub fn unknown_query_params(path: &str, uri: &Uri) -> Vec<String> {
+    let Some(known) = known_query_params(path) else {
+        return Vec::new();
+    };
+    let Ok(Query(pairs)) = Query::<Vec<(String, String)>>::try_from_uri(uri) else {
+        return Vec::new();
+    };
+    let mut unknown = Vec::new();
+    for (name, _) in pairs {
+        if name != CACHE_BUSTER_PARAM && !known.contains(&name.as_str()) && !unknown.contains(&name)
+        {
+            unknown.push(name);
+        }
+    }
+    unknown
+}
+
+/// 400 `UNKNOWN_PARAM` naming the query parameters `path` does not accept, with `STRICT_QUERY`
+pub fn unknown_params_response(
+    state: &AppState,
+    headers: &HeaderMap,
+    path: &str,
+    uri: &Uri,
+) -> Option<Response<Body>> {
+    if !state.strict_query {
+        return None;
+    }
+    let unknown = unknown_query_params(path, uri);
+    if unknown.is_empty() {
+        return None;
+    }
+    let error = ApiError {
+        error: format!("Unknown query parameter: {}", unknown.join(", ")),
+        code: "UNKNOWN_PARAM",
+        field: None,
+    };
+    Some(build_error_response(
+        &error,
+        ResponseFormat::negotiate(headers, None),
+    ))
+}
+
+/// Middleware rejecting query parameters the endpoint does not accept (`STRICT_QUERY`)
+pub async fn reject_unknown_params(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response<Body> {
+    let path = request.uri().path();
+    match unknown_params_response(&state, request.headers(), path, request.uri()) {
+        Some(response) => response,
+        None => next.run(request).await,
+    }
+}
+
+/// Reject addresses that can never be geolocated, matching the MCP tools
+///
+/// Only applied with `reject_private`; otherwise such IPs get an empty response.
+/// Ranges in the `GEOIP_OVERRIDES_PATH` table are accepted.
+fn validate_public_ip(state: &AppState, ip: &str) -> Result<(), ApiError> {
+    validate_ip(ip)?;
+    if is_unlocatable(state, ip) {
+        return Err(ApiError {
+            error: format!("Private/loopback IP address not supported: {}", ip),
+            code: "PRIVATE_IP",
+            field: Some("ip"),
+        });
+    }
+    Ok(())
+}
+
+/// Earliest and latest accepted `at` timestamps (1900-01-01 and 2100-01-01 UTC)
+const MIN_AT_TIMESTAMP: i64 = -2_208_988_800;
+const MAX_AT_TIMESTAMP: i64 = 4_102_444_800;
+
+/// Validate the `at` timestamp range and convert it to a UTC instant
+fn validate_timestamp(at: i64) -> Result<DateTime<Utc>, ApiError> {
+    (MIN_AT_TIMESTAMP..=MAX_AT_TIMESTAMP)
+        .contains(&at)
+        .then(|| DateTime::from_timestamp(at, 0))
+        .flatten()
+        .ok_or_else(|| ApiError {
+            error: format!(
+                "Timestamp must be between {} and {}, got: {}",
+                MIN_AT_TIMESTAMP, MAX_AT_TIMESTAMP, at
+            ),
+            code: "INVALID_TIMESTAMP",
+            field: Some("at"),
+        })
+}
+
 /// Validate latitude range (-90 to 90)
+///
+/// `NaN` and infinities (which `f64` parsing accepts as "NaN", "inf") are
+/// rejected up front rather than relying on the range check.
 fn validate_latitude(lat: f64) -> Result<(), ApiError> {
+    if !lat.is_finite() {
+        return Err(ApiError {
+            error: format!("Latitude must be a finite number, got: {}", lat),
+            code: "INVALID_LATITUDE",
+            field: Some("lat"),
+        });
+    }
     if !(-90.0..=90.0).contains(&lat) {
         return Err(ApiError {
             error: format!("Latitude must be between -90 and 90, got: {}", lat),
             code: "INVALID_LATITUDE",
+            field: Some("lat"),
         });
     }
     Ok(())
 }
 
-/// Validate longitude range (-180 to 180)
+/// Validate longitude range (-180 to 180), rejecting non-finite values
 fn validate_longitude(lng: f64) -> Result<(), ApiError> {
+    if !lng.is_finite() {
+        return Err(ApiError {
+            error: format!("Longitude must be a finite number, got: {}", lng),
+            code: "INVALID_LONGITUDE",
+            field: Some("long"),
+        });
+    }
     if !(-180.0..=180.0).contains(&lng) {
         return Err(ApiError {
             error: format!("Longitude must be between -180 and 180, got: {}", lng),
             code: "INVALID_LONGITUDE",
+            field: Some("long"),
         });
     }
     Ok(())
@@ -171,18 +668,18 @@ pub fn extract_client_ip(headers: &HeaderMap, connect_info: Option<SocketAddr>)
         .get("CF-Connecting-IP")
         .and_then(|v| v.to_str().ok())
     {
-        return cf_ip.to_string();
+        return strip_port(cf_ip).to_string();
     }
 
     // Check X-Real-IP (common with nginx)
     if let Some(real_ip) = headers.get("X-Real-IP").and_then(|v| v.to_str().ok()) {
-        return real_ip.to_string();
+        return strip_port(real_ip).to_string();
     }
 
     // Check X-Forwarded-For (take first IP in chain)
     if let Some(forwarded_for) = headers.get("X-Forwarded-For").and_then(|v| v.to_str().ok()) {
         if let Some(first_ip) = forwarded_for.split(',').next() {
-            return first_ip.trim().to_string();
+            return strip_port(first_ip).to_string();
         }
     }
 
@@ -192,23 +689,348 @@ pub fn extract_client_ip(headers: &HeaderMap, connect_info: Option<SocketAddr>)
         .unwrap_or_else(|| "-".to_string())
 }
 
+/// Drop a `:port` suffix some proxies append to forwarded addresses
+///
+/// Handles `203.0.113.1:443` and bracketed IPv6 (`[2001:db8::1]:443` or
+/// `[2001:db8::1]`); bare IPv6 addresses and anything unparseable are
+/// returned trimmed but otherwise unchanged for `validate_ip` to judge.
+fn strip_port(value: &str) -> &str {
+    let value = value.trim();
+    if value.parse::<IpAddr>().is_ok() {
+        return value;
+    }
+    if let Some(rest) = value.strip_prefix('[') {
+        if let Some((host, port)) = rest.split_once(']') {
+            if port.is_empty()
+                || port
+                    .strip_prefix(':')
+                    .is_some_and(|p| p.parse::<u16>().is_ok())
+            {
+                return host;
+            }
+        }
+        return value;
+    }
+    match value.rsplit_once(':') {
+        Some((host, port)) if port.parse::<u16>().is_ok() && host.parse::<Ipv4Addr>().is_ok() => {
+            host
+        }
+        _ => value,
+    }
+}
+
+/// Return `code` only when it names a different country than `located`
+fn other_country(code: &Option<String>, located: &Option<String>) -> Option<String> {
+    code.as_ref()
+        .filter(|c| Some(*c) != located.as_ref())
+        .cloned()
+}
+
+/// Timezone name for an IP and its source ("coordinates" or "database")
+///
+/// Coordinates (tzf-rs) are preferred since they reflect the located city;
+/// MaxMind's own `time_zone` covers records without them (e.g. country-only).
+fn resolve_timezone(
+    geo_data: &GeoData,
+    timezones: &dyn TimezoneProvider,
+) -> Option<(String, &'static str)> {
+    let from_coordinates = match (geo_data.latitude, geo_data.longitude) {
+        (Some(lat), Some(lng)) => timezones.lookup(lat, lng),
+        _ => None,
+    };
+
+    match (from_coordinates, &geo_data.time_zone) {
+        // Nautical zones are an approximation, so MaxMind's zone wins over them
+        (Some(tz), None) if is_nautical_timezone(&tz) => Some((tz, "nautical")),
+        (Some(tz), Some(database)) if is_nautical_timezone(&tz) => {
+            Some((database.clone(), "database"))
+        }
+        (Some(tz), database) => {
+            if let Some(database) = database.as_ref().filter(|db| **db != tz) {
+                tracing::debug!(
+                    coordinates = %tz,
+                    database = %database,
+                    "timezone from coordinates disagrees with database"
+                );
+            }
+            Some((tz, "coordinates"))
+        }
+        (None, Some(database)) => Some((database.clone(), "database")),
+        (None, None) => None,
+    }
+}
+
+/// Full-response paths dropped via `OMIT_FIELDS`
+///
+/// Entries are comma-separated `section` or `section.field` paths into the
+/// full response (e.g. `location.zipcode,location.latitude`). Omitting either
+/// form of a coordinate (`latitude` or `latitude_f`) drops both, and the
+/// nearest airport is not derived from hidden coordinates.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OmitFields(Vec<(String, Option<String>)>);
+
+impl OmitFields {
+    /// Parse an `OMIT_FIELDS` value, rejecting paths the full response lacks
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut fields = Vec::new();
+        for path in spec.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (section, field) = match path.split_once('.') {
+                Some((section, field)) => (section, Some(field)),
+                None => (path, None),
+            };
+            if !omit_path(&mut IpGeoResponseFull::default(), section, field) {
+                return Err(format!(
+                    "OMIT_FIELDS entries must be 'section' or 'section.field' paths of the full response, got '{}'",
+                    path
+                ));
+            }
+            fields.push((section.to_string(), field.map(String::from)));
+        }
+        Ok(Self(fields))
+    }
+
+    /// Whether `section.field` (or its whole section) is omitted
+    fn contains(&self, section: &str, field: &str) -> bool {
+        self.0
+            .iter()
+            .any(|(s, f)| s == section && f.as_deref().is_none_or(|f| f == field))
+    }
+
+    /// Drop the omitted paths from a full response
+    fn apply(&self, response: &mut IpGeoResponseFull) {
+        for (section, field) in &self.0 {
+            omit_path(response, section, field.as_deref());
+        }
+    }
+}
+
+/// Clear an optional field that may sit in an absent section
+fn clear<T>(field: Option<&mut Option<T>>) {
+    if let Some(field) = field {
+        *field = None;
+    }
+}
+
+/// Clear `section` or `section.field` of a full response
+///
+/// Returns false for paths the full response does not have.
+fn omit_path(response: &mut IpGeoResponseFull, section: &str, field: Option<&str>) -> bool {
+    let Some(field) = field else {
+        match section {
+            "ip" => response.ip = None,
+            "location" => response.location = None,
+            "country_metadata" => response.country_metadata = None,
+            "currency" => response.currency = None,
+            "region" => response.region = None,
+            "time_zone" => response.time_zone = None,
+            "network" => response.network = None,
+            _ => return false,
+        }
+        return true;
+    };
+    match section {
+        "location" => {
+            let l = response.location.as_mut();
+            match field {
+                "continent_code" => clear(l.map(|l| &mut l.continent_code)),
+                "continent_name" => clear(l.map(|l| &mut l.continent_name)),
+                "country_code2" => clear(l.map(|l| &mut l.country_code2)),
+                "country_code3" => clear(l.map(|l| &mut l.country_code3)),
+                "country_name" => clear(l.map(|l| &mut l.country_name)),
+                "country_name_official" => clear(l.map(|l| &mut l.country_name_official)),
+                "country_capital" => clear(l.map(|l| &mut l.country_capital)),
+                "state_prov" => clear(l.map(|l| &mut l.state_prov)),
+                "state_code" => clear(l.map(|l| &mut l.state_code)),
+                "district" => clear(l.map(|l| &mut l.district)),
+                "city" => clear(l.map(|l| &mut l.city)),
+                "zipcode" => clear(l.map(|l| &mut l.zipcode)),
+                "latitude" | "latitude_f" => {
+                    if let Some(l) = l {
+                        l.latitude = None;
+                        l.latitude_f = None;
+                    }
+                }
+                "longitude" | "longitude_f" => {
+                    if let Some(l) = l {
+                        l.longitude = None;
+                        l.longitude_f = None;
+                    }
+                }
+                "is_eu" => clear(l.map(|l| &mut l.is_eu)),
+                "country_flag" => clear(l.map(|l| &mut l.country_flag)),
+                "geoname_id" => clear(l.map(|l| &mut l.geoname_id)),
+                "country_geoname_id" => clear(l.map(|l| &mut l.country_geoname_id)),
+                "state_geoname_id" => clear(l.map(|l| &mut l.state_geoname_id)),
+                "country_emoji" => clear(l.map(|l| &mut l.country_emoji)),
+                "registered_country_code" => clear(l.map(|l| &mut l.registered_country_code)),
+                "represented_country_code" => clear(l.map(|l| &mut l.represented_country_code)),
+                "metro_code" => clear(l.map(|l| &mut l.metro_code)),
+                "coordinate_source" => clear(l.map(|l| &mut l.coordinate_source)),
+                "nearest_airport" => clear(l.map(|l| &mut l.nearest_airport)),
+                _ => return false,
+            }
+        }
+        "country_metadata" => {
+            let m = response.country_metadata.as_mut();
+            match field {
+                "calling_code" => clear(m.map(|m| &mut m.calling_code)),
+                "tld" => clear(m.map(|m| &mut m.tld)),
+                "languages" => clear(m.map(|m| &mut m.languages)),
+                _ => return false,
+            }
+        }
+        "currency" => {
+            let c = response.currency.as_mut();
+            match field {
+                "code" => clear(c.map(|c| &mut c.code)),
+                "name" => clear(c.map(|c| &mut c.name)),
+                "symbol" => clear(c.map(|c| &mut c.symbol)),
+                _ => return false,
+            }
+        }
+        "region" => {
+            let r = response.region.as_mut();
+            match field {
+                "code" => clear(r.map(|r| &mut r.code)),
+                "name" => clear(r.map(|r| &mut r.name)),
+                "subregion_code" => clear(r.map(|r| &mut r.subregion_code)),
+                "subregion" => clear(r.map(|r| &mut r.subregion)),
+                _ => return false,
+            }
+        }
+        "time_zone" => {
+            let t = response.time_zone.as_mut();
+            match field {
+                "name" => clear(t.map(|t| &mut t.name)),
+                "offset" => clear(t.map(|t| &mut t.offset)),
+                "offset_with_dst" => clear(t.map(|t| &mut t.offset_with_dst)),
+                "abbreviation" => clear(t.map(|t| &mut t.abbreviation)),
+                "current_time" => clear(t.map(|t| &mut t.current_time)),
+                "current_time_unix" => clear(t.map(|t| &mut t.current_time_unix)),
+                "current_time_unix_ms" => clear(t.map(|t| &mut t.current_time_unix_ms)),
+                "is_dst" => clear(t.map(|t| &mut t.is_dst)),
+                "dst_savings" => clear(t.map(|t| &mut t.dst_savings)),
+                "dst_exists" => clear(t.map(|t| &mut t.dst_exists)),
+                "source" => clear(t.map(|t| &mut t.source)),
+                _ => return false,
+            }
+        }
+        "network" => {
+            let n = response.network.as_mut();
+            match field {
+                "connection_type" => clear(n.map(|n| &mut n.connection_type)),
+                "network" => clear(n.map(|n| &mut n.network)),
+                "ip_type" => clear(n.map(|n| &mut n.ip_type)),
+                _ => return false,
+            }
+        }
+        _ => return false,
+    }
+    true
+}
+
+/// Round a coordinate to `dp` decimal places (1 is roughly 11 km)
+fn round_coordinate(value: f64, dp: u32) -> f64 {
+    let factor = 10f64.powi(dp as i32);
+    (value * factor).round() / factor
+}
+
+/// Default for `COORD_MAX_PRECISION`: 6 decimal places is about 11 cm
+pub const DEFAULT_COORD_MAX_PRECISION: u32 = 6;
+
+/// How lookup responses are shaped, shared by the REST handlers and MCP tools
+#[derive(Debug, Clone)]
+pub struct ResponseOptions {
+    /// Full-response paths dropped from responses (`OMIT_FIELDS`)
+    pub omit_fields: OmitFields,
+    /// Decimal places returned coordinates are rounded to (`COORD_ROUND_DP`)
+    pub coord_round_dp: Option<u32>,
+    /// Decimal places kept from `lat`/`long` query values (`COORD_MAX_PRECISION`)
+    pub coord_max_precision: u32,
+    /// Flag file served for codes without a flag (`DEFAULT_FLAG`)
+    pub default_flag: String,
+    /// Prefix every route is served under behind a reverse proxy, e.g.
+    /// `/geoip`, or "" at the root (`PATH_PREFIX`)
+    pub path_prefix: String,
+    /// Per-country `languages` consulted before the built-in map (`LANGUAGE_OVERRIDES`)
+    pub language_overrides: HashMap<String, String>,
+    /// Attribution returned with MCP geoip tool results (`DATA_ATTRIBUTION`)
+    pub data_attribution: Option<String>,
+}
+
+impl Default for ResponseOptions {
+    fn default() -> Self {
+        Self {
+            omit_fields: OmitFields::default(),
+            coord_round_dp: None,
+            coord_max_precision: DEFAULT_COORD_MAX_PRECISION,
+            default_flag: DEFAULT_FLAG.to_string(),
+            path_prefix: String::new(),
+            language_overrides: HashMap::new(),
+            data_attribution: Some(crate::DEFAULT_DATA_ATTRIBUTION.to_string()),
+        }
+    }
+}
+
+impl ResponseOptions {
+    /// A validated query coordinate, rounded to `coord_max_precision` places
+    ///
+    /// Digits past that are noise for a timezone lookup, so clients can't make
+    /// the service carry arbitrarily precise floats around.
+    pub(crate) fn limit_coordinate_precision(&self, value: f64) -> f64 {
+        round_coordinate(value, self.coord_max_precision)
+    }
+
+    /// A returned coordinate, coarsened when `coord_round_dp` is set
+    fn coarsen_coordinate(&self, value: Option<f64>) -> Option<f64> {
+        match self.coord_round_dp {
+            Some(dp) => value.map(|v| round_coordinate(v, dp)),
+            None => value,
+        }
+    }
+
+    /// Flag path for a country code, under `path_prefix`
+    pub(crate) fn flag_path(&self, country_code: &str) -> String {
+        format!(
+            "{}{}",
+            self.path_prefix,
+            get_flag_path(country_code, &self.default_flag)
+        )
+    }
+}
+
 /// Build full response from GeoData
-fn build_full_response(ip: &str, geo_data: &GeoData) -> IpGeoResponseFull {
+///
+/// Shared with the MCP tools so both interfaces return identical data.
+/// Fields listed in `options.omit_fields` are dropped.
+pub(crate) fn build_full_response(
+    ip: &str,
+    geo_data: &GeoData,
+    timezones: &dyn TimezoneProvider,
+    options: &ResponseOptions,
+) -> IpGeoResponseFull {
     let country_code = geo_data.country_code.as_deref();
     let country_meta = get_country_metadata(country_code);
 
-    // Get timezone from coordinates if available
-    let timezone_name = match (geo_data.latitude, geo_data.longitude) {
-        (Some(lat), Some(lng)) => lookup_timezone(lat, lng),
-        _ => None,
+    let timezone = resolve_timezone(geo_data, timezones);
+
+    // Country-only records get the country centre so map clients have a pin
+    let (latitude, longitude, coordinate_source) = match (geo_data.latitude, geo_data.longitude) {
+        (Some(lat), Some(lng)) => (Some(lat), Some(lng), None),
+        _ => match country_code.and_then(get_country_centroid) {
+            Some((lat, lng)) => (Some(lat), Some(lng), Some(COUNTRY_CENTROID_SOURCE)),
+            None => (None, None, None),
+        },
     };
+    let latitude = options.coarsen_coordinate(latitude);
+    let longitude = options.coarsen_coordinate(longitude);
 
     // Get timezone details
-    let tz_details = timezone_name
+    let tz_details = timezone
         .as_ref()
-        .and_then(|tz| get_timezone_details(tz));
+        .and_then(|(tz, source)| Some((get_timezone_details(tz)?, *source)));
 
-    IpGeoResponseFull {
+    let mut response = IpGeoResponseFull {
         ip: Some(ip.to_string()),
         location: Some(LocationInfo {
             continent_code: country_meta.map(|m| m.continent_code.to_string()),
@@ -226,12 +1048,27 @@ fn build_full_response(ip: &str, geo_data: &GeoData) -> IpGeoResponseFull {
             district: None,
             city: geo_data.city.clone(),
             zipcode: geo_data.postal_code.clone(),
-            latitude: geo_data.latitude.map(|l| format!("{:.5}", l)),
-            longitude: geo_data.longitude.map(|l| format!("{:.5}", l)),
+            latitude: latitude.map(|l| format!("{:.5}", l)),
+            longitude: longitude.map(|l| format!("{:.5}", l)),
+            latitude_f: latitude,
+            longitude_f: longitude,
             is_eu: country_meta.map(|m| m.is_eu),
-            country_flag: country_code.map(get_flag_path),
+            country_flag: country_code.map(|code| options.flag_path(code)),
             geoname_id: geo_data.geoname_id.map(|id| id.to_string()),
+            country_geoname_id: geo_data.country_geoname_id.map(|id| id.to_string()),
+            state_geoname_id: geo_data.state_geoname_id.map(|id| id.to_string()),
             country_emoji: country_meta.map(|m| m.flag_emoji.to_string()),
+            registered_country_code: other_country(
+                &geo_data.registered_country_code,
+                &geo_data.country_code,
+            ),
+            represented_country_code: other_country(
+                &geo_data.represented_country_code,
+                &geo_data.country_code,
+            ),
+            metro_code: geo_data.metro_code,
+            coordinate_source: coordinate_source.map(str::to_string),
+            nearest_airport: None,
         }),
         country_metadata: Some(CountryMetadataInfo {
             calling_code: country_meta.map(|m| m.calling_code.to_string()),
@@ -239,38 +1076,52 @@ fn build_full_response(ip: &str, geo_data: &GeoData) -> IpGeoResponseFull {
             languages: country_meta
                 .map(|m| m.languages.split(',').map(|s| s.to_string()).collect()),
         }),
-        currency: country_meta.map(|m| CurrencyInfo {
-            code: Some(m.currency_code.to_string()),
-            name: Some(m.currency_name.to_string()),
-            symbol: Some(m.currency_symbol.to_string()),
+        currency: country_code
+            .and_then(currency_for_country)
+            .map(|c| CurrencyInfo {
+                code: Some(c.code.to_string()),
+                name: Some(c.name.to_string()),
+                symbol: Some(c.symbol.to_string()),
+            }),
+        region: country_meta.map(|m| RegionInfo {
+            code: Some(m.region_code.to_string()),
+            name: get_m49_region_name(m.region_code).map(str::to_string),
+            subregion_code: get_m49_subregion_code(m.subregion).map(str::to_string),
+            subregion: Some(m.subregion.to_string()),
         }),
-        time_zone: tz_details.map(|tz| TimeZoneInfoFull {
-            name: Some(tz.name),
-            offset: Some(tz.offset_hours),
-            offset_with_dst: Some(tz.offset_with_dst_hours),
-            current_time: Some(tz.current_time),
-            current_time_unix: Some(tz.current_time_unix),
-            is_dst: Some(tz.is_dst),
-            dst_savings: Some(tz.dst_savings_hours),
-            dst_exists: Some(tz.dst_exists),
+        time_zone: tz_details.map(|(tz, source)| time_zone_info_full(tz, Some(source.to_string()))),
+        network: (geo_data.connection_type.is_some() || geo_data.network.is_some()).then(|| {
+            NetworkInfo {
+                connection_type: geo_data.connection_type.clone(),
+                network: geo_data.network.clone(),
+                ..Default::default()
+            }
         }),
-    }
+    };
+    options.omit_fields.apply(&mut response);
+    response
 }
 
 /// Build simple response from GeoData
-fn build_simple_response(geo_data: &GeoData) -> IpGeoResponse {
-    let timezone_name = match (geo_data.latitude, geo_data.longitude) {
-        (Some(lat), Some(lng)) => lookup_timezone(lat, lng).unwrap_or_default(),
-        _ => String::new(),
-    };
-
-    let languages = get_languages(geo_data.country_code.as_deref());
+pub(crate) fn build_simple_response(
+    geo_data: &GeoData,
+    timezones: &dyn TimezoneProvider,
+    options: &ResponseOptions,
+) -> IpGeoResponse {
+    let timezone_name = resolve_timezone(geo_data, timezones)
+        .map(|(tz, _)| tz)
+        .unwrap_or_default();
+
+    let languages = get_languages_with(
+        &options.language_overrides,
+        geo_data.country_code.as_deref(),
+    );
 
     IpGeoResponse {
-        latitude: geo_data.latitude,
-        longitude: geo_data.longitude,
-        city: geo_data.city.clone().unwrap_or_default(),
-        country_name: geo_data.country_name.clone().unwrap_or_default(),
+        latitude: options.coarsen_coordinate(geo_data.latitude),
+        longitude: options.coarsen_coordinate(geo_data.longitude),
+        city: geo_data.city.clone(),
+        country_name: geo_data.country_name.clone(),
         time_zone: TimeZoneInfo {
             name: timezone_name,
         },
@@ -278,78 +1129,589 @@ fn build_simple_response(geo_data: &GeoData) -> IpGeoResponse {
     }
 }
 
-/// Get geolocation for an IP address (simple format)
+/// Look up an IP in the GeoIP database
 ///
-/// Returns basic geographic location data for a given IP address.
-/// Use the `fields` parameter with "*" or "location" for full format response.
-/// Supports content negotiation: use Accept: application/x-protobuf for protobuf response.
-#[utoipa::path(
-    get,
-    path = "/ipgeo",
-    params(IpGeoQuery),
-    responses(
-        (status = 200, description = "Successful geolocation lookup", body = IpGeoResponse),
-        (status = 400, description = "Invalid IP address", body = ApiErrorResponse)
-    ),
-    tag = "IP Geolocation"
-)]
-pub async fn ipgeo_handler(
-    State(state): State<AppState>,
-    headers: HeaderMap,
-    Query(params): Query<IpGeoQuery>,
-) -> Response<Body> {
-    let ip = params.ip.trim();
-    let use_protobuf = accepts_protobuf(get_accept_header(&headers));
+/// Addresses missing from the database are `Ok(None)` (an empty response);
+/// any other failure (corruption, I/O) is a `DATABASE_ERROR` so it is not
+/// mistaken for a successful lookup with no data. Non-public addresses (see
+/// `classify_ip`) are `Ok(None)` without querying the database at all, unless
+/// the override table has them.
+fn lookup_geo(state: &AppState, ip: &str) -> Result<Option<GeoData>, ApiError> {
+    if !state.geoip.is_loaded() {
+        return Err(ApiError {
+            error: "Geolocation database not loaded yet".to_string(),
+            code: "DATABASE_NOT_LOADED",
+            field: None,
+        });
+    }
+    if is_unlocatable(state, ip) {
+        return Ok(None);
+    }
 
-    // Validate IP address
-    if let Err(e) = validate_ip(ip) {
-        return build_error_response(&e, use_protobuf);
+    match state.geoip.lookup(ip) {
+        Ok(geo_data) => Ok(Some(geo_data)),
+        Err(GeoIpError::NotFound) => Ok(None),
+        Err(e) => {
+            tracing::error!("GeoIP lookup failed for {}: {}", ip, e);
+            Err(ApiError {
+                error: "Geolocation database error".to_string(),
+                code: "DATABASE_ERROR",
+                field: None,
+            })
+        }
     }
+}
 
-    // Check cache first (only for simple format and JSON)
-    if params.fields.is_none() && !use_protobuf {
-        if let Some(cached) = state.cache.get(ip) {
-            let proto: geolocation::IpGeoResponse = (&cached).into();
-            return build_response(&cached, proto, use_protobuf);
+/// Drop centroid-filled coordinates for `no_centroid=true`
+fn strip_centroid(response: &mut IpGeoResponseFull) {
+    if let Some(location) = response.location.as_mut() {
+        if location.coordinate_source.as_deref() == Some(COUNTRY_CENTROID_SOURCE) {
+            location.latitude = None;
+            location.longitude = None;
+            location.latitude_f = None;
+            location.longitude_f = None;
+            location.coordinate_source = None;
         }
     }
+}
 
-    // Lookup in MaxMind database
-    let geo_result = state.geoip.lookup(ip);
+/// Fill `location.nearest_airport` for `airport=true`
+///
+/// Country centroids are too coarse to pick an airport from, so only
+/// coordinates from the database are used. Coordinates dropped by
+/// `OMIT_FIELDS` are gone by now, so they never leak through the airport.
+fn add_nearest_airport(response: &mut IpGeoResponseFull, omit_fields: &OmitFields) {
+    if omit_fields.contains("location", "nearest_airport") {
+        return;
+    }
+    let Some(location) = response.location.as_mut() else {
+        return;
+    };
+    if location.coordinate_source.is_some() {
+        return;
+    }
+    if let (Some(lat), Some(lng)) = (location.latitude_f, location.longitude_f) {
+        location.nearest_airport =
+            nearest_airport(lat, lng).map(|(iata, distance)| NearestAirportInfo {
+                iata: iata.to_string(),
+                distance_km: (distance * 10.0).round() / 10.0,
+            });
+    }
+}
 
-    // Determine response format based on fields parameter
-    let use_full_format = params
-        .fields
-        .as_ref()
-        .map(|f| f.contains('*') || f.contains("location"))
-        .unwrap_or(false);
+/// Whether `ip` is a non-public address the override table does not cover
+fn is_unlocatable(state: &AppState, ip: &str) -> bool {
+    non_public_network(ip).is_some() && !state.geoip.is_overridden(ip)
+}
 
-    if use_full_format {
-        // Full response format
-        let response = match geo_result {
-            Ok(geo_data) => build_full_response(ip, &geo_data),
-            Err(_) => IpGeoResponseFull {
-                ip: Some(ip.to_string()),
-                ..Default::default()
-            },
-        };
+/// `network.ip_type` note for addresses that are never looked up
+fn non_public_network(ip: &str) -> Option<NetworkInfo> {
+    let ip_type = classify_ip(&ip.parse().ok()?);
+    (!ip_type.is_public()).then(|| NetworkInfo {
+        ip_type: Some(ip_type.as_str().to_string()),
+        ..Default::default()
+    })
+}
 
-        let proto: geolocation::IpGeoResponseFull = (&response).into();
-        build_response(&response, proto, use_protobuf)
-    } else {
-        // Simple response format (backward compatible)
-        let response = match geo_result {
-            Ok(geo_data) => build_simple_response(&geo_data),
-            Err(_) => IpGeoResponse::default(),
-        };
+/// Look up the simple response for one IP, going through the cache
+fn lookup_simple_cached(state: &AppState, ip: &str) -> Result<IpGeoResponse, ApiError> {
+    if let Some(cached) = state.cache.get(ip) {
+        return Ok(cached);
+    }
 
-        // Cache the simple response (JSON only)
-        if !use_protobuf {
-            state.cache.insert(ip.to_string(), response.clone());
+    let response = match lookup_geo(state, ip)? {
+        Some(geo_data) => {
+            build_simple_response(&geo_data, state.timezones.as_ref(), &state.response_options)
         }
+        None => IpGeoResponse::default(),
+    };
+    state.cache.insert(ip.to_string(), response.clone());
+    Ok(response)
+}
+
+/// Response shape for `/ipgeo`, selected by `?fields=`
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum FormatMode {
+    /// Absent or empty `fields`: the simple response
+    Simple,
+    /// `*`, or a list naming `location`: the full response
+    Full,
+    /// `both`: the simple and full responses side by side
+    Both,
+    /// Any other list: only the named fields of the simple response
+    Sparse(Vec<String>),
+}
+
+/// Fields of the simple response that can be selected with `?fields=`
+const SPARSE_FIELDS: &[&str] = &[
+    "latitude",
+    "longitude",
+    "city",
+    "country_name",
+    "time_zone",
+    "languages",
+];
+
+/// Resolve `?fields=` into a response shape
+///
+/// `*` anywhere in the list wins, then `location` anywhere in the list (both
+/// select the full response, as they always have); `both` on its own selects
+/// the combined response, any other non-empty list is a sparse fieldset, and
+/// an absent or empty value is the simple response.
+fn resolve_format(fields: Option<&str>) -> FormatMode {
+    let fields: Vec<&str> = fields
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|field| !field.is_empty())
+        .collect();
+
+    if fields.is_empty() {
+        FormatMode::Simple
+    } else if fields.contains(&"*") || fields.contains(&"location") {
+        FormatMode::Full
+    } else if fields == ["both"] {
+        FormatMode::Both
+    } else {
+        FormatMode::Sparse(fields.into_iter().map(String::from).collect())
+    }
+}
+
+/// Build a sparse fieldset response from the (cached) simple response
+///
+/// Sparse responses are always JSON; unknown field names are a 400
+/// `INVALID_FIELDS` rather than being silently dropped.
+fn build_sparse_response(
+    state: &AppState,
+    ip: &str,
+    fields: &[String],
+    nulls: bool,
+    format: ResponseFormat,
+) -> Response<Body> {
+    let format = if format.is_protobuf() {
+        ResponseFormat::Json
+    } else {
+        format
+    };
+
+    if let Some(unknown) = fields
+        .iter()
+        .find(|field| !SPARSE_FIELDS.contains(&field.as_str()))
+    {
+        let error = ApiError {
+            error: format!(
+                "Unknown field: {} (expected *, location, both, or any of {})",
+                unknown,
+                SPARSE_FIELDS.join(", ")
+            ),
+            code: "INVALID_FIELDS",
+            field: Some("fields"),
+        };
+        return build_error_response(&error, format);
+    }
+
+    let response = match lookup_simple_cached(state, ip) {
+        Ok(response) => response.to_json(nulls),
+        Err(e) => return build_error_response(&e, format),
+    };
+    let sparse: serde_json::Map<String, serde_json::Value> = fields
+        .iter()
+        .filter_map(|field| Some((field.clone(), response.get(field)?.clone())))
+        .collect();
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
+        .header(header::CACHE_CONTROL, CACHE_CONTROL)
+        .body(Body::from(format.json_body(&sparse)))
+        .unwrap()
+}
+
+/// Resolve a comma-separated list of IPs into a JSON array of simple responses
+///
+/// Entries keep the request order; invalid IPs (and non-public ones with
+/// `reject_private`) yield an `{ip, error, code}` object in their slot instead
+/// of failing the whole request.
+fn build_multi_ip_response(
+    state: &AppState,
+    ips: &str,
+    reject_private: bool,
+    nulls: bool,
+    format: ResponseFormat,
+) -> Response<Body> {
+    // Bulk responses are always JSON
+    let format = if format.is_protobuf() {
+        ResponseFormat::Json
+    } else {
+        format
+    };
+
+    let ips: Vec<&str> = ips
+        .split(',')
+        .map(str::trim)
+        .filter(|ip| !ip.is_empty())
+        .collect();
+
+    let max_ips = state.bulk_max_ips;
+    if ips.len() > max_ips {
+        let error = ApiError {
+            error: format!(
+                "Too many IPs: {} provided, maximum is {}",
+                ips.len(),
+                max_ips
+            ),
+            code: "TOO_MANY_IPS",
+            field: Some("ip"),
+        };
+        return build_error_response(&error, format);
+    }
+
+    let results: Vec<serde_json::Value> = ips
+        .iter()
+        .map(|ip| {
+            let result = if reject_private {
+                validate_public_ip(state, ip)
+            } else {
+                validate_ip(ip)
+            };
+            match result.and_then(|()| lookup_simple_cached(state, ip)) {
+                Ok(response) => response.to_json(nulls),
+                Err(e) => serde_json::json!({ "ip": ip, "error": e.error, "code": e.code }),
+            }
+        })
+        .collect();
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
+        .header(header::CACHE_CONTROL, CACHE_CONTROL)
+        .body(Body::from(format.json_body(&results)))
+        .unwrap()
+}
+
+/// Get geolocation for an IP address (simple format)
+///
+/// Returns basic geographic location data for a given IP address.
+/// Use the `fields` parameter with "*" or "location" for full format response.
+/// Pass several comma-separated IPs (up to `BULK_MAX_IPS`, default 100) to get a JSON array of simple
+/// responses in the same order, with error objects for invalid entries.
+/// `ip=self` looks up the caller's own address, like `/`.
+/// Supports content negotiation: use Accept: application/x-protobuf for protobuf response.
+#[utoipa::path(
+    get,
+    path = "/ipgeo",
+    params(IpGeoQuery),
+    responses(
+        (status = 200, description = "Successful geolocation lookup", body = IpGeoResponse),
+        (status = 204, description = "No geolocation data for the IP, with empty_as_204"),
+        (status = 400, description = "Invalid IP address", body = ApiErrorResponse),
+        (status = 422, description = "Non-public IP with reject_private", body = ApiErrorResponse),
+        (status = 503, description = "GeoIP database error", body = ApiErrorResponse)
+    ),
+    tag = "IP Geolocation"
+)]
+pub async fn ipgeo_handler(
+    State(state): State<AppState>,
+    connect_info: Option<Extension<ConnectInfo<SocketAddr>>>,
+    headers: HeaderMap,
+    Query(params): Query<IpGeoQuery>,
+) -> Response<Body> {
+    let client_ip = extract_client_ip(
+        &headers,
+        connect_info.map(|Extension(ConnectInfo(addr))| addr),
+    );
+    ipgeo_response(&state, &headers, &client_ip, &params)
+}
+
+/// Serve `/ipgeo` independently of the transport (shared by HTTP/1.1+2 and HTTP/3)
+///
+/// `client_ip` is the caller's address as seen through any proxy headers,
+/// looked up for `ip=self`.
+pub fn ipgeo_response(
+    state: &AppState,
+    headers: &HeaderMap,
+    client_ip: &str,
+    params: &IpGeoQuery,
+) -> Response<Body> {
+    if !params.ip.trim().eq_ignore_ascii_case(SELF_IP) {
+        return ipgeo_lookup_response(state, headers, params.ip.trim(), params);
+    }
+
+    // The same URL answers differently per caller, so shared caches must not
+    // store it
+    let mut response = ipgeo_lookup_response(state, headers, client_ip, params);
+    response.headers_mut().insert(
+        header::CACHE_CONTROL,
+        HeaderValue::from_static(CACHE_CONTROL_PRIVATE),
+    );
+    response
+}
+
+/// `/ipgeo` for `ip`, with `ip=self` already resolved to the caller
+fn ipgeo_lookup_response(
+    state: &AppState,
+    headers: &HeaderMap,
+    ip: &str,
+    params: &IpGeoQuery,
+) -> Response<Body> {
+    let format = ResponseFormat::negotiate(headers, params.pretty);
+
+    // Reject oversized values before splitting or validating them
+    if params.ip.len() > state.max_query_len {
+        let error = ApiError {
+            error: format!(
+                "Query value too long: {} bytes, maximum is {}",
+                params.ip.len(),
+                state.max_query_len
+            ),
+            code: "QUERY_TOO_LONG",
+            field: Some("ip"),
+        };
+        return build_error_response(&error, format);
+    }
+
+    let reject_private = params.reject_private.unwrap_or(state.reject_private);
+    let empty_as_204 = params.empty_as_204.unwrap_or(state.empty_as_204);
+    let nulls = params.nulls.unwrap_or(false);
+
+    // Multiple comma-separated IPs (JSON only, simple format)
+    if ip.contains(',') {
+        return build_multi_ip_response(state, ip, reject_private, nulls, format);
+    }
+
+    // Validate IP address
+    if let Err(e) = validate_ip(ip) {
+        return build_error_response(&e, format);
+    }
+    if reject_private {
+        if let Err(e) = validate_public_ip(state, ip) {
+            return build_error_response(&e, format);
+        }
+    }
+
+    let mode = resolve_format(params.fields.as_deref());
+    if let FormatMode::Sparse(fields) = &mode {
+        return build_sparse_response(state, ip, fields, nulls, format);
+    }
+
+    // Check cache first (only for simple format and JSON)
+    if mode == FormatMode::Simple && !format.is_protobuf() {
+        if let Some(cached) = state.cache.get(ip) {
+            if empty_as_204 && cached.is_empty() {
+                return with_cache_status(no_content_response(), true);
+            }
+            let proto: geolocation::IpGeoResponse = (&cached).into();
+            return with_cache_status(build_response(&cached.to_json(nulls), proto, format), true);
+        }
+    }
+
+    // Lookup in MaxMind database
+    let geo_result = match lookup_geo(state, ip) {
+        Ok(geo_result) => geo_result,
+        Err(e) => return build_error_response(&e, format),
+    };
+    if empty_as_204 && geo_result.is_none() {
+        if mode == FormatMode::Simple && !format.is_protobuf() {
+            state.cache.insert(ip.to_string(), IpGeoResponse::default());
+            return with_cache_status(no_content_response(), false);
+        }
+        return no_content_response();
+    }
+
+    if mode == FormatMode::Full {
+        // Full response format
+        let (response, cache_control) =
+            ipgeo_full_from_lookup(state, ip, geo_result.as_ref(), params);
+        let proto: geolocation::IpGeoResponseFull = (&response).into();
+        build_response_with_cache_control(&response, proto, format, cache_control)
+    } else if mode == FormatMode::Both {
+        // Both formats from one lookup, always JSON (protobuf has no combined message)
+        let simple = geo_result
+            .as_ref()
+            .map(|g| build_simple_response(g, state.timezones.as_ref(), &state.response_options))
+            .unwrap_or_default();
+        let (full, cache_control) = ipgeo_full_from_lookup(state, ip, geo_result.as_ref(), params);
+        let body = serde_json::json!({ "simple": simple.to_json(nulls), "full": full });
+        state.cache.insert(ip.to_string(), simple);
+
+        let format = if format.is_protobuf() {
+            ResponseFormat::Json
+        } else {
+            format
+        };
+        Response::builder()
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
+            .header(header::CACHE_CONTROL, cache_control)
+            .body(Body::from(format.json_body(&body)))
+            .unwrap()
+    } else {
+        // Simple response format (backward compatible)
+        let response = match geo_result {
+            Some(geo_data) => {
+                build_simple_response(&geo_data, state.timezones.as_ref(), &state.response_options)
+            }
+            None => IpGeoResponse::default(),
+        };
 
         let proto: geolocation::IpGeoResponse = (&response).into();
-        build_response(&response, proto, use_protobuf)
+        let built = build_response(&response.to_json(nulls), proto, format);
+
+        // Cache the simple response (JSON only)
+        if format.is_protobuf() {
+            built
+        } else {
+            state.cache.insert(ip.to_string(), response);
+            with_cache_status(built, false)
+        }
+    }
+}
+
+/// Full response for an `/ipgeo` lookup with its options applied, and its Cache-Control value
+fn ipgeo_full_from_lookup(
+    state: &AppState,
+    ip: &str,
+    geo_data: Option<&GeoData>,
+    params: &IpGeoQuery,
+) -> (IpGeoResponseFull, &'static str) {
+    let mut response = match geo_data {
+        Some(geo_data) => build_full_response(
+            ip,
+            geo_data,
+            state.timezones.as_ref(),
+            &state.response_options,
+        ),
+        None => IpGeoResponseFull {
+            ip: Some(ip.to_string()),
+            ..Default::default()
+        },
+    };
+    if params.no_centroid.unwrap_or(false) {
+        strip_centroid(&mut response);
+    }
+    if params.airport.unwrap_or(false) {
+        add_nearest_airport(&mut response, &state.response_options.omit_fields);
+    }
+    if let Some(time_format) = params.time_format {
+        apply_time_format(&mut response, time_format);
+    }
+    if let Some(name_source) = params.name_source {
+        apply_name_source(&mut response, name_source);
+    }
+    let cache_control = apply_live_time(&mut response, params);
+    (response, cache_control)
+}
+
+/// Drop the current time from `response` unless `live_time` asks for it,
+/// returning the matching Cache-Control value
+///
+/// The embedded current time goes stale immediately, so only responses
+/// without it may be cached long-term.
+fn apply_live_time(response: &mut IpGeoResponseFull, params: &IpGeoQuery) -> &'static str {
+    if params.live_time.unwrap_or(true) {
+        return CACHE_CONTROL_LIVE;
+    }
+    if let Some(tz) = response.time_zone.as_mut() {
+        tz.current_time = None;
+        tz.current_time_unix = None;
+        tz.current_time_unix_ms = None;
+    }
+    CACHE_CONTROL
+}
+
+/// Full-format `time_zone` block for the current instant
+fn time_zone_info_full(tz: TimezoneDetails, source: Option<String>) -> TimeZoneInfoFull {
+    TimeZoneInfoFull {
+        name: Some(tz.name),
+        offset: Some(tz.offset_hours),
+        offset_with_dst: Some(tz.offset_with_dst_hours),
+        abbreviation: tz.abbreviation,
+        current_time: Some(tz.current_time),
+        current_time_unix: Some(tz.current_time_unix),
+        current_time_unix_ms: Some(tz.current_time_unix_ms),
+        is_dst: Some(tz.is_dst),
+        dst_savings: Some(tz.dst_savings_hours),
+        dst_exists: Some(tz.dst_exists),
+        source,
+    }
+}
+
+/// Recompute the time-dependent `time_zone` fields of a cached full response
+fn refresh_time_zone(response: &mut IpGeoResponseFull, omit_fields: &OmitFields) {
+    let Some(tz) = response.time_zone.as_mut() else {
+        return;
+    };
+    if let Some(details) = tz.name.as_deref().and_then(get_timezone_details) {
+        *tz = time_zone_info_full(details, tz.source.take());
+        omit_fields.apply(response);
+    }
+}
+
+/// Cache key for a `/v1/ipgeo` lookup
+///
+/// Only the IP and the parameters that change the cached response are part of
+/// the key. Cache-busting (`_=1712345678`) and other unknown parameters,
+/// formatting (`pretty`, `format`) and options applied after the cache
+/// (`live_time`, `time_format`, `name_source`, `explain`) all share one entry.
+pub fn canonical_cache_key(ip: &str, params: &IpGeoQuery) -> String {
+    full_cache_key(
+        ip,
+        params.airport.unwrap_or(false),
+        params.no_centroid.unwrap_or(false),
+    )
+}
+
+fn full_cache_key(ip: &str, airport: bool, no_centroid: bool) -> String {
+    let mut key = ip.to_string();
+    let mut separator = '?';
+    for (name, set) in [("airport", airport), ("no_centroid", no_centroid)] {
+        if set {
+            key.push(separator);
+            key.push_str(name);
+            separator = '&';
+        }
+    }
+    key
+}
+
+/// Remove every `/v1/ipgeo` variant cached for an IP, returning whether any existed
+fn invalidate_full_cache(state: &AppState, ip: &str) -> bool {
+    let mut invalidated = false;
+    for airport in [false, true] {
+        for no_centroid in [false, true] {
+            invalidated |= state
+                .full_cache
+                .invalidate(&full_cache_key(ip, airport, no_centroid));
+        }
+    }
+    invalidated
+}
+
+/// Take `location.country_name` from the requested source
+///
+/// Countries missing from the embedded table keep the database name, and a
+/// name dropped by `OMIT_FIELDS` stays dropped.
+fn apply_name_source(response: &mut IpGeoResponseFull, name_source: NameSource) {
+    if name_source != NameSource::Table {
+        return;
+    }
+    let Some(location) = response.location.as_mut() else {
+        return;
+    };
+    let table_name = get_country_metadata(location.country_code2.as_deref()).map(|m| m.name);
+    if let (Some(country_name), Some(table_name)) = (location.country_name.as_mut(), table_name) {
+        *country_name = table_name.to_string();
+    }
+}
+
+/// Render `time_zone.current_time` in the requested format
+fn apply_time_format(response: &mut IpGeoResponseFull, time_format: TimeFormat) {
+    if let Some(current_time) = response
+        .time_zone
+        .as_mut()
+        .and_then(|tz| tz.current_time.as_mut())
+    {
+        *current_time = time_format.convert(current_time);
     }
 }
 
@@ -364,7 +1726,10 @@ pub async fn ipgeo_handler(
     params(IpGeoQuery),
     responses(
         (status = 200, description = "Successful geolocation lookup", body = IpGeoResponseFull),
-        (status = 400, description = "Invalid IP address", body = ApiErrorResponse)
+        (status = 204, description = "No geolocation data for the IP, with empty_as_204"),
+        (status = 400, description = "Invalid IP address", body = ApiErrorResponse),
+        (status = 422, description = "Non-public IP with reject_private", body = ApiErrorResponse),
+        (status = 503, description = "GeoIP database error", body = ApiErrorResponse)
     ),
     tag = "IP Geolocation"
 )]
@@ -372,28 +1737,114 @@ pub async fn ipgeo_full_handler(
     State(state): State<AppState>,
     headers: HeaderMap,
     Query(params): Query<IpGeoQuery>,
+) -> Response<Body> {
+    ipgeo_full_response(&state, &headers, &params)
+}
+
+/// Serve `/v1/ipgeo` independently of the transport
+pub fn ipgeo_full_response(
+    state: &AppState,
+    headers: &HeaderMap,
+    params: &IpGeoQuery,
 ) -> Response<Body> {
     let ip = params.ip.trim();
-    let use_protobuf = accepts_protobuf(get_accept_header(&headers));
+    let format = ResponseFormat::negotiate(headers, params.pretty);
 
     // Validate IP address
     if let Err(e) = validate_ip(ip) {
-        return build_error_response(&e, use_protobuf);
+        return build_error_response(&e, format);
+    }
+    if params.reject_private.unwrap_or(state.reject_private) {
+        if let Err(e) = validate_public_ip(state, ip) {
+            return build_error_response(&e, format);
+        }
     }
 
-    // Lookup in MaxMind database
-    let geo_result = state.geoip.lookup(ip);
+    // Explain needs the raw lookup, so it always goes to the database
+    let explain_requested = state.enable_explain && params.explain.unwrap_or(false);
+    let cache_key = canonical_cache_key(ip, params);
+    let cached = if explain_requested {
+        None
+    } else {
+        state.full_cache.get(&cache_key)
+    };
+    let cache_hit = cached.is_some();
 
-    let response = match geo_result {
-        Ok(geo_data) => build_full_response(ip, &geo_data),
-        Err(_) => IpGeoResponseFull {
-            ip: Some(ip.to_string()),
-            ..Default::default()
-        },
+    let (mut response, explain) = if let Some(mut response) = cached {
+        refresh_time_zone(&mut response, &state.response_options.omit_fields);
+        (response, None)
+    } else {
+        // Lookup in MaxMind database
+        let geo_result = match lookup_geo(state, ip) {
+            Ok(geo_result) => geo_result,
+            Err(e) => return build_error_response(&e, format),
+        };
+        if geo_result.is_none() && params.empty_as_204.unwrap_or(state.empty_as_204) {
+            return with_cache_status(no_content_response(), false);
+        }
+
+        let explain = explain_requested.then(|| explain_lookup(state, geo_result.as_ref()));
+
+        // Only found lookups are cached, so a hit never needs `empty_as_204`
+        let found = geo_result.is_some();
+        let mut response = match geo_result {
+            Some(geo_data) => build_full_response(
+                ip,
+                &geo_data,
+                state.timezones.as_ref(),
+                &state.response_options,
+            ),
+            None => IpGeoResponseFull {
+                ip: Some(ip.to_string()),
+                network: non_public_network(ip),
+                ..Default::default()
+            },
+        };
+        if params.no_centroid.unwrap_or(false) {
+            strip_centroid(&mut response);
+        }
+        if params.airport.unwrap_or(false) {
+            add_nearest_airport(&mut response, &state.response_options.omit_fields);
+        }
+        if found {
+            state.full_cache.insert(cache_key, response.clone());
+        }
+        (response, explain)
     };
+    if let Some(time_format) = params.time_format {
+        apply_time_format(&mut response, time_format);
+    }
+    if let Some(name_source) = params.name_source {
+        apply_name_source(&mut response, name_source);
+    }
+
+    let cache_control = apply_live_time(&mut response, params);
 
     let proto: geolocation::IpGeoResponseFull = (&response).into();
-    build_response(&response, proto, use_protobuf)
+    let resp = match explain {
+        // `_debug` is a JSON-only diagnostic; protobuf keeps the plain message
+        Some(explain) if !format.is_protobuf() => {
+            let mut body = serde_json::to_value(&response).unwrap_or_default();
+            body["_debug"] = serde_json::to_value(explain).unwrap_or_default();
+            build_response_with_cache_control(&body, proto, format, cache_control)
+        }
+        _ => build_response_with_cache_control(&response, proto, format, cache_control),
+    };
+    with_cache_status(resp, cache_hit)
+}
+
+/// Describe how a `/v1/ipgeo` lookup was resolved for `explain=true`
+fn explain_lookup(state: &AppState, geo_data: Option<&GeoData>) -> ExplainInfo {
+    ExplainInfo {
+        network: geo_data.and_then(|g| g.network.clone()),
+        database_type: state.geoip.metadata().database_type,
+        // Explain lookups bypass the cache
+        cached: false,
+        // The state always comes from the first (least specific) subdivision
+        subdivision_index: geo_data
+            .filter(|g| g.state_prov.is_some() || g.state_code.is_some())
+            .map(|_| 0),
+    }
 }
 
 /// Get timezone for coordinates (simple format)
@@ -411,185 +1862,1224 @@ pub async fn ipgeo_full_handler(
     tag = "Timezone"
 )]
 pub async fn timezone_handler(
+    State(state): State<AppState>,
     headers: HeaderMap,
     Query(params): Query<TimezoneQuery>,
 ) -> Response<Body> {
-    let use_protobuf = accepts_protobuf(get_accept_header(&headers));
+    timezone_response(&state, &headers, &params)
+}
+
+/// Serve `/timezone` independently of the transport
+pub fn timezone_response(
+    state: &AppState,
+    headers: &HeaderMap,
+    params: &TimezoneQuery,
+) -> Response<Body> {
+    let format = ResponseFormat::negotiate(headers, params.pretty);
 
     // Validate coordinates
     if let Err(e) = validate_latitude(params.lat) {
-        return build_error_response(&e, use_protobuf);
+        return build_error_response(&e, format);
     }
     if let Err(e) = validate_longitude(params.long) {
-        return build_error_response(&e, use_protobuf);
+        return build_error_response(&e, format);
     }
 
-    let timezone = lookup_timezone(params.lat, params.long).unwrap_or_default();
-    let response = TimezoneResponse { timezone };
+    let response = build_timezone_response(
+        state.timezones.as_ref(),
+        &state.response_options,
+        params.lat,
+        params.long,
+    );
 
     let proto: geolocation::TimezoneResponse = (&response).into();
-    build_response(&response, proto, use_protobuf)
+    build_response(&response, proto, format)
+}
+
+/// Get timezone for coordinates (full format)
+///
+/// Returns comprehensive timezone details including current time,
+/// UTC offset, and daylight saving time information.
+/// Supports content negotiation: use Accept: application/x-protobuf for protobuf response.
+#[utoipa::path(
+    get,
+    path = "/v1/timezone",
+    params(TimezoneQuery),
+    responses(
+        (status = 200, description = "Successful timezone lookup", body = TimezoneResponseFull),
+        (status = 400, description = "Invalid coordinates or timestamp", body = ApiErrorResponse)
+    ),
+    tag = "Timezone"
+)]
+pub async fn timezone_full_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<TimezoneQuery>,
+) -> Response<Body> {
+    timezone_full_response(&state, &headers, &params)
+}
+
+/// Serve `/v1/timezone` independently of the transport
+pub fn timezone_full_response(
+    state: &AppState,
+    headers: &HeaderMap,
+    params: &TimezoneQuery,
+) -> Response<Body> {
+    let format = ResponseFormat::negotiate(headers, params.pretty);
+
+    // Validate coordinates
+    if let Err(e) = validate_latitude(params.lat) {
+        return build_error_response(&e, format);
+    }
+    if let Err(e) = validate_longitude(params.long) {
+        return build_error_response(&e, format);
+    }
+    let at = match params.at.map(validate_timestamp).transpose() {
+        Ok(at) => at.unwrap_or_else(Utc::now),
+        Err(e) => return build_error_response(&e, format),
+    };
+
+    let mut response = build_timezone_full_response(
+        state.timezones.as_ref(),
+        &state.response_options,
+        params.lat,
+        params.long,
+        at,
+        params.candidates.unwrap_or(false),
+    );
+    if let Some(time_format) = params.time_format {
+        response.current_time = response
+            .current_time
+            .map(|current_time| time_format.convert(&current_time));
+    }
+
+    let proto: geolocation::TimezoneResponseFull = (&response).into();
+    build_response(&response, proto, format)
+}
+
+/// Build the simple timezone response for validated coordinates
+///
+/// Shared with the MCP timezone tool so both return the `/timezone` payload.
+pub(crate) fn build_timezone_response(
+    timezones: &dyn TimezoneProvider,
+    options: &ResponseOptions,
+    lat: f64,
+    lng: f64,
+) -> TimezoneResponse {
+    let timezone = timezones
+        .lookup(
+            options.limit_coordinate_precision(lat),
+            options.limit_coordinate_precision(lng),
+        )
+        .unwrap_or_default();
+    TimezoneResponse { timezone }
+}
+
+/// Build the full timezone response for validated coordinates
+///
+/// Shared with the MCP timezone tools so both return the `/v1/timezone` payload.
+/// `with_candidates` adds every zone whose polygon contains the point.
+pub(crate) fn build_timezone_full_response(
+    timezones: &dyn TimezoneProvider,
+    options: &ResponseOptions,
+    lat: f64,
+    lng: f64,
+    at: DateTime<Utc>,
+    with_candidates: bool,
+) -> TimezoneResponseFull {
+    let (lat, lng) = (
+        options.limit_coordinate_precision(lat),
+        options.limit_coordinate_precision(lng),
+    );
+    let candidates = with_candidates.then(|| timezones.candidates(lat, lng));
+    match timezones.lookup(lat, lng) {
+        Some(tz_name) => {
+            let source = if is_nautical_timezone(&tz_name) {
+                "nautical"
+            } else {
+                "coordinates"
+            };
+            let details = memoized_timezone_details_at(&tz_name, at);
+            TimezoneResponseFull {
+                timezone: tz_name,
+                offset: details.as_ref().map(|d| d.offset_hours),
+                offset_with_dst: details.as_ref().map(|d| d.offset_with_dst_hours),
+                abbreviation: details.as_ref().and_then(|d| d.abbreviation.clone()),
+                current_time: details.as_ref().map(|d| d.current_time.clone()),
+                current_time_unix: details.as_ref().map(|d| d.current_time_unix),
+                current_time_unix_ms: details.as_ref().map(|d| d.current_time_unix_ms),
+                is_dst: details.as_ref().map(|d| d.is_dst),
+                dst_exists: details.as_ref().map(|d| d.dst_exists),
+                candidates,
+                source: Some(source.to_string()),
+            }
+        }
+        None => TimezoneResponseFull {
+            timezone: String::new(),
+            offset: None,
+            offset_with_dst: None,
+            abbreviation: None,
+            current_time: None,
+            current_time_unix: None,
+            current_time_unix_ms: None,
+            is_dst: None,
+            dst_exists: None,
+            candidates,
+            source: None,
+        },
+    }
+}
+
+/// Get timezones for a batch of coordinates (full format)
+///
+/// Resolves up to `BULK_MAX_IPS` (default 100) points in one request and
+/// returns a JSON array of full timezone responses in the same order.
+/// Points with out-of-range coordinates yield an `{lat, lon, error, code}`
+/// object in their slot instead of failing the whole request.
+#[utoipa::path(
+    post,
+    path = "/v1/timezone/batch",
+    params(FormatQuery),
+    request_body = TimezoneBatchRequest,
+    responses(
+        (status = 200, description = "Timezone per point, in request order", body = [TimezoneResponseFull]),
+        (status = 400, description = "Too many points", body = ApiErrorResponse)
+    ),
+    tag = "Timezone"
+)]
+pub async fn timezone_batch_handler(
+    State(state): State<AppState>,
+    Query(params): Query<FormatQuery>,
+    Json(request): Json<TimezoneBatchRequest>,
+) -> Response<Body> {
+    // Batch responses are always JSON
+    let format = if params.pretty.unwrap_or(false) {
+        ResponseFormat::PrettyJson
+    } else {
+        ResponseFormat::Json
+    };
+
+    let max_points = state.bulk_max_ips;
+    if request.points.len() > max_points {
+        let error = ApiError {
+            error: format!(
+                "Too many points: {} provided, maximum is {}",
+                request.points.len(),
+                max_points
+            ),
+            code: "TOO_MANY_POINTS",
+            field: Some("points"),
+        };
+        return build_error_response(&error, format);
+    }
+
+    // Every point in the batch describes the same instant
+    let now = Utc::now();
+    let results: Vec<serde_json::Value> = request
+        .points
+        .iter()
+        .map(|point| {
+            match validate_latitude(point.lat).and_then(|()| validate_longitude(point.lon)) {
+                Ok(()) => serde_json::to_value(build_timezone_full_response(
+                    state.timezones.as_ref(),
+                    &state.response_options,
+                    point.lat,
+                    point.lon,
+                    now,
+                    false,
+                ))
+                .unwrap(),
+                Err(e) => serde_json::json!({
+                    "lat": point.lat,
+                    "lon": point.lon,
+                    "error": e.error,
+                    "code": e.code,
+                }),
+            }
+        })
+        .collect();
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
+        .header(header::CACHE_CONTROL, "no-store")
+        .body(Body::from(format.json_body(&results)))
+        .unwrap()
+}
+
+/// Compare the current time in several timezones
+///
+/// Returns each zone's current UTC offset and its difference in hours from
+/// the first listed zone, e.g. for scheduling across offices. Responses are
+/// always JSON and never cached, since offsets change with DST.
+#[utoipa::path(
+    get,
+    path = "/v1/timezone/compare",
+    params(TimezoneCompareQuery),
+    responses(
+        (status = 200, description = "Offsets relative to the first zone", body = TimezoneCompareResponse),
+        (status = 400, description = "Unknown timezone or too many zones", body = ApiErrorResponse)
+    ),
+    tag = "Timezone"
+)]
+pub async fn timezone_compare_handler(
+    State(state): State<AppState>,
+    Query(params): Query<TimezoneCompareQuery>,
+) -> Response<Body> {
+    timezone_compare_response(&state, &params)
+}
+
+/// Serve `/v1/timezone/compare` independently of the transport
+pub fn timezone_compare_response(
+    state: &AppState,
+    params: &TimezoneCompareQuery,
+) -> Response<Body> {
+    let format = if params.pretty.unwrap_or(false) {
+        ResponseFormat::PrettyJson
+    } else {
+        ResponseFormat::Json
+    };
+
+    let names: Vec<&str> = params
+        .zones
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .collect();
+    if names.is_empty() {
+        let error = ApiError {
+            error: "At least one timezone is required".to_string(),
+            code: "INVALID_TIMEZONE",
+            field: Some("zones"),
+        };
+        return build_error_response(&error, format);
+    }
+    let max_zones = state.bulk_max_ips;
+    if names.len() > max_zones {
+        let error = ApiError {
+            error: format!(
+                "Too many zones: {} provided, maximum is {}",
+                names.len(),
+                max_zones
+            ),
+            code: "TOO_MANY_ZONES",
+            field: Some("zones"),
+        };
+        return build_error_response(&error, format);
+    }
+
+    let mut details = Vec::with_capacity(names.len());
+    for name in &names {
+        match get_timezone_details(name) {
+            Some(d) => details.push(d),
+            None => {
+                let error = ApiError {
+                    error: format!("Invalid timezone: {name}"),
+                    code: "INVALID_TIMEZONE",
+                    field: Some("zones"),
+                };
+                return build_error_response(&error, format);
+            }
+        }
+    }
+
+    let time_format = params.time_format.unwrap_or_default();
+    let reference_secs = details[0].offset_seconds;
+    let response = TimezoneCompareResponse {
+        reference: details[0].name.clone(),
+        zones: details
+            .into_iter()
+            .map(|d| TimezoneCompareEntry {
+                offset: f64::from(d.offset_seconds) / 3600.0,
+                difference: f64::from(d.offset_seconds - reference_secs) / 3600.0,
+                timezone: d.name,
+                abbreviation: d.abbreviation,
+                current_time: time_format.convert(&d.current_time),
+                is_dst: d.is_dst,
+            })
+            .collect(),
+    };
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
+        .header(header::CACHE_CONTROL, "no-store")
+        .body(Body::from(format.json_body(&response)))
+        .unwrap()
+}
+
+/// List supported countries
+///
+/// Returns the embedded country dataset sorted by code, for client-side
+/// dropdowns and validation. Always JSON; cached for 2 weeks since the data
+/// only changes with a new release.
+#[utoipa::path(
+    get,
+    path = "/v1/countries",
+    params(CountriesQuery),
+    responses(
+        (status = 200, description = "Supported countries", body = Vec<CountryEntry>)
+    ),
+    tag = "Countries"
+)]
+pub async fn countries_handler(Query(params): Query<CountriesQuery>) -> Response<Body> {
+    countries_response(&params)
+}
+
+/// Serve `/v1/countries` independently of the transport
+pub fn countries_response(params: &CountriesQuery) -> Response<Body> {
+    let format = if params.pretty.unwrap_or(false) {
+        ResponseFormat::PrettyJson
+    } else {
+        ResponseFormat::Json
+    };
+    let eu_only = params.eu_only.unwrap_or(false);
+
+    let mut countries: Vec<CountryEntry> = COUNTRY_DATA
+        .iter()
+        .filter(|(_, m)| !eu_only || m.is_eu)
+        .map(|(code, m)| CountryEntry {
+            code: code.to_string(),
+            name: m.name.to_string(),
+            continent: m.continent_code.to_string(),
+            currency: m.currency_code.to_string(),
+            is_eu: m.is_eu,
+            calling_code: m.calling_code.to_string(),
+            tld: m.tld.to_string(),
+            emoji: m.flag_emoji.to_string(),
+        })
+        .collect();
+    countries.sort_by(|a, b| a.code.cmp(&b.code));
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
+        .header(header::CACHE_CONTROL, CACHE_CONTROL)
+        .body(Body::from(format.json_body(&countries)))
+        .unwrap()
+}
+
+/// List supported timezones
+///
+/// Returns every IANA zone name known to the server, sorted, for timezone
+/// pickers. Always JSON; cached for 2 weeks, except with `with_offsets=true`,
+/// which is never cached since offsets change with DST.
+#[utoipa::path(
+    get,
+    path = "/v1/timezones",
+    params(TimezonesQuery),
+    responses(
+        (status = 200, description = "Supported timezones", body = Vec<TimezoneListEntry>)
+    ),
+    tag = "Timezone"
+)]
+pub async fn timezones_handler(Query(params): Query<TimezonesQuery>) -> Response<Body> {
+    timezones_response(&params)
+}
+
+/// Serve `/v1/timezones` independently of the transport
+pub fn timezones_response(params: &TimezonesQuery) -> Response<Body> {
+    let format = if params.pretty.unwrap_or(false) {
+        ResponseFormat::PrettyJson
+    } else {
+        ResponseFormat::Json
+    };
+    let with_offsets = params.with_offsets.unwrap_or(false);
+
+    let mut zones: Vec<TimezoneListEntry> = chrono_tz::TZ_VARIANTS
+        .iter()
+        .map(|tz| TimezoneListEntry {
+            name: tz.name().to_string(),
+            offset: with_offsets
+                .then(|| get_timezone_details(tz.name()))
+                .flatten()
+                .map(|d| f64::from(d.offset_seconds) / 3600.0),
+        })
+        .collect();
+    zones.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/json; charset=utf-8")
+        .header(
+            header::CACHE_CONTROL,
+            if with_offsets {
+                "no-store"
+            } else {
+                CACHE_CONTROL
+            },
+        )
+        .body(Body::from(format.json_body(&zones)))
+        .unwrap()
+}
+
+/// Get the country flag for an IP address
+///
+/// Returns just the country code, flag emoji, and flag SVG path, for UI badges
+/// that don't need the full response. Fields are empty for unknown IPs.
+/// Supports content negotiation: use Accept: application/x-protobuf for protobuf response.
+#[utoipa::path(
+    get,
+    path = "/v1/flag",
+    params(IpGeoQuery),
+    responses(
+        (status = 200, description = "Successful flag lookup", body = FlagResponse),
+        (status = 400, description = "Invalid IP address", body = ApiErrorResponse),
+        (status = 503, description = "GeoIP database error", body = ApiErrorResponse)
+    ),
+    tag = "IP Geolocation"
+)]
+pub async fn flag_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<IpGeoQuery>,
+) -> Response<Body> {
+    flag_response(&state, &headers, &params)
+}
+
+/// Serve `/v1/flag` independently of the transport
+pub fn flag_response(state: &AppState, headers: &HeaderMap, params: &IpGeoQuery) -> Response<Body> {
+    let ip = params.ip.trim();
+    let format = ResponseFormat::negotiate(headers, params.pretty);
+
+    if let Err(e) = validate_ip(ip) {
+        return build_error_response(&e, format);
+    }
+
+    let country_code = match lookup_geo(state, ip) {
+        Ok(geo_result) => geo_result.and_then(|geo_data| geo_data.country_code),
+        Err(e) => return build_error_response(&e, format),
+    };
+
+    let response = match get_country_metadata(country_code.as_deref()) {
+        Some(meta) => {
+            let code = country_code.unwrap_or_default();
+            FlagResponse {
+                emoji: meta.flag_emoji.to_string(),
+                svg: state.response_options.flag_path(&code),
+                country_code: code,
+            }
+        }
+        None => FlagResponse::default(),
+    };
+
+    let proto: geolocation::FlagResponse = (&response).into();
+    build_response(&response, proto, format)
+}
+
+/// Get geolocation for client's IP
+///
+/// Returns geolocation data for the requesting client's IP address.
+/// Automatically detects the client IP from CF-Connecting-IP, X-Real-IP,
+/// X-Forwarded-For headers, or the direct connection.
+/// Supports content negotiation: use Accept: application/x-protobuf for protobuf response.
+#[utoipa::path(
+    get,
+    path = "/",
+    params(FormatQuery),
+    responses(
+        (status = 200, description = "Successful geolocation lookup", body = IpGeoResponse),
+        (status = 503, description = "GeoIP database error", body = ApiErrorResponse)
+    ),
+    tag = "IP Geolocation"
+)]
+pub async fn root_handler(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Query(params): Query<FormatQuery>,
+) -> Response<Body> {
+    let ip = extract_client_ip(&headers, Some(addr));
+    self_response(&state, &headers, ip, &params)
+}
+
+/// Serve `/` for an already extracted client IP, independently of the transport
+pub fn self_response(
+    state: &AppState,
+    headers: &HeaderMap,
+    ip: String,
+    params: &FormatQuery,
+) -> Response<Body> {
+    let mut response = negotiated_self_response(state, headers, ip, params);
+    // With ROOT_HTML the same URL serves a page or data depending on `Accept`,
+    // so caches must keep them apart
+    if state.root_html {
+        response
+            .headers_mut()
+            .insert(header::VARY, HeaderValue::from_static("Accept"));
+    }
+    response
+}
+
+/// `/` as HTML, JSON or protobuf, whichever the request negotiates
+fn negotiated_self_response(
+    state: &AppState,
+    headers: &HeaderMap,
+    ip: String,
+    params: &FormatQuery,
+) -> Response<Body> {
+    let format = ResponseFormat::negotiate(headers, params.pretty);
+
+    // Validate IP address (should always be valid from extraction, but be safe)
+    if let Err(e) = validate_ip(&ip) {
+        return build_error_response(&e, format);
+    }
+
+    if state.root_html && !format.is_protobuf() && accepts_html(get_accept_header(headers)) {
+        return html_self_response(state, &ip);
+    }
+
+    // Check cache first (JSON only)
+    if !format.is_protobuf() {
+        if let Some(cached) = state.cache.get(&ip) {
+            let proto: geolocation::IpGeoResponse = (&cached).into();
+            return with_cache_status(build_response(&cached, proto, format), true);
+        }
+    }
+
+    // Lookup in MaxMind database
+    let geo_result = match lookup_geo(state, &ip) {
+        Ok(geo_result) => geo_result,
+        Err(e) => return build_error_response(&e, format),
+    };
+
+    // Simple response format (same as /ipgeo)
+    let response = match geo_result {
+        Some(geo_data) => {
+            build_simple_response(&geo_data, state.timezones.as_ref(), &state.response_options)
+        }
+        None => IpGeoResponse::default(),
+    };
+
+    let proto: geolocation::IpGeoResponse = (&response).into();
+    let built = build_response(&response, proto, format);
+
+    // Cache the response (JSON only)
+    if format.is_protobuf() {
+        built
+    } else {
+        state.cache.insert(ip, response);
+        with_cache_status(built, false)
+    }
+}
+
+/// Render `/` as an HTML page for browsers
+fn html_self_response(state: &AppState, ip: &str) -> Response<Body> {
+    let response = match lookup_simple_cached(state, ip) {
+        Ok(response) => response,
+        Err(e) => return build_error_response(&e, ResponseFormat::Json),
+    };
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "text/html; charset=utf-8")
+        // The page is per-visitor; `self_response` adds `Vary: Accept`
+        .header(header::CACHE_CONTROL, "no-store")
+        .body(Body::from(render_self_page(
+            ip,
+            &response,
+            &state.public_url(),
+            &state.response_options.path_prefix,
+        )))
+        .unwrap()
+}
+
+/// Stream geolocation for the requesting client (Server-Sent Events)
+///
+/// Sends a `location` event with the full-format response for the client's IP
+/// on connect, then re-sends it every 5 minutes so long-lived clients pick up
+/// database updates. The client IP is fixed for the lifetime of a connection,
+/// so clients that change networks get the new location when they reconnect.
+/// A heartbeat comment is sent every 30 seconds to keep proxies from closing
+/// the connection.
+#[utoipa::path(
+    get,
+    path = "/v1/self/stream",
+    responses(
+        (status = 200, description = "Stream of `location` events", body = IpGeoResponseFull, content_type = "text/event-stream")
+    ),
+    tag = "IP Geolocation"
+)]
+pub async fn self_stream_handler(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let ip = extract_client_ip(&headers, Some(addr));
+
+    let stream = async_stream::stream! {
+        let mut refresh = tokio::time::interval(SELF_STREAM_REFRESH);
+        loop {
+            // The first tick completes immediately, giving the initial snapshot
+            refresh.tick().await;
+            yield Ok(self_location_event(&state, &ip));
+        }
+    };
+
+    Sse::new(stream).keep_alive(
+        KeepAlive::new()
+            .interval(SELF_STREAM_KEEP_ALIVE)
+            .text("ping"),
+    )
+}
+
+/// Build a `location` event for the client IP, or an `error` event
+fn self_location_event(state: &AppState, ip: &str) -> Event {
+    let result = validate_ip(ip).and_then(|()| lookup_geo(state, ip));
+    match result {
+        Ok(geo_result) => {
+            let response = match geo_result {
+                Some(geo_data) => build_full_response(
+                    ip,
+                    &geo_data,
+                    state.timezones.as_ref(),
+                    &state.response_options,
+                ),
+                None => IpGeoResponseFull {
+                    ip: Some(ip.to_string()),
+                    network: non_public_network(ip),
+                    ..Default::default()
+                },
+            };
+            Event::default()
+                .event("location")
+                .data(serde_json::to_string(&response).unwrap())
+        }
+        Err(e) => Event::default()
+            .event("error")
+            .data(serde_json::to_string(&e).unwrap()),
+    }
+}
+
+/// Health check endpoint
+///
+/// Returns OK if the service is running.
+#[utoipa::path(
+    get,
+    path = "/health",
+    responses(
+        (status = 200, description = "Service is healthy", body = String, example = json!("OK"))
+    ),
+    tag = "Health"
+)]
+pub async fn health_handler() -> impl IntoResponse {
+    (StatusCode::OK, "OK")
+}
+
+/// Database health endpoint
+///
+/// Returns metadata about the loaded GeoIP database, including how many days
+/// ago it was built and whether that exceeds `MAX_DB_AGE_DAYS`.
+#[utoipa::path(
+    get,
+    path = "/health/database",
+    responses(
+        (status = 200, description = "Database metadata", body = DatabaseHealthResponse)
+    ),
+    tag = "Health"
+)]
+pub async fn health_database_handler(
+    State(state): State<AppState>,
+) -> Json<DatabaseHealthResponse> {
+    let metadata = state.geoip.metadata();
+    let age_days = metadata.age_days();
+    let stale = state.geoip.is_loaded() && age_days > state.max_db_age_days;
+    if stale {
+        tracing::warn!(
+            "GeoIP database is {} days old (MAX_DB_AGE_DAYS={}); update it",
+            age_days,
+            state.max_db_age_days
+        );
+    }
+
+    Json(DatabaseHealthResponse {
+        age_days,
+        stale,
+        database_type: metadata.database_type,
+        build_epoch: metadata.build_epoch,
+        node_count: metadata.node_count,
+        ip_version: metadata.ip_version,
+    })
+}
+
+/// Readiness endpoint
+///
+/// Reports the status of each subsystem. Answers 503 until every enabled
+/// subsystem is `"ok"`, so load balancers hold traffic while the database
+/// is still missing.
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    responses(
+        (status = 200, description = "All enabled subsystems are ready", body = ReadinessResponse),
+        (status = 503, description = "A subsystem is not ready", body = ReadinessResponse)
+    ),
+    tag = "Health"
+)]
+pub async fn health_ready_handler(State(state): State<AppState>) -> Response<Body> {
+    let status = |ok: bool| if ok { "ok" } else { "down" }.to_string();
+    let response = ReadinessResponse {
+        database: if state.geoip.is_loaded() {
+            "ok".to_string()
+        } else {
+            "not_loaded".to_string()
+        },
+        cache: "ok".to_string(),
+        http3: match &state.http3_running {
+            Some(running) => status(running.load(Ordering::Relaxed)),
+            None => "disabled".to_string(),
+        },
+    };
+
+    let ready = [&response.database, &response.cache, &response.http3]
+        .iter()
+        .all(|s| *s == "ok" || *s == "disabled");
+    let code = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (code, [(header::CACHE_CONTROL, "no-store")], Json(response)).into_response()
+}
+
+/// Escape a Prometheus label value (backslash, double quote, newline)
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Prometheus metrics endpoint (text exposition format)
+///
+/// Exposes `geoip_up` and a `geoip_build_info` gauge fixed at 1 whose labels
+/// carry the server version and database metadata, as a join target for dashboards.
+pub async fn metrics_handler(State(state): State<AppState>) -> impl IntoResponse {
+    let metadata = state.geoip.metadata();
+    let body = format!(
+        "# HELP geoip_up Whether the geolocation service is up.\n\
+         # TYPE geoip_up gauge\n\
+         geoip_up {}\n\
+         # HELP geoip_build_info Server version and GeoIP database metadata.\n\
+         # TYPE geoip_build_info gauge\n\
+         geoip_build_info{{version=\"{}\",db_type=\"{}\",db_build_epoch=\"{}\"}} 1\n",
+        u8::from(state.geoip.is_loaded()),
+        env!("CARGO_PKG_VERSION"),
+        escape_label_value(&metadata.database_type),
+        metadata.build_epoch,
+    );
+
+    (
+        [(
+            header::CONTENT_TYPE,
+            "text/plain; version=0.0.4; charset=utf-8",
+        )],
+        body,
+    )
 }
 
-/// Get timezone for coordinates (full format)
+/// Build version endpoint
 ///
-/// Returns comprehensive timezone details including current time,
-/// UTC offset, and daylight saving time information.
-/// Supports content negotiation: use Accept: application/x-protobuf for protobuf response.
+/// Returns the crate version, git commit, and build time of the running server.
 #[utoipa::path(
     get,
-    path = "/v1/timezone",
-    params(TimezoneQuery),
+    path = "/version",
     responses(
-        (status = 200, description = "Successful timezone lookup", body = TimezoneResponseFull),
-        (status = 400, description = "Invalid coordinates", body = ApiErrorResponse)
+        (status = 200, description = "Build information", body = VersionResponse)
     ),
-    tag = "Timezone"
+    tag = "Health"
 )]
-pub async fn timezone_full_handler(
+pub async fn version_handler() -> Json<VersionResponse> {
+    Json(VersionResponse {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        git_sha: option_env!("GIT_SHA").map(String::from),
+        build_time: env!("BUILD_TIME").to_string(),
+    })
+}
+
+/// Flush the lookup cache
+///
+/// Evicts every cached response and returns the number of entries removed.
+/// Requires `Authorization: Bearer <ADMIN_TOKEN>`.
+pub async fn admin_cache_flush_handler(
+    State(state): State<AppState>,
     headers: HeaderMap,
-    Query(params): Query<TimezoneQuery>,
 ) -> Response<Body> {
-    let use_protobuf = accepts_protobuf(get_accept_header(&headers));
+    if let Err((status, e)) = check_admin_token(&state, &headers) {
+        return (status, Json(e)).into_response();
+    }
 
-    // Validate coordinates
-    if let Err(e) = validate_latitude(params.lat) {
-        return build_error_response(&e, use_protobuf);
+    let evicted = state.cache.clear() + state.full_cache.clear();
+    tracing::info!(evicted, "admin flushed lookup cache");
+
+    Json(serde_json::json!({ "evicted": evicted })).into_response()
+}
+
+/// Invalidate the cached response for a single IP
+///
+/// Requires `Authorization: Bearer <ADMIN_TOKEN>`.
+pub async fn admin_cache_invalidate_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(ip): Path<String>,
+) -> Response<Body> {
+    if let Err((status, e)) = check_admin_token(&state, &headers) {
+        return (status, Json(e)).into_response();
     }
-    if let Err(e) = validate_longitude(params.long) {
-        return build_error_response(&e, use_protobuf);
+    if let Err(e) = validate_ip(&ip) {
+        return (StatusCode::BAD_REQUEST, Json(e)).into_response();
     }
 
-    let timezone_name = lookup_timezone(params.lat, params.long);
-
-    let response = match &timezone_name {
-        Some(tz_name) => {
-            let details = get_timezone_details(tz_name);
-            TimezoneResponseFull {
-                timezone: tz_name.clone(),
-                offset: details.as_ref().map(|d| d.offset_hours),
-                offset_with_dst: details.as_ref().map(|d| d.offset_with_dst_hours),
-                current_time: details.as_ref().map(|d| d.current_time.clone()),
-                current_time_unix: details.as_ref().map(|d| d.current_time_unix),
-                is_dst: details.as_ref().map(|d| d.is_dst),
-                dst_exists: details.as_ref().map(|d| d.dst_exists),
-            }
-        }
-        None => TimezoneResponseFull {
-            timezone: String::new(),
-            offset: None,
-            offset_with_dst: None,
-            current_time: None,
-            current_time_unix: None,
-            is_dst: None,
-            dst_exists: None,
-        },
-    };
+    let invalidated = state.cache.invalidate(&ip);
+    let invalidated = invalidate_full_cache(&state, &ip) || invalidated;
 
-    let proto: geolocation::TimezoneResponseFull = (&response).into();
-    build_response(&response, proto, use_protobuf)
+    Json(serde_json::json!({ "ip": ip, "invalidated": invalidated })).into_response()
 }
 
-/// Get geolocation for client's IP
+/// Reopen the GeoIP database(s) from disk and swap them in
 ///
-/// Returns geolocation data for the requesting client's IP address.
-/// Automatically detects the client IP from CF-Connecting-IP, X-Real-IP,
-/// X-Forwarded-For headers, or the direct connection.
-/// Supports content negotiation: use Accept: application/x-protobuf for protobuf response.
-#[utoipa::path(
-    get,
-    path = "/",
-    responses(
-        (status = 200, description = "Successful geolocation lookup", body = IpGeoResponse)
-    ),
-    tag = "IP Geolocation"
-)]
-pub async fn root_handler(
+/// An optional JSON body gives new paths for any of the databases. All of them
+/// are opened before any is swapped in, so they change together; if one fails
+/// to open, the current set stays in use. Flushes the lookup cache so no
+/// answers from the old databases are served, and tells MCP SSE clients
+/// that `geoip://data-source` changed.
+/// Requires `Authorization: Bearer <ADMIN_TOKEN>`.
+pub async fn admin_reload_handler(
     State(state): State<AppState>,
-    ConnectInfo(addr): ConnectInfo<SocketAddr>,
     headers: HeaderMap,
+    body: Option<Json<ReloadRequest>>,
 ) -> Response<Body> {
-    let ip = extract_client_ip(&headers, Some(addr));
-    let use_protobuf = accepts_protobuf(get_accept_header(&headers));
-
-    // Validate IP address (should always be valid from extraction, but be safe)
-    if let Err(e) = validate_ip(&ip) {
-        return build_error_response(&e, use_protobuf);
+    if let Err((status, e)) = check_admin_token(&state, &headers) {
+        return (status, Json(e)).into_response();
     }
+    let Some(reloader) = state.reloader.clone() else {
+        let error = ApiError {
+            error: "Database reload is not available".to_string(),
+            code: "RELOAD_UNAVAILABLE",
+            field: None,
+        };
+        return (StatusCode::NOT_FOUND, Json(error)).into_response();
+    };
 
-    // Check cache first (JSON only)
-    if !use_protobuf {
-        if let Some(cached) = state.cache.get(&ip) {
-            let proto: geolocation::IpGeoResponse = (&cached).into();
-            return build_response(&cached, proto, use_protobuf);
+    let request = body.map(|Json(request)| request).unwrap_or_default();
+    // Opening the databases is blocking file I/O that can take seconds
+    let result = tokio::task::spawn_blocking(move || {
+        reloader.reload_from(|current| DatabasePaths {
+            city: request.city.unwrap_or_else(|| current.city.clone()),
+            connection_type: request
+                .connection_type
+                .or_else(|| current.connection_type.clone()),
+            fallback: request.fallback.or_else(|| current.fallback.clone()),
+        })
+    })
+    .await
+    .unwrap_or_else(|e| Err(format!("reload task failed: {}", e)));
+
+    match result {
+        Ok(metadata) => {
+            let evicted = state.cache.clear() + state.full_cache.clear();
+            tracing::info!(
+                database_type = %metadata.database_type,
+                build_epoch = metadata.build_epoch,
+                evicted,
+                "admin reloaded GeoIP database"
+            );
+            if let Some(tx) = &state.mcp_notifications {
+                // Fails only when no SSE client is connected
+                let _ = tx.send(McpNotification::resource_updated(&format!(
+                    "{RESOURCE_URI_PREFIX}data-source"
+                )));
+            }
+            Json(serde_json::json!({
+                "reloaded": true,
+                "database_type": metadata.database_type,
+                "build_epoch": metadata.build_epoch,
+            }))
+            .into_response()
+        }
+        Err(e) => {
+            tracing::error!("GeoIP database reload failed: {}", e);
+            let error = ApiError {
+                error: format!("Reload failed: {}", e),
+                code: "DATABASE_ERROR",
+                field: None,
+            };
+            (error.status(), Json(error)).into_response()
         }
     }
+}
 
-    // Lookup in MaxMind database
-    let geo_result = state.geoip.lookup(&ip);
+/// Return the raw MaxMind record for an IP
+///
+/// Shows every field the database has (e.g. `is_in_european_union`, the
+/// database `time_zone`, `metro_code`), not just those mapped into responses.
+/// `record` is null when the IP isn't in the database.
+/// Requires `Authorization: Bearer <ADMIN_TOKEN>`.
+pub async fn admin_raw_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<RawRecordQuery>,
+) -> Response<Body> {
+    if let Err((status, e)) = check_admin_token(&state, &headers) {
+        return (status, Json(e)).into_response();
+    }
+    let ip = params.ip.trim();
+    if let Err(e) = validate_ip(ip) {
+        return (StatusCode::BAD_REQUEST, Json(e)).into_response();
+    }
 
-    // Simple response format (same as /ipgeo)
-    let response = match geo_result {
-        Ok(geo_data) => build_simple_response(&geo_data),
-        Err(_) => IpGeoResponse::default(),
+    let record = match raw_record(&state, ip) {
+        Ok(record) => record,
+        Err(e) => return (e.status(), Json(e)).into_response(),
     };
 
-    // Cache the response (JSON only)
-    if !use_protobuf {
-        state.cache.insert(ip, response.clone());
+    Json(serde_json::json!({ "ip": ip, "record": record })).into_response()
+}
+
+/// Raw MaxMind record for an IP, null when it isn't in the database
+fn raw_record(state: &AppState, ip: &str) -> Result<serde_json::Value, ApiError> {
+    match state.geoip.lookup_raw(ip) {
+        Ok(record) => Ok(record),
+        Err(GeoIpError::NotFound) => Ok(serde_json::Value::Null),
+        Err(e) => {
+            tracing::error!("Raw GeoIP lookup failed for {}: {}", ip, e);
+            Err(ApiError {
+                error: "Geolocation database error".to_string(),
+                code: "DATABASE_ERROR",
+                field: None,
+            })
+        }
     }
+}
 
-    let proto: geolocation::IpGeoResponse = (&response).into();
-    build_response(&response, proto, use_protobuf)
+/// Everything known about an IP in one object, for support tickets
+///
+/// Combines the simple and full responses, the raw MaxMind record, whether
+/// each response is currently cached, and where the timezone came from.
+/// Nothing is added to the caches. Requires `Authorization: Bearer <ADMIN_TOKEN>`.
+pub async fn admin_all_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<RawRecordQuery>,
+) -> Response<Body> {
+    if let Err((status, e)) = check_admin_token(&state, &headers) {
+        return (status, Json(e)).into_response();
+    }
+    let ip = params.ip.trim();
+    if let Err(e) = validate_ip(ip) {
+        return (StatusCode::BAD_REQUEST, Json(e)).into_response();
+    }
+
+    let geo_data = match lookup_geo(&state, ip) {
+        Ok(geo_data) => geo_data,
+        Err(e) => return (e.status(), Json(e)).into_response(),
+    };
+    let record = match raw_record(&state, ip) {
+        Ok(record) => record,
+        Err(e) => return (e.status(), Json(e)).into_response(),
+    };
+
+    let simple = geo_data
+        .as_ref()
+        .map(|g| build_simple_response(g, state.timezones.as_ref(), &state.response_options))
+        .unwrap_or_default();
+    let full = match geo_data.as_ref() {
+        Some(geo_data) => build_full_response(
+            ip,
+            geo_data,
+            state.timezones.as_ref(),
+            &state.response_options,
+        ),
+        None => IpGeoResponseFull {
+            ip: Some(ip.to_string()),
+            network: non_public_network(ip),
+            ..Default::default()
+        },
+    };
+    let from_coordinates = geo_data
+        .as_ref()
+        .and_then(|g| state.timezones.lookup(g.latitude?, g.longitude?));
+
+    Json(serde_json::json!({
+        "ip": ip,
+        "simple": simple,
+        "full": full,
+        "record": record,
+        "cache": {
+            "simple": state.cache.contains_key(ip),
+            "full": state.full_cache.contains_key(&full_cache_key(ip, false, false)),
+        },
+        "timezone": {
+            "resolved": full.time_zone,
+            "coordinates": from_coordinates,
+            "database": geo_data.as_ref().and_then(|g| g.time_zone.clone()),
+        },
+    }))
+    .into_response()
 }
 
-/// Health check endpoint
+/// The generated OpenAPI spec as YAML, with `public_url` as its server
 ///
-/// Returns OK if the service is running.
-#[utoipa::path(
-    get,
-    path = "/health",
-    responses(
-        (status = 200, description = "Service is healthy", body = String, example = json!("OK"))
-    ),
-    tag = "Health"
-)]
-pub async fn health_handler() -> impl IntoResponse {
-    (StatusCode::OK, "OK")
+/// `Content-Length` is set explicitly so `HEAD` requests, which Axum answers
+/// from the same handler without a body, report the spec's size.
+fn openapi_yaml_response(public_url: &str) -> Response<Body> {
+    let mut openapi = ApiDoc::openapi();
+    openapi.servers = Some(vec![utoipa::openapi::Server::new(public_url)]);
+
+    let spec = openapi.to_yaml().unwrap();
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, "application/yaml; charset=utf-8")
+        .header(header::CONTENT_LENGTH, spec.len())
+        .body(Body::from(spec))
+        .unwrap()
 }
 
 /// OpenAPI specification handler
 ///
 /// Returns the OpenAPI 3.0 specification generated from the code.
-pub async fn openapi_handler(State(state): State<AppState>) -> impl IntoResponse {
-    let mut openapi = ApiDoc::openapi();
-
-    // Update server URL from environment
-    openapi.servers = Some(vec![utoipa::openapi::Server::new(&state.base_url)]);
+pub async fn openapi_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    openapi_yaml_response(&state.request_public_url(&headers))
+}
 
-    let spec = openapi.to_yaml().unwrap();
+/// LLM-friendly documentation handler
+///
+/// Returns plain text documentation optimized for LLM consumption.
+pub async fn llms_txt_handler(headers: HeaderMap) -> impl IntoResponse {
+    let (language, body) = negotiate_llms_txt(&headers);
     (
         StatusCode::OK,
-        [(header::CONTENT_TYPE, "application/yaml; charset=utf-8")],
-        spec,
+        [
+            (header::CONTENT_TYPE, "text/plain; charset=utf-8"),
+            (header::CONTENT_LANGUAGE, language),
+            (header::VARY, "Accept-Language"),
+        ],
+        body,
     )
 }
 
-/// LLM-friendly documentation handler
+/// Embedded `llms.txt` translations; the first entry is the default
+const LLMS_TXT_VARIANTS: &[(&str, &str)] = &[
+    ("en", include_str!("../llms.txt")),
+    ("es", include_str!("../llms.es.txt")),
+];
+
+/// Pick the `llms.txt` variant best matching `Accept-Language`
 ///
-/// Returns plain text documentation optimized for LLM consumption.
-pub async fn llms_txt_handler() -> impl IntoResponse {
+/// Ranges are tried by descending q-value and matched on their primary subtag
+/// (`es-MX` picks `es`); anything unmatched falls back to English.
+fn negotiate_llms_txt(headers: &HeaderMap) -> (&'static str, &'static str) {
+    let accept_language = headers
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or_default();
+
+    let mut ranges: Vec<(&str, f32)> = accept_language
+        .split(',')
+        .filter_map(|range| {
+            let mut params = range.split(';');
+            let tag = params.next()?.trim();
+            let q = match params.find_map(|p| p.trim().strip_prefix("q=")) {
+                Some(q) => q.parse().ok()?,
+                None => 1.0,
+            };
+            (!tag.is_empty() && q > 0.0).then_some((tag, q))
+        })
+        .collect();
+    // Stable sort, so equal q-values keep the client's order
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    ranges
+        .iter()
+        .find_map(|(tag, _)| {
+            let primary = tag.split('-').next()?;
+            LLMS_TXT_VARIANTS
+                .iter()
+                .find(|(language, _)| language.eq_ignore_ascii_case(primary))
+                .copied()
+        })
+        .unwrap_or(LLMS_TXT_VARIANTS[0])
+}
+
+/// Protobuf schema handler
+///
+/// Returns `proto/geolocation.proto` so clients can generate stubs for the
+/// version named in the protobuf content type.
+pub async fn proto_definition_handler() -> impl IntoResponse {
     (
         StatusCode::OK,
-        [(header::CONTENT_TYPE, "text/plain; charset=utf-8")],
-        include_str!("../llms.txt"),
+        [
+            (header::CONTENT_TYPE, "text/plain; charset=utf-8"),
+            (header::CACHE_CONTROL, CACHE_CONTROL),
+        ],
+        PROTO_DEFINITION,
+    )
+}
+
+/// Content type of the `/schema/*` documents
+const SCHEMA_CONTENT_TYPE: &str = "application/schema+json";
+
+/// JSON Schema for `/ipgeo` and `/v1/ipgeo` responses
+///
+/// Uses the same definitions as the MCP `geoip://schema` resource, so REST
+/// clients can validate responses without the OpenAPI tooling.
+pub async fn schema_ipgeo_handler() -> Response<Body> {
+    ipgeo_schema_response()
+}
+
+/// Serve `/schema/ipgeo` independently of the transport
+pub fn ipgeo_schema_response() -> Response<Body> {
+    schema_response(
+        "IP Geolocation Responses",
+        serde_json::json!({
+            "IpGeoResponseSimple": schemas::ip_geo_response_simple_schema(),
+            "IpGeoResponseFull": schemas::ip_geo_response_full_schema(),
+        }),
+    )
+}
+
+/// JSON Schema for `/timezone` and `/v1/timezone` responses
+pub async fn schema_timezone_handler() -> Response<Body> {
+    timezone_schema_response()
+}
+
+/// Serve `/schema/timezone` independently of the transport
+pub fn timezone_schema_response() -> Response<Body> {
+    schema_response(
+        "Timezone Responses",
+        serde_json::json!({
+            "TimezoneResponseSimple": schemas::timezone_response_simple_schema(),
+            "TimezoneResponseFull": schemas::timezone_response_full_schema(),
+        }),
     )
 }
 
+/// Wrap schema definitions in a draft-07 document
+fn schema_response(title: &str, definitions: serde_json::Value) -> Response<Body> {
+    let schema = serde_json::json!({
+        "$schema": "http://json-schema.org/draft-07/schema#",
+        "title": title,
+        "definitions": definitions,
+    });
+
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, SCHEMA_CONTENT_TYPE)
+        .header(header::CACHE_CONTROL, CACHE_CONTROL)
+        .body(Body::from(serde_json::to_string_pretty(&schema).unwrap()))
+        .unwrap()
+}
+
 /// Robots.txt handler
 ///
 /// Returns robots.txt for search engine crawlers.
-pub async fn robots_txt_handler(State(state): State<AppState>) -> impl IntoResponse {
+pub async fn robots_txt_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
     let robots = format!(
         r#"User-agent: *
 Allow: /
@@ -597,7 +3087,7 @@ Allow: /
 # Sitemap location
 Sitemap: {}/sitemap.xml
 "#,
-        state.base_url
+        state.request_public_url(&headers)
     );
 
     (
@@ -610,8 +3100,11 @@ Sitemap: {}/sitemap.xml
 /// Sitemap XML handler
 ///
 /// Returns a sitemap.xml for search engine and agent discovery.
-pub async fn sitemap_handler(State(state): State<AppState>) -> impl IntoResponse {
-    let base = &state.base_url;
+pub async fn sitemap_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let base = state.request_public_url(&headers);
     let sitemap = format!(
         r#"<?xml version="1.0" encoding="UTF-8"?>
 <urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
@@ -663,25 +3156,21 @@ pub async fn sitemap_handler(State(state): State<AppState>) -> impl IntoResponse
 /// Well-known OpenAPI handler (redirects to /openapi.yaml)
 ///
 /// Serves the OpenAPI spec from the standard .well-known location.
-pub async fn wellknown_openapi_handler(State(state): State<AppState>) -> impl IntoResponse {
-    let mut openapi = ApiDoc::openapi();
-
-    // Update server URL from environment
-    openapi.servers = Some(vec![utoipa::openapi::Server::new(&state.base_url)]);
-
-    let spec = openapi.to_yaml().unwrap();
-    (
-        StatusCode::OK,
-        [(header::CONTENT_TYPE, "application/yaml; charset=utf-8")],
-        spec,
-    )
+pub async fn wellknown_openapi_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    openapi_yaml_response(&state.request_public_url(&headers))
 }
 
 /// Well-known AI plugin manifest
 ///
 /// Returns an AI plugin manifest for ChatGPT-style agent discovery.
-pub async fn wellknown_ai_plugin_handler(State(state): State<AppState>) -> impl IntoResponse {
-    let base = &state.base_url;
+pub async fn wellknown_ai_plugin_handler(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let base = state.request_public_url(&headers);
     let manifest = serde_json::json!({
         "schema_version": "v1",
         "name_for_human": "IP Geolocation API",
@@ -710,6 +3199,8 @@ pub async fn wellknown_ai_plugin_handler(State(state): State<AppState>) -> impl
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::languages::get_languages;
+    use crate::timezone::lookup_timezone;
 
     #[test]
     fn test_validate_ip_valid() {
@@ -725,6 +3216,82 @@ mod tests {
         assert!(validate_ip("").is_err());
     }
 
+    #[test]
+    fn test_duplicate_query_params() {
+        let dups = |uri: &str| duplicate_query_params(&uri.parse().unwrap());
+        assert!(dups("/ipgeo?ip=8.8.8.8&pretty=true").is_empty());
+        assert!(dups("/ipgeo").is_empty());
+        assert_eq!(dups("/ipgeo?ip=8.8.8.8&ip=1.1.1.1&ip=9.9.9.9"), ["ip"]);
+        // Names are compared after percent-decoding
+        assert_eq!(
+            dups("/ipgeo?pretty=true&ip=8.8.8.8&%69p=1.1.1.1&pretty=false"),
+            ["ip", "pretty"]
+        );
+    }
+
+    #[test]
+    fn test_unknown_query_params() {
+        let unknown = |path: &str, uri: &str| unknown_query_params(path, &uri.parse().unwrap());
+        assert!(unknown("/ipgeo", "/ipgeo?ip=8.8.8.8&apiKey=x&live_time=false").is_empty());
+        assert_eq!(
+            unknown("/ipgeo", "/ipgeo?adress=8.8.8.8&pretty=true&_=1&adress=x"),
+            ["adress"]
+        );
+        assert!(unknown("/v1/timezone", "/v1/timezone?lat=1&long=2&_=1712345678").is_empty());
+        // `api_key` is spelled camelCase, and only on the endpoints that take it
+        assert_eq!(
+            unknown("/v1/ipgeo", "/v1/ipgeo?ip=1.1.1.1&api_key=x"),
+            ["api_key"]
+        );
+        assert_eq!(
+            unknown("/v1/countries", "/v1/countries?apiKey=x"),
+            ["apiKey"]
+        );
+        // Endpoints without a query are not checked
+        assert!(unknown("/health", "/health?verbose=1").is_empty());
+    }
+
+    #[test]
+    fn test_canonical_cache_key() {
+        let key = |uri: &str| {
+            let Query(params) = Query::<IpGeoQuery>::try_from_uri(&uri.parse().unwrap()).unwrap();
+            canonical_cache_key(&params.ip, &params)
+        };
+        assert_eq!(key("/v1/ipgeo?ip=8.8.8.8"), "8.8.8.8");
+        assert_eq!(
+            key(
+                "/v1/ipgeo?ip=8.8.8.8&_=1712345678&pretty=true&live_time=false&time_format=iso8601"
+            ),
+            "8.8.8.8"
+        );
+        assert_eq!(
+            key("/v1/ipgeo?no_centroid=true&ip=8.8.8.8&airport=true"),
+            "8.8.8.8?airport&no_centroid"
+        );
+        assert_eq!(key("/v1/ipgeo?ip=8.8.8.8&airport=false"), "8.8.8.8");
+    }
+
+    #[test]
+    fn test_extract_client_ip_strips_port() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "X-Forwarded-For",
+            "203.0.113.1:443, 10.0.0.1".parse().unwrap(),
+        );
+        assert_eq!(extract_client_ip(&headers, None), "203.0.113.1");
+
+        headers.insert("X-Forwarded-For", "[2001:db8::1]:443".parse().unwrap());
+        assert_eq!(extract_client_ip(&headers, None), "2001:db8::1");
+
+        headers.insert("X-Real-IP", "[2001:db8::2]".parse().unwrap());
+        assert_eq!(extract_client_ip(&headers, None), "2001:db8::2");
+
+        // Bare IPv6 is left alone even though it contains colons
+        assert_eq!(strip_port("2001:db8::1"), "2001:db8::1");
+        assert_eq!(strip_port(" 8.8.8.8 "), "8.8.8.8");
+        assert_eq!(strip_port("not-an-ip:80"), "not-an-ip:80");
+    }
+
     #[test]
     fn test_validate_latitude_valid() {
         assert!(validate_latitude(0.0).is_ok());
@@ -755,6 +3322,42 @@ mod tests {
         assert!(validate_longitude(360.0).is_err());
     }
 
+    #[test]
+    fn test_round_coordinate() {
+        assert_eq!(round_coordinate(37.751, 1), 37.8);
+        assert_eq!(round_coordinate(-97.822, 1), -97.8);
+        assert_eq!(round_coordinate(59.329504, 0), 59.0);
+        assert_eq!(round_coordinate(18.069532, 3), 18.07);
+    }
+
+    #[test]
+    fn test_limit_coordinate_precision_default() {
+        assert_eq!(
+            ResponseOptions::default().limit_coordinate_precision(59.32950412345678),
+            59.329504
+        );
+        assert_eq!(
+            ResponseOptions::default().limit_coordinate_precision(-97.8220004999),
+            -97.822
+        );
+        assert_eq!(
+            ResponseOptions::default().limit_coordinate_precision(18.5),
+            18.5
+        );
+    }
+
+    #[test]
+    fn test_validate_coordinates_non_finite() {
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let e = validate_latitude(value).unwrap_err();
+            assert_eq!(e.code, "INVALID_LATITUDE");
+            assert!(e.error.contains("finite"));
+            let e = validate_longitude(value).unwrap_err();
+            assert_eq!(e.code, "INVALID_LONGITUDE");
+            assert!(e.error.contains("finite"));
+        }
+    }
+
     #[test]
     fn test_timezone_lookup_stockholm() {
         let tz = lookup_timezone(59.329504, 18.069532);
@@ -785,6 +3388,32 @@ mod tests {
         assert_eq!(langs, "");
     }
 
+    #[test]
+    fn test_check_admin_token() {
+        use crate::cache::{CacheConfig, GeoCache};
+        use crate::geoip::mock::MockGeoIpReader;
+        use std::sync::Arc;
+
+        let mut state = AppState::new(
+            Arc::new(MockGeoIpReader::new()),
+            Arc::new(GeoCache::new(CacheConfig::default())),
+            "https://test.example.com".to_string(),
+        );
+        let mut headers = HeaderMap::new();
+        headers.insert(header::AUTHORIZATION, "Bearer secret".parse().unwrap());
+
+        // Disabled when no token is configured, even if one is sent
+        let (status, _) = check_admin_token(&state, &headers).unwrap_err();
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        state.admin_token = Some("secret".to_string());
+        assert!(check_admin_token(&state, &headers).is_ok());
+
+        headers.insert(header::AUTHORIZATION, "Bearer wrong".parse().unwrap());
+        let (status, _) = check_admin_token(&state, &headers).unwrap_err();
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+    }
+
     #[test]
     fn test_app_state_clone() {
         fn assert_clone<T: Clone>() {}
@@ -803,12 +3432,14 @@ mod tests {
             state_code: None,
             postal_code: None,
             geoname_id: None,
+            ..Default::default()
         };
 
-        let response = build_simple_response(&geo_data);
+        let response =
+            build_simple_response(&geo_data, &TzfTimezoneProvider, &ResponseOptions::default());
 
         assert_eq!(response.latitude, Some(51.5074));
-        assert_eq!(response.city, "London");
+        assert_eq!(response.city.as_deref(), Some("London"));
         assert_eq!(response.time_zone.name, "Europe/London");
     }
 
@@ -824,9 +3455,15 @@ mod tests {
             state_code: Some("KS".to_string()),
             postal_code: Some("67401".to_string()),
             geoname_id: Some(123456),
+            ..Default::default()
         };
 
-        let response = build_full_response("8.8.8.8", &geo_data);
+        let response = build_full_response(
+            "8.8.8.8",
+            &geo_data,
+            &TzfTimezoneProvider,
+            &ResponseOptions::default(),
+        );
 
         assert_eq!(response.ip, Some("8.8.8.8".to_string()));
         assert!(response.location.is_some());
@@ -834,6 +3471,133 @@ mod tests {
         assert_eq!(location.country_code2, Some("US".to_string()));
         assert_eq!(location.country_code3, Some("USA".to_string()));
         assert_eq!(location.is_eu, Some(false));
+        assert!(location.registered_country_code.is_none());
+        assert!(location.represented_country_code.is_none());
+    }
+
+    #[test]
+    fn test_build_full_response_timezone_prefers_coordinates() {
+        let geo_data = GeoData {
+            latitude: Some(59.329504),
+            longitude: Some(18.069532),
+            time_zone: Some("Europe/Oslo".to_string()),
+            ..Default::default()
+        };
+
+        let tz = build_full_response(
+            "1.2.3.4",
+            &geo_data,
+            &TzfTimezoneProvider,
+            &ResponseOptions::default(),
+        )
+        .time_zone
+        .unwrap();
+        assert_eq!(tz.name.as_deref(), Some("Europe/Stockholm"));
+        assert_eq!(tz.source.as_deref(), Some("coordinates"));
+    }
+
+    #[test]
+    fn test_build_full_response_timezone_from_database() {
+        let geo_data = GeoData {
+            country_code: Some("SE".to_string()),
+            time_zone: Some("Europe/Stockholm".to_string()),
+            ..Default::default()
+        };
+
+        let tz = build_full_response(
+            "1.2.3.4",
+            &geo_data,
+            &TzfTimezoneProvider,
+            &ResponseOptions::default(),
+        )
+        .time_zone
+        .unwrap();
+        assert_eq!(tz.name.as_deref(), Some("Europe/Stockholm"));
+        assert_eq!(tz.source.as_deref(), Some("database"));
+        assert!(tz.offset.is_some());
+        assert_eq!(
+            build_simple_response(&geo_data, &TzfTimezoneProvider, &ResponseOptions::default())
+                .time_zone
+                .name,
+            "Europe/Stockholm"
+        );
+    }
+
+    #[test]
+    fn test_build_full_response_timezone_nautical() {
+        // Mid-Atlantic, no MaxMind zone: approximate from the longitude
+        let mut geo_data = GeoData {
+            latitude: Some(0.0),
+            longitude: Some(-30.0),
+            ..Default::default()
+        };
+        let tz = build_full_response(
+            "1.2.3.4",
+            &geo_data,
+            &TzfTimezoneProvider,
+            &ResponseOptions::default(),
+        )
+        .time_zone
+        .unwrap();
+        assert_eq!(tz.name.as_deref(), Some("Etc/GMT+2"));
+        assert_eq!(tz.source.as_deref(), Some("nautical"));
+        assert_eq!(tz.offset, Some(-2));
+
+        // A real zone from the database beats the approximation
+        geo_data.time_zone = Some("Atlantic/Azores".to_string());
+        let tz = build_full_response(
+            "1.2.3.4",
+            &geo_data,
+            &TzfTimezoneProvider,
+            &ResponseOptions::default(),
+        )
+        .time_zone
+        .unwrap();
+        assert_eq!(tz.name.as_deref(), Some("Atlantic/Azores"));
+        assert_eq!(tz.source.as_deref(), Some("database"));
+    }
+
+    #[test]
+    fn test_build_full_response_region() {
+        let geo_data = GeoData {
+            country_code: Some("DE".to_string()),
+            ..Default::default()
+        };
+
+        let region = build_full_response(
+            "1.2.3.4",
+            &geo_data,
+            &TzfTimezoneProvider,
+            &ResponseOptions::default(),
+        )
+        .region
+        .unwrap();
+        assert_eq!(region.code.as_deref(), Some("150"));
+        assert_eq!(region.name.as_deref(), Some("Europe"));
+        assert_eq!(region.subregion_code.as_deref(), Some("155"));
+        assert_eq!(region.subregion.as_deref(), Some("Western Europe"));
+    }
+
+    #[test]
+    fn test_build_full_response_registered_represented_country() {
+        let geo_data = GeoData {
+            country_code: Some("DE".to_string()),
+            registered_country_code: Some("US".to_string()),
+            represented_country_code: Some("DE".to_string()),
+            ..Default::default()
+        };
+
+        let location = build_full_response(
+            "1.2.3.4",
+            &geo_data,
+            &TzfTimezoneProvider,
+            &ResponseOptions::default(),
+        )
+        .location
+        .unwrap();
+        assert_eq!(location.registered_country_code, Some("US".to_string()));
+        // Same as the located country, so omitted
+        assert!(location.represented_country_code.is_none());
     }
 
     #[test]
@@ -842,8 +3606,8 @@ mod tests {
 
         assert!(response.latitude.is_none());
         assert!(response.longitude.is_none());
-        assert!(response.city.is_empty());
-        assert!(response.country_name.is_empty());
+        assert!(response.city.is_none());
+        assert!(response.country_name.is_none());
         assert!(response.time_zone.name.is_empty());
         assert!(response.languages.is_empty());
     }
@@ -870,8 +3634,55 @@ mod tests {
 
     #[test]
     fn test_flag_path() {
-        let path = get_flag_path("US");
-        assert_eq!(path, "/static/flags/us.svg");
+        let options = ResponseOptions::default();
+        assert_eq!(options.flag_path("US"), "/static/flags/us.svg");
+
+        let options = ResponseOptions {
+            path_prefix: "/geoip".to_string(),
+            default_flag: "xx".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(options.flag_path("US"), "/geoip/static/flags/us.svg");
+        assert_eq!(options.flag_path("ZZ"), "/geoip/static/flags/xx.svg");
+    }
+
+    #[test]
+    fn test_negotiate_llms_txt() {
+        let language = |accept: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::ACCEPT_LANGUAGE, accept.parse().unwrap());
+            negotiate_llms_txt(&headers).0
+        };
+
+        assert_eq!(negotiate_llms_txt(&HeaderMap::new()).0, "en");
+        assert_eq!(language("es"), "es");
+        assert_eq!(language("es-MX,es;q=0.9"), "es");
+        assert_eq!(language("fr, es;q=0.8, en;q=0.9"), "en");
+        assert_eq!(language("de, es;q=0.5"), "es");
+        assert_eq!(language("es;q=0, *"), "en");
+    }
+
+    #[test]
+    fn test_resolve_format() {
+        assert_eq!(resolve_format(None), FormatMode::Simple);
+        assert_eq!(resolve_format(Some("")), FormatMode::Simple);
+        assert_eq!(resolve_format(Some(" , ")), FormatMode::Simple);
+        assert_eq!(resolve_format(Some("*")), FormatMode::Full);
+        assert_eq!(resolve_format(Some("city,*")), FormatMode::Full);
+        assert_eq!(resolve_format(Some("location")), FormatMode::Full);
+        assert_eq!(resolve_format(Some("location,city")), FormatMode::Full);
+        assert_eq!(resolve_format(Some("both")), FormatMode::Both);
+        assert_eq!(resolve_format(Some("both,*")), FormatMode::Full);
+        assert_eq!(
+            resolve_format(Some("city, country_name")),
+            FormatMode::Sparse(vec!["city".to_string(), "country_name".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_escape_label_value() {
+        assert_eq!(escape_label_value("GeoLite2-City"), "GeoLite2-City");
+        assert_eq!(escape_label_value("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
     }
 
     #[test]
@@ -879,6 +3690,7 @@ mod tests {
         let error = ApiError {
             error: "Test error".to_string(),
             code: "TEST_ERROR",
+            field: None,
         };
         let json = serde_json::to_string(&error).unwrap();
         assert!(json.contains("Test error"));
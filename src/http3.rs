@@ -5,18 +5,34 @@
 
 use std::net::SocketAddr;
 use std::path::Path;
+use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::Duration;
 
+use axum::body::Body;
+use axum::extract::{ConnectInfo, Query, State};
+use axum::response::IntoResponse;
+use axum::Router;
 use bytes::Bytes;
 use h3::quic::BidiStream;
 use h3::server::RequestStream;
 use h3_quinn::quinn;
-use http::{Request, Response, StatusCode};
+use http::{header, HeaderMap, HeaderValue, Request, Response, StatusCode};
 use rustls::pki_types::{CertificateDer, PrivateKeyDer};
 use tokio::fs;
-use tracing::{error, info};
-
-use crate::handlers::ApiDoc;
+use tracing::{error, info, warn};
+
+use crate::handlers::{
+    countries_response, duplicate_params_response, extract_client_ip, flag_response,
+    ipgeo_full_response, ipgeo_response, ipgeo_schema_response, self_response,
+    timezone_compare_response, timezone_full_response, timezone_response, timezone_schema_response,
+    timezones_response, unknown_params_response, ApiDoc, AppState,
+};
+use crate::models::{
+    CountriesQuery, FormatQuery, IpGeoQuery, TimezoneCompareQuery, TimezoneQuery, TimezonesQuery,
+};
+use crate::ResponseLayers;
+use tower::ServiceExt;
 use utoipa::OpenApi;
 
 /// Configuration for HTTP/3 server
@@ -28,8 +44,26 @@ pub struct Http3Config {
     pub cert_path: String,
     /// Path to TLS private key file (PEM format)
     pub key_path: String,
+    /// Generate a self-signed certificate at `cert_path`/`key_path` when the
+    /// certificate is missing (local testing only)
+    pub self_signed: bool,
+    /// Maximum concurrent request streams per connection
+    pub max_concurrent_bidi_streams: u32,
+    /// Close connections idle for longer than this
+    pub max_idle_timeout: Duration,
+    /// Send keep-alive pings at this interval (`None` disables them)
+    pub keep_alive_interval: Option<Duration>,
 }
 
+/// Default `max_concurrent_bidi_streams` (quinn's default)
+pub const DEFAULT_MAX_CONCURRENT_BIDI_STREAMS: u32 = 100;
+
+/// Default `max_idle_timeout` (quinn's default)
+pub const DEFAULT_MAX_IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Hosts covered by the generated self-signed certificate
+pub const SELF_SIGNED_HOSTS: &[&str] = &["localhost", "127.0.0.1", "::1"];
+
 /// Load TLS certificates from PEM files
 pub async fn load_certs(
     cert_path: &str,
@@ -51,10 +85,34 @@ pub async fn load_certs(
     Ok((certs, key))
 }
 
+/// Generate a self-signed certificate for local HTTP/3 testing
+///
+/// Returns the PEM-encoded certificate and private key, with `hosts` as
+/// subject alternative names (IP addresses become IP SANs).
+pub fn generate_self_signed(
+    hosts: &[String],
+) -> Result<(String, String), Box<dyn std::error::Error + Send + Sync>> {
+    let rcgen::CertifiedKey { cert, key_pair } = rcgen::generate_simple_self_signed(hosts)?;
+    Ok((cert.pem(), key_pair.serialize_pem()))
+}
+
+/// Build the QUIC transport settings from the stream, idle, and keep-alive limits
+pub fn transport_config(
+    config: &Http3Config,
+) -> Result<quinn::TransportConfig, Box<dyn std::error::Error + Send + Sync>> {
+    let mut transport = quinn::TransportConfig::default();
+    transport
+        .max_concurrent_bidi_streams(config.max_concurrent_bidi_streams.into())
+        .max_idle_timeout(Some(config.max_idle_timeout.try_into()?))
+        .keep_alive_interval(config.keep_alive_interval);
+    Ok(transport)
+}
+
 /// Create QUIC server configuration with TLS
 pub fn create_quic_config(
     certs: Vec<CertificateDer<'static>>,
     key: PrivateKeyDer<'static>,
+    transport: quinn::TransportConfig,
 ) -> Result<quinn::ServerConfig, Box<dyn std::error::Error + Send + Sync>> {
     let mut tls_config = rustls::ServerConfig::builder()
         .with_no_client_auth()
@@ -62,32 +120,159 @@ pub fn create_quic_config(
 
     tls_config.alpn_protocols = vec![b"h3".to_vec()];
 
-    let quic_config = quinn::ServerConfig::with_crypto(Arc::new(
+    let mut quic_config = quinn::ServerConfig::with_crypto(Arc::new(
         quinn::crypto::rustls::QuicServerConfig::try_from(tls_config)?,
     ));
+    quic_config.transport_config(Arc::new(transport));
 
     Ok(quic_config)
 }
 
-/// Handle an HTTP/3 request
-async fn handle_request<S>(
+/// Extract the client IP for an HTTP/3 request
+///
+/// Proxy headers win, as with `extract_client_ip`; otherwise the QUIC peer
+/// address is used. Dual-stack sockets report IPv4 peers as IPv4-mapped IPv6
+/// (`::ffff:a.b.c.d`), so those are unmapped before the lookup.
+pub fn quic_client_ip(headers: &HeaderMap, remote_address: SocketAddr) -> String {
+    let peer = SocketAddr::new(remote_address.ip().to_canonical(), remote_address.port());
+    extract_client_ip(headers, Some(peer))
+}
+
+/// Path a request is routed on, or `None` when it is outside `path_prefix` (`PATH_PREFIX`)
+///
+/// Only paths under the prefix exist, like the Axum server's nested router,
+/// and `/ipgeo/` is served as `/ipgeo`, like its trailing_slash_layer.
+fn route_path<'a>(uri: &'a http::Uri, path_prefix: &str) -> Option<&'a str> {
+    let path = uri.path().strip_prefix(path_prefix)?;
+    Some(match path.trim_end_matches('/') {
+        "" => "/",
+        path => path,
+    })
+}
+
+/// Wrap [`route_request`] in the same [`ResponseLayers`] as the Axum server
+///
+/// Requests are served with [`serve_request`].
+pub fn http3_router(state: AppState, layers: &ResponseLayers) -> Router {
+    let attribution = layers.data_attribution.clone();
+    let router = Router::new()
+        .fallback(
+            move |State(state): State<AppState>,
+                  ConnectInfo(remote_address): ConnectInfo<SocketAddr>,
+                  req: axum::extract::Request| {
+                let attribution = attribution.clone();
+                async move {
+                    let req = Request::from_parts(req.into_parts().0, ());
+                    let mut response = route_request(&state, &req, remote_address);
+                    if let Some(attribution) = attribution {
+                        let path_prefix = &state.response_options.path_prefix;
+                        if route_path(req.uri(), path_prefix)
+                            .is_some_and(|p| crate::ATTRIBUTED_PATHS.contains(&p))
+                        {
+                            response
+                                .headers_mut()
+                                .insert("x-data-attribution", attribution);
+                        }
+                    }
+                    response
+                }
+            },
+        )
+        .with_state(state);
+    layers.apply(router)
+}
+
+/// Serve an HTTP/3 request through a router built by [`http3_router`]
+pub async fn serve_request(
+    router: &Router,
     req: Request<()>,
-    stream: &mut RequestStream<S, Bytes>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
-where
-    S: BidiStream<Bytes>,
-{
-    let path = req.uri().path();
-    let method = req.method();
+    remote_address: SocketAddr,
+) -> Response<Body> {
+    let (parts, ()) = req.into_parts();
+    let mut req = Request::from_parts(parts, Body::empty());
+    req.extensions_mut().insert(ConnectInfo(remote_address));
+    match router.clone().oneshot(req).await {
+        Ok(response) => response,
+        Err(infallible) => match infallible {},
+    }
+}
 
-    info!(
-        method = %method,
-        path = %path,
-        "HTTP/3 request"
-    );
+/// Route an HTTP/3 request to the shared, transport-agnostic handlers
+///
+/// Query strings are parsed from the request URI with the same `Query`
+/// extractors the Axum routes use, so validation and error bodies match.
+/// `remote_address` is the QUIC peer, used for `/` without proxy headers.
+pub fn route_request(
+    state: &AppState,
+    req: &Request<()>,
+    remote_address: SocketAddr,
+) -> Response<Body> {
+    let headers = req.headers();
+    let uri = req.uri();
+
+    // Same as the Axum server's reject_duplicate_params middleware
+    if let Some(response) = duplicate_params_response(headers, uri) {
+        return response;
+    }
+
+    let Some(path) = route_path(uri, &state.response_options.path_prefix) else {
+        return text_response(
+            StatusCode::NOT_FOUND,
+            "text/plain; charset=utf-8",
+            String::new(),
+        );
+    };
+
+    // Same as the Axum server's reject_unknown_params middleware
+    if let Some(response) = unknown_params_response(state, headers, path, uri) {
+        return response;
+    }
 
-    let (status, content_type, body) = match path {
-        "/health" => (
+    match path {
+        "/" => match Query::<FormatQuery>::try_from_uri(uri) {
+            Ok(Query(params)) => {
+                let ip = quic_client_ip(headers, remote_address);
+                self_response(state, headers, ip, &params)
+            }
+            Err(rejection) => rejection.into_response(),
+        },
+        "/ipgeo" => match Query::<IpGeoQuery>::try_from_uri(uri) {
+            Ok(Query(params)) => {
+                let client_ip = quic_client_ip(headers, remote_address);
+                ipgeo_response(state, headers, &client_ip, &params)
+            }
+            Err(rejection) => rejection.into_response(),
+        },
+        "/v1/ipgeo" => match Query::<IpGeoQuery>::try_from_uri(uri) {
+            Ok(Query(params)) => ipgeo_full_response(state, headers, &params),
+            Err(rejection) => rejection.into_response(),
+        },
+        "/v1/flag" => match Query::<IpGeoQuery>::try_from_uri(uri) {
+            Ok(Query(params)) => flag_response(state, headers, &params),
+            Err(rejection) => rejection.into_response(),
+        },
+        "/timezone" => match Query::<TimezoneQuery>::try_from_uri(uri) {
+            Ok(Query(params)) => timezone_response(state, headers, &params),
+            Err(rejection) => rejection.into_response(),
+        },
+        "/v1/timezone" => match Query::<TimezoneQuery>::try_from_uri(uri) {
+            Ok(Query(params)) => timezone_full_response(state, headers, &params),
+            Err(rejection) => rejection.into_response(),
+        },
+        "/v1/timezone/compare" => match Query::<TimezoneCompareQuery>::try_from_uri(uri) {
+            Ok(Query(params)) => timezone_compare_response(state, &params),
+            Err(rejection) => rejection.into_response(),
+        },
+        "/v1/timezones" => match Query::<TimezonesQuery>::try_from_uri(uri) {
+            Ok(Query(params)) => timezones_response(&params),
+            Err(rejection) => rejection.into_response(),
+        },
+        "/v1/countries" => match Query::<CountriesQuery>::try_from_uri(uri) {
+            Ok(Query(params)) => countries_response(&params),
+            Err(rejection) => rejection.into_response(),
+        },
+
+        "/health" => text_response(
             StatusCode::OK,
             "text/plain; charset=utf-8",
             "OK".to_string(),
@@ -95,10 +280,13 @@ where
 
         "/openapi.yaml" | "/.well-known/openapi.yaml" => {
             let spec = ApiDoc::openapi().to_yaml().unwrap();
-            (StatusCode::OK, "application/yaml; charset=utf-8", spec)
+            text_response(StatusCode::OK, "application/yaml; charset=utf-8", spec)
         }
 
-        "/llms.txt" => (
+        "/schema/ipgeo" => ipgeo_schema_response(),
+        "/schema/timezone" => timezone_schema_response(),
+
+        "/llms.txt" => text_response(
             StatusCode::OK,
             "text/plain; charset=utf-8",
             include_str!("../llms.txt").to_string(),
@@ -115,7 +303,7 @@ where
   <url><loc>/ipgeo</loc><priority>0.9</priority></url>
   <url><loc>/timezone</loc><priority>0.9</priority></url>
 </urlset>"#;
-            (
+            text_response(
                 StatusCode::OK,
                 "application/xml; charset=utf-8",
                 sitemap.to_string(),
@@ -123,29 +311,57 @@ where
         }
 
         _ => {
-            // For other endpoints, return a redirect hint to use HTTP/1.1 or HTTP/2
-            // Full API functionality requires the Axum handlers with state
+            // Streaming, batch, MCP, and admin endpoints are only served by the Axum server
             let body = serde_json::json!({
                 "error": "This endpoint requires HTTP/1.1 or HTTP/2",
-                "hint": "HTTP/3 currently supports /health, /openapi.yaml, /.well-known/openapi.yaml, /llms.txt, and /sitemap.xml",
+                "hint": "HTTP/3 supports /, /ipgeo, /v1/ipgeo, /v1/flag, /timezone, /v1/timezone, /v1/timezone/compare, /health, /openapi.yaml, /.well-known/openapi.yaml, /schema/ipgeo, /schema/timezone, /llms.txt, and /sitemap.xml",
                 "code": "HTTP3_LIMITED"
             });
-            (
+            text_response(
                 StatusCode::NOT_IMPLEMENTED,
                 "application/json; charset=utf-8",
                 body.to_string(),
             )
         }
-    };
+    }
+}
 
-    let response = Response::builder()
+/// Build a response with a fixed body and content type
+fn text_response(status: StatusCode, content_type: &'static str, body: String) -> Response<Body> {
+    Response::builder()
         .status(status)
-        .header("content-type", content_type)
-        .header("alt-svc", "h3=\":443\"; ma=86400")
-        .body(())?;
+        .header(header::CONTENT_TYPE, content_type)
+        .body(Body::from(body))
+        .unwrap()
+}
 
-    stream.send_response(response).await?;
-    stream.send_data(Bytes::from(body)).await?;
+/// Handle an HTTP/3 request
+async fn handle_request<S>(
+    router: &Router,
+    remote_address: SocketAddr,
+    alt_svc: &HeaderValue,
+    req: Request<()>,
+    stream: &mut RequestStream<S, Bytes>,
+) -> Result<(), Box<dyn std::error::Error + Send + Sync>>
+where
+    S: BidiStream<Bytes>,
+{
+    info!(
+        method = %req.method(),
+        path = %req.uri().path(),
+        "HTTP/3 request"
+    );
+
+    let (mut parts, body) = serve_request(router, req, remote_address)
+        .await
+        .into_parts();
+    let body = axum::body::to_bytes(body, usize::MAX).await?;
+    parts.headers.insert(header::ALT_SVC, alt_svc.clone());
+
+    stream
+        .send_response(Response::from_parts(parts, ()))
+        .await?;
+    stream.send_data(body).await?;
     stream.finish().await?;
 
     Ok(())
@@ -158,6 +374,8 @@ where
 ///
 /// # Arguments
 /// * `config` - HTTP/3 server configuration
+/// * `state` - Shared application state (GeoIP reader, cache, base URL)
+/// * `layers` - Response headers, signing and load shedding shared with the Axum server
 ///
 /// # Example
 /// ```ignore
@@ -165,12 +383,30 @@ where
 ///     bind_address: "0.0.0.0:443".parse().unwrap(),
 ///     cert_path: "cert.pem".to_string(),
 ///     key_path: "key.pem".to_string(),
+///     self_signed: false,
+///     max_concurrent_bidi_streams: DEFAULT_MAX_CONCURRENT_BIDI_STREAMS,
+///     max_idle_timeout: DEFAULT_MAX_IDLE_TIMEOUT,
+///     keep_alive_interval: None,
 /// };
-/// run_http3_server(config).await?;
+/// run_http3_server(config, state.clone(), ResponseLayers::default()).await?;
 /// ```
 pub async fn run_http3_server(
     config: Http3Config,
+    state: AppState,
+    layers: ResponseLayers,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    if config.self_signed && !Path::new(&config.cert_path).exists() {
+        let hosts: Vec<String> = SELF_SIGNED_HOSTS.iter().map(|h| h.to_string()).collect();
+        let (cert_pem, key_pem) = generate_self_signed(&hosts)?;
+        fs::write(&config.cert_path, cert_pem).await?;
+        fs::write(&config.key_path, key_pem).await?;
+        warn!(
+            "Generated self-signed certificate for {} at {} (not for production)",
+            hosts.join(", "),
+            config.cert_path
+        );
+    }
+
     // Check if certificate files exist
     if !Path::new(&config.cert_path).exists() {
         error!(
@@ -191,7 +427,7 @@ pub async fn run_http3_server(
     let (certs, key) = load_certs(&config.cert_path, &config.key_path).await?;
 
     // Create QUIC configuration
-    let quic_config = create_quic_config(certs, key)?;
+    let quic_config = create_quic_config(certs, key, transport_config(&config)?)?;
 
     // Create QUIC endpoint
     let endpoint = quinn::Endpoint::server(quic_config, config.bind_address)?;
@@ -200,13 +436,21 @@ pub async fn run_http3_server(
         "HTTP/3 server listening on {} (UDP/QUIC)",
         config.bind_address
     );
+    if let Some(running) = &state.http3_running {
+        running.store(true, Ordering::Relaxed);
+    }
+
+    let alt_svc = crate::alt_svc_value(config.bind_address.port());
+    let router = http3_router(state.clone(), &layers);
 
     // Accept connections
     while let Some(incoming) = endpoint.accept().await {
+        let router = router.clone();
+        let alt_svc = alt_svc.clone();
         tokio::spawn(async move {
             match incoming.await {
                 Ok(connection) => {
-                    if let Err(e) = handle_connection(connection).await {
+                    if let Err(e) = handle_connection(connection, router, alt_svc).await {
                         error!("HTTP/3 connection error: {}", e);
                     }
                 }
@@ -217,22 +461,33 @@ pub async fn run_http3_server(
         });
     }
 
+    if let Some(running) = &state.http3_running {
+        running.store(false, Ordering::Relaxed);
+    }
     Ok(())
 }
 
 /// Handle an HTTP/3 connection
 async fn handle_connection(
     connection: quinn::Connection,
+    router: Router,
+    alt_svc: HeaderValue,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let remote_address = connection.remote_address();
     let mut h3_conn = h3::server::Connection::new(h3_quinn::Connection::new(connection)).await?;
 
     loop {
         match h3_conn.accept().await {
             Ok(Some(resolver)) => {
+                let router = router.clone();
+                let alt_svc = alt_svc.clone();
                 tokio::spawn(async move {
                     match resolver.resolve_request().await {
                         Ok((req, mut stream)) => {
-                            if let Err(e) = handle_request(req, &mut stream).await {
+                            if let Err(e) =
+                                handle_request(&router, remote_address, &alt_svc, req, &mut stream)
+                                    .await
+                            {
                                 error!("HTTP/3 request error: {}", e);
                             }
                         }
@@ -266,11 +521,192 @@ mod tests {
             bind_address: "0.0.0.0:443".parse().unwrap(),
             cert_path: "cert.pem".to_string(),
             key_path: "key.pem".to_string(),
+            self_signed: false,
+            max_concurrent_bidi_streams: DEFAULT_MAX_CONCURRENT_BIDI_STREAMS,
+            max_idle_timeout: DEFAULT_MAX_IDLE_TIMEOUT,
+            keep_alive_interval: None,
         };
 
         assert_eq!(config.bind_address.port(), 443);
     }
 
+    #[test]
+    fn test_transport_config_custom_values() {
+        let config = Http3Config {
+            bind_address: "0.0.0.0:443".parse().unwrap(),
+            cert_path: "cert.pem".to_string(),
+            key_path: "key.pem".to_string(),
+            self_signed: false,
+            max_concurrent_bidi_streams: 25,
+            max_idle_timeout: Duration::from_secs(120),
+            keep_alive_interval: Some(Duration::from_secs(15)),
+        };
+
+        // TransportConfig has no getters, so check its Debug output
+        let transport = format!("{:?}", transport_config(&config).unwrap());
+        assert!(transport.contains("max_concurrent_bidi_streams: 25"));
+        assert!(transport.contains("max_idle_timeout: Some(120000)"));
+        assert!(transport.contains("keep_alive_interval: Some(15s)"));
+    }
+
+    #[tokio::test]
+    async fn test_generate_self_signed_loads() {
+        let hosts = vec!["localhost".to_string(), "127.0.0.1".to_string()];
+        let (cert_pem, key_pem) = generate_self_signed(&hosts).unwrap();
+
+        let dir = std::env::temp_dir().join(format!("ipgeo-h3-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cert_path = dir.join("cert.pem");
+        let key_path = dir.join("key.pem");
+        std::fs::write(&cert_path, cert_pem).unwrap();
+        std::fs::write(&key_path, key_pem).unwrap();
+
+        let (certs, key) = load_certs(cert_path.to_str().unwrap(), key_path.to_str().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(certs.len(), 1);
+        assert!(matches!(key, PrivateKeyDer::Pkcs8(_)));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_route_request_uses_shared_handlers() {
+        use crate::cache::{CacheConfig, GeoCache};
+        use crate::geoip::mock::MockGeoIpReader;
+        use crate::models::GeoData;
+
+        let geoip = MockGeoIpReader::new().with_response(
+            "8.8.8.8",
+            Ok(GeoData {
+                city: Some("Mountain View".to_string()),
+                country_code: Some("US".to_string()),
+                ..Default::default()
+            }),
+        );
+        let state = AppState::new(
+            Arc::new(geoip),
+            Arc::new(GeoCache::new(CacheConfig::default())),
+            "https://test.example.com".to_string(),
+        );
+
+        async fn get(state: &AppState, uri: &str) -> (StatusCode, serde_json::Value) {
+            let req = Request::builder().uri(uri).body(()).unwrap();
+            let peer = "203.0.113.9:4433".parse().unwrap();
+            let (parts, body) = route_request(state, &req, peer).into_parts();
+            let body = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+            (parts.status, serde_json::from_slice(&body).unwrap())
+        }
+
+        let (status, json) = get(&state, "/ipgeo?ip=8.8.8.8").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["city"], "Mountain View");
+
+        let (status, json) = get(&state, "/ipgeo/?ip=8.8.8.8").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["city"], "Mountain View");
+
+        let (status, json) = get(&state, "/v1/ipgeo?ip=8.8.8.8").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["ip"], "8.8.8.8");
+
+        let (status, json) = get(&state, "/v1/ipgeo?ip=not-an-ip").await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(json["code"], "INVALID_IP");
+
+        let (status, json) = get(&state, "/timezone?lat=59.33&long=18.07").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(json["timezone"], "Europe/Stockholm");
+
+        // Without proxy headers, `/` looks up the QUIC peer (a documentation
+        // address here, so the response is empty rather than an error)
+        let (status, json) = get(&state, "/").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(json.is_object());
+
+        let (status, json) = get(&state, "/v1/self/stream").await;
+        assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
+        assert_eq!(json["code"], "HTTP3_LIMITED");
+    }
+
+    #[tokio::test]
+    async fn test_http3_router_applies_response_layers() {
+        use crate::cache::{CacheConfig, GeoCache};
+        use crate::geoip::mock::MockGeoIpReader;
+        use crate::models::GeoData;
+        use crate::signing::{sign, signing_key, SIGNATURE_HEADER};
+
+        let geoip = MockGeoIpReader::new().with_response(
+            "8.8.8.8",
+            Ok(GeoData {
+                country_code: Some("US".to_string()),
+                ..Default::default()
+            }),
+        );
+        let state = AppState::new(
+            Arc::new(geoip),
+            Arc::new(GeoCache::new(CacheConfig::default())),
+            "https://test.example.com".to_string(),
+        );
+        let layers = ResponseLayers {
+            data_attribution: Some(HeaderValue::from_static(crate::DEFAULT_DATA_ATTRIBUTION)),
+            security_headers: true,
+            signing_key: Some(signing_key("secret")),
+            concurrency_limit: None,
+        };
+        let router = http3_router(state.clone(), &layers);
+        let peer: SocketAddr = "203.0.113.9:4433".parse().unwrap();
+        let get = |uri: &str| Request::builder().uri(uri).body(()).unwrap();
+
+        let (parts, body) = serve_request(&router, get("/ipgeo?ip=8.8.8.8"), peer)
+            .await
+            .into_parts();
+        let body = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+        assert_eq!(parts.status, StatusCode::OK);
+        assert_eq!(
+            parts.headers["x-data-attribution"],
+            crate::DEFAULT_DATA_ATTRIBUTION
+        );
+        assert_eq!(parts.headers[SIGNATURE_HEADER], sign(b"secret", &body));
+        assert_eq!(parts.headers[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+        assert!(parts.headers.contains_key(header::SERVER));
+
+        let response = serve_request(&router, get("/v1/flag?ip=8.8.8.8"), peer).await;
+        assert!(response.headers().contains_key("x-data-attribution"));
+
+        // Non-geolocation endpoints are signed but not attributed
+        let response = serve_request(&router, get("/timezone?lat=59.33&long=18.07"), peer).await;
+        assert!(!response.headers().contains_key("x-data-attribution"));
+        assert!(response.headers().contains_key(SIGNATURE_HEADER));
+
+        // MAX_CONCURRENT_REQUESTS sheds QUIC requests too
+        let layers = ResponseLayers {
+            concurrency_limit: Some(crate::overload::concurrency_limit(0)),
+            ..Default::default()
+        };
+        let router = http3_router(state, &layers);
+        let response = serve_request(&router, get("/ipgeo?ip=8.8.8.8"), peer).await;
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    #[test]
+    fn test_quic_client_ip() {
+        let peer: SocketAddr = "[2001:4860:4860::8888]:4433".parse().unwrap();
+        assert_eq!(
+            quic_client_ip(&HeaderMap::new(), peer),
+            "2001:4860:4860::8888"
+        );
+
+        // IPv4 peers on a dual-stack socket arrive IPv4-mapped
+        let mapped: SocketAddr = "[::ffff:8.8.8.8]:4433".parse().unwrap();
+        assert_eq!(quic_client_ip(&HeaderMap::new(), mapped), "8.8.8.8");
+
+        // Proxy headers take precedence, as on HTTP/1.1 and HTTP/2
+        let mut headers = HeaderMap::new();
+        headers.insert("X-Forwarded-For", "1.1.1.1, 10.0.0.1".parse().unwrap());
+        assert_eq!(quic_client_ip(&headers, mapped), "1.1.1.1");
+    }
+
     #[test]
     fn test_accepts_protobuf_helper() {
         use crate::proto::accepts_protobuf;
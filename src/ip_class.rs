@@ -0,0 +1,83 @@
+use std::net::IpAddr;
+
+use crate::bogons::is_bogon;
+
+/// Kind of address, deciding whether a GeoIP lookup is meaningful
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpType {
+    /// Globally routable address, looked up in the database
+    Public,
+    /// The zero address (`0.0.0.0` or `::`)
+    Unspecified,
+    /// Private, loopback, documentation, CGNAT, or other bogon range
+    Reserved,
+}
+
+impl IpType {
+    /// Value reported as `network.ip_type`
+    pub fn as_str(self) -> &'static str {
+        match self {
+            IpType::Public => "public",
+            IpType::Unspecified => "unspecified",
+            IpType::Reserved => "reserved",
+        }
+    }
+
+    /// Whether the address should be looked up in the GeoIP database
+    pub fn is_public(self) -> bool {
+        self == IpType::Public
+    }
+}
+
+/// Classify an address before looking it up
+///
+/// Non-public addresses can never be geolocated, so handlers answer them with
+/// an empty response instead of querying MaxMind.
+pub fn classify_ip(ip: &IpAddr) -> IpType {
+    if ip.is_unspecified() {
+        IpType::Unspecified
+    } else if is_bogon(ip) {
+        IpType::Reserved
+    } else {
+        IpType::Public
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_ip_unspecified() {
+        assert_eq!(
+            classify_ip(&"0.0.0.0".parse().unwrap()),
+            IpType::Unspecified
+        );
+        assert_eq!(classify_ip(&"::".parse().unwrap()), IpType::Unspecified);
+        assert_eq!(IpType::Unspecified.as_str(), "unspecified");
+    }
+
+    #[test]
+    fn test_classify_ip_reserved() {
+        assert_eq!(
+            classify_ip(&"100.64.0.1".parse().unwrap()),
+            IpType::Reserved
+        );
+        assert_eq!(classify_ip(&"192.0.2.1".parse().unwrap()), IpType::Reserved);
+        assert_eq!(
+            classify_ip(&"2001:db8::1".parse().unwrap()),
+            IpType::Reserved
+        );
+        assert!(!IpType::Reserved.is_public());
+    }
+
+    #[test]
+    fn test_classify_ip_public() {
+        assert_eq!(classify_ip(&"8.8.8.8".parse().unwrap()), IpType::Public);
+        assert_eq!(
+            classify_ip(&"2001:4860:4860::8888".parse().unwrap()),
+            IpType::Public
+        );
+        assert!(IpType::Public.is_public());
+    }
+}
@@ -165,17 +165,51 @@ pub static COUNTRY_LANGUAGES: LazyLock<HashMap<&'static str, &'static str>> = La
     m
 });
 
+/// Parse `LANGUAGE_OVERRIDES`, a semicolon-separated list of `CC=codes` pairs
+///
+/// Example: `US=es-US,es;GB=en`
+pub fn parse_language_overrides(spec: &str) -> Result<HashMap<String, String>, String> {
+    spec.split(';')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (code, languages) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("expected 'CC=codes', got '{}'", entry))?;
+            let code = code.trim();
+            if code.len() != 2 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(format!("invalid country code '{}'", code));
+            }
+            Ok((code.to_uppercase(), languages.trim().to_string()))
+        })
+        .collect()
+}
+
 /// Get languages string for a country code
 /// Returns empty string if country code is not found
 #[must_use]
 pub fn get_languages(country_code: Option<&str>) -> String {
-    match country_code {
-        Some(code) => COUNTRY_LANGUAGES
-            .get(code.to_uppercase().as_str())
+    get_languages_with(&HashMap::new(), country_code)
+}
+
+/// Look up languages in `overrides` (from `LANGUAGE_OVERRIDES`) first,
+/// falling back to the static map
+#[must_use]
+pub fn get_languages_with(
+    overrides: &HashMap<String, String>,
+    country_code: Option<&str>,
+) -> String {
+    let Some(code) = country_code.map(str::to_uppercase) else {
+        return String::new();
+    };
+
+    match overrides.get(&code) {
+        Some(languages) => languages.clone(),
+        None => COUNTRY_LANGUAGES
+            .get(code.as_str())
             .copied()
             .unwrap_or("")
             .to_string(),
-        None => String::new(),
     }
 }
 
@@ -203,4 +237,20 @@ mod tests {
     fn test_get_languages_none() {
         assert_eq!(get_languages(None), "");
     }
+
+    #[test]
+    fn test_get_languages_override() {
+        let overrides = parse_language_overrides("us=es-US,es; GB=en").unwrap();
+        assert_eq!(get_languages_with(&overrides, Some("US")), "es-US,es");
+        assert_eq!(get_languages_with(&overrides, Some("gb")), "en");
+        // Countries without an override fall back to the static map
+        assert_eq!(get_languages_with(&overrides, Some("SE")), "sv-SE,sv");
+    }
+
+    #[test]
+    fn test_parse_language_overrides_invalid() {
+        assert!(parse_language_overrides("US").is_err());
+        assert!(parse_language_overrides("USA=en").is_err());
+        assert!(parse_language_overrides("").unwrap().is_empty());
+    }
 }
@@ -1,17 +1,67 @@
+// serde_json's json! macro recurses per token; the full response schema in
+// mcp::schemas outgrows the default limit of 128
+#![recursion_limit = "256"]
+
+pub mod airports;
+pub mod bogons;
 pub mod cache;
 pub mod country_data;
+pub mod formats;
 pub mod geoip;
 pub mod handlers;
 pub mod http3;
+pub mod ip_class;
 pub mod languages;
+pub mod logging;
 pub mod mcp;
 pub mod models;
+pub mod overload;
 pub mod proto;
+pub mod selftest;
+pub mod signing;
+pub mod static_mounts;
 pub mod timezone;
 pub mod tz_utils;
 
-use axum::http::Method;
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderValue, Method};
+use axum::middleware::Next;
+use axum::response::Response;
+use axum::Router;
+use tower::layer::util::Stack;
 use tower_http::cors::{Any, CorsLayer};
+use tower_http::normalize_path::NormalizePathLayer;
+use tower_http::set_header::SetResponseHeaderLayer;
+
+/// Parse a `PATH_PREFIX` value into the prefix every route is served under
+/// behind a reverse proxy (e.g. "/geoip")
+///
+/// Leading/trailing slashes are optional (`geoip/` becomes `/geoip`); `/` or an
+/// empty value means no prefix ("").
+pub fn parse_path_prefix(value: &str) -> Result<String, String> {
+    let segments = value.trim().trim_matches('/');
+    if segments.contains(['?', '#', ' ']) || segments.contains("//") {
+        return Err(format!(
+            "PATH_PREFIX must be a URL path like /geoip, got '{}'",
+            value
+        ));
+    }
+    if segments.is_empty() {
+        Ok(String::new())
+    } else {
+        Ok(format!("/{}", segments))
+    }
+}
+
+/// Serve every route of `app` under `prefix` (see `parse_path_prefix`); an empty
+/// prefix returns `app` unchanged. Shared by the server and the tests.
+pub fn nest_under_prefix(app: Router, prefix: &str) -> Router {
+    if prefix.is_empty() {
+        app
+    } else {
+        Router::new().nest(prefix, app)
+    }
+}
 
 /// CORS policy for the public API: any origin may read these read-only
 /// geolocation responses (GET/HEAD only), so browser apps — for example static
@@ -22,3 +72,151 @@ pub fn cors_layer() -> CorsLayer {
         .allow_origin(Any)
         .allow_methods([Method::GET, Method::HEAD])
 }
+
+/// Adds `Server: ipgeolocation/<version>` to every response so behavior can be
+/// correlated with deploys. Shared by the server and the integration tests.
+pub fn server_header_layer() -> SetResponseHeaderLayer<HeaderValue> {
+    SetResponseHeaderLayer::if_not_present(
+        header::SERVER,
+        HeaderValue::from_static(concat!("ipgeolocation/", env!("CARGO_PKG_VERSION"))),
+    )
+}
+
+/// Strips trailing slashes before routing, so `/ipgeo/` is served as `/ipgeo`
+/// instead of a confusing 404. Must wrap the whole `Router` (layers added with
+/// `Router::layer` run after routing). Shared by the server and the tests.
+pub fn trailing_slash_layer() -> NormalizePathLayer {
+    NormalizePathLayer::trim_trailing_slash()
+}
+
+/// `Alt-Svc` value advertising HTTP/3 on `port` for 24 hours
+pub fn alt_svc_value(port: u16) -> HeaderValue {
+    HeaderValue::from_str(&format!("h3=\":{}\"; ma=86400", port)).unwrap()
+}
+
+/// Adds `Alt-Svc: h3=":<port>"; ma=86400` so browsers upgrade to HTTP/3.
+/// Applied to the HTTP/1.1+2 server only when `HTTP3_ENABLED` is set.
+pub fn alt_svc_layer(port: u16) -> SetResponseHeaderLayer<HeaderValue> {
+    SetResponseHeaderLayer::if_not_present(header::ALT_SVC, alt_svc_value(port))
+}
+
+/// Default `X-Data-Attribution` text, as required by the GeoLite2 license
+pub const DEFAULT_DATA_ATTRIBUTION: &str = "GeoLite2 data by MaxMind (https://www.maxmind.com)";
+
+/// Routes returning GeoLite2-derived data, relative to `PATH_PREFIX`
+///
+/// Both transports add `X-Data-Attribution` to exactly these. `/v1/flag`
+/// resolves the country from an IP, so its answer is derived data too.
+pub const ATTRIBUTED_PATHS: &[&str] = &[
+    "/",
+    "/ipgeo",
+    "/v1/ipgeo",
+    "/v1/flag",
+    "/v1/self/stream",
+    "/admin/all",
+];
+
+/// Adds `X-Data-Attribution` to responses for [`ATTRIBUTED_PATHS`] so the
+/// attribution travels with the data
+async fn add_data_attribution(
+    State(attribution): State<HeaderValue>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let attributed = ATTRIBUTED_PATHS.contains(&request.uri().path());
+    let mut response = next.run(request).await;
+    if attributed {
+        response
+            .headers_mut()
+            .insert("x-data-attribution", attribution);
+    }
+    response
+}
+
+/// `X-Content-Type-Options`, `X-Frame-Options` and `Referrer-Policy` layers
+pub type SecurityHeadersLayer = Stack<
+    SetResponseHeaderLayer<HeaderValue>,
+    Stack<SetResponseHeaderLayer<HeaderValue>, SetResponseHeaderLayer<HeaderValue>>,
+>;
+
+/// Adds `X-Content-Type-Options: nosniff`, `X-Frame-Options: DENY` and
+/// `Referrer-Policy: no-referrer` so browsers don't sniff JSON as HTML or frame
+/// the landing page. Skipped via `DISABLE_SECURITY_HEADERS` when a proxy sets them.
+pub fn security_headers_layer() -> SecurityHeadersLayer {
+    Stack::new(
+        SetResponseHeaderLayer::if_not_present(
+            header::X_CONTENT_TYPE_OPTIONS,
+            HeaderValue::from_static("nosniff"),
+        ),
+        Stack::new(
+            SetResponseHeaderLayer::if_not_present(
+                header::X_FRAME_OPTIONS,
+                HeaderValue::from_static("DENY"),
+            ),
+            SetResponseHeaderLayer::if_not_present(
+                header::REFERRER_POLICY,
+                HeaderValue::from_static("no-referrer"),
+            ),
+        ),
+    )
+}
+
+/// Response layers shared by the HTTP/1.1+2 and HTTP/3 servers
+///
+/// Both transports wrap their routes with [`apply`](Self::apply), so a lookup
+/// carries the same headers and signature whichever protocol served it.
+#[derive(Clone, Default)]
+pub struct ResponseLayers {
+    /// `X-Data-Attribution` for geolocation routes (`DATA_ATTRIBUTION`)
+    pub data_attribution: Option<HeaderValue>,
+    /// Add nosniff / X-Frame-Options / Referrer-Policy (off with `DISABLE_SECURITY_HEADERS`)
+    pub security_headers: bool,
+    /// Sign response bodies into `X-Signature` (`RESPONSE_SIGNING_KEY`)
+    pub signing_key: Option<signing::SigningKey>,
+    /// Shed requests beyond `MAX_CONCURRENT_REQUESTS` with 503 OVERLOADED
+    pub concurrency_limit: Option<overload::ConcurrencyLimit>,
+}
+
+impl ResponseLayers {
+    /// Add `X-Data-Attribution` to the [`ATTRIBUTED_PATHS`] of `app`
+    ///
+    /// Applied before nesting under `PATH_PREFIX`, so paths match unprefixed.
+    /// `None` (an empty `DATA_ATTRIBUTION`) adds nothing.
+    pub fn attribute(&self, app: Router) -> Router {
+        match &self.data_attribution {
+            Some(attribution) => app.layer(axum::middleware::from_fn_with_state(
+                attribution.clone(),
+                add_data_attribution,
+            )),
+            None => app,
+        }
+    }
+
+    /// Wrap `app` with load shedding, signing, and the security and `Server` headers
+    pub fn apply(&self, app: Router) -> Router {
+        let app = match &self.concurrency_limit {
+            Some(limit) => app.layer(axum::middleware::from_fn_with_state(
+                limit.clone(),
+                overload::shed_overload,
+            )),
+            None => app,
+        };
+
+        // Nothing outside this layer rewrites the body, so the signature
+        // matches the bytes sent
+        let app = match &self.signing_key {
+            Some(key) => app.layer(axum::middleware::from_fn_with_state(
+                key.clone(),
+                signing::sign_response,
+            )),
+            None => app,
+        };
+
+        let app = if self.security_headers {
+            app.layer(security_headers_layer())
+        } else {
+            app
+        };
+        app.layer(server_header_layer())
+    }
+}
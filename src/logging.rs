@@ -0,0 +1,257 @@
+use std::str::FromStr;
+
+use axum::http::Uri;
+use tracing::Subscriber;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+/// Log output format, selected via `LOG_FORMAT`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogFormat {
+    /// Human-readable text (default)
+    #[default]
+    Pretty,
+    /// One JSON object per line, for Loki/ELK ingestion
+    Json,
+}
+
+impl FromStr for LogFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "pretty" => Ok(Self::Pretty),
+            "json" => Ok(Self::Json),
+            other => Err(format!(
+                "unknown log format '{}' (expected 'pretty' or 'json')",
+                other
+            )),
+        }
+    }
+}
+
+/// Query parameters carrying credentials, never written to access logs
+const SECRET_PARAMS: &[&str] = &["apiKey", "api_key"];
+
+/// Query parameters whose values `LOG_REDACT_IP_PARAM` hides from access logs
+const LOCATION_PARAMS: &[&str] = &["ip", "lat", "long"];
+
+/// Render a request URI for the access log with `apiKey` values (and, with
+/// `redact_location`, `ip`, `lat`, and `long` values) replaced by
+/// `[redacted]`, keeping every other parameter as sent
+pub fn redact_uri(uri: &Uri, redact_location: bool) -> String {
+    let Some(query) = uri.query() else {
+        return uri.to_string();
+    };
+    let redacted = |key: &str| {
+        SECRET_PARAMS.contains(&key) || (redact_location && LOCATION_PARAMS.contains(&key))
+    };
+    let query: Vec<String> = query
+        .split('&')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, _)) if redacted(key) => format!("{key}=[redacted]"),
+            _ => pair.to_string(),
+        })
+        .collect();
+    format!("{}?{}", uri.path(), query.join("&"))
+}
+
+/// How the access log reports a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessLogLevel {
+    /// Logged at info (the default)
+    Info,
+    /// Logged at debug, for paths listed in `QUIET_PATHS`
+    Debug,
+    /// Not logged at all (`/favicon.ico`, requested by every browser visit)
+    Skip,
+}
+
+/// Parse `QUIET_PATHS`: comma-separated paths, where a trailing `*` matches
+/// every path with that prefix (e.g. `/health*`)
+pub fn parse_quiet_paths(spec: &str) -> Vec<String> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Decide how the access log reports a request for `path`
+pub fn access_log_level(path: &str, quiet_paths: &[String]) -> AccessLogLevel {
+    if path == "/favicon.ico" {
+        return AccessLogLevel::Skip;
+    }
+    let quiet = quiet_paths
+        .iter()
+        .any(|quiet| match quiet.strip_suffix('*') {
+            Some(prefix) => path.starts_with(prefix),
+            None => path == quiet,
+        });
+    if quiet {
+        AccessLogLevel::Debug
+    } else {
+        AccessLogLevel::Info
+    }
+}
+
+/// Build the stdout formatting layer for the given format
+pub fn fmt_layer<S>(format: LogFormat) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fmt_layer_with_writer(format, std::io::stdout)
+}
+
+/// Build the formatting layer writing to an arbitrary writer
+///
+/// In JSON mode, span fields (request_id, client_ip, method, uri) are emitted
+/// under `span` and event fields (status, latency_ms) are flattened to the top
+/// level, so every value stays a structured key rather than part of the message.
+fn fmt_layer_with_writer<S, W>(format: LogFormat, writer: W) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    let layer = tracing_subscriber::fmt::layer().with_writer(writer);
+    match format {
+        LogFormat::Pretty => layer.boxed(),
+        LogFormat::Json => layer
+            .json()
+            .flatten_event(true)
+            .with_current_span(true)
+            .with_span_list(false)
+            .boxed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+    use tracing_subscriber::layer::SubscriberExt;
+
+    #[derive(Clone, Default)]
+    struct BufWriter(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for BufWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for BufWriter {
+        type Writer = Self;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    #[test]
+    fn test_log_format_from_str() {
+        assert_eq!("pretty".parse::<LogFormat>(), Ok(LogFormat::Pretty));
+        assert_eq!("JSON".parse::<LogFormat>(), Ok(LogFormat::Json));
+        assert!("xml".parse::<LogFormat>().is_err());
+        assert_eq!(LogFormat::default(), LogFormat::Pretty);
+    }
+
+    #[test]
+    fn test_access_log_level() {
+        let quiet = parse_quiet_paths(" /health*, /metrics ,");
+        assert_eq!(quiet, vec!["/health*", "/metrics"]);
+
+        assert_eq!(access_log_level("/health", &quiet), AccessLogLevel::Debug);
+        assert_eq!(
+            access_log_level("/health/database", &quiet),
+            AccessLogLevel::Debug
+        );
+        assert_eq!(access_log_level("/metrics", &quiet), AccessLogLevel::Debug);
+        // Exact entries don't match longer paths
+        assert_eq!(access_log_level("/metrics2", &quiet), AccessLogLevel::Info);
+        assert_eq!(access_log_level("/ipgeo", &quiet), AccessLogLevel::Info);
+        assert_eq!(access_log_level("/health", &[]), AccessLogLevel::Info);
+        assert_eq!(access_log_level("/favicon.ico", &[]), AccessLogLevel::Skip);
+    }
+
+    #[test]
+    fn test_redact_uri() {
+        let uri: Uri = "/v1/ipgeo?ip=8.8.8.8&pretty=true".parse().unwrap();
+        assert_eq!(
+            redact_uri(&uri, true),
+            "/v1/ipgeo?ip=[redacted]&pretty=true"
+        );
+        assert_eq!(redact_uri(&uri, false), "/v1/ipgeo?ip=8.8.8.8&pretty=true");
+
+        let uri: Uri = "/timezone?lat=59.33&long=18.07".parse().unwrap();
+        assert_eq!(
+            redact_uri(&uri, true),
+            "/timezone?lat=[redacted]&long=[redacted]"
+        );
+
+        // Only exact parameter names are redacted
+        let uri: Uri = "/ipgeo?ips=1&zip=2".parse().unwrap();
+        assert_eq!(redact_uri(&uri, true), "/ipgeo?ips=1&zip=2");
+        let uri: Uri = "/health".parse().unwrap();
+        assert_eq!(redact_uri(&uri, true), "/health");
+    }
+
+    #[test]
+    fn test_redact_uri_api_key() {
+        // Credentials are redacted whether or not location redaction is on
+        let uri: Uri = "/ipgeo?ip=8.8.8.8&apiKey=secret".parse().unwrap();
+        assert_eq!(
+            redact_uri(&uri, false),
+            "/ipgeo?ip=8.8.8.8&apiKey=[redacted]"
+        );
+        assert_eq!(
+            redact_uri(&uri, true),
+            "/ipgeo?ip=[redacted]&apiKey=[redacted]"
+        );
+        let uri: Uri = "/timezone?api_key=secret&lat=1&long=2".parse().unwrap();
+        assert_eq!(
+            redact_uri(&uri, false),
+            "/timezone?api_key=[redacted]&lat=1&long=2"
+        );
+    }
+
+    #[test]
+    fn test_json_layer_emits_structured_lines() {
+        let buf = BufWriter::default();
+        let subscriber = tracing_subscriber::registry()
+            .with(fmt_layer_with_writer(LogFormat::Json, buf.clone()));
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!(
+                "request",
+                request_id = "abc-123",
+                method = "GET",
+                uri = "/v1/ipgeo",
+                client_ip = "8.8.8.8",
+            );
+            let _guard = span.enter();
+            tracing::info!(
+                status = 200u16,
+                latency_ms = 3u64,
+                "finished processing request"
+            );
+        });
+
+        let output = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+        let line = output.lines().next().expect("one log line");
+        let json: serde_json::Value = serde_json::from_str(line).expect("valid JSON");
+
+        assert_eq!(json["status"], 200);
+        assert_eq!(json["latency_ms"], 3);
+        assert_eq!(json["span"]["request_id"], "abc-123");
+        assert_eq!(json["span"]["client_ip"], "8.8.8.8");
+        assert_eq!(json["span"]["method"], "GET");
+        assert_eq!(json["span"]["uri"], "/v1/ipgeo");
+    }
+}
@@ -1,37 +1,68 @@
 use axum::{
-    extract::ConnectInfo,
-    routing::{get, post},
+    extract::{ConnectInfo, Request},
+    http::HeaderValue,
+    routing::{delete, get, post},
     Router,
 };
 use std::env;
 use std::net::SocketAddr;
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 use std::time::Duration;
-use tower_http::services::ServeDir;
+use tower::Layer;
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
+use tower_http::services::ServeFile;
 use tower_http::trace::TraceLayer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use ipgeolocation::cache::{CacheConfig, GeoCache};
-use ipgeolocation::geoip::GeoIpReader;
+use ipgeolocation::country_data::{parse_default_flag, DEFAULT_FLAG};
+use ipgeolocation::geoip::{
+    parse_name_locale, ChainedGeoIpReader, DatabasePaths, GeoIpReader, NullGeoIpReader,
+    OverrideGeoIpReader, ReloadableGeoIpReader, SharedGeoIpReader, DEFAULT_NAME_LOCALE,
+};
 use ipgeolocation::handlers::{
-    extract_client_ip, health_handler, ipgeo_full_handler, ipgeo_handler, llms_txt_handler,
-    openapi_handler, robots_txt_handler, root_handler, sitemap_handler, timezone_full_handler,
-    timezone_handler, wellknown_ai_plugin_handler, wellknown_openapi_handler, AppState,
+    admin_all_handler, admin_cache_flush_handler, admin_cache_invalidate_handler,
+    admin_raw_handler, admin_reload_handler, countries_handler, extract_client_ip, flag_handler,
+    health_database_handler, health_handler, health_ready_handler, ipgeo_full_handler,
+    ipgeo_handler, llms_txt_handler, metrics_handler, openapi_handler, proto_definition_handler,
+    reject_duplicate_params, reject_unknown_params, robots_txt_handler, root_handler,
+    schema_ipgeo_handler, schema_timezone_handler, self_stream_handler, sitemap_handler,
+    timezone_batch_handler, timezone_compare_handler, timezone_full_handler, timezone_handler,
+    timezones_handler, version_handler, wellknown_ai_plugin_handler, wellknown_openapi_handler,
+    AppState, OmitFields, ResponseOptions, DEFAULT_COORD_MAX_PRECISION, DEFAULT_MAX_DB_AGE_DAYS,
+    DEFAULT_MAX_QUERY_LEN,
+};
+use ipgeolocation::http3::{
+    run_http3_server, Http3Config, DEFAULT_MAX_CONCURRENT_BIDI_STREAMS, DEFAULT_MAX_IDLE_TIMEOUT,
+};
+use ipgeolocation::languages::parse_language_overrides;
+use ipgeolocation::logging::{
+    access_log_level, fmt_layer, parse_quiet_paths, redact_uri, AccessLogLevel, LogFormat,
 };
-use ipgeolocation::http3::{run_http3_server, Http3Config};
 use ipgeolocation::mcp::{
-    mcp_batch_handler, mcp_info_handler, mcp_jsonrpc_handler, mcp_sse_handler, McpState,
+    mcp_batch_handler, mcp_info_handler, mcp_jsonrpc_handler, mcp_sse_handler,
+    parse_disabled_tools, BulkLimits, McpState, BULK_LOOKUP_MAX_IPS, DEFAULT_MCP_BULK_CONCURRENCY,
 };
+use ipgeolocation::overload::concurrency_limit;
+use ipgeolocation::selftest::run_selftest;
+use ipgeolocation::signing::signing_key;
+use ipgeolocation::static_mounts::{parse_static_mounts, serve_static_dir, with_static_mounts};
+use ipgeolocation::ResponseLayers;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
-    // Initialize tracing
+    // Initialize tracing (LOG_FORMAT=json for structured log ingestion)
+    let log_format: LogFormat = env::var("LOG_FORMAT")
+        .map(|f| f.parse().expect("Invalid LOG_FORMAT"))
+        .unwrap_or_default();
+
     tracing_subscriber::registry()
         .with(
             tracing_subscriber::EnvFilter::try_from_default_env()
                 .unwrap_or_else(|_| "ipgeolocation=info,tower_http=info".into()),
         )
-        .with(tracing_subscriber::fmt::layer())
+        .with(fmt_layer(log_format))
         .init();
 
     // Load configuration from environment
@@ -43,8 +74,32 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let geoip_db_path =
         env::var("GEOIP_DB_PATH").unwrap_or_else(|_| "data/GeoLite2-City.mmdb".to_string());
 
+    // Optional secondary City database consulted when the primary has no entry
+    let geoip_fallback_db_path = env::var("GEOIP_DB_FALLBACK_PATH").ok();
+
+    // Optional GeoIP2 Connection-Type database (adds network.connection_type)
+    let connection_type_db_path = env::var("CONNECTION_TYPE_DB_PATH").ok();
+
+    // Optional JSON table of ip/cidr -> location consulted before the database
+    let geoip_overrides_path = env::var("GEOIP_OVERRIDES_PATH").ok();
+
+    // Locale place names are taken from before falling back to the others
+    let name_locale = env::var("DEFAULT_NAME_LOCALE")
+        .map(|v| parse_name_locale(&v).expect("Invalid DEFAULT_NAME_LOCALE"))
+        .unwrap_or(DEFAULT_NAME_LOCALE);
+
+    // Start without a database (geo endpoints answer 503) if it can't be opened
+    let geoip_db_optional = env::var("GEOIP_DB_OPTIONAL")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
+
     let static_dir = env::var("STATIC_DIR").unwrap_or_else(|_| "static".to_string());
 
+    // Additional static directories as comma-separated `path=dir` pairs
+    let extra_static_mounts = env::var("EXTRA_STATIC_MOUNTS")
+        .map(|spec| parse_static_mounts(&spec).expect("Invalid EXTRA_STATIC_MOUNTS"))
+        .unwrap_or_default();
+
     let cache_size: u64 = env::var("CACHE_SIZE")
         .unwrap_or_else(|_| "10000".to_string())
         .parse()
@@ -55,10 +110,22 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .parse()
         .expect("Invalid CACHE_TTL_SECS");
 
+    let cache_ttl_jitter_pct: u8 = env::var("CACHE_TTL_JITTER_PCT")
+        .unwrap_or_else(|_| "0".to_string())
+        .parse()
+        .ok()
+        .filter(|pct| *pct < 100)
+        .expect("Invalid CACHE_TTL_JITTER_PCT (must be 0-99)");
+
     // Base URL for API documentation (OpenAPI, sitemap, etc.)
     let base_url =
         env::var("BASE_URL").unwrap_or_else(|_| "https://geoip.vpetersson.com".to_string());
 
+    // Subpath every route is served under behind a reverse proxy (e.g. /geoip)
+    let path_prefix = env::var("PATH_PREFIX")
+        .map(|v| ipgeolocation::parse_path_prefix(&v).expect("Invalid PATH_PREFIX"))
+        .unwrap_or_default();
+
     // HTTP/3 configuration (optional)
     let http3_enabled = env::var("HTTP3_ENABLED")
         .map(|v| v == "true" || v == "1")
@@ -69,35 +136,273 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .unwrap_or_else(|_| "0.0.0.0:443".parse().unwrap());
     let tls_cert_path = env::var("TLS_CERT_PATH").unwrap_or_else(|_| "cert.pem".to_string());
     let tls_key_path = env::var("TLS_KEY_PATH").unwrap_or_else(|_| "key.pem".to_string());
+    let http3_self_signed = env::var("HTTP3_SELF_SIGNED")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
+    let http3_max_streams: u32 = env::var("HTTP3_MAX_CONCURRENT_STREAMS")
+        .map(|v| v.parse().expect("Invalid HTTP3_MAX_CONCURRENT_STREAMS"))
+        .unwrap_or(DEFAULT_MAX_CONCURRENT_BIDI_STREAMS);
+    let http3_idle_timeout = env::var("HTTP3_IDLE_TIMEOUT_SECS")
+        .map(|v| Duration::from_secs(v.parse().expect("Invalid HTTP3_IDLE_TIMEOUT_SECS")))
+        .unwrap_or(DEFAULT_MAX_IDLE_TIMEOUT);
+    // Keep-alive pings are off unless configured (0 also disables them)
+    let http3_keep_alive = env::var("HTTP3_KEEP_ALIVE_SECS")
+        .ok()
+        .map(|v| v.parse().expect("Invalid HTTP3_KEEP_ALIVE_SECS"))
+        .filter(|secs| *secs > 0)
+        .map(Duration::from_secs);
+
+    // Concurrency limit (unlimited when unset)
+    let max_concurrent_requests: Option<usize> = env::var("MAX_CONCURRENT_REQUESTS")
+        .ok()
+        .map(|v| v.parse().expect("Invalid MAX_CONCURRENT_REQUESTS"));
+
+    // Attribution sent with geolocation data; empty disables it
+    let data_attribution = match env::var("DATA_ATTRIBUTION") {
+        Ok(text) if text.is_empty() => None,
+        Ok(text) => Some(text),
+        Err(_) => Some(ipgeolocation::DEFAULT_DATA_ATTRIBUTION.to_string()),
+    };
+
+    let security_headers = !env::var("DISABLE_SECURITY_HEADERS")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
 
-    // Initialize GeoIP reader
-    tracing::info!("Loading GeoIP database from: {}", geoip_db_path);
-    let geoip_reader = GeoIpReader::open(&geoip_db_path).map_err(|e| {
-        format!(
-            "Failed to open GeoIP database at '{}': {}",
-            geoip_db_path, e
+    // Optional HMAC key for the X-Signature response header
+    let response_signing_key = env::var("RESPONSE_SIGNING_KEY")
+        .ok()
+        .filter(|key| !key.is_empty())
+        .map(|key| signing_key(&key));
+
+    // Headers, signing and load shedding applied by both the HTTP/1.1+2 and
+    // HTTP/3 servers
+    let response_layers = ResponseLayers {
+        data_attribution: data_attribution
+            .as_deref()
+            .map(|text| HeaderValue::from_str(text).expect("Invalid DATA_ATTRIBUTION")),
+        security_headers,
+        signing_key: response_signing_key,
+        concurrency_limit: max_concurrent_requests.map(concurrency_limit),
+    };
+
+    // Bulk lookup cap shared by the REST batch endpoints and the MCP bulk tools
+    let bulk_max_ips: usize = env::var("BULK_MAX_IPS")
+        .map(|v| {
+            v.parse()
+                .ok()
+                .filter(|max| *max >= 1)
+                .expect("Invalid BULK_MAX_IPS (must be >= 1)")
+        })
+        .unwrap_or(BULK_LOOKUP_MAX_IPS);
+
+    // Blocking threads the MCP bulk tool may use at once
+    let mcp_bulk_concurrency: usize = env::var("MCP_BULK_CONCURRENCY")
+        .map(|v| {
+            v.parse()
+                .ok()
+                .filter(|max| *max >= 1)
+                .expect("Invalid MCP_BULK_CONCURRENCY (must be >= 1)")
+        })
+        .unwrap_or(DEFAULT_MCP_BULK_CONCURRENCY);
+
+    // MCP tools hidden from clients
+    let mcp_disabled_tools = env::var("MCP_DISABLE_TOOLS")
+        .map(|spec| parse_disabled_tools(&spec).expect("Invalid MCP_DISABLE_TOOLS"))
+        .unwrap_or_default();
+
+    // Response shaping shared by the REST API and the MCP tools
+    let response_options = ResponseOptions {
+        // Full-response fields dropped, e.g. location.zipcode
+        omit_fields: env::var("OMIT_FIELDS")
+            .map(|spec| OmitFields::parse(&spec).expect("Invalid OMIT_FIELDS"))
+            .unwrap_or_default(),
+        // Decimal places returned coordinates are rounded to
+        coord_round_dp: env::var("COORD_ROUND_DP").ok().map(|v| {
+            v.parse()
+                .ok()
+                .filter(|dp| *dp <= 10)
+                .expect("Invalid COORD_ROUND_DP (must be 0-10)")
+        }),
+        // Decimal places kept from timezone query coordinates
+        coord_max_precision: env::var("COORD_MAX_PRECISION")
+            .map(|v| {
+                v.parse()
+                    .ok()
+                    .filter(|dp| *dp <= 10)
+                    .expect("Invalid COORD_MAX_PRECISION (must be 0-10)")
+            })
+            .unwrap_or(DEFAULT_COORD_MAX_PRECISION),
+        // Flag for country codes without one
+        default_flag: env::var("DEFAULT_FLAG")
+            .map(|v| parse_default_flag(&v).expect("Invalid DEFAULT_FLAG"))
+            .unwrap_or_else(|_| DEFAULT_FLAG.to_string()),
+        path_prefix: path_prefix.clone(),
+        // Per-country overrides for the `languages` field
+        language_overrides: env::var("LANGUAGE_OVERRIDES")
+            .map(|spec| parse_language_overrides(&spec).expect("Invalid LANGUAGE_OVERRIDES"))
+            .unwrap_or_default(),
+        data_attribution,
+    };
+
+    // Keep IPs and coordinates out of the access log URIs; API keys are
+    // always redacted
+    let log_redact_ip_param = env::var("LOG_REDACT_IP_PARAM")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
+    let log_uri = move |uri: &axum::http::Uri| redact_uri(uri, log_redact_ip_param);
+
+    // Paths (e.g. load balancer health checks) logged at debug instead of info
+    let quiet_paths = Arc::new(parse_quiet_paths(
+        &env::var("QUIET_PATHS").unwrap_or_default(),
+    ));
+    let span_quiet_paths = quiet_paths.clone();
+
+    // Admin endpoints are only enabled when a token is configured
+    let admin_token = env::var("ADMIN_TOKEN").ok().filter(|t| !t.is_empty());
+
+    // Make REST lookups reject non-public IPs like the MCP tools do
+    let reject_private = env::var("REJECT_PRIVATE_IPS")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
+
+    // Answer lookups that found nothing with 204 instead of an empty 200
+    let empty_as_204 = env::var("EMPTY_AS_204")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
+
+    // Browsers visiting `/` get an HTML page instead of raw JSON
+    let root_html = !env::var("DISABLE_ROOT_HTML")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
+
+    // Allow `explain=true` debug output on /v1/ipgeo
+    let enable_explain = env::var("ENABLE_EXPLAIN")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
+
+    // Build discovery URLs from X-Forwarded-Proto/-Host instead of BASE_URL
+    let detect_base_url = env::var("DETECT_BASE_URL")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
+
+    // Reject query parameters an endpoint does not accept with 400 UNKNOWN_PARAM
+    let strict_query = env::var("STRICT_QUERY")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
+
+    // Cap on the `ip` query value, checked before splitting multi-IP lists
+    let max_query_len: usize = env::var("MAX_QUERY_LENGTH")
+        .map(|v| v.parse().expect("Invalid MAX_QUERY_LENGTH"))
+        .unwrap_or(DEFAULT_MAX_QUERY_LEN);
+
+    // Warn about databases older than this (GeoLite2 updates weekly)
+    let max_db_age_days: u64 = env::var("MAX_DB_AGE_DAYS")
+        .map(|v| v.parse().expect("Invalid MAX_DB_AGE_DAYS"))
+        .unwrap_or(DEFAULT_MAX_DB_AGE_DAYS);
+
+    // Log a few sample lookups at startup to catch misconfiguration early
+    let startup_selftest = env::var("STARTUP_SELFTEST")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
+
+    // Open the GeoIP database(s); reopened the same way by POST /admin/reload
+    let database_paths = DatabasePaths {
+        city: geoip_db_path,
+        connection_type: connection_type_db_path,
+        fallback: geoip_fallback_db_path,
+    };
+    let open_databases = move |paths: &DatabasePaths| {
+        open_geoip(
+            &paths.city,
+            paths.connection_type.as_deref(),
+            paths.fallback.as_deref(),
+            name_locale,
         )
-    })?;
+    };
+    let initial_reader = match open_databases(&database_paths) {
+        Ok(reader) => reader,
+        // Start anyway when the database is fetched after boot (e.g. by a sidecar)
+        Err(e) if geoip_db_optional => {
+            tracing::warn!(
+                "{}; starting without a database until POST /admin/reload",
+                e
+            );
+            Arc::new(NullGeoIpReader)
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let reloader = Arc::new(ReloadableGeoIpReader::new(
+        initial_reader,
+        database_paths,
+        Box::new(open_databases),
+    ));
+    let geoip: SharedGeoIpReader = match geoip_overrides_path {
+        Some(path) => {
+            let overrides = OverrideGeoIpReader::load(&path, reloader.clone())?;
+            tracing::info!("Loaded {} GeoIP overrides from: {}", overrides.len(), path);
+            Arc::new(overrides)
+        }
+        None => reloader.clone(),
+    };
+
+    let db_age_days = geoip.metadata().age_days();
+    if geoip.is_loaded() && db_age_days > max_db_age_days {
+        tracing::warn!(
+            "GeoIP database is {} days old (MAX_DB_AGE_DAYS={}); update it",
+            db_age_days,
+            max_db_age_days
+        );
+    }
 
     // Initialize cache
     let cache_config = CacheConfig {
         max_capacity: cache_size,
         ttl: Duration::from_secs(cache_ttl_secs),
+        ttl_jitter_pct: cache_ttl_jitter_pct,
     };
-    let cache = GeoCache::new(cache_config);
+    let cache = GeoCache::new(cache_config.clone());
+    let full_cache = GeoCache::new(cache_config);
 
-    // Create shared GeoIP reader
-    let geoip: Arc<ipgeolocation::geoip::GeoIpReader> = Arc::new(geoip_reader);
+    let response_options = Arc::new(response_options);
+
+    // Create shared state for MCP
+    let mcp_state = McpState::new(geoip.clone())
+        .with_response_options(response_options.clone())
+        .with_bulk_limits(BulkLimits::new(bulk_max_ips, mcp_bulk_concurrency))
+        .with_disabled_tools(mcp_disabled_tools);
 
     // Create shared state for REST API
     let state = AppState {
-        geoip: geoip.clone(),
-        cache: Arc::new(cache),
-        base_url: base_url.clone(),
+        admin_token,
+        max_query_len,
+        reject_private,
+        empty_as_204,
+        reloader: Some(reloader),
+        enable_explain,
+        detect_base_url,
+        strict_query,
+        response_options,
+        bulk_max_ips,
+        root_html,
+        max_db_age_days,
+        mcp_notifications: Some(mcp_state.notification_tx.clone()),
+        http3_running: http3_enabled.then(|| Arc::new(AtomicBool::new(false))),
+        full_cache: Arc::new(full_cache),
+        ..AppState::new(geoip.clone(), Arc::new(cache), base_url.clone())
     };
+    // MCP resolves timezones with the same provider as the REST API
+    let mcp_state = mcp_state.with_timezones(state.timezones.clone());
 
-    // Create shared state for MCP
-    let mcp_state = McpState::new(geoip);
+    if startup_selftest {
+        let failures = run_selftest(geoip.as_ref(), state.timezones.as_ref());
+        if failures.is_empty() {
+            tracing::info!("Startup self-test passed");
+        }
+    }
+
+    // HTTP/3 serves the same lookups, sharing the reader, cache and layers
+    let http3_state = state.clone();
+    let http3_layers = response_layers.clone();
 
     // Build MCP router (nested under /mcp)
     let mcp_router = Router::new()
@@ -121,11 +426,24 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         // Full format endpoints (extended format)
         .route("/v1/ipgeo", get(ipgeo_full_handler))
         .route("/v1/timezone", get(timezone_full_handler))
+        .route("/v1/timezone/batch", post(timezone_batch_handler))
+        .route("/v1/timezone/compare", get(timezone_compare_handler))
+        .route("/v1/timezones", get(timezones_handler))
+        .route("/v1/flag", get(flag_handler))
+        .route("/v1/countries", get(countries_handler))
+        .route("/v1/self/stream", get(self_stream_handler))
         // Health check
         .route("/health", get(health_handler))
+        .route("/health/database", get(health_database_handler))
+        .route("/health/ready", get(health_ready_handler))
+        .route("/version", get(version_handler))
+        .route("/metrics", get(metrics_handler))
         // API documentation for LLMs and agents
         .route("/openapi.yaml", get(openapi_handler))
         .route("/llms.txt", get(llms_txt_handler))
+        .route("/proto/geolocation.proto", get(proto_definition_handler))
+        .route("/schema/ipgeo", get(schema_ipgeo_handler))
+        .route("/schema/timezone", get(schema_timezone_handler))
         .route("/sitemap.xml", get(sitemap_handler))
         .route("/robots.txt", get(robots_txt_handler))
         // .well-known discovery endpoints
@@ -134,18 +452,64 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             "/.well-known/ai-plugin.json",
             get(wellknown_ai_plugin_handler),
         )
+        // Admin endpoints (require ADMIN_TOKEN)
+        .route("/admin/cache/flush", post(admin_cache_flush_handler))
+        .route("/admin/cache/{ip}", delete(admin_cache_invalidate_handler))
+        .route("/admin/raw", get(admin_raw_handler))
+        .route("/admin/all", get(admin_all_handler))
+        .route("/admin/reload", post(admin_reload_handler))
         // MCP endpoints (Model Context Protocol)
         .nest("/mcp", mcp_router)
         // Static files (flags, etc.)
-        .nest_service("/static", ServeDir::new(&static_dir))
-        .with_state(state)
+        .nest_service("/static", serve_static_dir(&static_dir))
+        // Browsers request this unprompted; serve it from the static dir
+        .route_service(
+            "/favicon.ico",
+            ServeFile::new(std::path::Path::new(&static_dir).join("favicon.ico")),
+        );
+
+    // Operator-configured extra static directories
+    for mount in &extra_static_mounts {
+        tracing::info!("Serving {} from {}", mount.path, mount.dir);
+    }
+    let app = with_static_mounts(app, &extra_static_mounts)
+        .with_state(state.clone())
+        // With STRICT_QUERY, typos like `?adress=` get 400 UNKNOWN_PARAM
+        .layer(axum::middleware::from_fn_with_state(
+            state,
+            reject_unknown_params,
+        ))
+        // `?ip=a&ip=b` gets 400 DUPLICATE_PARAM instead of an arbitrary pick
+        .layer(axum::middleware::from_fn(reject_duplicate_params));
+    // X-Data-Attribution on the routes returning GeoLite2-derived data
+    let app = response_layers.attribute(app);
+    let app = ipgeolocation::nest_under_prefix(app, &path_prefix);
+
+    // Shed load beyond MAX_CONCURRENT_REQUESTS with 503 OVERLOADED, sign
+    // bodies with RESPONSE_SIGNING_KEY and add the security and Server headers.
+    // Applied inside the access log so rejected requests are still logged.
+    if response_layers.signing_key.is_some() {
+        tracing::info!("Response signing enabled (X-Signature)");
+    }
+    let app = response_layers.apply(app);
+
+    // Advertise HTTP/3 so browsers upgrade on their next request
+    let app = if http3_enabled {
+        app.layer(ipgeolocation::alt_svc_layer(http3_bind_address.port()))
+    } else {
+        app
+    };
+
+    let app = app
         // Access logging layer with proxy-aware client IP extraction
-        // Silences logging for favicon.ico (expected 404 from browsers)
+        // Silences logging for favicon.ico (requested by every browser visit)
+        // and demotes QUIET_PATHS to debug
         .layer(
             TraceLayer::new_for_http()
-                .make_span_with(|request: &axum::http::Request<_>| {
+                .make_span_with(move |request: &axum::http::Request<_>| {
+                    let level = access_log_level(request.uri().path(), &span_quiet_paths);
                     // Skip creating detailed spans for favicon.ico
-                    if request.uri().path() == "/favicon.ico" {
+                    if level == AccessLogLevel::Skip {
                         return tracing::debug_span!("favicon");
                     }
 
@@ -156,59 +520,112 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
                     let client_ip = extract_client_ip(request.headers(), connect_info);
 
-                    tracing::info_span!(
-                        "request",
-                        method = %request.method(),
-                        uri = %request.uri(),
-                        client_ip = %client_ip,
-                    )
-                })
-                .on_request(|request: &axum::http::Request<_>, _span: &tracing::Span| {
-                    // Skip logging for favicon.ico
-                    if request.uri().path() == "/favicon.ico" {
-                        return;
+                    let request_id = request
+                        .headers()
+                        .get("x-request-id")
+                        .and_then(|v| v.to_str().ok())
+                        .unwrap_or_default();
+
+                    let uri = log_uri(request.uri());
+                    if level == AccessLogLevel::Debug {
+                        tracing::debug_span!(
+                            "request",
+                            request_id = %request_id,
+                            method = %request.method(),
+                            uri = %uri,
+                            client_ip = %client_ip,
+                        )
+                    } else {
+                        tracing::info_span!(
+                            "request",
+                            request_id = %request_id,
+                            method = %request.method(),
+                            uri = %uri,
+                            client_ip = %client_ip,
+                        )
                     }
-                    tracing::info!(
-                        method = %request.method(),
-                        uri = %request.uri(),
-                        "started processing request"
-                    );
                 })
+                .on_request(
+                    move |request: &axum::http::Request<_>, _span: &tracing::Span| {
+                        let uri = log_uri(request.uri());
+                        match access_log_level(request.uri().path(), &quiet_paths) {
+                            // Skip logging for favicon.ico
+                            AccessLogLevel::Skip => {}
+                            AccessLogLevel::Debug => tracing::debug!(
+                                method = %request.method(),
+                                uri = %uri,
+                                "started processing request"
+                            ),
+                            AccessLogLevel::Info => tracing::info!(
+                                method = %request.method(),
+                                uri = %uri,
+                                "started processing request"
+                            ),
+                        }
+                    },
+                )
                 .on_response(
                     |response: &axum::http::Response<_>,
                      latency: std::time::Duration,
                      span: &tracing::Span| {
-                        // Skip logging for favicon.ico (check span name)
-                        if span.metadata().map(|m| m.name()) == Some("favicon") {
-                            return;
+                        let status = response.status().as_u16();
+                        let latency_ms = latency.as_millis() as u64;
+                        // The span carries the level chosen for the request; a
+                        // disabled span means its level is filtered out anyway
+                        match span.metadata() {
+                            // Skip logging for favicon.ico (check span name)
+                            Some(m) if m.name() == "favicon" => {}
+                            Some(m) if *m.level() == tracing::Level::INFO => {
+                                tracing::info!(status, latency_ms, "finished processing request")
+                            }
+                            _ => tracing::debug!(status, latency_ms, "finished processing request"),
                         }
-                        tracing::info!(
-                            status = %response.status().as_u16(),
-                            latency_ms = %latency.as_millis(),
-                            "finished processing request"
-                        );
                     },
                 ),
         )
+        // Tag each request with an X-Request-Id (kept if the client sent one)
+        // and echo it on the response so logs can be correlated with clients
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
         // CORS is the outermost layer so it wraps every route (and answers
         // preflight requests) uniformly.
         .layer(cors);
+    // Wraps the router itself so `/ipgeo/` is rewritten before route matching
+    let app = ipgeolocation::trailing_slash_layer().layer(app);
 
     tracing::info!("Starting server on {}", bind_address);
+    if !path_prefix.is_empty() {
+        tracing::info!("Serving every endpoint under {}", path_prefix);
+    }
     tracing::info!("Endpoints:");
     tracing::info!("  GET /                - Geolocation for client's IP");
     tracing::info!("  GET /ipgeo           - Simple IP geolocation");
     tracing::info!("  GET /timezone        - Simple timezone lookup");
     tracing::info!("  GET /v1/ipgeo        - Full IP geolocation (extended format)");
     tracing::info!("  GET /v1/timezone     - Full timezone details");
+    tracing::info!("  GET /v1/timezone/compare - Offsets relative to a reference zone");
+    tracing::info!("  GET /v1/timezones    - Supported IANA timezones");
+    tracing::info!("  GET /v1/flag         - Country flag emoji and SVG path");
+    tracing::info!("  GET /v1/countries    - Supported countries and metadata");
     tracing::info!("  GET /static/flags/*  - Country flag SVGs");
     tracing::info!("  GET /health          - Health check");
+    tracing::info!("  GET /health/database - GeoIP database metadata and age");
+    tracing::info!("  GET /health/ready    - Per-subsystem readiness");
+    tracing::info!("  GET /version         - Build version information");
     tracing::info!("  GET /openapi.yaml    - OpenAPI specification");
     tracing::info!("  GET /llms.txt        - LLM-friendly documentation");
+    tracing::info!("  GET /proto/geolocation.proto - Protobuf schema");
+    tracing::info!("  GET /schema/ipgeo    - JSON Schema for IP lookup responses");
+    tracing::info!("  GET /schema/timezone - JSON Schema for timezone responses");
     tracing::info!("  GET /sitemap.xml     - Sitemap for discovery");
     tracing::info!("  GET /robots.txt      - Robots.txt for crawlers");
     tracing::info!("  GET /.well-known/openapi.yaml   - OpenAPI (well-known)");
     tracing::info!("  GET /.well-known/ai-plugin.json - AI plugin manifest");
+    tracing::info!("  POST   /admin/cache/flush - Flush lookup cache (ADMIN_TOKEN)");
+    tracing::info!("  DELETE /admin/cache/{{ip}} - Invalidate one cached IP (ADMIN_TOKEN)");
+    tracing::info!("  GET    /admin/raw    - Raw MaxMind record for an IP (ADMIN_TOKEN)");
+    tracing::info!("  GET    /admin/all    - Every view of an IP, for debugging (ADMIN_TOKEN)");
+    tracing::info!("  POST   /admin/reload - Reopen the GeoIP database(s) (ADMIN_TOKEN)");
     tracing::info!("");
     tracing::info!("MCP (Model Context Protocol):");
     tracing::info!("  POST /mcp            - JSON-RPC endpoint");
@@ -226,13 +643,17 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             bind_address: http3_bind_address,
             cert_path: tls_cert_path,
             key_path: tls_key_path,
+            self_signed: http3_self_signed,
+            max_concurrent_bidi_streams: http3_max_streams,
+            max_idle_timeout: http3_idle_timeout,
+            keep_alive_interval: http3_keep_alive,
         };
         tracing::info!(
             "HTTP/3 enabled on {} (requires TLS certificates)",
             http3_bind_address
         );
         tokio::spawn(async move {
-            if let Err(e) = run_http3_server(http3_config).await {
+            if let Err(e) = run_http3_server(http3_config, http3_state, http3_layers).await {
                 tracing::error!("HTTP/3 server error: {}", e);
             }
         });
@@ -242,9 +663,50 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let listener = tokio::net::TcpListener::bind(bind_address).await?;
     axum::serve(
         listener,
-        app.into_make_service_with_connect_info::<SocketAddr>(),
+        axum::ServiceExt::<Request>::into_make_service_with_connect_info::<SocketAddr>(app),
     )
     .await?;
 
     Ok(())
 }
+
+/// Open the City database with the optional Connection-Type and fallback databases
+///
+/// Place names are taken from `name_locale` first in both City databases.
+fn open_geoip(
+    db_path: &str,
+    connection_type_path: Option<&str>,
+    fallback_path: Option<&str>,
+    name_locale: &'static str,
+) -> Result<SharedGeoIpReader, String> {
+    tracing::info!("Loading GeoIP database from: {}", db_path);
+    let reader = match connection_type_path {
+        Some(path) => {
+            tracing::info!("Loading Connection-Type database from: {}", path);
+            GeoIpReader::open_with_connection_type(db_path, path)
+        }
+        None => GeoIpReader::open(db_path),
+    }
+    .map_err(|e| format!("Failed to open GeoIP database at '{}': {}", db_path, e))?
+    .with_name_locale(name_locale);
+
+    // Chain with the fallback database if configured
+    match fallback_path {
+        Some(path) => {
+            tracing::info!("Loading fallback GeoIP database from: {}", path);
+            let fallback = GeoIpReader::open(path)
+                .map_err(|e| {
+                    format!(
+                        "Failed to open fallback GeoIP database at '{}': {}",
+                        path, e
+                    )
+                })?
+                .with_name_locale(name_locale);
+            Ok(Arc::new(ChainedGeoIpReader::new(vec![
+                Arc::new(reader),
+                Arc::new(fallback),
+            ])))
+        }
+        None => Ok(Arc::new(reader)),
+    }
+}
@@ -15,24 +15,36 @@ use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::convert::Infallible;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::broadcast;
 
 use crate::geoip::SharedGeoIpReader;
-use crate::handlers::extract_client_ip;
+use crate::handlers::{extract_client_ip, ResponseOptions};
+use crate::timezone::{SharedTimezoneProvider, TzfTimezoneProvider};
 
+use super::prompts::{get_prompt, list_prompts};
 use super::resources::{list_resource_infos, read_resource};
 use super::schemas;
 use super::tools::{
-    handle_geoip_bulk_lookup, handle_geoip_lookup, handle_geoip_lookup_self, handle_timezone_lookup,
+    handle_country_info, handle_geoip_bulk_lookup, handle_geoip_lookup, handle_geoip_lookup_self,
+    handle_timezone_bulk_lookup, handle_timezone_lookup, BulkLimits,
 };
 
 /// MCP server state for Axum handlers
 #[derive(Clone)]
 pub struct McpState {
     pub geoip: SharedGeoIpReader,
+    /// Timezone lookups from coordinates, shared with `AppState::timezones`
+    pub timezones: SharedTimezoneProvider,
     /// Broadcast channel for SSE notifications (optional)
     pub notification_tx: broadcast::Sender<McpNotification>,
+    /// Response shaping shared with `AppState::response_options`
+    pub response_options: Arc<ResponseOptions>,
+    /// Bulk tool cap and concurrency (`BULK_MAX_IPS`, `MCP_BULK_CONCURRENCY`)
+    pub bulk_limits: BulkLimits,
+    /// Tools hidden from clients (`MCP_DISABLE_TOOLS`)
+    pub disabled_tools: Vec<String>,
 }
 
 impl McpState {
@@ -40,9 +52,42 @@ impl McpState {
         let (notification_tx, _) = broadcast::channel(100);
         Self {
             geoip,
+            timezones: Arc::new(TzfTimezoneProvider),
             notification_tx,
+            response_options: Arc::new(ResponseOptions::default()),
+            bulk_limits: BulkLimits::default(),
+            disabled_tools: Vec::new(),
         }
     }
+
+    /// Resolve timezones with `timezones` instead of tzf-rs
+    pub fn with_timezones(mut self, timezones: SharedTimezoneProvider) -> Self {
+        self.timezones = timezones;
+        self
+    }
+
+    /// Shape tool results with `options` instead of the defaults
+    pub fn with_response_options(mut self, options: Arc<ResponseOptions>) -> Self {
+        self.response_options = options;
+        self
+    }
+
+    /// Cap and pace the bulk tools with `limits`
+    pub fn with_bulk_limits(mut self, limits: BulkLimits) -> Self {
+        self.bulk_limits = limits;
+        self
+    }
+
+    /// Hide the `disabled` tools, as if they did not exist
+    pub fn with_disabled_tools(mut self, disabled: Vec<String>) -> Self {
+        self.disabled_tools = disabled;
+        self
+    }
+
+    /// Whether a tool is exposed (not listed in `disabled_tools`)
+    pub fn is_tool_enabled(&self, name: &str) -> bool {
+        !self.disabled_tools.iter().any(|tool| tool == name)
+    }
 }
 
 /// JSON-RPC request structure
@@ -82,20 +127,82 @@ pub struct McpNotification {
     pub params: Value,
 }
 
+impl McpNotification {
+    /// `notifications/resources/updated` telling clients to re-read `uri`
+    pub fn resource_updated(uri: &str) -> Self {
+        Self {
+            method: "notifications/resources/updated".to_string(),
+            params: json!({ "uri": uri }),
+        }
+    }
+}
+
 // JSON-RPC error codes
 const INVALID_REQUEST: i32 = -32600;
 const METHOD_NOT_FOUND: i32 = -32601;
 const INVALID_PARAMS: i32 = -32602;
 
+/// MCP protocol versions the HTTP transport speaks, newest first
+const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-03-26", "2024-11-05"];
+
 /// MCP server information
 fn server_info() -> Value {
     json!({
         "name": "ip-geolocation-mcp",
         "version": env!("CARGO_PKG_VERSION"),
-        "protocolVersion": "2024-11-05"
+        "protocolVersion": SUPPORTED_PROTOCOL_VERSIONS[0]
     })
 }
 
+/// Handle the initialize method, negotiating the protocol version
+///
+/// A supported `protocolVersion` is echoed back; clients that omit it get the
+/// newest one. Anything else is rejected with the supported list, as in the
+/// MCP spec's version negotiation, rather than silently answering with a
+/// version the client does not speak.
+fn handle_initialize(id: Value, params: Option<Value>) -> JsonRpcResponse {
+    let requested = params
+        .as_ref()
+        .and_then(|p| p.get("protocolVersion"))
+        .and_then(|v| v.as_str());
+
+    let version = match requested {
+        None => SUPPORTED_PROTOCOL_VERSIONS[0],
+        Some(requested) => match SUPPORTED_PROTOCOL_VERSIONS
+            .iter()
+            .find(|v| **v == requested)
+        {
+            Some(version) => version,
+            None => {
+                return JsonRpcResponse {
+                    jsonrpc: "2.0".to_string(),
+                    id,
+                    result: None,
+                    error: Some(JsonRpcError {
+                        code: INVALID_PARAMS,
+                        message: "Unsupported protocol version".to_string(),
+                        data: Some(json!({
+                            "supported": SUPPORTED_PROTOCOL_VERSIONS,
+                            "requested": requested
+                        })),
+                    }),
+                };
+            }
+        },
+    };
+
+    JsonRpcResponse {
+        jsonrpc: "2.0".to_string(),
+        id,
+        result: Some(json!({
+            "protocolVersion": version,
+            "serverInfo": server_info(),
+            "capabilities": server_capabilities()
+        })),
+        error: None,
+    }
+}
+
 /// MCP server capabilities
 fn server_capabilities() -> Value {
     json!({
@@ -106,37 +213,58 @@ fn server_capabilities() -> Value {
             "subscribe": false,
             "listChanged": false
         },
-        "prompts": null,
+        "prompts": {
+            "listChanged": false
+        },
         "logging": null
     })
 }
 
-/// List of available tools
-fn list_tools() -> Value {
-    json!({
-        "tools": [
-            {
-                "name": "geoip_lookup",
-                "description": "Look up geographic location for an IP address. Returns city, country, coordinates, timezone, currency, and other location metadata.",
-                "inputSchema": schemas::geoip_lookup_input_schema()
-            },
-            {
-                "name": "geoip_bulk_lookup",
-                "description": "Look up geographic locations for multiple IP addresses in a single request. Maximum 100 IPs per request. Returns results and errors separately.",
-                "inputSchema": schemas::geoip_bulk_lookup_input_schema()
-            },
-            {
-                "name": "geoip_lookup_self",
-                "description": "Look up geographic location for the caller's IP address. Available via HTTP transport.",
-                "inputSchema": schemas::geoip_lookup_self_input_schema()
-            },
-            {
-                "name": "timezone_lookup",
-                "description": "Look up IANA timezone for geographic coordinates. Returns timezone name, current offset, DST information, and current local time.",
-                "inputSchema": schemas::timezone_lookup_input_schema()
-            }
-        ]
-    })
+/// List of available tools, minus those disabled via `MCP_DISABLE_TOOLS`
+fn list_tools(state: &McpState) -> Value {
+    let max_items = state.bulk_limits.max_items;
+    let tools = [
+        json!({
+            "name": "geoip_lookup",
+            "description": "Look up geographic location for an IP address. Returns city, country, coordinates, timezone, currency, and other location metadata.",
+            "inputSchema": schemas::geoip_lookup_input_schema()
+        }),
+        json!({
+            "name": "geoip_bulk_lookup",
+            "description": format!("Look up geographic locations for multiple IP addresses in a single request. Maximum {} IPs per request. Returns results and errors separately.", max_items),
+            "inputSchema": schemas::geoip_bulk_lookup_input_schema(max_items)
+        }),
+        json!({
+            "name": "geoip_lookup_self",
+            "description": "Look up geographic location for the caller's IP address. Available via HTTP transport.",
+            "inputSchema": schemas::geoip_lookup_self_input_schema()
+        }),
+        json!({
+            "name": "timezone_lookup",
+            "description": "Look up IANA timezone for geographic coordinates. Returns timezone name, current offset, DST information, and current local time.",
+            "inputSchema": schemas::timezone_lookup_input_schema()
+        }),
+        json!({
+            "name": "timezone_bulk_lookup",
+            "description": format!("Look up IANA timezones for multiple coordinate pairs in a single request. Maximum {} points per request. Returns one result per point in request order, with error objects for invalid coordinates.", max_items),
+            "inputSchema": schemas::timezone_bulk_lookup_input_schema(max_items)
+        }),
+        json!({
+            "name": "country_info",
+            "description": "Look up metadata for a country by ISO 3166-1 alpha-2 code: capital, currency, languages, continent, EU membership, calling code, TLD, and flag emoji.",
+            "inputSchema": schemas::country_info_input_schema()
+        }),
+    ];
+
+    let tools: Vec<Value> = tools
+        .into_iter()
+        .filter(|tool| {
+            tool["name"]
+                .as_str()
+                .is_some_and(|name| state.is_tool_enabled(name))
+        })
+        .collect();
+    json!({ "tools": tools })
 }
 
 /// List of available resources
@@ -184,16 +312,7 @@ pub async fn mcp_jsonrpc_handler(
     // Route to appropriate handler
     let response = match request.method.as_str() {
         // MCP lifecycle methods
-        "initialize" => JsonRpcResponse {
-            jsonrpc: "2.0".to_string(),
-            id: request.id,
-            result: Some(json!({
-                "protocolVersion": "2024-11-05",
-                "serverInfo": server_info(),
-                "capabilities": server_capabilities()
-            })),
-            error: None,
-        },
+        "initialize" => handle_initialize(request.id, request.params),
 
         "initialized" => JsonRpcResponse {
             jsonrpc: "2.0".to_string(),
@@ -213,11 +332,11 @@ pub async fn mcp_jsonrpc_handler(
         "tools/list" => JsonRpcResponse {
             jsonrpc: "2.0".to_string(),
             id: request.id,
-            result: Some(list_tools()),
+            result: Some(list_tools(&state)),
             error: None,
         },
 
-        "tools/call" => handle_tool_call(&state.geoip, &caller_ip, request.id, request.params),
+        "tools/call" => handle_tool_call(&state, &caller_ip, request.id, request.params).await,
 
         // Resource methods
         "resources/list" => JsonRpcResponse {
@@ -227,7 +346,14 @@ pub async fn mcp_jsonrpc_handler(
             error: None,
         },
 
-        "resources/read" => handle_resource_read(request.id, request.params),
+        "resources/read" => handle_resource_read(&state, request.id, request.params),
+        "prompts/list" => JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            id: request.id,
+            result: Some(list_prompts()),
+            error: None,
+        },
+        "prompts/get" => handle_prompt_get(request.id, request.params),
 
         // Unknown method
         _ => JsonRpcResponse {
@@ -246,8 +372,8 @@ pub async fn mcp_jsonrpc_handler(
 }
 
 /// Handle tools/call method
-fn handle_tool_call(
-    geoip: &SharedGeoIpReader,
+async fn handle_tool_call(
+    state: &McpState,
     caller_ip: &str,
     id: Value,
     params: Option<Value>,
@@ -271,11 +397,54 @@ fn handle_tool_call(
     let tool_name = params.get("name").and_then(|n| n.as_str());
     let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
 
+    // Disabled tools are treated as if they didn't exist
+    if let Some(name) = tool_name.filter(|name| !state.is_tool_enabled(name)) {
+        return JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(JsonRpcError {
+                code: METHOD_NOT_FOUND,
+                message: format!("Unknown tool: {}", name),
+                data: None,
+            }),
+        };
+    }
+
     let tool_result = match tool_name {
-        Some("geoip_lookup") => handle_geoip_lookup(geoip, arguments),
-        Some("geoip_bulk_lookup") => handle_geoip_bulk_lookup(geoip, arguments),
-        Some("geoip_lookup_self") => handle_geoip_lookup_self(geoip, Some(caller_ip), arguments),
-        Some("timezone_lookup") => handle_timezone_lookup(arguments),
+        Some("geoip_lookup") => handle_geoip_lookup(
+            &state.geoip,
+            &state.timezones,
+            &state.response_options,
+            arguments,
+        ),
+        Some("geoip_bulk_lookup") => {
+            handle_geoip_bulk_lookup(
+                &state.geoip,
+                &state.timezones,
+                &state.response_options,
+                &state.bulk_limits,
+                arguments,
+            )
+            .await
+        }
+        Some("geoip_lookup_self") => handle_geoip_lookup_self(
+            &state.geoip,
+            &state.timezones,
+            &state.response_options,
+            Some(caller_ip),
+            arguments,
+        ),
+        Some("timezone_lookup") => {
+            handle_timezone_lookup(&state.timezones, &state.response_options, arguments)
+        }
+        Some("timezone_bulk_lookup") => handle_timezone_bulk_lookup(
+            &state.timezones,
+            &state.response_options,
+            &state.bulk_limits,
+            arguments,
+        ),
+        Some("country_info") => handle_country_info(arguments),
         Some(name) => {
             return JsonRpcResponse {
                 jsonrpc: "2.0".to_string(),
@@ -338,8 +507,49 @@ fn handle_tool_call(
     }
 }
 
+/// Handle prompts/get method
+fn handle_prompt_get(id: Value, params: Option<Value>) -> JsonRpcResponse {
+    let params = params.unwrap_or_else(|| json!({}));
+
+    let name = match params.get("name").and_then(|n| n.as_str()) {
+        Some(n) => n,
+        None => {
+            return JsonRpcResponse {
+                jsonrpc: "2.0".to_string(),
+                id,
+                result: None,
+                error: Some(JsonRpcError {
+                    code: INVALID_PARAMS,
+                    message: "Missing prompt name".to_string(),
+                    data: None,
+                }),
+            };
+        }
+    };
+    let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+
+    match get_prompt(name, &arguments) {
+        Ok(prompt) => JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: Some(prompt),
+            error: None,
+        },
+        Err(message) => JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            id,
+            result: None,
+            error: Some(JsonRpcError {
+                code: INVALID_PARAMS,
+                message,
+                data: None,
+            }),
+        },
+    }
+}
+
 /// Handle resources/read method
-fn handle_resource_read(id: Value, params: Option<Value>) -> JsonRpcResponse {
+fn handle_resource_read(state: &McpState, id: Value, params: Option<Value>) -> JsonRpcResponse {
     let params = match params {
         Some(p) => p,
         None => {
@@ -372,7 +582,7 @@ fn handle_resource_read(id: Value, params: Option<Value>) -> JsonRpcResponse {
         }
     };
 
-    match read_resource(uri) {
+    match read_resource(uri, state.bulk_limits.max_items) {
         Some(contents) => {
             let content_json = match contents {
                 mcp_protocol_sdk::protocol::types::ResourceContents::Text {
@@ -432,16 +642,16 @@ pub async fn mcp_batch_handler(
 ) -> Response {
     let caller_ip = extract_client_ip(&headers, Some(addr));
 
-    let responses: Vec<JsonRpcResponse> = requests
-        .into_iter()
-        .map(|request| handle_single_request(&state.geoip, &caller_ip, request))
-        .collect();
+    let mut responses = Vec::with_capacity(requests.len());
+    for request in requests {
+        responses.push(handle_single_request(&state, &caller_ip, request).await);
+    }
 
     Json(responses).into_response()
 }
 
-fn handle_single_request(
-    geoip: &SharedGeoIpReader,
+async fn handle_single_request(
+    state: &McpState,
     caller_ip: &str,
     request: JsonRpcRequest,
 ) -> JsonRpcResponse {
@@ -459,16 +669,7 @@ fn handle_single_request(
     }
 
     match request.method.as_str() {
-        "initialize" => JsonRpcResponse {
-            jsonrpc: "2.0".to_string(),
-            id: request.id,
-            result: Some(json!({
-                "protocolVersion": "2024-11-05",
-                "serverInfo": server_info(),
-                "capabilities": server_capabilities()
-            })),
-            error: None,
-        },
+        "initialize" => handle_initialize(request.id, request.params),
         "initialized" | "ping" => JsonRpcResponse {
             jsonrpc: "2.0".to_string(),
             id: request.id,
@@ -478,17 +679,24 @@ fn handle_single_request(
         "tools/list" => JsonRpcResponse {
             jsonrpc: "2.0".to_string(),
             id: request.id,
-            result: Some(list_tools()),
+            result: Some(list_tools(state)),
             error: None,
         },
-        "tools/call" => handle_tool_call(geoip, caller_ip, request.id, request.params),
+        "tools/call" => handle_tool_call(state, caller_ip, request.id, request.params).await,
         "resources/list" => JsonRpcResponse {
             jsonrpc: "2.0".to_string(),
             id: request.id,
             result: Some(list_resources()),
             error: None,
         },
-        "resources/read" => handle_resource_read(request.id, request.params),
+        "resources/read" => handle_resource_read(state, request.id, request.params),
+        "prompts/list" => JsonRpcResponse {
+            jsonrpc: "2.0".to_string(),
+            id: request.id,
+            result: Some(list_prompts()),
+            error: None,
+        },
+        "prompts/get" => handle_prompt_get(request.id, request.params),
         _ => JsonRpcResponse {
             jsonrpc: "2.0".to_string(),
             id: request.id,
@@ -537,12 +745,13 @@ pub async fn mcp_sse_handler(
 }
 
 /// MCP server info endpoint (for discovery)
-pub async fn mcp_info_handler() -> impl IntoResponse {
+pub async fn mcp_info_handler(State(state): State<McpState>) -> impl IntoResponse {
     Json(json!({
         "name": "ip-geolocation-mcp",
         "version": env!("CARGO_PKG_VERSION"),
         "protocol": "MCP",
-        "protocolVersion": "2024-11-05",
+        "protocolVersion": SUPPORTED_PROTOCOL_VERSIONS[0],
+        "supportedProtocolVersions": SUPPORTED_PROTOCOL_VERSIONS,
         "transports": ["http", "stdio"],
         "endpoints": {
             "jsonrpc": "/mcp",
@@ -550,8 +759,9 @@ pub async fn mcp_info_handler() -> impl IntoResponse {
             "info": "/mcp/info"
         },
         "capabilities": server_capabilities(),
-        "tools": list_tools()["tools"],
-        "resources": list_resources()["resources"]
+        "tools": list_tools(&state)["tools"],
+        "resources": list_resources()["resources"],
+        "prompts": list_prompts()["prompts"]
     }))
 }
 
@@ -566,11 +776,33 @@ mod tests {
         assert!(info["protocolVersion"].as_str().is_some());
     }
 
+    #[test]
+    fn test_initialize_protocol_version() {
+        let response = handle_initialize(json!(1), Some(json!({"protocolVersion": "2024-11-05"})));
+        assert_eq!(response.result.unwrap()["protocolVersion"], "2024-11-05");
+
+        // No version requested: the newest supported one
+        let response = handle_initialize(json!(2), Some(json!({})));
+        assert_eq!(
+            response.result.unwrap()["protocolVersion"],
+            SUPPORTED_PROTOCOL_VERSIONS[0]
+        );
+
+        let response = handle_initialize(json!(3), Some(json!({"protocolVersion": "1999-01-01"})));
+        assert!(response.result.is_none());
+        let error = response.error.unwrap();
+        assert_eq!(error.code, INVALID_PARAMS);
+        let data = error.data.unwrap();
+        assert_eq!(data["requested"], "1999-01-01");
+        assert_eq!(data["supported"][0], SUPPORTED_PROTOCOL_VERSIONS[0]);
+    }
+
     #[test]
     fn test_list_tools() {
-        let tools = list_tools();
+        let state = McpState::new(Arc::new(crate::geoip::NullGeoIpReader));
+        let tools = list_tools(&state);
         let tools_arr = tools["tools"].as_array().unwrap();
-        assert_eq!(tools_arr.len(), 4);
+        assert_eq!(tools_arr.len(), 6);
 
         let names: Vec<&str> = tools_arr
             .iter()
@@ -580,13 +812,15 @@ mod tests {
         assert!(names.contains(&"geoip_bulk_lookup"));
         assert!(names.contains(&"geoip_lookup_self"));
         assert!(names.contains(&"timezone_lookup"));
+        assert!(names.contains(&"timezone_bulk_lookup"));
+        assert!(names.contains(&"country_info"));
     }
 
     #[test]
     fn test_list_resources() {
         let resources = list_resources();
         let resources_arr = resources["resources"].as_array().unwrap();
-        assert_eq!(resources_arr.len(), 4);
+        assert_eq!(resources_arr.len(), 5);
     }
 
     #[test]
@@ -594,5 +828,31 @@ mod tests {
         let caps = server_capabilities();
         assert!(caps["tools"].is_object());
         assert!(caps["resources"].is_object());
+        assert!(caps["prompts"].is_object());
+    }
+
+    #[test]
+    fn test_prompts_list_and_get() {
+        let prompts = list_prompts();
+        assert_eq!(prompts["prompts"][0]["name"], "explain_ip_geolocation");
+
+        let missing = handle_prompt_get(json!(1), None);
+        assert_eq!(missing.error.unwrap().code, INVALID_PARAMS);
+
+        let response = handle_prompt_get(
+            json!(2),
+            Some(json!({"name": "explain_ip_geolocation", "arguments": {"ip": "1.1.1.1"}})),
+        );
+        let result = response.result.unwrap();
+        assert!(result["messages"][0]["content"]["text"]
+            .as_str()
+            .unwrap()
+            .contains("Explain the geolocation of 1.1.1.1"));
+
+        let response = handle_prompt_get(
+            json!(3),
+            Some(json!({"name": "explain_ip_geolocation", "arguments": {}})),
+        );
+        assert_eq!(response.error.unwrap().code, INVALID_PARAMS);
     }
 }
@@ -1,14 +1,16 @@
 //! MCP (Model Context Protocol) server module for IP Geolocation API
 //!
 //! This module provides MCP server functionality, exposing the IP geolocation
-//! and timezone lookup capabilities as MCP tools and resources.
+//! and timezone lookup capabilities as MCP tools, resources, and prompts.
 //!
 //! ## Tools
 //!
 //! - `geoip_lookup` - Look up geographic location for an IP address
-//! - `geoip_bulk_lookup` - Look up multiple IP addresses (max 100)
+//! - `geoip_bulk_lookup` - Look up multiple IP addresses (max 100 by default, see `BULK_MAX_IPS`)
 //! - `geoip_lookup_self` - Look up the caller's IP (HTTP transport only)
 //! - `timezone_lookup` - Look up timezone for coordinates
+//! - `timezone_bulk_lookup` - Look up timezones for multiple coordinates (same cap as bulk IP lookups)
+//! - `country_info` - Look up country metadata by ISO 3166-1 alpha-2 code
 //!
 //! ## Resources
 //!
@@ -16,6 +18,11 @@
 //! - `geoip://data-source` - Information about data sources
 //! - `geoip://limits` - API limits and constraints
 //! - `geoip://privacy` - Privacy and data handling information
+//! - `geoip://country/{code}` - Country metadata for an ISO 3166-1 alpha-2 code
+//!
+//! ## Prompts
+//!
+//! - `explain_ip_geolocation` - Explain an IP's location in plain language (HTTP transport)
 //!
 //! ## Transports
 //!
@@ -33,21 +40,31 @@
 //! - `GET /mcp/info` - Server capabilities and discovery
 
 pub mod axum_handlers;
+pub mod prompts;
 pub mod resources;
 pub mod schemas;
 pub mod tools;
 
 // Axum handler exports (for HTTP integration)
 pub use axum_handlers::{
-    mcp_batch_handler, mcp_info_handler, mcp_jsonrpc_handler, mcp_sse_handler, McpState,
+    mcp_batch_handler, mcp_info_handler, mcp_jsonrpc_handler, mcp_sse_handler, McpNotification,
+    McpState,
 };
 
+// Prompt exports
+pub use prompts::{get_prompt, list_prompts, EXPLAIN_GEOLOCATION_PROMPT};
+
 // Resource exports
-pub use resources::{list_resource_infos, read_resource, GeoIpResourceHandler};
+pub use resources::{
+    country_metadata_json, get_country_resource, list_resource_infos, read_resource,
+    GeoIpResourceHandler,
+};
 
 // Tool exports
 pub use tools::{
-    handle_geoip_bulk_lookup, handle_geoip_lookup, handle_geoip_lookup_self,
-    handle_timezone_lookup, GeoIpBulkLookupHandler, GeoIpLookupHandler, GeoIpLookupSelfHandler,
-    McpErrorCode, McpToolContext, TimezoneLookupHandler, BULK_LOOKUP_MAX_IPS,
+    handle_country_info, handle_geoip_bulk_lookup, handle_geoip_lookup, handle_geoip_lookup_self,
+    handle_timezone_bulk_lookup, handle_timezone_lookup, CountryInfoHandler,
+    GeoIpBulkLookupHandler, GeoIpLookupHandler, GeoIpLookupSelfHandler, McpErrorCode,
+    McpToolContext, TimezoneBulkLookupHandler, TimezoneLookupHandler, BULK_LOOKUP_MAX_IPS,
 };
+pub use tools::{parse_disabled_tools, BulkLimits, DEFAULT_MCP_BULK_CONCURRENCY, TOOL_NAMES};
@@ -0,0 +1,93 @@
+//! MCP prompt templates for IP geolocation
+//!
+//! Prompts are reusable message templates that clients fill in with
+//! arguments and send to their model, e.g. to have it explain a lookup.
+
+use std::net::IpAddr;
+
+use serde_json::{json, Value};
+
+use super::schemas;
+
+/// Name of the prompt asking the model to explain an IP's geolocation
+pub const EXPLAIN_GEOLOCATION_PROMPT: &str = "explain_ip_geolocation";
+
+/// List of available prompts (`prompts/list` result)
+pub fn list_prompts() -> Value {
+    json!({
+        "prompts": [
+            {
+                "name": EXPLAIN_GEOLOCATION_PROMPT,
+                "description": "Explain the geolocation of an IP address in plain language, using the geoip_lookup tool.",
+                "arguments": schemas::explain_geolocation_prompt_arguments()
+            }
+        ]
+    })
+}
+
+/// Fill in a prompt template (`prompts/get` result)
+///
+/// Returns an error message for unknown prompts or missing/invalid arguments.
+pub fn get_prompt(name: &str, arguments: &Value) -> Result<Value, String> {
+    if name != EXPLAIN_GEOLOCATION_PROMPT {
+        return Err(format!("Prompt not found: {}", name));
+    }
+
+    let ip = arguments
+        .get("ip")
+        .and_then(Value::as_str)
+        .ok_or_else(|| "Missing required argument: ip".to_string())?;
+    // Only real addresses are interpolated into the prompt text
+    let ip: IpAddr = ip
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid IP address: {}", ip))?;
+
+    Ok(json!({
+        "description": format!("Explain the geolocation of {}", ip),
+        "messages": [
+            {
+                "role": "user",
+                "content": {
+                    "type": "text",
+                    "text": format!(
+                        "Explain the geolocation of {} in plain language. Use the geoip_lookup \
+                         tool to find where it is (city, region, country), its local time zone \
+                         and currency, and mention how precise IP-based location can be.",
+                        ip
+                    )
+                }
+            }
+        ]
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_list_prompts() {
+        let prompts = list_prompts();
+        let prompts = prompts["prompts"].as_array().unwrap();
+        assert_eq!(prompts.len(), 1);
+        assert_eq!(prompts[0]["name"], EXPLAIN_GEOLOCATION_PROMPT);
+        assert_eq!(prompts[0]["arguments"][0]["name"], "ip");
+        assert_eq!(prompts[0]["arguments"][0]["required"], true);
+    }
+
+    #[test]
+    fn test_get_prompt_fills_ip() {
+        let prompt = get_prompt(EXPLAIN_GEOLOCATION_PROMPT, &json!({"ip": "8.8.8.8"})).unwrap();
+        let text = prompt["messages"][0]["content"]["text"].as_str().unwrap();
+        assert!(text.starts_with("Explain the geolocation of 8.8.8.8 in plain language."));
+        assert_eq!(prompt["messages"][0]["role"], "user");
+    }
+
+    #[test]
+    fn test_get_prompt_errors() {
+        assert!(get_prompt("unknown", &json!({"ip": "8.8.8.8"})).is_err());
+        assert!(get_prompt(EXPLAIN_GEOLOCATION_PROMPT, &json!({})).is_err());
+        assert!(get_prompt(EXPLAIN_GEOLOCATION_PROMPT, &json!({"ip": "not-an-ip"})).is_err());
+    }
+}
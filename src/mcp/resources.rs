@@ -12,11 +12,14 @@ use mcp_protocol_sdk::protocol::types::{Resource as ResourceInfo, ResourceConten
 use serde_json::json;
 
 use super::schemas;
-use super::tools::BULK_LOOKUP_MAX_IPS;
+use crate::country_data::get_country_metadata;
 
 /// Resource URI prefix for geoip resources
 pub const RESOURCE_URI_PREFIX: &str = "geoip://";
 
+/// Resource URI prefix for per-country metadata (`geoip://country/{code}`)
+pub const COUNTRY_URI_PREFIX: &str = "geoip://country/";
+
 /// Get the schema resource content
 pub fn get_schema_resource() -> ResourceContents {
     let schema = json!({
@@ -89,14 +92,14 @@ pub fn get_data_source_resource() -> ResourceContents {
     }
 }
 
-/// Get the limits resource content
-pub fn get_limits_resource() -> ResourceContents {
+/// Get the limits resource content, with `bulk_max_ips` as the bulk cap
+pub fn get_limits_resource(bulk_max_ips: usize) -> ResourceContents {
     let content = json!({
         "title": "API Limits and Constraints",
         "description": "Information about rate limits and operational constraints",
         "limits": {
             "bulk_lookup": {
-                "max_ips_per_request": BULK_LOOKUP_MAX_IPS,
+                "max_ips_per_request": bulk_max_ips,
                 "description": "Maximum number of IP addresses that can be looked up in a single bulk request"
             },
             "cache": {
@@ -176,6 +179,34 @@ pub fn get_privacy_resource() -> ResourceContents {
     }
 }
 
+/// Get the country metadata resource for an ISO 3166-1 alpha-2 code
+///
+/// Returns `None` for codes missing from the embedded dataset.
+pub fn get_country_resource(code: &str) -> Option<ResourceContents> {
+    let code = code.to_uppercase();
+    let content = country_metadata_json(&code)?;
+
+    Some(ResourceContents::Text {
+        uri: format!("{}{}", COUNTRY_URI_PREFIX, code),
+        text: serde_json::to_string_pretty(&content).unwrap(),
+        mime_type: Some("application/json".to_string()),
+        meta: None,
+    })
+}
+
+/// Country metadata with its `iso_code`, shared by the country resource and
+/// the `country_info` tool
+///
+/// Returns `None` for codes missing from the embedded dataset.
+pub fn country_metadata_json(code: &str) -> Option<serde_json::Value> {
+    let code = code.to_uppercase();
+    let metadata = get_country_metadata(Some(&code))?;
+
+    let mut content = serde_json::to_value(metadata).unwrap();
+    content["iso_code"] = json!(code);
+    Some(content)
+}
+
 /// List of all available resource infos
 pub fn list_resource_infos() -> Vec<ResourceInfo> {
     vec![
@@ -205,7 +236,7 @@ pub fn list_resource_infos() -> Vec<ResourceInfo> {
             uri: format!("{}limits", RESOURCE_URI_PREFIX),
             name: "API Limits".to_string(),
             description: Some(
-                "Bulk lookup cap (100), cache TTL, and operational constraints".to_string(),
+                "Bulk lookup cap, cache TTL, and operational constraints".to_string(),
             ),
             mime_type: Some("application/json".to_string()),
             annotations: None,
@@ -223,22 +254,40 @@ pub fn list_resource_infos() -> Vec<ResourceInfo> {
             title: None,
             meta: None,
         },
+        ResourceInfo {
+            uri: format!("{}{{code}}", COUNTRY_URI_PREFIX),
+            name: "Country Metadata".to_string(),
+            description: Some(
+                "Currency, capital, languages, and calling code for an ISO 3166-1 alpha-2 country code (e.g. geoip://country/DE)"
+                    .to_string(),
+            ),
+            mime_type: Some("application/json".to_string()),
+            annotations: None,
+            size: None,
+            title: None,
+            meta: None,
+        },
     ]
 }
 
-/// Read a resource by URI
-pub fn read_resource(uri: &str) -> Option<ResourceContents> {
+/// Read a resource by URI; `geoip://limits` reports `bulk_max_ips`
+pub fn read_resource(uri: &str, bulk_max_ips: usize) -> Option<ResourceContents> {
     match uri {
         "geoip://schema" => Some(get_schema_resource()),
         "geoip://data-source" => Some(get_data_source_resource()),
-        "geoip://limits" => Some(get_limits_resource()),
+        "geoip://limits" => Some(get_limits_resource(bulk_max_ips)),
         "geoip://privacy" => Some(get_privacy_resource()),
-        _ => None,
+        _ => uri
+            .strip_prefix(COUNTRY_URI_PREFIX)
+            .and_then(get_country_resource),
     }
 }
 
 /// Resource handler for GeoIP resources
-pub struct GeoIpResourceHandler;
+pub struct GeoIpResourceHandler {
+    /// Bulk cap reported by `geoip://limits`
+    pub bulk_max_ips: usize,
+}
 
 #[async_trait]
 impl ResourceHandler for GeoIpResourceHandler {
@@ -247,7 +296,7 @@ impl ResourceHandler for GeoIpResourceHandler {
         uri: &str,
         _params: &HashMap<String, String>,
     ) -> McpResult<Vec<ResourceContents>> {
-        match read_resource(uri) {
+        match read_resource(uri, self.bulk_max_ips) {
             Some(contents) => Ok(vec![contents]),
             None => Err(mcp_protocol_sdk::core::error::McpError::ResourceNotFound(
                 format!("Resource not found: {}", uri),
@@ -263,11 +312,12 @@ impl ResourceHandler for GeoIpResourceHandler {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::mcp::BULK_LOOKUP_MAX_IPS;
 
     #[test]
-    fn test_list_resource_infos_returns_four() {
+    fn test_list_resource_infos_returns_five() {
         let resources = list_resource_infos();
-        assert_eq!(resources.len(), 4);
+        assert_eq!(resources.len(), 5);
     }
 
     #[test]
@@ -278,35 +328,56 @@ mod tests {
         assert!(uris.contains(&"geoip://data-source"));
         assert!(uris.contains(&"geoip://limits"));
         assert!(uris.contains(&"geoip://privacy"));
+        assert!(uris.contains(&"geoip://country/{code}"));
     }
 
     #[test]
     fn test_read_resource_schema() {
-        let content = read_resource("geoip://schema");
+        let content = read_resource("geoip://schema", BULK_LOOKUP_MAX_IPS);
         assert!(content.is_some());
     }
 
     #[test]
     fn test_read_resource_data_source() {
-        let content = read_resource("geoip://data-source");
+        let content = read_resource("geoip://data-source", BULK_LOOKUP_MAX_IPS);
         assert!(content.is_some());
     }
 
     #[test]
     fn test_read_resource_limits() {
-        let content = read_resource("geoip://limits");
+        let content = read_resource("geoip://limits", BULK_LOOKUP_MAX_IPS);
         assert!(content.is_some());
     }
 
     #[test]
     fn test_read_resource_privacy() {
-        let content = read_resource("geoip://privacy");
+        let content = read_resource("geoip://privacy", BULK_LOOKUP_MAX_IPS);
         assert!(content.is_some());
     }
 
+    #[test]
+    fn test_read_resource_country() {
+        let content = read_resource("geoip://country/DE", BULK_LOOKUP_MAX_IPS);
+        if let Some(ResourceContents::Text { uri, text, .. }) = content {
+            assert_eq!(uri, "geoip://country/DE");
+            let json: serde_json::Value = serde_json::from_str(&text).unwrap();
+            assert_eq!(json["iso_code"], "DE");
+            assert_eq!(json["capital"], "Berlin");
+            assert_eq!(json["currency_code"], "EUR");
+        } else {
+            panic!("Expected Text content");
+        }
+    }
+
+    #[test]
+    fn test_read_resource_country_unknown() {
+        assert!(read_resource("geoip://country/ZZ", BULK_LOOKUP_MAX_IPS).is_none());
+        assert!(read_resource("geoip://country/", BULK_LOOKUP_MAX_IPS).is_none());
+    }
+
     #[test]
     fn test_read_resource_unknown() {
-        let content = read_resource("geoip://unknown");
+        let content = read_resource("geoip://unknown", BULK_LOOKUP_MAX_IPS);
         assert!(content.is_none());
     }
 
@@ -325,13 +396,10 @@ mod tests {
 
     #[test]
     fn test_limits_resource_has_bulk_cap() {
-        let content = get_limits_resource();
+        let content = get_limits_resource(250);
         if let ResourceContents::Text { text, .. } = content {
             let json: serde_json::Value = serde_json::from_str(&text).unwrap();
-            assert_eq!(
-                json["limits"]["bulk_lookup"]["max_ips_per_request"],
-                BULK_LOOKUP_MAX_IPS
-            );
+            assert_eq!(json["limits"]["bulk_lookup"]["max_ips_per_request"], 250);
         } else {
             panic!("Expected Text content");
         }
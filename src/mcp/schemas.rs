@@ -24,8 +24,8 @@ pub fn geoip_lookup_input_schema() -> Value {
     })
 }
 
-/// JSON Schema for geoip_bulk_lookup tool input
-pub fn geoip_bulk_lookup_input_schema() -> Value {
+/// JSON Schema for geoip_bulk_lookup tool input (`maxItems` is the bulk cap)
+pub fn geoip_bulk_lookup_input_schema(max_ips: usize) -> Value {
     json!({
         "type": "object",
         "properties": {
@@ -34,8 +34,8 @@ pub fn geoip_bulk_lookup_input_schema() -> Value {
                 "items": {
                     "type": "string"
                 },
-                "maxItems": 100,
-                "description": "Array of IPv4 or IPv6 addresses to lookup (max 100)"
+                "maxItems": max_ips,
+                "description": format!("Array of IPv4 or IPv6 addresses to lookup (max {})", max_ips)
             },
             "format": {
                 "type": "string",
@@ -91,6 +91,54 @@ pub fn timezone_lookup_input_schema() -> Value {
     })
 }
 
+/// JSON Schema for timezone_bulk_lookup tool input (`maxItems` is the bulk cap)
+pub fn timezone_bulk_lookup_input_schema(max_points: usize) -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "points": {
+                "type": "array",
+                "items": {
+                    "type": "object",
+                    "properties": {
+                        "lat": {
+                            "type": "number",
+                            "minimum": -90,
+                            "maximum": 90,
+                            "description": "Latitude coordinate (-90 to 90)"
+                        },
+                        "lon": {
+                            "type": "number",
+                            "minimum": -180,
+                            "maximum": 180,
+                            "description": "Longitude coordinate (-180 to 180)"
+                        }
+                    },
+                    "required": ["lat", "lon"]
+                },
+                "maxItems": max_points,
+                "description": format!("Array of coordinate pairs to resolve (max {})", max_points)
+            }
+        },
+        "required": ["points"]
+    })
+}
+
+/// JSON Schema for country_info tool input
+pub fn country_info_input_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "code": {
+                "type": "string",
+                "pattern": "^[A-Za-z]{2}$",
+                "description": "ISO 3166-1 alpha-2 country code (e.g., 'DE')"
+            }
+        },
+        "required": ["code"]
+    })
+}
+
 /// JSON Schema for simple IP geolocation response
 pub fn ip_geo_response_simple_schema() -> Value {
     json!({
@@ -155,10 +203,26 @@ pub fn ip_geo_response_full_schema() -> Value {
                     "zipcode": { "type": "string", "description": "Postal/ZIP code" },
                     "latitude": { "type": "string", "description": "Latitude as string" },
                     "longitude": { "type": "string", "description": "Longitude as string" },
+                    "latitude_f": { "type": "number", "description": "Latitude as a number" },
+                    "longitude_f": { "type": "number", "description": "Longitude as a number" },
                     "is_eu": { "type": "boolean", "description": "Whether the country is in the EU" },
                     "country_flag": { "type": "string", "description": "Path to country flag SVG" },
                     "geoname_id": { "type": "string", "description": "GeoNames ID" },
-                    "country_emoji": { "type": "string", "description": "Country flag emoji" }
+                    "country_geoname_id": { "type": "string", "description": "GeoNames ID of the country" },
+                    "state_geoname_id": { "type": "string", "description": "GeoNames ID of the state/province" },
+                    "country_emoji": { "type": "string", "description": "Country flag emoji" },
+                    "registered_country_code": { "type": "string", "description": "Country where the network is registered, if different from the located country" },
+                    "represented_country_code": { "type": "string", "description": "Country represented by the IP's users (e.g. embassies, military bases), if different" },
+                    "metro_code": { "type": "integer", "description": "Nielsen DMA/metro code (US only)" },
+                    "coordinate_source": { "type": "string", "description": "\"country_centroid\" when latitude/longitude are the country centre because the database had no coordinates" },
+                    "nearest_airport": {
+                        "type": "object",
+                        "description": "Closest major airport (only with airport=true)",
+                        "properties": {
+                            "iata": { "type": "string", "description": "IATA airport code" },
+                            "distance_km": { "type": "number", "description": "Distance from the coordinates in kilometres" }
+                        }
+                    }
                 }
             },
             "country_metadata": {
@@ -181,17 +245,37 @@ pub fn ip_geo_response_full_schema() -> Value {
                     "symbol": { "type": "string", "description": "Currency symbol" }
                 }
             },
+            "region": {
+                "type": "object",
+                "properties": {
+                    "code": { "type": "string", "description": "UN M49 region code (e.g., 150)" },
+                    "name": { "type": "string", "description": "UN M49 region name (e.g., Europe)" },
+                    "subregion_code": { "type": "string", "description": "UN M49 subregion code (e.g., 155)" },
+                    "subregion": { "type": "string", "description": "UN M49 subregion name (e.g., Western Europe)" }
+                }
+            },
             "time_zone": {
                 "type": "object",
                 "properties": {
                     "name": { "type": "string", "description": "IANA timezone name" },
                     "offset": { "type": "integer", "description": "UTC offset in hours (without DST)" },
                     "offset_with_dst": { "type": "integer", "description": "UTC offset in hours (with DST)" },
+                    "abbreviation": { "type": "string", "description": "Timezone abbreviation currently in effect (e.g., CET or CEST)" },
                     "current_time": { "type": "string", "description": "Current local time" },
                     "current_time_unix": { "type": "number", "description": "Current time as Unix timestamp" },
+                    "current_time_unix_ms": { "type": "integer", "description": "Current time as Unix milliseconds" },
                     "is_dst": { "type": "boolean", "description": "Whether DST is active" },
                     "dst_savings": { "type": "integer", "description": "DST offset in hours" },
-                    "dst_exists": { "type": "boolean", "description": "Whether DST is observed" }
+                    "dst_exists": { "type": "boolean", "description": "Whether DST is observed" },
+                    "source": { "type": "string", "description": "Where name came from: coordinates or database" }
+                }
+            },
+            "network": {
+                "type": "object",
+                "description": "Matched network and, with a Connection-Type database, the connection type",
+                "properties": {
+                    "connection_type": { "type": "string", "description": "Dialup, Cable/DSL, Corporate, Cellular, or Satellite" },
+                    "network": { "type": "string", "description": "Database network (CIDR) the IP matched, e.g. 8.8.8.0/24" }
                 }
             }
         }
@@ -245,14 +329,32 @@ pub fn timezone_response_full_schema() -> Value {
             "timezone": { "type": "string", "description": "IANA timezone name" },
             "offset": { "type": "integer", "description": "UTC offset in hours (without DST)" },
             "offset_with_dst": { "type": "integer", "description": "UTC offset in hours (with DST)" },
+            "abbreviation": { "type": "string", "description": "Timezone abbreviation currently in effect (e.g., CET or CEST)" },
             "current_time": { "type": "string", "description": "Current local time" },
             "current_time_unix": { "type": "number", "description": "Current time as Unix timestamp" },
+            "current_time_unix_ms": { "type": "integer", "description": "Current time as Unix milliseconds" },
             "is_dst": { "type": "boolean", "description": "Whether DST is active" },
-            "dst_exists": { "type": "boolean", "description": "Whether DST is observed" }
+            "dst_exists": { "type": "boolean", "description": "Whether DST is observed" },
+            "candidates": {
+                "type": "array",
+                "items": { "type": "string" },
+                "description": "Zones within about 5 km, most likely first (REST candidates=true only)"
+            }
         }
     })
 }
 
+/// Arguments of the `explain_ip_geolocation` prompt
+pub fn explain_geolocation_prompt_arguments() -> Value {
+    json!([
+        {
+            "name": "ip",
+            "description": "IPv4 or IPv6 address to explain (e.g., '8.8.8.8')",
+            "required": true
+        }
+    ])
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -269,8 +371,15 @@ mod tests {
 
     #[test]
     fn test_geoip_bulk_lookup_input_schema_has_max_items() {
-        let schema = geoip_bulk_lookup_input_schema();
-        assert_eq!(schema["properties"]["ips"]["maxItems"], 100);
+        let schema = geoip_bulk_lookup_input_schema(250);
+        assert_eq!(schema["properties"]["ips"]["maxItems"], 250);
+    }
+
+    #[test]
+    fn test_timezone_bulk_lookup_input_schema_has_max_items() {
+        let schema = timezone_bulk_lookup_input_schema(250);
+        assert_eq!(schema["properties"]["points"]["maxItems"], 250);
+        assert!(schema["properties"]["points"]["items"]["properties"]["lat"].is_object());
     }
 
     #[test]
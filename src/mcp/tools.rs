@@ -5,27 +5,92 @@
 
 use std::collections::HashMap;
 use std::net::IpAddr;
+use std::sync::Arc;
 
 use async_trait::async_trait;
+use chrono::Utc;
 use mcp_protocol_sdk::core::error::McpResult;
 use mcp_protocol_sdk::core::tool::ToolHandler;
 use mcp_protocol_sdk::protocol::types::{CallToolResult, ContentBlock};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use tokio::sync::Semaphore;
 
-use crate::country_data::{get_country_metadata, get_flag_path};
+use super::resources::country_metadata_json;
 use crate::geoip::{GeoIpError, SharedGeoIpReader};
-use crate::languages::get_languages;
-use crate::models::{
-    CountryMetadataInfo, CurrencyInfo, GeoData, IpGeoResponse, IpGeoResponseFull, LocationInfo,
-    TimeZoneInfo, TimeZoneInfoFull, TimezoneResponse, TimezoneResponseFull,
+use crate::handlers::{
+    build_full_response, build_simple_response, build_timezone_full_response,
+    build_timezone_response, ResponseOptions,
 };
-use crate::timezone::lookup_timezone;
-use crate::tz_utils::get_timezone_details;
+use crate::ip_class::{classify_ip, IpType};
+use crate::models::{IpGeoResponseFull, TimezonePoint};
+use crate::timezone::{SharedTimezoneProvider, TimezoneProvider, TzfTimezoneProvider};
 
-/// Maximum number of IPs allowed in a bulk lookup
+/// Default maximum number of IPs allowed in a bulk lookup
 pub const BULK_LOOKUP_MAX_IPS: usize = 100;
 
+/// Default number of blocking threads `geoip_bulk_lookup` may use at once
+pub const DEFAULT_MCP_BULK_CONCURRENCY: usize = 4;
+
+/// Limits on the bulk tools, shared by every call a server handles
+#[derive(Debug, Clone)]
+pub struct BulkLimits {
+    /// Most IPs or points one bulk call may carry (`BULK_MAX_IPS`)
+    pub max_items: usize,
+    /// Blocking threads `geoip_bulk_lookup` may use at once (`MCP_BULK_CONCURRENCY`)
+    pub concurrency: usize,
+    /// Permits shared by every `geoip_bulk_lookup` call, so concurrent calls
+    /// together never hold more than `concurrency` blocking threads
+    permits: Arc<Semaphore>,
+}
+
+impl BulkLimits {
+    pub fn new(max_items: usize, concurrency: usize) -> Self {
+        Self {
+            max_items,
+            concurrency,
+            permits: Arc::new(Semaphore::new(concurrency)),
+        }
+    }
+}
+
+impl Default for BulkLimits {
+    fn default() -> Self {
+        Self::new(BULK_LOOKUP_MAX_IPS, DEFAULT_MCP_BULK_CONCURRENCY)
+    }
+}
+
+/// Names of every MCP tool this server provides
+pub const TOOL_NAMES: &[&str] = &[
+    "geoip_lookup",
+    "geoip_bulk_lookup",
+    "geoip_lookup_self",
+    "timezone_lookup",
+    "timezone_bulk_lookup",
+    "country_info",
+];
+
+/// Parse `MCP_DISABLE_TOOLS`, a comma-separated list of tool names to hide
+pub fn parse_disabled_tools(spec: &str) -> Result<Vec<String>, String> {
+    let disabled: Vec<String> = spec
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(String::from)
+        .collect();
+    if let Some(unknown) = disabled
+        .iter()
+        .find(|name| !TOOL_NAMES.contains(&name.as_str()))
+    {
+        return Err(format!(
+            "unknown tool '{}' (known: {})",
+            unknown,
+            TOOL_NAMES.join(", ")
+        ));
+    }
+    Ok(disabled)
+}
+
 /// Error codes for MCP tools
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum McpErrorCode {
@@ -35,6 +100,7 @@ pub enum McpErrorCode {
     BulkLimitExceeded,
     InvalidLatitude,
     InvalidLongitude,
+    InvalidCountryCode,
     StdioNoCallerIp,
 }
 
@@ -47,6 +113,7 @@ impl McpErrorCode {
             Self::BulkLimitExceeded => "BULK_LIMIT_EXCEEDED",
             Self::InvalidLatitude => "INVALID_LATITUDE",
             Self::InvalidLongitude => "INVALID_LONGITUDE",
+            Self::InvalidCountryCode => "INVALID_COUNTRY_CODE",
             Self::StdioNoCallerIp => "STDIO_NO_CALLER_IP",
         }
     }
@@ -84,6 +151,18 @@ pub struct TimezoneLookupInput {
     pub format: String,
 }
 
+/// Input parameters for timezone_bulk_lookup tool
+#[derive(Debug, Deserialize)]
+pub struct TimezoneBulkLookupInput {
+    pub points: Vec<TimezonePoint>,
+}
+
+/// Input parameters for country_info tool
+#[derive(Debug, Deserialize)]
+pub struct CountryInfoInput {
+    pub code: String,
+}
+
 fn default_format() -> String {
     "full".to_string()
 }
@@ -103,25 +182,13 @@ pub struct BulkLookupResult {
     pub errors: Vec<BulkLookupError>,
 }
 
-/// Check if an IP address is private/loopback/link-local
-fn is_private_ip(ip: &IpAddr) -> bool {
-    match ip {
-        IpAddr::V4(ipv4) => {
-            ipv4.is_loopback()
-                || ipv4.is_private()
-                || ipv4.is_link_local()
-                || ipv4.is_broadcast()
-                || ipv4.is_unspecified()
-        }
-        IpAddr::V6(ipv6) => {
-            ipv6.is_loopback()
-                || ipv6.is_unspecified()
-                // Unique local addresses (fc00::/7) - RFC 4193
-                || (ipv6.segments()[0] & 0xfe00) == 0xfc00
-                // Link-local addresses (fe80::/10) - RFC 4291
-                || (ipv6.segments()[0] & 0xffc0) == 0xfe80
-        }
-    }
+/// Result of a timezone bulk lookup, one entry per point in request order
+///
+/// Each entry is a full timezone response, or `{lat, lon, code, message}`
+/// for a point with out-of-range coordinates.
+#[derive(Debug, Serialize)]
+pub struct TimezoneBulkLookupResult {
+    pub results: Vec<Value>,
 }
 
 /// Validate an IP address string
@@ -134,90 +201,33 @@ fn validate_ip(ip_str: &str) -> Result<IpAddr, (McpErrorCode, String)> {
     })
 }
 
-/// Build a simple response from GeoData
-fn build_simple_response(geo_data: &GeoData) -> IpGeoResponse {
-    let timezone_name = match (geo_data.latitude, geo_data.longitude) {
-        (Some(lat), Some(lng)) => lookup_timezone(lat, lng).unwrap_or_default(),
-        _ => String::new(),
-    };
-
-    let languages = get_languages(geo_data.country_code.as_deref());
-
-    IpGeoResponse {
-        latitude: geo_data.latitude,
-        longitude: geo_data.longitude,
-        city: geo_data.city.clone().unwrap_or_default(),
-        country_name: geo_data.country_name.clone().unwrap_or_default(),
-        time_zone: TimeZoneInfo {
-            name: timezone_name,
-        },
-        languages,
+/// Validate a latitude/longitude pair
+fn validate_coordinates(lat: f64, lon: f64) -> Result<(), (McpErrorCode, String)> {
+    if !lat.is_finite() {
+        return Err((
+            McpErrorCode::InvalidLatitude,
+            format!("Latitude must be a finite number, got: {}", lat),
+        ));
     }
-}
-
-/// Build a full response from GeoData
-fn build_full_response(ip: &str, geo_data: &GeoData) -> IpGeoResponseFull {
-    let country_code = geo_data.country_code.as_deref();
-    let country_meta = get_country_metadata(country_code);
-
-    // Get timezone from coordinates if available
-    let timezone_name = match (geo_data.latitude, geo_data.longitude) {
-        (Some(lat), Some(lng)) => lookup_timezone(lat, lng),
-        _ => None,
-    };
-
-    // Get timezone details
-    let tz_details = timezone_name
-        .as_ref()
-        .and_then(|tz| get_timezone_details(tz));
-
-    IpGeoResponseFull {
-        ip: Some(ip.to_string()),
-        location: Some(LocationInfo {
-            continent_code: country_meta.map(|m| m.continent_code.to_string()),
-            continent_name: country_meta.map(|m| m.continent_name.to_string()),
-            country_code2: geo_data.country_code.clone(),
-            country_code3: country_meta.map(|m| m.iso_code3.to_string()),
-            country_name: geo_data.country_name.clone(),
-            country_name_official: country_meta.map(|m| m.official_name.to_string()),
-            country_capital: country_meta.map(|m| m.capital.to_string()),
-            state_prov: geo_data.state_prov.clone(),
-            state_code: geo_data
-                .state_code
-                .as_ref()
-                .map(|sc| format!("{}-{}", geo_data.country_code.as_deref().unwrap_or(""), sc)),
-            district: None,
-            city: geo_data.city.clone(),
-            zipcode: geo_data.postal_code.clone(),
-            latitude: geo_data.latitude.map(|l| format!("{:.5}", l)),
-            longitude: geo_data.longitude.map(|l| format!("{:.5}", l)),
-            is_eu: country_meta.map(|m| m.is_eu),
-            country_flag: country_code.map(get_flag_path),
-            geoname_id: geo_data.geoname_id.map(|id| id.to_string()),
-            country_emoji: country_meta.map(|m| m.flag_emoji.to_string()),
-        }),
-        country_metadata: Some(CountryMetadataInfo {
-            calling_code: country_meta.map(|m| m.calling_code.to_string()),
-            tld: country_meta.map(|m| m.tld.to_string()),
-            languages: country_meta
-                .map(|m| m.languages.split(',').map(|s| s.to_string()).collect()),
-        }),
-        currency: country_meta.map(|m| CurrencyInfo {
-            code: Some(m.currency_code.to_string()),
-            name: Some(m.currency_name.to_string()),
-            symbol: Some(m.currency_symbol.to_string()),
-        }),
-        time_zone: tz_details.map(|tz| TimeZoneInfoFull {
-            name: Some(tz.name),
-            offset: Some(tz.offset_hours),
-            offset_with_dst: Some(tz.offset_with_dst_hours),
-            current_time: Some(tz.current_time),
-            current_time_unix: Some(tz.current_time_unix),
-            is_dst: Some(tz.is_dst),
-            dst_savings: Some(tz.dst_savings_hours),
-            dst_exists: Some(tz.dst_exists),
-        }),
+    if !(-90.0..=90.0).contains(&lat) {
+        return Err((
+            McpErrorCode::InvalidLatitude,
+            format!("Latitude must be between -90 and 90, got: {}", lat),
+        ));
+    }
+    if !lon.is_finite() {
+        return Err((
+            McpErrorCode::InvalidLongitude,
+            format!("Longitude must be a finite number, got: {}", lon),
+        ));
+    }
+    if !(-180.0..=180.0).contains(&lon) {
+        return Err((
+            McpErrorCode::InvalidLongitude,
+            format!("Longitude must be between -180 and 180, got: {}", lon),
+        ));
     }
+    Ok(())
 }
 
 /// Create a text content block
@@ -259,35 +269,69 @@ fn success_result<T: Serialize>(result: &T) -> CallToolResult {
     }
 }
 
+/// Create a success CallToolResult for GeoLite2-derived data
+///
+/// Tool results have no response headers, so the attribution REST sends as
+/// `X-Data-Attribution` goes in `_meta.attribution` instead.
+fn attributed_result<T: Serialize>(result: &T, options: &ResponseOptions) -> CallToolResult {
+    let mut result = success_result(result);
+    result.meta = options.data_attribution.as_ref().map(|attribution| {
+        HashMap::from([(
+            "attribution".to_string(),
+            Value::String(attribution.clone()),
+        )])
+    });
+    result
+}
+
 /// Tool handler for geoip_lookup
 pub struct GeoIpLookupHandler {
     pub geoip: SharedGeoIpReader,
+    pub timezones: SharedTimezoneProvider,
+    pub options: Arc<ResponseOptions>,
 }
 
 #[async_trait]
 impl ToolHandler for GeoIpLookupHandler {
     async fn call(&self, arguments: HashMap<String, Value>) -> McpResult<CallToolResult> {
         let args = serde_json::to_value(arguments).unwrap_or_default();
-        Ok(handle_geoip_lookup(&self.geoip, args))
+        Ok(handle_geoip_lookup(
+            &self.geoip,
+            &self.timezones,
+            &self.options,
+            args,
+        ))
     }
 }
 
 /// Tool handler for geoip_bulk_lookup
 pub struct GeoIpBulkLookupHandler {
     pub geoip: SharedGeoIpReader,
+    pub timezones: SharedTimezoneProvider,
+    pub options: Arc<ResponseOptions>,
+    pub limits: BulkLimits,
 }
 
 #[async_trait]
 impl ToolHandler for GeoIpBulkLookupHandler {
     async fn call(&self, arguments: HashMap<String, Value>) -> McpResult<CallToolResult> {
         let args = serde_json::to_value(arguments).unwrap_or_default();
-        Ok(handle_geoip_bulk_lookup(&self.geoip, args))
+        Ok(handle_geoip_bulk_lookup(
+            &self.geoip,
+            &self.timezones,
+            &self.options,
+            &self.limits,
+            args,
+        )
+        .await)
     }
 }
 
 /// Tool handler for geoip_lookup_self
 pub struct GeoIpLookupSelfHandler {
     pub geoip: SharedGeoIpReader,
+    pub timezones: SharedTimezoneProvider,
+    pub options: Arc<ResponseOptions>,
     pub caller_ip: Option<String>,
 }
 
@@ -297,6 +341,8 @@ impl ToolHandler for GeoIpLookupSelfHandler {
         let args = serde_json::to_value(arguments).unwrap_or_default();
         Ok(handle_geoip_lookup_self(
             &self.geoip,
+            &self.timezones,
+            &self.options,
             self.caller_ip.as_deref(),
             args,
         ))
@@ -304,18 +350,26 @@ impl ToolHandler for GeoIpLookupSelfHandler {
 }
 
 /// Tool handler for timezone_lookup
-pub struct TimezoneLookupHandler;
+pub struct TimezoneLookupHandler {
+    pub timezones: SharedTimezoneProvider,
+    pub options: Arc<ResponseOptions>,
+}
 
 #[async_trait]
 impl ToolHandler for TimezoneLookupHandler {
     async fn call(&self, arguments: HashMap<String, Value>) -> McpResult<CallToolResult> {
         let args = serde_json::to_value(arguments).unwrap_or_default();
-        Ok(handle_timezone_lookup(args))
+        Ok(handle_timezone_lookup(&self.timezones, &self.options, args))
     }
 }
 
 /// Handle geoip_lookup tool call
-pub fn handle_geoip_lookup(geoip: &SharedGeoIpReader, args: Value) -> CallToolResult {
+pub fn handle_geoip_lookup(
+    geoip: &SharedGeoIpReader,
+    timezones: &SharedTimezoneProvider,
+    options: &ResponseOptions,
+    args: Value,
+) -> CallToolResult {
     // Parse input
     let input: GeoIpLookupInput = match serde_json::from_value(args) {
         Ok(i) => i,
@@ -331,7 +385,7 @@ pub fn handle_geoip_lookup(geoip: &SharedGeoIpReader, args: Value) -> CallToolRe
     };
 
     // Check for private IP
-    if is_private_ip(&ip) {
+    if classify_ip(&ip) != IpType::Public && !geoip.is_overridden(&input.ip) {
         return error_result(
             McpErrorCode::PrivateIp,
             &format!("Private/loopback IP address not supported: {}", input.ip),
@@ -344,11 +398,12 @@ pub fn handle_geoip_lookup(geoip: &SharedGeoIpReader, args: Value) -> CallToolRe
     match geo_result {
         Ok(geo_data) => {
             if input.format == "simple" {
-                let response = build_simple_response(&geo_data);
-                success_result(&response)
+                let response = build_simple_response(&geo_data, timezones.as_ref(), options);
+                attributed_result(&response, options)
             } else {
-                let response = build_full_response(&input.ip, &geo_data);
-                success_result(&response)
+                let response =
+                    build_full_response(&input.ip, &geo_data, timezones.as_ref(), options);
+                attributed_result(&response, options)
             }
         }
         Err(GeoIpError::NotFound) => error_result(
@@ -360,7 +415,17 @@ pub fn handle_geoip_lookup(geoip: &SharedGeoIpReader, args: Value) -> CallToolRe
 }
 
 /// Handle geoip_bulk_lookup tool call
-pub fn handle_geoip_bulk_lookup(geoip: &SharedGeoIpReader, args: Value) -> CallToolResult {
+///
+/// The IPs are split into up to `limits.concurrency` chunks looked up on the
+/// blocking pool, so large calls don't stall the async runtime; results and
+/// errors keep the order of the input.
+pub async fn handle_geoip_bulk_lookup(
+    geoip: &SharedGeoIpReader,
+    timezones: &SharedTimezoneProvider,
+    options: &Arc<ResponseOptions>,
+    limits: &BulkLimits,
+    args: Value,
+) -> CallToolResult {
     // Parse input
     let input: GeoIpBulkLookupInput = match serde_json::from_value(args) {
         Ok(i) => i,
@@ -370,72 +435,95 @@ pub fn handle_geoip_bulk_lookup(geoip: &SharedGeoIpReader, args: Value) -> CallT
     };
 
     // Check bulk limit
-    if input.ips.len() > BULK_LOOKUP_MAX_IPS {
+    let max_ips = limits.max_items;
+    if input.ips.len() > max_ips {
         return error_result(
             McpErrorCode::BulkLimitExceeded,
             &format!(
                 "Bulk lookup limit exceeded: {} IPs provided, maximum is {}",
                 input.ips.len(),
-                BULK_LOOKUP_MAX_IPS
+                max_ips
             ),
         );
     }
 
+    let chunk_size = input.ips.len().div_ceil(limits.concurrency).max(1);
+    let mut tasks = Vec::new();
+    for chunk in input.ips.chunks(chunk_size) {
+        let permit = limits
+            .permits
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("bulk lookup semaphore is never closed");
+        let geoip = geoip.clone();
+        let timezones = timezones.clone();
+        let options = options.clone();
+        let chunk = chunk.to_vec();
+        tasks.push(tokio::task::spawn_blocking(move || {
+            let _permit = permit;
+            chunk
+                .iter()
+                .map(|ip_str| bulk_lookup_entry(&geoip, timezones.as_ref(), &options, ip_str))
+                .collect::<Vec<_>>()
+        }));
+    }
+
     let mut results = Vec::new();
     let mut errors = Vec::new();
-
-    for ip_str in &input.ips {
-        // Validate IP
-        match validate_ip(ip_str) {
-            Ok(ip) => {
-                if is_private_ip(&ip) {
-                    errors.push(BulkLookupError {
-                        ip: ip_str.clone(),
-                        code: McpErrorCode::PrivateIp.as_str().to_string(),
-                        message: "Private/loopback IP address not supported".to_string(),
-                    });
-                    continue;
-                }
-
-                match geoip.lookup(ip_str) {
-                    Ok(geo_data) => {
-                        let response = build_full_response(ip_str, &geo_data);
-                        results.push(response);
-                    }
-                    Err(GeoIpError::NotFound) => {
-                        errors.push(BulkLookupError {
-                            ip: ip_str.clone(),
-                            code: McpErrorCode::NotFound.as_str().to_string(),
-                            message: "IP address not found in database".to_string(),
-                        });
-                    }
-                    Err(e) => {
-                        errors.push(BulkLookupError {
-                            ip: ip_str.clone(),
-                            code: McpErrorCode::InvalidIp.as_str().to_string(),
-                            message: format!("Lookup error: {}", e),
-                        });
-                    }
-                }
-            }
-            Err((code, msg)) => {
-                errors.push(BulkLookupError {
-                    ip: ip_str.clone(),
-                    code: code.as_str().to_string(),
-                    message: msg,
-                });
+    for task in tasks {
+        for entry in task.await.expect("bulk lookup task panicked") {
+            match entry {
+                Ok(response) => results.push(response),
+                Err(error) => errors.push(error),
             }
         }
     }
 
     let bulk_result = BulkLookupResult { results, errors };
-    success_result(&bulk_result)
+    attributed_result(&bulk_result, options)
+}
+
+/// Look up one IP of a bulk call, as a full response or a per-IP error
+fn bulk_lookup_entry(
+    geoip: &SharedGeoIpReader,
+    timezones: &dyn TimezoneProvider,
+    options: &ResponseOptions,
+    ip_str: &str,
+) -> Result<IpGeoResponseFull, BulkLookupError> {
+    let error = |code: McpErrorCode, message: String| BulkLookupError {
+        ip: ip_str.to_string(),
+        code: code.as_str().to_string(),
+        message,
+    };
+
+    let ip = validate_ip(ip_str).map_err(|(code, msg)| error(code, msg))?;
+    if classify_ip(&ip) != IpType::Public && !geoip.is_overridden(ip_str) {
+        return Err(error(
+            McpErrorCode::PrivateIp,
+            "Private/loopback IP address not supported".to_string(),
+        ));
+    }
+
+    match geoip.lookup(ip_str) {
+        Ok(geo_data) => Ok(build_full_response(ip_str, &geo_data, timezones, options)),
+        Err(GeoIpError::NotFound) => Err(error(
+            McpErrorCode::NotFound,
+            "IP address not found in database".to_string(),
+        )),
+        Err(e) => Err(error(
+            McpErrorCode::InvalidIp,
+            format!("Lookup error: {}", e),
+        )),
+    }
 }
 
 /// Handle geoip_lookup_self tool call
 /// Note: This only works with HTTP/SSE transport. Returns error for STDIO.
 pub fn handle_geoip_lookup_self(
     geoip: &SharedGeoIpReader,
+    timezones: &SharedTimezoneProvider,
+    options: &ResponseOptions,
     caller_ip: Option<&str>,
     args: Value,
 ) -> CallToolResult {
@@ -467,7 +555,7 @@ pub fn handle_geoip_lookup_self(
     };
 
     // Check for private IP
-    if is_private_ip(&ip) {
+    if classify_ip(&ip) != IpType::Public && !geoip.is_overridden(&ip_str) {
         return error_result(
             McpErrorCode::PrivateIp,
             &format!("Private/loopback IP address not supported: {}", ip_str),
@@ -480,11 +568,11 @@ pub fn handle_geoip_lookup_self(
     match geo_result {
         Ok(geo_data) => {
             if input.format == "simple" {
-                let response = build_simple_response(&geo_data);
-                success_result(&response)
+                let response = build_simple_response(&geo_data, timezones.as_ref(), options);
+                attributed_result(&response, options)
             } else {
-                let response = build_full_response(&ip_str, &geo_data);
-                success_result(&response)
+                let response = build_full_response(&ip_str, &geo_data, timezones.as_ref(), options);
+                attributed_result(&response, options)
             }
         }
         Err(GeoIpError::NotFound) => error_result(
@@ -496,7 +584,11 @@ pub fn handle_geoip_lookup_self(
 }
 
 /// Handle timezone_lookup tool call
-pub fn handle_timezone_lookup(args: Value) -> CallToolResult {
+pub fn handle_timezone_lookup(
+    timezones: &SharedTimezoneProvider,
+    options: &ResponseOptions,
+    args: Value,
+) -> CallToolResult {
     // Parse input
     let input: TimezoneLookupInput = match serde_json::from_value(args) {
         Ok(i) => i,
@@ -508,61 +600,157 @@ pub fn handle_timezone_lookup(args: Value) -> CallToolResult {
         }
     };
 
-    // Validate latitude
-    if !(-90.0..=90.0).contains(&input.lat) {
+    // Validate coordinates
+    if let Err((code, msg)) = validate_coordinates(input.lat, input.lon) {
+        return error_result(code, &msg);
+    }
+
+    if input.format == "simple" {
+        success_result(&build_timezone_response(
+            timezones.as_ref(),
+            options,
+            input.lat,
+            input.lon,
+        ))
+    } else {
+        success_result(&build_timezone_full_response(
+            timezones.as_ref(),
+            options,
+            input.lat,
+            input.lon,
+            Utc::now(),
+            false,
+        ))
+    }
+}
+
+/// Handle timezone_bulk_lookup tool call
+pub fn handle_timezone_bulk_lookup(
+    timezones: &SharedTimezoneProvider,
+    options: &ResponseOptions,
+    limits: &BulkLimits,
+    args: Value,
+) -> CallToolResult {
+    // Parse input
+    let input: TimezoneBulkLookupInput = match serde_json::from_value(args) {
+        Ok(i) => i,
+        Err(e) => {
+            return error_result(
+                McpErrorCode::InvalidLatitude,
+                &format!("Invalid input: {}", e),
+            );
+        }
+    };
+
+    // Check bulk limit (shared with IP bulk lookups)
+    let max_points = limits.max_items;
+    if input.points.len() > max_points {
         return error_result(
-            McpErrorCode::InvalidLatitude,
-            &format!("Latitude must be between -90 and 90, got: {}", input.lat),
+            McpErrorCode::BulkLimitExceeded,
+            &format!(
+                "Bulk lookup limit exceeded: {} points provided, maximum is {}",
+                input.points.len(),
+                max_points
+            ),
         );
     }
 
-    // Validate longitude
-    if !(-180.0..=180.0).contains(&input.lon) {
+    // Every point in the batch describes the same instant, as on /v1/timezone/batch
+    let now = Utc::now();
+    let results = input
+        .points
+        .iter()
+        .map(|point| match validate_coordinates(point.lat, point.lon) {
+            Ok(()) => serde_json::to_value(build_timezone_full_response(
+                timezones.as_ref(),
+                options,
+                point.lat,
+                point.lon,
+                now,
+                false,
+            ))
+            .unwrap(),
+            Err((code, message)) => serde_json::json!({
+                "lat": point.lat,
+                "lon": point.lon,
+                "code": code.as_str(),
+                "message": message,
+            }),
+        })
+        .collect();
+
+    success_result(&TimezoneBulkLookupResult { results })
+}
+
+/// Tool handler for timezone_bulk_lookup
+pub struct TimezoneBulkLookupHandler {
+    pub timezones: SharedTimezoneProvider,
+    pub options: Arc<ResponseOptions>,
+    pub limits: BulkLimits,
+}
+
+#[async_trait]
+impl ToolHandler for TimezoneBulkLookupHandler {
+    async fn call(&self, arguments: HashMap<String, Value>) -> McpResult<CallToolResult> {
+        let args = serde_json::to_value(arguments).unwrap_or_default();
+        Ok(handle_timezone_bulk_lookup(
+            &self.timezones,
+            &self.options,
+            &self.limits,
+            args,
+        ))
+    }
+}
+
+/// Handle country_info tool call
+pub fn handle_country_info(args: Value) -> CallToolResult {
+    // Parse input
+    let input: CountryInfoInput = match serde_json::from_value(args) {
+        Ok(i) => i,
+        Err(e) => {
+            return error_result(
+                McpErrorCode::InvalidCountryCode,
+                &format!("Invalid input: {}", e),
+            );
+        }
+    };
+
+    let code = input.code.trim();
+    if code.len() != 2 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
         return error_result(
-            McpErrorCode::InvalidLongitude,
-            &format!("Longitude must be between -180 and 180, got: {}", input.lon),
+            McpErrorCode::InvalidCountryCode,
+            &format!(
+                "Country code must be ISO 3166-1 alpha-2, got: {}",
+                input.code
+            ),
         );
     }
 
-    // Look up timezone
-    let timezone_name = lookup_timezone(input.lat, input.lon);
+    match country_metadata_json(code) {
+        Some(content) => success_result(&content),
+        None => error_result(
+            McpErrorCode::NotFound,
+            &format!("Unknown country code: {}", code.to_uppercase()),
+        ),
+    }
+}
+
+/// Tool handler for country_info
+pub struct CountryInfoHandler;
 
-    if input.format == "simple" {
-        let response = TimezoneResponse {
-            timezone: timezone_name.clone().unwrap_or_default(),
-        };
-        success_result(&response)
-    } else {
-        let response = match &timezone_name {
-            Some(tz_name) => {
-                let details = get_timezone_details(tz_name);
-                TimezoneResponseFull {
-                    timezone: tz_name.clone(),
-                    offset: details.as_ref().map(|d| d.offset_hours),
-                    offset_with_dst: details.as_ref().map(|d| d.offset_with_dst_hours),
-                    current_time: details.as_ref().map(|d| d.current_time.clone()),
-                    current_time_unix: details.as_ref().map(|d| d.current_time_unix),
-                    is_dst: details.as_ref().map(|d| d.is_dst),
-                    dst_exists: details.as_ref().map(|d| d.dst_exists),
-                }
-            }
-            None => TimezoneResponseFull {
-                timezone: String::new(),
-                offset: None,
-                offset_with_dst: None,
-                current_time: None,
-                current_time_unix: None,
-                is_dst: None,
-                dst_exists: None,
-            },
-        };
-        success_result(&response)
+#[async_trait]
+impl ToolHandler for CountryInfoHandler {
+    async fn call(&self, arguments: HashMap<String, Value>) -> McpResult<CallToolResult> {
+        let args = serde_json::to_value(arguments).unwrap_or_default();
+        Ok(handle_country_info(args))
     }
 }
 
 /// MCP Tool context holding shared state
 pub struct McpToolContext {
     pub geoip: SharedGeoIpReader,
+    /// Timezone lookups from coordinates (tzf-rs unless replaced)
+    pub timezones: SharedTimezoneProvider,
     /// Caller IP address (only set for SSE transport)
     pub caller_ip: Option<String>,
 }
@@ -571,6 +759,7 @@ impl McpToolContext {
     pub fn new(geoip: SharedGeoIpReader) -> Self {
         Self {
             geoip,
+            timezones: Arc::new(TzfTimezoneProvider),
             caller_ip: None,
         }
     }
@@ -585,6 +774,7 @@ impl McpToolContext {
 mod tests {
     use super::*;
     use crate::geoip::mock::MockGeoIpReader;
+    use crate::models::GeoData;
     use std::sync::Arc;
 
     fn mock_geoip() -> SharedGeoIpReader {
@@ -600,11 +790,20 @@ mod tests {
                 state_code: Some("CA".to_string()),
                 postal_code: Some("94043".to_string()),
                 geoname_id: Some(5375480),
+                ..Default::default()
             }),
         );
         Arc::new(mock)
     }
 
+    fn tzf() -> SharedTimezoneProvider {
+        Arc::new(TzfTimezoneProvider)
+    }
+
+    fn options() -> Arc<ResponseOptions> {
+        Arc::new(ResponseOptions::default())
+    }
+
     #[test]
     fn test_validate_ip_valid() {
         assert!(validate_ip("8.8.8.8").is_ok());
@@ -618,27 +817,79 @@ mod tests {
     }
 
     #[test]
-    fn test_is_private_ip() {
-        assert!(is_private_ip(&"127.0.0.1".parse().unwrap()));
-        assert!(is_private_ip(&"192.168.1.1".parse().unwrap()));
-        assert!(is_private_ip(&"10.0.0.1".parse().unwrap()));
-        assert!(is_private_ip(&"172.16.0.1".parse().unwrap()));
-        assert!(!is_private_ip(&"8.8.8.8".parse().unwrap()));
+    fn test_handle_geoip_lookup_bogons() {
+        // CGNAT, TEST-NET-1 and benchmarking ranges are rejected like REST
+        // does, even when the database has a record for them
+        let bogons = ["100.64.0.1", "192.0.2.1", "198.18.0.1", "10.0.0.1"];
+        let mock = bogons.iter().fold(MockGeoIpReader::new(), |mock, ip| {
+            mock.with_response(ip, Ok(GeoData::default()))
+        });
+        let geoip: SharedGeoIpReader = Arc::new(mock);
+
+        for ip in bogons {
+            let result =
+                handle_geoip_lookup(&geoip, &tzf(), &options(), serde_json::json!({ "ip": ip }));
+            assert!(result.is_error.unwrap_or(false), "{ip}");
+            let ContentBlock::Text { text, .. } = &result.content[0] else {
+                panic!("expected text content");
+            };
+            assert!(text.contains("Private/loopback"), "{ip}: {text}");
+        }
+    }
+
+    #[test]
+    fn test_handle_geoip_lookup_override() {
+        // Operator overrides give private ranges a location
+        let geoip: SharedGeoIpReader = Arc::new(
+            crate::geoip::OverrideGeoIpReader::from_json(
+                r#"{"10.0.0.0/8": {"city": "VPN", "country_code": "SE"}}"#,
+                Arc::new(MockGeoIpReader::new()),
+            )
+            .unwrap(),
+        );
+        let result = handle_geoip_lookup(
+            &geoip,
+            &tzf(),
+            &options(),
+            serde_json::json!({ "ip": "10.1.2.3", "format": "simple" }),
+        );
+        assert!(!result.is_error.unwrap_or(true));
+        let ContentBlock::Text { text, .. } = &result.content[0] else {
+            panic!("expected text content");
+        };
+        assert!(text.contains("VPN"), "{text}");
+
+        let entry = bulk_lookup_entry(&geoip, tzf().as_ref(), &options(), "10.1.2.3").unwrap();
+        assert_eq!(entry.location.unwrap().city.as_deref(), Some("VPN"));
+        assert!(bulk_lookup_entry(&geoip, tzf().as_ref(), &options(), "192.168.0.1").is_err());
     }
 
     #[test]
     fn test_handle_geoip_lookup_valid() {
         let geoip = mock_geoip();
         let args = serde_json::json!({ "ip": "8.8.8.8" });
-        let result = handle_geoip_lookup(&geoip, args);
+        let result = handle_geoip_lookup(&geoip, &tzf(), &options(), args);
         assert!(!result.is_error.unwrap_or(true));
+        assert_eq!(
+            result.meta.unwrap()["attribution"],
+            crate::DEFAULT_DATA_ATTRIBUTION
+        );
+
+        // An empty DATA_ATTRIBUTION leaves it out
+        let options = ResponseOptions {
+            data_attribution: None,
+            ..ResponseOptions::default()
+        };
+        let args = serde_json::json!({ "ip": "8.8.8.8" });
+        let result = handle_geoip_lookup(&geoip, &tzf(), &options, args);
+        assert!(result.meta.is_none());
     }
 
     #[test]
     fn test_handle_geoip_lookup_invalid_ip() {
         let geoip = mock_geoip();
         let args = serde_json::json!({ "ip": "not-an-ip" });
-        let result = handle_geoip_lookup(&geoip, args);
+        let result = handle_geoip_lookup(&geoip, &tzf(), &options(), args);
         assert!(result.is_error.unwrap_or(false));
     }
 
@@ -646,45 +897,194 @@ mod tests {
     fn test_handle_geoip_lookup_private_ip() {
         let geoip = mock_geoip();
         let args = serde_json::json!({ "ip": "127.0.0.1" });
-        let result = handle_geoip_lookup(&geoip, args);
+        let result = handle_geoip_lookup(&geoip, &tzf(), &options(), args);
         assert!(result.is_error.unwrap_or(false));
     }
 
-    #[test]
-    fn test_handle_bulk_lookup_exceeds_limit() {
+    #[tokio::test]
+    async fn test_handle_bulk_lookup_exceeds_limit() {
         let geoip = mock_geoip();
         let ips: Vec<String> = (0..101).map(|i| format!("8.8.8.{}", i % 256)).collect();
         let args = serde_json::json!({ "ips": ips });
-        let result = handle_geoip_bulk_lookup(&geoip, args);
+        let result =
+            handle_geoip_bulk_lookup(&geoip, &tzf(), &options(), &BulkLimits::default(), args)
+                .await;
         assert!(result.is_error.unwrap_or(false));
     }
 
+    #[tokio::test]
+    async fn test_handle_bulk_lookup_complete_and_ordered() {
+        let mut mock = MockGeoIpReader::new();
+        for i in 0..90 {
+            mock = mock.with_response(
+                &format!("8.8.{}.1", i),
+                Ok(GeoData {
+                    city: Some(format!("City {}", i)),
+                    ..Default::default()
+                }),
+            );
+        }
+        let geoip: SharedGeoIpReader = Arc::new(mock);
+
+        // Every tenth entry is an error, so errors land in several chunks too
+        let ips: Vec<String> = (0..100)
+            .map(|i| match i % 10 {
+                0 => format!("10.0.0.{}", i),
+                _ => format!("8.8.{}.1", i - i / 10 - 1),
+            })
+            .collect();
+        let result = handle_geoip_bulk_lookup(
+            &geoip,
+            &tzf(),
+            &options(),
+            &BulkLimits::default(),
+            serde_json::json!({ "ips": ips }),
+        )
+        .await;
+        assert!(!result.is_error.unwrap_or(true));
+
+        let structured = result.structured_content.unwrap();
+        let results = structured["results"].as_array().unwrap();
+        let errors = structured["errors"].as_array().unwrap();
+        assert_eq!(results.len(), 90);
+        assert_eq!(errors.len(), 10);
+        for (i, result) in results.iter().enumerate() {
+            assert_eq!(result["ip"], format!("8.8.{}.1", i));
+            assert_eq!(result["location"]["city"], format!("City {}", i));
+        }
+        for (i, error) in errors.iter().enumerate() {
+            assert_eq!(error["ip"], format!("10.0.0.{}", i * 10));
+            assert_eq!(error["code"], "PRIVATE_IP");
+        }
+    }
+
     #[test]
     fn test_handle_geoip_lookup_self_no_caller_ip() {
         let geoip = mock_geoip();
         let args = serde_json::json!({});
-        let result = handle_geoip_lookup_self(&geoip, None, args);
+        let result = handle_geoip_lookup_self(&geoip, &tzf(), &options(), None, args);
         assert!(result.is_error.unwrap_or(false));
     }
 
     #[test]
     fn test_handle_timezone_lookup_valid() {
         let args = serde_json::json!({ "lat": 59.329504, "lon": 18.069532 });
-        let result = handle_timezone_lookup(args);
+        let result = handle_timezone_lookup(&tzf(), &options(), args);
         assert!(!result.is_error.unwrap_or(true));
     }
 
+    #[test]
+    fn test_timezone_lookup_matches_rest_payload() {
+        let args = serde_json::json!({ "lat": 59.329504123456, "lon": 18.069532 });
+        let mcp = handle_timezone_lookup(&tzf(), &options(), args)
+            .structured_content
+            .unwrap();
+        let rest = serde_json::to_value(build_timezone_full_response(
+            &TzfTimezoneProvider,
+            &ResponseOptions::default(),
+            59.329504,
+            18.069532,
+            Utc::now(),
+            false,
+        ))
+        .unwrap();
+
+        let keys = |v: &Value| v.as_object().unwrap().keys().cloned().collect::<Vec<_>>();
+        assert_eq!(keys(&mcp), keys(&rest));
+        for field in ["timezone", "offset", "abbreviation", "is_dst", "dst_exists"] {
+            assert_eq!(mcp[field], rest[field], "{field}");
+        }
+    }
+
+    #[test]
+    fn test_timezone_tools_use_provider() {
+        use crate::timezone::mock::MockTimezoneProvider;
+
+        let timezones: SharedTimezoneProvider = Arc::new(MockTimezoneProvider::new("Etc/UTC"));
+        let args = serde_json::json!({ "lat": 59.329504, "lon": 18.069532 });
+        let result = handle_timezone_lookup(&timezones, &options(), args);
+        assert_eq!(result.structured_content.unwrap()["timezone"], "Etc/UTC");
+
+        let args = serde_json::json!({ "ip": "8.8.8.8" });
+        let result = handle_geoip_lookup(&mock_geoip(), &timezones, &options(), args);
+        let json = result.structured_content.unwrap();
+        assert_eq!(json["time_zone"]["name"], "Etc/UTC");
+    }
+
     #[test]
     fn test_handle_timezone_lookup_invalid_lat() {
         let args = serde_json::json!({ "lat": 91.0, "lon": 0.0 });
-        let result = handle_timezone_lookup(args);
+        let result = handle_timezone_lookup(&tzf(), &options(), args);
+        assert!(result.is_error.unwrap_or(false));
+    }
+
+    #[test]
+    fn test_handle_timezone_bulk_lookup_mixed() {
+        let args = serde_json::json!({
+            "points": [
+                { "lat": 59.329504, "lon": 18.069532 },
+                { "lat": 91.0, "lon": 0.0 },
+                { "lat": 40.7128, "lon": -74.006 }
+            ]
+        });
+        let result = handle_timezone_bulk_lookup(&tzf(), &options(), &BulkLimits::default(), args);
+        assert!(!result.is_error.unwrap_or(true));
+
+        let structured = result.structured_content.unwrap();
+        let results = structured["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0]["timezone"], "Europe/Stockholm");
+        assert_eq!(results[1]["code"], "INVALID_LATITUDE");
+        assert_eq!(results[1]["lat"], 91.0);
+        assert_eq!(results[2]["timezone"], "America/New_York");
+    }
+
+    #[test]
+    fn test_handle_timezone_bulk_lookup_exceeds_limit() {
+        let points: Vec<Value> = (0..101)
+            .map(|_| serde_json::json!({ "lat": 0.0, "lon": 0.0 }))
+            .collect();
+        let result = handle_timezone_bulk_lookup(
+            &tzf(),
+            &options(),
+            &BulkLimits::default(),
+            serde_json::json!({ "points": points }),
+        );
         assert!(result.is_error.unwrap_or(false));
     }
 
     #[test]
     fn test_handle_timezone_lookup_invalid_lon() {
         let args = serde_json::json!({ "lat": 0.0, "lon": 181.0 });
-        let result = handle_timezone_lookup(args);
+        let result = handle_timezone_lookup(&tzf(), &options(), args);
+        assert!(result.is_error.unwrap_or(false));
+    }
+
+    #[test]
+    fn test_handle_country_info() {
+        let result = handle_country_info(serde_json::json!({ "code": "de" }));
+        assert!(!result.is_error.unwrap_or(true));
+
+        let structured = result.structured_content.unwrap();
+        assert_eq!(structured["iso_code"], "DE");
+        assert_eq!(structured["capital"], "Berlin");
+        assert_eq!(structured["currency_code"], "EUR");
+        assert_eq!(structured["is_eu"], true);
+        assert_eq!(structured["calling_code"], "+49");
+    }
+
+    #[test]
+    fn test_handle_country_info_unknown() {
+        let result = handle_country_info(serde_json::json!({ "code": "XX" }));
+        assert!(result.is_error.unwrap_or(false));
+        let ContentBlock::Text { text, .. } = &result.content[0] else {
+            panic!("expected text content");
+        };
+        assert!(text.contains("NOT_FOUND"));
+
+        let result = handle_country_info(serde_json::json!({ "code": "Germany" }));
+        assert!(result.is_error.unwrap_or(false));
+        let result = handle_country_info(serde_json::json!({}));
         assert!(result.is_error.unwrap_or(false));
     }
 }
@@ -1,6 +1,9 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use utoipa::{IntoParams, ToSchema};
 
+use crate::country_data::NameSource;
+use crate::tz_utils::TimeFormat;
+
 /// Query parameters for the /ipgeo endpoint
 #[derive(Debug, Deserialize, IntoParams)]
 #[serde(rename_all = "camelCase")]
@@ -9,12 +12,75 @@ pub struct IpGeoQuery {
     /// API key (accepted but not validated)
     #[serde(default)]
     pub api_key: Option<String>,
-    /// IP address to lookup (IPv4 or IPv6)
+    /// IP address to lookup (IPv4 or IPv6); `/ipgeo` also accepts a comma-separated
+    /// list, or `self` for the caller's own address
     #[param(example = "8.8.8.8")]
     pub ip: String,
-    /// Fields to include (optional, use "*" or "location" for full format)
+    /// Fields to include: "*" or a list containing "location" selects the full
+    /// format, any other comma-separated list only those fields of the simple
+    /// format (`/ipgeo` only)
     #[serde(default)]
     pub fields: Option<String>,
+    /// Include `current_time`/`current_time_unix` in the full response (default true).
+    /// Set to false to get a response that can be cached long-term.
+    #[serde(default, rename = "live_time")]
+    pub live_time: Option<bool>,
+    /// Format of `time_zone.current_time` in the full response: `maxmind`
+    /// (default, "2024-01-15 14:30:00.123-0800") or `iso8601` (RFC 3339)
+    #[serde(default, rename = "time_format")]
+    #[param(inline)]
+    pub time_format: Option<TimeFormat>,
+    /// Source of `location.country_name` in the full response: `database`
+    /// (default, the GeoIP database's name) or `table` (the embedded country table)
+    #[serde(default, rename = "name_source")]
+    #[param(inline)]
+    pub name_source: Option<NameSource>,
+    /// Indent the JSON response for readability (default false, ignored for protobuf)
+    #[serde(default)]
+    pub pretty: Option<bool>,
+    /// Reject non-public IPs with 422 `PRIVATE_IP` instead of returning an empty
+    /// response (default from `REJECT_PRIVATE_IPS`, normally false)
+    #[serde(default, rename = "reject_private")]
+    pub reject_private: Option<bool>,
+    /// Answer valid IPs with no geolocation data with 204 No Content instead
+    /// of an empty 200 (default from `EMPTY_AS_204`, normally false)
+    #[serde(default, rename = "empty_as_204")]
+    pub empty_as_204: Option<bool>,
+    /// Serialize unknown `city`/`country_name` as `null` instead of `""`
+    /// (`/ipgeo` only, default false)
+    #[serde(default)]
+    pub nulls: Option<bool>,
+    /// Leave coordinates empty instead of falling back to the country centre
+    /// when the database has none (full format only, default false)
+    #[serde(default, rename = "no_centroid")]
+    pub no_centroid: Option<bool>,
+    /// Add `location.nearest_airport` computed from the resolved coordinates
+    /// (full format only, default false)
+    #[serde(default)]
+    pub airport: Option<bool>,
+    /// Add a `_debug` object describing how the lookup was resolved
+    /// (`/v1/ipgeo` JSON only, ignored unless `ENABLE_EXPLAIN` is set)
+    #[serde(default)]
+    pub explain: Option<bool>,
+}
+
+impl IpGeoQuery {
+    /// Query parameter names as they appear in the URL, for `STRICT_QUERY`
+    pub const PARAMS: &'static [&'static str] = &[
+        "apiKey",
+        "ip",
+        "fields",
+        "live_time",
+        "time_format",
+        "name_source",
+        "pretty",
+        "reject_private",
+        "empty_as_204",
+        "nulls",
+        "no_centroid",
+        "airport",
+        "explain",
+    ];
 }
 
 /// Query parameters for the /timezone endpoint
@@ -31,6 +97,156 @@ pub struct TimezoneQuery {
     /// Longitude coordinate (-180 to 180)
     #[param(example = 18.069532, minimum = -180, maximum = 180)]
     pub long: f64,
+    /// Unix timestamp to compute the offset and DST state for instead of now
+    /// (`/v1/timezone` only, years 1900-2100)
+    #[serde(default)]
+    #[param(example = 1751371200)]
+    pub at: Option<i64>,
+    /// Add `candidates` listing zones near the coordinates, most likely first
+    /// (`/v1/timezone` only, default false)
+    #[serde(default)]
+    pub candidates: Option<bool>,
+    /// Format of `current_time`: `maxmind` (default) or `iso8601` (RFC 3339)
+    /// (`/v1/timezone` only)
+    #[serde(default, rename = "time_format")]
+    #[param(inline)]
+    pub time_format: Option<TimeFormat>,
+    /// Indent the JSON response for readability (default false, ignored for protobuf)
+    #[serde(default)]
+    pub pretty: Option<bool>,
+}
+
+impl TimezoneQuery {
+    /// Query parameter names as they appear in the URL, for `STRICT_QUERY`
+    pub const PARAMS: &'static [&'static str] = &[
+        "apiKey",
+        "lat",
+        "long",
+        "at",
+        "candidates",
+        "time_format",
+        "pretty",
+    ];
+}
+
+/// Query parameters for endpoints that only take output options
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FormatQuery {
+    /// Indent the JSON response for readability (default false, ignored for protobuf)
+    #[serde(default)]
+    pub pretty: Option<bool>,
+}
+
+impl FormatQuery {
+    /// Query parameter names as they appear in the URL, for `STRICT_QUERY`
+    pub const PARAMS: &'static [&'static str] = &["pretty"];
+}
+
+/// Optional body of `POST /admin/reload`: new paths for the databases to swap in
+///
+/// Omitted databases are reopened from their current paths.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ReloadRequest {
+    /// Primary City database
+    #[serde(default)]
+    pub city: Option<String>,
+    /// Connection-Type database
+    #[serde(default)]
+    pub connection_type: Option<String>,
+    /// Fallback City database
+    #[serde(default)]
+    pub fallback: Option<String>,
+}
+
+/// Query parameters for `/admin/raw` and `/admin/all`
+#[derive(Debug, Deserialize)]
+pub struct RawRecordQuery {
+    /// IPv4 or IPv6 address to look up
+    pub ip: String,
+}
+
+impl RawRecordQuery {
+    /// Query parameter names as they appear in the URL, for `STRICT_QUERY`
+    pub const PARAMS: &'static [&'static str] = &["ip"];
+}
+
+/// Query parameters for /v1/timezone/compare
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TimezoneCompareQuery {
+    /// Comma-separated IANA timezone names; the first is the reference zone
+    #[param(example = "Europe/Berlin,Asia/Tokyo,America/New_York")]
+    pub zones: String,
+    /// Format of `current_time`: `maxmind` (default) or `iso8601` (RFC 3339)
+    #[serde(default)]
+    #[param(inline)]
+    pub time_format: Option<TimeFormat>,
+    /// Indent the JSON response for readability (default false)
+    #[serde(default)]
+    pub pretty: Option<bool>,
+}
+
+impl TimezoneCompareQuery {
+    /// Query parameter names as they appear in the URL, for `STRICT_QUERY`
+    pub const PARAMS: &'static [&'static str] = &["zones", "time_format", "pretty"];
+}
+
+/// Query parameters for /v1/countries
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CountriesQuery {
+    /// Only return EU member states (default false)
+    #[serde(default)]
+    pub eu_only: Option<bool>,
+    /// Indent the JSON response for readability (default false)
+    #[serde(default)]
+    pub pretty: Option<bool>,
+}
+
+impl CountriesQuery {
+    /// Query parameter names as they appear in the URL, for `STRICT_QUERY`
+    pub const PARAMS: &'static [&'static str] = &["eu_only", "pretty"];
+}
+
+/// Query parameters for /v1/timezones
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TimezonesQuery {
+    /// Include each zone's current UTC offset (default false)
+    #[serde(default)]
+    pub with_offsets: Option<bool>,
+    /// Indent the JSON response for readability (default false)
+    #[serde(default)]
+    pub pretty: Option<bool>,
+}
+
+impl TimezonesQuery {
+    /// Query parameter names as they appear in the URL, for `STRICT_QUERY`
+    pub const PARAMS: &'static [&'static str] = &["with_offsets", "pretty"];
+}
+
+/// A coordinate pair in a timezone batch request
+#[derive(Debug, Clone, Copy, Deserialize, ToSchema)]
+pub struct TimezonePoint {
+    /// Latitude coordinate (-90 to 90)
+    pub lat: f64,
+    /// Longitude coordinate (-180 to 180)
+    pub lon: f64,
+}
+
+/// Request body for POST /v1/timezone/batch
+#[derive(Debug, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "points": [
+        {"lat": 59.329504, "lon": 18.069532},
+        {"lat": 40.7128, "lon": -74.006}
+    ]
+}))]
+pub struct TimezoneBatchRequest {
+    /// Coordinates to resolve, answered in the same order
+    pub points: Vec<TimezonePoint>,
 }
 
 // ============================================================================
@@ -101,6 +317,12 @@ pub struct LocationInfo {
     /// Longitude as string with 5 decimal places
     #[serde(skip_serializing_if = "Option::is_none")]
     pub longitude: Option<String>,
+    /// Latitude as a number, for consumers that don't want to parse `latitude`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latitude_f: Option<f64>,
+    /// Longitude as a number, for consumers that don't want to parse `longitude`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longitude_f: Option<f64>,
     /// Whether the country is in the European Union
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_eu: Option<bool>,
@@ -110,9 +332,44 @@ pub struct LocationInfo {
     /// GeoNames ID for the location
     #[serde(skip_serializing_if = "Option::is_none")]
     pub geoname_id: Option<String>,
+    /// GeoNames ID for the country
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country_geoname_id: Option<String>,
+    /// GeoNames ID for the state/province
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state_geoname_id: Option<String>,
     /// Country flag emoji
     #[serde(skip_serializing_if = "Option::is_none")]
     pub country_emoji: Option<String>,
+    /// Country where the network is registered, when it differs from `country_code2`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registered_country_code: Option<String>,
+    /// Country the IP's users represent (e.g. embassies, military bases), when it differs from `country_code2`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub represented_country_code: Option<String>,
+    /// Nielsen DMA/metro code for US locations (e.g., 807 for San Francisco-Oakland-San Jose)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metro_code: Option<u16>,
+    /// "country_centroid" when the database had no coordinates and
+    /// `latitude`/`longitude` are the centre of the country instead
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coordinate_source: Option<String>,
+    /// Closest major airport to the coordinates (only with `airport=true`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nearest_airport: Option<NearestAirportInfo>,
+}
+
+/// Closest major airport to the resolved coordinates
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
+#[schema(example = json!({
+    "iata": "SJC",
+    "distance_km": 13.6
+}))]
+pub struct NearestAirportInfo {
+    /// IATA airport code (e.g., "SJC")
+    pub iata: String,
+    /// Great-circle distance from the coordinates in kilometres
+    pub distance_km: f64,
 }
 
 /// Country metadata including calling code, TLD, and languages
@@ -153,14 +410,75 @@ pub struct CurrencyInfo {
     pub symbol: Option<String>,
 }
 
+/// UN M49 geographic region of the country
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
+#[schema(example = json!({
+    "code": "150",
+    "name": "Europe",
+    "subregion_code": "155",
+    "subregion": "Western Europe"
+}))]
+pub struct RegionInfo {
+    /// M49 region code (e.g., "150")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    /// M49 region name (e.g., "Europe")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// M49 subregion code (e.g., "155")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subregion_code: Option<String>,
+    /// M49 subregion name (e.g., "Western Europe")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subregion: Option<String>,
+}
+
+/// Network information from optional MaxMind databases
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
+#[schema(example = json!({"connection_type": "Cable/DSL", "network": "8.8.8.0/24"}))]
+pub struct NetworkInfo {
+    /// Connection type: "Dialup", "Cable/DSL", "Corporate", "Cellular", or "Satellite"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub connection_type: Option<String>,
+    /// Database network (CIDR) the IP matched; a short prefix means coarse data
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+    /// Set for addresses that are not looked up: "unspecified" (0.0.0.0, ::) or
+    /// "reserved" (private, loopback, documentation, CGNAT, and other bogons)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_type: Option<String>,
+}
+
+/// How a `/v1/ipgeo` lookup was resolved, returned as `_debug` with `explain=true`
+#[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
+#[schema(example = json!({
+    "network": "8.8.8.0/24",
+    "database_type": "GeoLite2-City",
+    "cached": false,
+    "subdivision_index": 0
+}))]
+pub struct ExplainInfo {
+    /// Database network (CIDR) the IP matched, absent when it was not found
+    pub network: Option<String>,
+    /// `database_type` from the metadata of the database that was queried
+    pub database_type: String,
+    /// Whether the result was served from the lookup cache
+    pub cached: bool,
+    /// Index into the MaxMind subdivisions the state was taken from, absent
+    /// when the record has none
+    pub subdivision_index: Option<usize>,
+}
+
 /// Detailed timezone information including DST data
 #[derive(Debug, Clone, Serialize, Deserialize, Default, ToSchema)]
 #[schema(example = json!({
     "name": "America/Los_Angeles",
     "offset": -8,
     "offset_with_dst": -7,
+    "abbreviation": "PST",
     "current_time": "2024-01-15 14:30:00.123-0800",
     "current_time_unix": 1705355400.123,
+    "current_time_unix_ms": 1705355400123_i64,
     "is_dst": false,
     "dst_savings": 1,
     "dst_exists": true
@@ -175,12 +493,18 @@ pub struct TimeZoneInfoFull {
     /// UTC offset in hours (with DST if active)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub offset_with_dst: Option<i32>,
+    /// Timezone abbreviation currently in effect (e.g., "PST" or "PDT")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abbreviation: Option<String>,
     /// Current local time in the timezone
     #[serde(skip_serializing_if = "Option::is_none")]
     pub current_time: Option<String>,
     /// Current time as Unix timestamp
     #[serde(skip_serializing_if = "Option::is_none")]
     pub current_time_unix: Option<f64>,
+    /// Current time as integer Unix milliseconds, free of float rounding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_time_unix_ms: Option<i64>,
     /// Whether daylight saving time is currently active
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_dst: Option<bool>,
@@ -190,6 +514,10 @@ pub struct TimeZoneInfoFull {
     /// Whether DST is observed in this timezone
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dst_exists: Option<bool>,
+    /// Where `name` came from: "coordinates" (tzf-rs), "database" (MaxMind), or
+    /// "nautical" (an `Etc/GMT±N` approximation for open-ocean coordinates)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
 }
 
 /// Full IP geolocation response with extended location, currency, and timezone data
@@ -225,9 +553,15 @@ pub struct IpGeoResponseFull {
     /// Currency information for the country
     #[serde(skip_serializing_if = "Option::is_none")]
     pub currency: Option<CurrencyInfo>,
+    /// UN M49 region and subregion of the country
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<RegionInfo>,
     /// Detailed timezone information
     #[serde(skip_serializing_if = "Option::is_none")]
     pub time_zone: Option<TimeZoneInfoFull>,
+    /// Network information (present only when a Connection-Type database is configured)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<NetworkInfo>,
 }
 
 // ============================================================================
@@ -259,10 +593,20 @@ pub struct IpGeoResponse {
     /// Longitude of the location
     #[serde(skip_serializing_if = "Option::is_none")]
     pub longitude: Option<f64>,
-    /// City name (empty string if unknown)
-    pub city: String,
-    /// Country name (empty string if unknown)
-    pub country_name: String,
+    /// City name (empty string if unknown, or `null` with `nulls=true`)
+    #[serde(
+        serialize_with = "serialize_empty_if_none",
+        deserialize_with = "deserialize_none_if_empty"
+    )]
+    #[schema(value_type = String, nullable)]
+    pub city: Option<String>,
+    /// Country name (empty string if unknown, or `null` with `nulls=true`)
+    #[serde(
+        serialize_with = "serialize_empty_if_none",
+        deserialize_with = "deserialize_none_if_empty"
+    )]
+    #[schema(value_type = String, nullable)]
+    pub country_name: Option<String>,
     /// Timezone information
     pub time_zone: TimeZoneInfo,
     /// Comma-separated language codes for the country
@@ -274,8 +618,8 @@ impl Default for IpGeoResponse {
         Self {
             latitude: None,
             longitude: None,
-            city: String::new(),
-            country_name: String::new(),
+            city: None,
+            country_name: None,
             time_zone: TimeZoneInfo {
                 name: String::new(),
             },
@@ -284,6 +628,116 @@ impl Default for IpGeoResponse {
     }
 }
 
+impl IpGeoResponse {
+    /// Whether the lookup found nothing (unknown or non-public IP)
+    pub fn is_empty(&self) -> bool {
+        self.latitude.is_none()
+            && self.longitude.is_none()
+            && self.city.is_none()
+            && self.country_name.is_none()
+    }
+
+    /// JSON value of the response, with unknown city/country as `null` when
+    /// `nulls` is set instead of the backward compatible `""`
+    pub fn to_json(&self, nulls: bool) -> serde_json::Value {
+        let mut value = serde_json::to_value(self).unwrap();
+        if nulls {
+            if self.city.is_none() {
+                value["city"] = serde_json::Value::Null;
+            }
+            if self.country_name.is_none() {
+                value["country_name"] = serde_json::Value::Null;
+            }
+        }
+        value
+    }
+}
+
+/// Serialize an unknown string as `""`, as the simple response always has
+fn serialize_empty_if_none<S: Serializer>(
+    value: &Option<String>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(value.as_deref().unwrap_or_default())
+}
+
+/// Read `""` or `null` back as unknown
+fn deserialize_none_if_empty<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    let value = Option::<String>::deserialize(deserializer)?;
+    Ok(value.filter(|s| !s.is_empty()))
+}
+
+/// GeoIP database metadata and freshness
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "database_type": "GeoLite2-City",
+    "build_epoch": 1705276800,
+    "node_count": 3954290,
+    "ip_version": 6,
+    "age_days": 12
+}))]
+pub struct DatabaseHealthResponse {
+    /// Database type (e.g., "GeoLite2-City")
+    pub database_type: String,
+    /// Unix timestamp when the database was built
+    pub build_epoch: u64,
+    /// Number of nodes in the search tree
+    pub node_count: u32,
+    /// IP version supported (4 or 6)
+    pub ip_version: u16,
+    /// Whole days since the database was built
+    pub age_days: u64,
+    /// Whether `age_days` exceeds `MAX_DB_AGE_DAYS`
+    pub stale: bool,
+}
+
+/// Per-subsystem status from /health/ready
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({"database": "ok", "cache": "ok", "http3": "disabled"}))]
+pub struct ReadinessResponse {
+    /// "ok", or "not_loaded" until the GeoIP database has been opened
+    pub database: String,
+    /// "ok" (the lookup cache is in-process and always available)
+    pub cache: String,
+    /// "ok" once the HTTP/3 server is listening, "down" before that or after
+    /// it failed, "disabled" when `HTTP3_ENABLED` is off
+    pub http3: String,
+}
+
+/// Build information for the running server
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "version": "0.2.0",
+    "git_sha": "3015f21",
+    "build_time": "2024-01-15T12:00:00Z"
+}))]
+pub struct VersionResponse {
+    /// Crate version
+    pub version: String,
+    /// Git commit the binary was built from (if known)
+    pub git_sha: Option<String>,
+    /// UTC build timestamp (RFC 3339)
+    pub build_time: String,
+}
+
+/// Lightweight flag response for UI badges
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "country_code": "US",
+    "emoji": "🇺🇸",
+    "svg": "/static/flags/us.svg"
+}))]
+pub struct FlagResponse {
+    /// ISO 3166-1 alpha-2 country code (empty if unknown)
+    pub country_code: String,
+    /// Country flag emoji (empty if unknown)
+    pub emoji: String,
+    /// Path to the country flag SVG (empty if unknown)
+    pub svg: String,
+}
+
 /// Simple timezone response with just the timezone name
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[schema(example = json!({"timezone": "Europe/Stockholm"}))]
@@ -298,10 +752,13 @@ pub struct TimezoneResponse {
     "timezone": "Europe/Stockholm",
     "offset": 1,
     "offset_with_dst": 2,
+    "abbreviation": "CET",
     "current_time": "2024-01-15 23:30:00.123+0100",
     "current_time_unix": 1705355400.123,
+    "current_time_unix_ms": 1705355400123_i64,
     "is_dst": false,
-    "dst_exists": true
+    "dst_exists": true,
+    "source": "coordinates"
 }))]
 pub struct TimezoneResponseFull {
     /// IANA timezone name
@@ -312,35 +769,132 @@ pub struct TimezoneResponseFull {
     /// UTC offset in hours (with DST if active)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub offset_with_dst: Option<i32>,
+    /// Timezone abbreviation currently in effect (e.g., "PST" or "PDT")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abbreviation: Option<String>,
     /// Current local time in the timezone
     #[serde(skip_serializing_if = "Option::is_none")]
     pub current_time: Option<String>,
     /// Current time as Unix timestamp
     #[serde(skip_serializing_if = "Option::is_none")]
     pub current_time_unix: Option<f64>,
+    /// Current time as integer Unix milliseconds, free of float rounding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub current_time_unix_ms: Option<i64>,
     /// Whether daylight saving time is currently active
     #[serde(skip_serializing_if = "Option::is_none")]
     pub is_dst: Option<bool>,
     /// Whether DST is observed in this timezone
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dst_exists: Option<bool>,
+    /// Zones within about 5 km, most likely first (only with `candidates=true`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub candidates: Option<Vec<String>>,
+    /// "coordinates" (tzf-rs) or "nautical" (an `Etc/GMT±N` approximation for
+    /// open-ocean coordinates), like `time_zone.source` on `/v1/ipgeo`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+/// One zone in a /v1/timezone/compare response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TimezoneCompareEntry {
+    /// IANA timezone name
+    pub timezone: String,
+    /// Current UTC offset in hours, fractional for zones like Asia/Kolkata
+    pub offset: f64,
+    /// Timezone abbreviation currently in effect
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub abbreviation: Option<String>,
+    /// Current local time in the timezone
+    pub current_time: String,
+    /// Whether daylight saving time is currently active
+    pub is_dst: bool,
+    /// Hours ahead of the reference zone (negative when behind)
+    pub difference: f64,
+}
+
+/// Current offsets of several zones relative to the first one
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "reference": "Asia/Tokyo",
+    "zones": [
+        {"timezone": "Asia/Tokyo", "offset": 9.0, "abbreviation": "JST", "current_time": "2024-01-15 23:30:00.123+0900", "is_dst": false, "difference": 0.0},
+        {"timezone": "Asia/Kolkata", "offset": 5.5, "abbreviation": "IST", "current_time": "2024-01-15 20:00:00.123+0530", "is_dst": false, "difference": -3.5}
+    ]
+}))]
+pub struct TimezoneCompareResponse {
+    /// Reference zone (the first one requested)
+    pub reference: String,
+    /// Every requested zone, in request order, including the reference
+    pub zones: Vec<TimezoneCompareEntry>,
+}
+
+/// One IANA zone in the /v1/timezones list
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({"name": "Asia/Kolkata", "offset": 5.5}))]
+pub struct TimezoneListEntry {
+    /// IANA timezone name
+    pub name: String,
+    /// Current UTC offset in hours, fractional for zones like Asia/Kolkata
+    /// (only with `with_offsets=true`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offset: Option<f64>,
+}
+
+/// One supported country in the /v1/countries list
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({
+    "code": "DE",
+    "name": "Germany",
+    "continent": "EU",
+    "currency": "EUR",
+    "is_eu": true,
+    "calling_code": "+49",
+    "tld": ".de",
+    "emoji": "🇩🇪"
+}))]
+pub struct CountryEntry {
+    /// ISO 3166-1 alpha-2 country code
+    pub code: String,
+    /// Common country name
+    pub name: String,
+    /// Continent code (e.g., "EU", "NA")
+    pub continent: String,
+    /// ISO 4217 currency code
+    pub currency: String,
+    /// Whether the country is an EU member state
+    pub is_eu: bool,
+    /// International calling code (e.g., "+49")
+    pub calling_code: String,
+    /// Country code top-level domain (e.g., ".de")
+    pub tld: String,
+    /// Country flag emoji
+    pub emoji: String,
 }
 
 /// API error response
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[schema(example = json!({
     "error": "Invalid IP address: not-an-ip",
-    "code": "INVALID_IP"
+    "code": "INVALID_IP",
+    "field": "ip"
 }))]
 pub struct ApiErrorResponse {
     /// Human-readable error message
     pub error: String,
     /// Machine-readable error code (INVALID_IP, INVALID_LATITUDE, INVALID_LONGITUDE)
     pub code: String,
+    /// Request parameter that failed validation ("ip", "lat", "long"), if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
 }
 
 /// Geolocation data extracted from MaxMind database
-#[derive(Debug, Clone)]
+///
+/// Also the value type of `GEOIP_OVERRIDES_PATH` entries, so every field is optional.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GeoData {
     pub latitude: Option<f64>,
     pub longitude: Option<f64>,
@@ -351,6 +905,22 @@ pub struct GeoData {
     pub state_code: Option<String>,
     pub postal_code: Option<String>,
     pub geoname_id: Option<u32>,
+    /// GeoNames ID of the country
+    pub country_geoname_id: Option<u32>,
+    /// GeoNames ID of the first subdivision (state/province)
+    pub state_geoname_id: Option<u32>,
+    /// Country where the ISP registered the network (may differ from the located country)
+    pub registered_country_code: Option<String>,
+    /// Country represented by users of the IP, e.g. a military base or embassy
+    pub represented_country_code: Option<String>,
+    /// Connection type from the optional Connection-Type database (e.g., "Cellular")
+    pub connection_type: Option<String>,
+    /// Nielsen DMA/metro code (US only)
+    pub metro_code: Option<u16>,
+    /// Database network (CIDR) the IP matched, e.g. "8.8.8.0/24"
+    pub network: Option<String>,
+    /// IANA timezone from the database record, used when coordinates are missing
+    pub time_zone: Option<String>,
 }
 
 #[cfg(test)]
@@ -362,8 +932,8 @@ mod tests {
         let response = IpGeoResponse::default();
         assert!(response.latitude.is_none());
         assert!(response.longitude.is_none());
-        assert_eq!(response.city, "");
-        assert_eq!(response.country_name, "");
+        assert!(response.city.is_none());
+        assert!(response.country_name.is_none());
         assert_eq!(response.time_zone.name, "");
         assert_eq!(response.languages, "");
     }
@@ -373,8 +943,8 @@ mod tests {
         let response = IpGeoResponse {
             latitude: Some(37.751),
             longitude: Some(-97.822),
-            city: "Test City".to_string(),
-            country_name: "Test Country".to_string(),
+            city: Some("Test City".to_string()),
+            country_name: Some("Test Country".to_string()),
             time_zone: TimeZoneInfo {
                 name: "America/Chicago".to_string(),
             },
@@ -391,7 +961,31 @@ mod tests {
         let json = r#"{"latitude":37.751,"longitude":-97.822,"city":"Test","country_name":"TC","time_zone":{"name":"America/Chicago"},"languages":"en"}"#;
         let response: IpGeoResponse = serde_json::from_str(json).unwrap();
         assert_eq!(response.latitude, Some(37.751));
-        assert_eq!(response.city, "Test");
+        assert_eq!(response.city.as_deref(), Some("Test"));
+    }
+
+    #[test]
+    fn test_ipgeo_response_unknown_strings() {
+        let response = IpGeoResponse {
+            city: Some("Test City".to_string()),
+            ..Default::default()
+        };
+
+        // Empty strings by default, for backward compatibility
+        let json = response.to_json(false);
+        assert_eq!(json["city"], "Test City");
+        assert_eq!(json["country_name"], "");
+        assert_eq!(serde_json::to_value(&response).unwrap(), json);
+
+        let json = response.to_json(true);
+        assert_eq!(json["city"], "Test City");
+        assert!(json["country_name"].is_null());
+
+        // Both spellings of unknown read back as None
+        let back: IpGeoResponse = serde_json::from_value(json).unwrap();
+        assert!(back.country_name.is_none());
+        let back: IpGeoResponse = serde_json::from_value(response.to_json(false)).unwrap();
+        assert!(back.country_name.is_none());
     }
 
     #[test]
@@ -447,6 +1041,7 @@ mod tests {
             state_code: Some("ENG".to_string()),
             postal_code: Some("SW1A".to_string()),
             geoname_id: Some(2643743),
+            ..Default::default()
         };
         let cloned = data.clone();
         assert_eq!(data.city, cloned.city);
@@ -475,11 +1070,13 @@ mod tests {
                 name: Some("US Dollar".to_string()),
                 symbol: Some("$".to_string()),
             }),
+            region: None,
             time_zone: Some(TimeZoneInfoFull {
                 name: Some("America/Los_Angeles".to_string()),
                 offset: Some(-8),
                 ..Default::default()
             }),
+            network: None,
         };
         let json = serde_json::to_string(&response).unwrap();
         assert!(json.contains("8.8.8.8"));
@@ -0,0 +1,49 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+use crate::handlers::ApiError;
+
+/// Seconds clients are asked to wait before retrying a shed request
+const RETRY_AFTER_SECS: &str = "1";
+
+/// Shared permit pool bounding the number of in-flight requests
+pub type ConcurrencyLimit = Arc<Semaphore>;
+
+/// Create a permit pool allowing `max` concurrent requests
+pub fn concurrency_limit(max: usize) -> ConcurrencyLimit {
+    Arc::new(Semaphore::new(max))
+}
+
+/// Middleware shedding requests once `MAX_CONCURRENT_REQUESTS` are in flight
+///
+/// Excess requests are rejected immediately with 503 `OVERLOADED` and a
+/// `Retry-After` header rather than queued, so a thundering herd cannot pile up
+/// behind the CPU-bound GeoIP and timezone lookups.
+pub async fn shed_overload(
+    State(limit): State<ConcurrencyLimit>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Ok(_permit) = limit.try_acquire() else {
+        tracing::warn!("Concurrency limit reached, shedding request");
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            [(header::RETRY_AFTER, RETRY_AFTER_SECS)],
+            Json(ApiError {
+                error: "Server is overloaded, please retry shortly".to_string(),
+                code: "OVERLOADED",
+                field: None,
+            }),
+        )
+            .into_response();
+    };
+
+    next.run(request).await
+}
@@ -17,8 +17,8 @@ impl From<&models::IpGeoResponse> for geolocation::IpGeoResponse {
         Self {
             latitude: resp.latitude,
             longitude: resp.longitude,
-            city: resp.city.clone(),
-            country_name: resp.country_name.clone(),
+            city: resp.city.clone().unwrap_or_default(),
+            country_name: resp.country_name.clone().unwrap_or_default(),
             time_zone: Some(geolocation::TimeZoneInfo {
                 name: resp.time_zone.name.clone(),
             }),
@@ -51,6 +51,21 @@ impl From<&models::IpGeoResponseFull> for geolocation::IpGeoResponseFull {
                 country_flag: l.country_flag.clone(),
                 geoname_id: l.geoname_id.clone(),
                 country_emoji: l.country_emoji.clone(),
+                registered_country_code: l.registered_country_code.clone(),
+                represented_country_code: l.represented_country_code.clone(),
+                country_geoname_id: l.country_geoname_id.clone(),
+                state_geoname_id: l.state_geoname_id.clone(),
+                latitude_f: l.latitude_f,
+                longitude_f: l.longitude_f,
+                metro_code: l.metro_code.map(u32::from),
+                coordinate_source: l.coordinate_source.clone(),
+                nearest_airport: l
+                    .nearest_airport
+                    .as_ref()
+                    .map(|a| geolocation::NearestAirport {
+                        iata: a.iata.clone(),
+                        distance_km: a.distance_km,
+                    }),
             }),
             country_metadata: resp.country_metadata.as_ref().map(|m| {
                 geolocation::CountryMetadataInfo {
@@ -64,6 +79,12 @@ impl From<&models::IpGeoResponseFull> for geolocation::IpGeoResponseFull {
                 name: c.name.clone(),
                 symbol: c.symbol.clone(),
             }),
+            region: resp.region.as_ref().map(|r| geolocation::RegionInfo {
+                code: r.code.clone(),
+                name: r.name.clone(),
+                subregion_code: r.subregion_code.clone(),
+                subregion: r.subregion.clone(),
+            }),
             time_zone: resp
                 .time_zone
                 .as_ref()
@@ -71,12 +92,31 @@ impl From<&models::IpGeoResponseFull> for geolocation::IpGeoResponseFull {
                     name: t.name.clone(),
                     offset: t.offset,
                     offset_with_dst: t.offset_with_dst,
+                    abbreviation: t.abbreviation.clone(),
                     current_time: t.current_time.clone(),
                     current_time_unix: t.current_time_unix,
+                    current_time_unix_ms: t.current_time_unix_ms,
                     is_dst: t.is_dst,
                     dst_savings: t.dst_savings,
                     dst_exists: t.dst_exists,
+                    source: t.source.clone(),
                 }),
+            network: resp.network.as_ref().map(|n| geolocation::NetworkInfo {
+                connection_type: n.connection_type.clone(),
+                ip_type: n.ip_type.clone(),
+                network: n.network.clone(),
+            }),
+        }
+    }
+}
+
+/// Convert FlagResponse to protobuf
+impl From<&models::FlagResponse> for geolocation::FlagResponse {
+    fn from(resp: &models::FlagResponse) -> Self {
+        Self {
+            country_code: resp.country_code.clone(),
+            emoji: resp.emoji.clone(),
+            svg: resp.svg.clone(),
         }
     }
 }
@@ -97,10 +137,14 @@ impl From<&models::TimezoneResponseFull> for geolocation::TimezoneResponseFull {
             timezone: resp.timezone.clone(),
             offset: resp.offset,
             offset_with_dst: resp.offset_with_dst,
+            abbreviation: resp.abbreviation.clone(),
             current_time: resp.current_time.clone(),
             current_time_unix: resp.current_time_unix,
+            current_time_unix_ms: resp.current_time_unix_ms,
             is_dst: resp.is_dst,
             dst_exists: resp.dst_exists,
+            candidates: resp.candidates.clone().unwrap_or_default(),
+            source: resp.source.clone(),
         }
     }
 }
@@ -114,7 +158,14 @@ pub fn encode_proto<T: Message>(msg: &T) -> Vec<u8> {
 ///
 /// This is used for all protobuf response bodies. The server also accepts
 /// `application/protobuf` in `Accept` headers for compatibility.
-pub const PROTOBUF_CONTENT_TYPE: &str = "application/x-protobuf";
+pub const PROTOBUF_CONTENT_TYPE: &str = "application/x-protobuf; proto=geolocation.v1";
+
+/// Schema version sent as the `proto` parameter of `PROTOBUF_CONTENT_TYPE`;
+/// bumped on incompatible changes to `proto/geolocation.proto`
+pub const PROTO_SCHEMA_VERSION: &str = "geolocation.v1";
+
+/// The `.proto` definition, served at `/proto/geolocation.proto`
+pub const PROTO_DEFINITION: &str = include_str!("../proto/geolocation.proto");
 
 /// Check if request accepts protobuf
 ///
@@ -131,13 +182,20 @@ mod tests {
     use super::*;
     use crate::models::{IpGeoResponse, TimeZoneInfo, TimezoneResponse};
 
+    #[test]
+    fn test_protobuf_content_type_has_version() {
+        assert!(PROTOBUF_CONTENT_TYPE.starts_with("application/x-protobuf;"));
+        assert!(PROTOBUF_CONTENT_TYPE.ends_with(&format!("proto={}", PROTO_SCHEMA_VERSION)));
+        assert!(accepts_protobuf(Some(PROTOBUF_CONTENT_TYPE)));
+    }
+
     #[test]
     fn test_ipgeo_response_to_proto() {
         let resp = IpGeoResponse {
             latitude: Some(37.751),
             longitude: Some(-97.822),
-            city: "Mountain View".to_string(),
-            country_name: "United States".to_string(),
+            city: Some("Mountain View".to_string()),
+            country_name: Some("United States".to_string()),
             time_zone: TimeZoneInfo {
                 name: "America/Chicago".to_string(),
             },
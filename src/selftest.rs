@@ -0,0 +1,99 @@
+//! Optional startup self-test (`STARTUP_SELFTEST`) that logs a few sample lookups
+//!
+//! Catches a wrong database path, an empty database, or broken timezone data
+//! before the first real request does.
+
+use crate::geoip::GeoIpLookup;
+use crate::timezone::TimezoneProvider;
+
+/// Well-known public IPs every City database should resolve
+pub const SELFTEST_IPS: &[&str] = &["8.8.8.8", "1.1.1.1"];
+
+/// Coordinates (Stockholm) and the timezone they must resolve to
+pub const SELFTEST_COORDINATES: (f64, f64, &str) = (59.329504, 18.069532, "Europe/Stockholm");
+
+/// Look up the sample IPs and coordinates, logging each result
+///
+/// Returns a description of every failed check; an empty list means the
+/// stack is wired correctly. Failures are also logged as warnings.
+pub fn run_selftest(geoip: &dyn GeoIpLookup, timezones: &dyn TimezoneProvider) -> Vec<String> {
+    let mut failures = Vec::new();
+
+    for ip in SELFTEST_IPS {
+        match geoip.lookup(ip) {
+            Ok(data) if data.country_code.is_some() => tracing::info!(
+                "Self-test: {} -> {}, {}",
+                ip,
+                data.city.as_deref().unwrap_or("(no city)"),
+                data.country_name.as_deref().unwrap_or_default()
+            ),
+            Ok(_) => failures.push(format!("{ip} resolved without a country")),
+            Err(e) => failures.push(format!("{ip} lookup failed: {e}")),
+        }
+    }
+
+    let (lat, lng, expected) = SELFTEST_COORDINATES;
+    match timezones.lookup(lat, lng) {
+        Some(tz) if tz == expected => tracing::info!("Self-test: ({}, {}) -> {}", lat, lng, tz),
+        Some(tz) => failures.push(format!(
+            "({lat}, {lng}) resolved to {tz}, expected {expected}"
+        )),
+        None => failures.push(format!("({lat}, {lng}) resolved to no timezone")),
+    }
+
+    for failure in &failures {
+        tracing::warn!("STARTUP SELF-TEST FAILED: {}", failure);
+    }
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geoip::mock::MockGeoIpReader;
+    use crate::models::GeoData;
+    use crate::timezone::mock::MockTimezoneProvider;
+    use crate::timezone::TzfTimezoneProvider;
+
+    fn resolved(city: &str, country_code: &str) -> GeoData {
+        GeoData {
+            city: Some(city.to_string()),
+            country_code: Some(country_code.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_run_selftest_passes() {
+        let mock = MockGeoIpReader::new()
+            .with_response("8.8.8.8", Ok(resolved("Mountain View", "US")))
+            .with_response("1.1.1.1", Ok(resolved("Sydney", "AU")));
+
+        assert!(run_selftest(&mock, &TzfTimezoneProvider).is_empty());
+    }
+
+    #[test]
+    fn test_run_selftest_reports_failures() {
+        let mock = MockGeoIpReader::new()
+            .with_response("8.8.8.8", Ok(GeoData::default()))
+            .with_response("1.1.1.1", Ok(resolved("Sydney", "AU")));
+
+        let failures = run_selftest(&mock, &TzfTimezoneProvider);
+        assert_eq!(failures, vec!["8.8.8.8 resolved without a country"]);
+
+        // Nothing resolves against an empty database
+        let failures = run_selftest(&MockGeoIpReader::new(), &TzfTimezoneProvider);
+        assert_eq!(failures.len(), 2);
+        assert!(failures[1].contains("1.1.1.1"));
+
+        // The configured timezone provider is what gets checked
+        let mock = MockGeoIpReader::new()
+            .with_response("8.8.8.8", Ok(resolved("Mountain View", "US")))
+            .with_response("1.1.1.1", Ok(resolved("Sydney", "AU")));
+        let failures = run_selftest(&mock, &MockTimezoneProvider::new("Etc/UTC"));
+        assert_eq!(
+            failures,
+            vec!["(59.329504, 18.069532) resolved to Etc/UTC, expected Europe/Stockholm"]
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{header, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::sync::Arc;
+
+/// Header carrying the response signature
+pub const SIGNATURE_HEADER: &str = "x-signature";
+
+/// Secret shared with clients that verify `X-Signature`
+pub type SigningKey = Arc<[u8]>;
+
+/// Create a signing key from `RESPONSE_SIGNING_KEY`
+pub fn signing_key(secret: &str) -> SigningKey {
+    Arc::from(secret.as_bytes())
+}
+
+/// Lowercase hex HMAC-SHA256 of `body` under `key`
+pub fn sign(key: &[u8], body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Compare two secrets without leaking where they differ through timing
+///
+/// Both sides are MACed first and the tags compared with `verify_slice`,
+/// which is constant time, so the expected length is not leaked either.
+pub fn secrets_match(provided: &[u8], expected: &[u8]) -> bool {
+    let tag = |secret: &[u8]| {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(b"ipgeolocation").expect("HMAC accepts any key");
+        mac.update(secret);
+        mac
+    };
+    tag(expected)
+        .verify_slice(&tag(provided).finalize().into_bytes())
+        .is_ok()
+}
+
+/// Middleware adding `X-Signature` to every response when `RESPONSE_SIGNING_KEY` is set
+///
+/// The signature covers the exact body bytes sent on the wire (no
+/// canonicalization), so clients verify it over the raw body before parsing.
+/// Streaming (SSE) responses are passed through unsigned since they have no
+/// final body to sign.
+pub async fn sign_response(
+    State(key): State<SigningKey>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let response = next.run(request).await;
+
+    let is_stream = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("text/event-stream"));
+    if is_stream {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            tracing::error!("Failed to buffer response for signing: {}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    let signature = sign(&key, &bytes);
+    parts.headers.insert(
+        SIGNATURE_HEADER,
+        HeaderValue::from_str(&signature).expect("hex is a valid header value"),
+    );
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_known_vector() {
+        // RFC 4231 test case 2
+        assert_eq!(
+            sign(b"Jefe", b"what do ya want for nothing?"),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_secrets_match() {
+        assert!(secrets_match(b"s3cret", b"s3cret"));
+        assert!(!secrets_match(b"s3cres", b"s3cret"));
+        assert!(!secrets_match(b"s3c", b"s3cret"));
+        assert!(!secrets_match(b"", b"s3cret"));
+    }
+}
@@ -0,0 +1,170 @@
+use std::path::Path;
+
+use axum::Router;
+use tower_http::services::ServeDir;
+
+/// An extra static-file directory served under a URL path prefix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StaticMount {
+    /// URL path prefix (e.g. "/assets")
+    pub path: String,
+    /// Directory on disk served under `path`
+    pub dir: String,
+}
+
+/// First path segments taken by the built-in routes, which mounts may not shadow
+const RESERVED_SEGMENTS: &[&str] = &[
+    "ipgeo",
+    "timezone",
+    "v1",
+    "health",
+    "version",
+    "metrics",
+    "openapi.yaml",
+    "llms.txt",
+    "proto",
+    "schema",
+    "sitemap.xml",
+    "robots.txt",
+    ".well-known",
+    "admin",
+    "mcp",
+    "static",
+    "favicon.ico",
+];
+
+/// Whether `path` equals `prefix` or lies below it
+fn is_within(path: &str, prefix: &str) -> bool {
+    path.strip_prefix(prefix)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+}
+
+/// Parse `EXTRA_STATIC_MOUNTS`, a comma-separated list of `path=dir` pairs
+///
+/// Example: `/assets=/srv/assets,/brand=./branding`
+///
+/// Mounts may not overlap the built-in routes or each other.
+pub fn parse_static_mounts(spec: &str) -> Result<Vec<StaticMount>, String> {
+    let mounts = spec
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (path, dir) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("expected 'path=dir', got '{}'", entry))?;
+            let (path, dir) = (path.trim().trim_end_matches('/'), dir.trim());
+
+            if !path.starts_with('/') || path.len() < 2 {
+                return Err(format!(
+                    "mount path must start with '/' and not be the root, got '{}'",
+                    path
+                ));
+            }
+            if dir.is_empty() {
+                return Err(format!("missing directory for mount '{}'", path));
+            }
+            let segment = path[1..].split('/').next().unwrap_or_default();
+            if RESERVED_SEGMENTS.contains(&segment) {
+                return Err(format!(
+                    "mount '{}' overlaps the built-in '/{}' routes",
+                    path, segment
+                ));
+            }
+
+            Ok(StaticMount {
+                path: path.to_string(),
+                dir: dir.to_string(),
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    for (i, mount) in mounts.iter().enumerate() {
+        if let Some(other) = mounts[..i].iter().find(|other| {
+            is_within(&mount.path, &other.path) || is_within(&other.path, &mount.path)
+        }) {
+            return Err(format!(
+                "mount '{}' overlaps mount '{}'",
+                mount.path, other.path
+            ));
+        }
+    }
+    Ok(mounts)
+}
+
+/// Serve a static directory, preferring precompressed siblings
+///
+/// When the client accepts them, `us.svg.br` or `us.svg.gz` next to `us.svg`
+/// is sent instead with the matching `Content-Encoding`. Files without one are
+/// served uncompressed.
+pub fn serve_static_dir(dir: impl AsRef<Path>) -> ServeDir {
+    ServeDir::new(dir).precompressed_br().precompressed_gzip()
+}
+
+/// Serve each mount's directory under its path prefix
+pub fn with_static_mounts<S>(router: Router<S>, mounts: &[StaticMount]) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    mounts.iter().fold(router, |router, mount| {
+        router.nest_service(&mount.path, serve_static_dir(&mount.dir))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_static_mounts() {
+        let mounts = parse_static_mounts("/assets=/srv/assets, /brand/=./branding").unwrap();
+        assert_eq!(
+            mounts,
+            vec![
+                StaticMount {
+                    path: "/assets".to_string(),
+                    dir: "/srv/assets".to_string(),
+                },
+                StaticMount {
+                    path: "/brand".to_string(),
+                    dir: "./branding".to_string(),
+                },
+            ]
+        );
+        assert!(parse_static_mounts("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_static_mounts_invalid() {
+        assert!(parse_static_mounts("/assets").is_err());
+        assert!(parse_static_mounts("assets=/srv/assets").is_err());
+        assert!(parse_static_mounts("/=/srv/assets").is_err());
+        assert!(parse_static_mounts("/assets=").is_err());
+    }
+
+    #[test]
+    fn test_parse_static_mounts_overlapping() {
+        let err = |spec: &str| parse_static_mounts(spec).unwrap_err();
+        assert_eq!(
+            err("/static=/srv/static"),
+            "mount '/static' overlaps the built-in '/static' routes"
+        );
+        assert!(parse_static_mounts("/v1=/srv/v1").is_err());
+        assert!(parse_static_mounts("/mcp/files=/srv/mcp").is_err());
+        assert!(parse_static_mounts("/ipgeo/=/srv/ipgeo").is_err());
+        assert_eq!(
+            err("/assets=/a,/assets/img=/b"),
+            "mount '/assets/img' overlaps mount '/assets'"
+        );
+        assert!(parse_static_mounts("/assets=/a,/assets=/b").is_err());
+        // Sharing a name prefix is not overlapping
+        assert_eq!(
+            parse_static_mounts("/assets=/a,/assets2=/b").unwrap().len(),
+            2
+        );
+        assert_eq!(
+            parse_static_mounts("/statics=/srv/statics").unwrap().len(),
+            1
+        );
+    }
+}
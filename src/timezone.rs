@@ -1,12 +1,48 @@
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
 use tzf_rs::DefaultFinder;
 
+/// Source of IANA timezone names for coordinates
+///
+/// [`TzfTimezoneProvider`] is the default; `AppState::timezones` can hold a
+/// higher-resolution implementation instead, or a mock in tests.
+pub trait TimezoneProvider: Send + Sync {
+    /// Timezone at the coordinates, or `None` if they are out of range
+    fn lookup(&self, lat: f64, lng: f64) -> Option<String>;
+
+    /// Zones near the coordinates, most likely first (for `candidates=true`)
+    ///
+    /// Defaults to just the zone from [`lookup`](Self::lookup).
+    fn candidates(&self, lat: f64, lng: f64) -> Vec<String> {
+        self.lookup(lat, lng).into_iter().collect()
+    }
+}
+
+/// Timezone boundaries embedded by tzf-rs, via [`lookup_timezone`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TzfTimezoneProvider;
+
+impl TimezoneProvider for TzfTimezoneProvider {
+    fn lookup(&self, lat: f64, lng: f64) -> Option<String> {
+        lookup_timezone(lat, lng)
+    }
+
+    fn candidates(&self, lat: f64, lng: f64) -> Vec<String> {
+        lookup_timezone_candidates(lat, lng)
+    }
+}
+
+/// Shared timezone provider wrapped in Arc for thread-safe access
+pub type SharedTimezoneProvider = Arc<dyn TimezoneProvider>;
+
 /// Global timezone finder instance
 /// tzf-rs embeds timezone boundary data at compile time
 static TZ_FINDER: LazyLock<DefaultFinder> = LazyLock::new(DefaultFinder::new);
 
 /// Lookup IANA timezone name for given coordinates
-/// Returns None if coordinates are invalid or not found
+/// Returns None if coordinates are invalid
+///
+/// Open-ocean coordinates resolve to a nautical `Etc/GMT±N` zone, either from
+/// tzf-rs or, where it has no zone at all, computed from the longitude.
 #[must_use]
 pub fn lookup_timezone(lat: f64, lng: f64) -> Option<String> {
     // Validate coordinate ranges
@@ -16,14 +52,95 @@ pub fn lookup_timezone(lat: f64, lng: f64) -> Option<String> {
 
     let tz_name = TZ_FINDER.get_tz_name(lng, lat);
 
-    // tzf-rs returns empty string for ocean/unclaimed areas
+    // tzf-rs returns empty string for some unclaimed areas
     if tz_name.is_empty() {
-        None
+        Some(nautical_timezone(lng))
     } else {
         Some(tz_name.to_string())
     }
 }
 
+/// Distance in degrees (about 5 km) probed around a point for nearby zones
+const CANDIDATE_RADIUS_DEG: f64 = 0.05;
+
+/// Zones within `CANDIDATE_RADIUS_DEG` of the coordinates, most likely first
+///
+/// Samples the point and eight neighbours; the zone `lookup_timezone` picks
+/// comes first, the rest follow by how many samples fall in them. Away from
+/// borders this is just the single best zone.
+#[must_use]
+pub fn lookup_timezone_candidates(lat: f64, lng: f64) -> Vec<String> {
+    let Some(best) = lookup_timezone(lat, lng) else {
+        return Vec::new();
+    };
+
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for d_lat in [-CANDIDATE_RADIUS_DEG, 0.0, CANDIDATE_RADIUS_DEG] {
+        for d_lng in [-CANDIDATE_RADIUS_DEG, 0.0, CANDIDATE_RADIUS_DEG] {
+            let sample_lat = (lat + d_lat).clamp(-90.0, 90.0);
+            let sample_lng = (lng + d_lng).clamp(-180.0, 180.0);
+            let mut names = TZ_FINDER.get_tz_names(sample_lng, sample_lat);
+            names.sort_unstable();
+            names.dedup();
+            for name in names.into_iter().filter(|n| !n.is_empty() && *n != best) {
+                match counts.iter_mut().find(|(n, _)| n == name) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((name.to_string(), 1)),
+                }
+            }
+        }
+    }
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    std::iter::once(best)
+        .chain(counts.into_iter().map(|(name, _)| name))
+        .collect()
+}
+
+/// Nautical timezone for a longitude: one hour per 15°, centered on multiples of 15°
+///
+/// `Etc/GMT` zones have inverted signs, so UTC-2 (around 30°W) is `Etc/GMT+2`.
+#[must_use]
+pub fn nautical_timezone(lng: f64) -> String {
+    let offset = (lng / 15.0).round().clamp(-12.0, 12.0) as i32;
+    if offset == 0 {
+        "Etc/GMT".to_string()
+    } else {
+        format!("Etc/GMT{:+}", -offset)
+    }
+}
+
+/// Whether `tz_name` is a nautical `Etc/GMT±N` zone rather than a real region
+#[must_use]
+pub fn is_nautical_timezone(tz_name: &str) -> bool {
+    tz_name.starts_with("Etc/GMT")
+}
+
+/// Mock timezone provider for testing
+pub mod mock {
+    use super::*;
+
+    /// Answers every in-range coordinate with the same zone
+    pub struct MockTimezoneProvider {
+        pub zone: String,
+    }
+
+    impl MockTimezoneProvider {
+        pub fn new(zone: &str) -> Self {
+            Self {
+                zone: zone.to_string(),
+            }
+        }
+    }
+
+    impl TimezoneProvider for MockTimezoneProvider {
+        fn lookup(&self, lat: f64, lng: f64) -> Option<String> {
+            ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lng))
+                .then(|| self.zone.clone())
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -46,6 +163,37 @@ mod tests {
         assert_eq!(tz, Some("Asia/Tokyo".to_string()));
     }
 
+    #[test]
+    fn test_lookup_timezone_candidates() {
+        // El Paso sits on the Denver / Ciudad Juarez border
+        let candidates = lookup_timezone_candidates(31.7587, -106.4869);
+        assert_eq!(candidates, vec!["America/Denver", "America/Ciudad_Juarez"]);
+
+        // Far from any border there is only one
+        let candidates = lookup_timezone_candidates(59.329504, 18.069532);
+        assert_eq!(candidates, vec!["Europe/Stockholm"]);
+
+        assert!(lookup_timezone_candidates(91.0, 0.0).is_empty());
+    }
+
+    #[test]
+    fn test_nautical_timezone() {
+        assert_eq!(nautical_timezone(-30.0), "Etc/GMT+2");
+        assert_eq!(nautical_timezone(-37.4), "Etc/GMT+2");
+        assert_eq!(nautical_timezone(3.0), "Etc/GMT");
+        assert_eq!(nautical_timezone(140.0), "Etc/GMT-9");
+        assert_eq!(nautical_timezone(180.0), "Etc/GMT-12");
+        assert_eq!(nautical_timezone(-180.0), "Etc/GMT+12");
+    }
+
+    #[test]
+    fn test_mid_atlantic_nautical() {
+        let tz = lookup_timezone(0.0, -30.0).unwrap();
+        assert_eq!(tz, "Etc/GMT+2");
+        assert!(is_nautical_timezone(&tz));
+        assert!(!is_nautical_timezone("Europe/London"));
+    }
+
     #[test]
     fn test_invalid_coordinates() {
         assert_eq!(lookup_timezone(91.0, 0.0), None);
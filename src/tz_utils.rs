@@ -1,16 +1,65 @@
 //! Timezone utilities for calculating offsets, DST, and current time
 
-use chrono::{DateTime, Offset, TimeZone, Utc};
-use chrono_tz::Tz;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use chrono::{DateTime, FixedOffset, Offset, SecondsFormat, TimeZone, Utc};
+use chrono_tz::{OffsetName, Tz};
+use moka::sync::Cache;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+/// MaxMind-style `current_time` layout, e.g. "2024-01-15 14:30:00.123-0800"
+const MAXMIND_TIME_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.3f%z";
+
+/// How `current_time` is rendered (`time_format` query parameter)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TimeFormat {
+    /// "2024-01-15 14:30:00.123-0800", as ipgeolocation.io clients expect
+    #[default]
+    Maxmind,
+    /// RFC 3339 / ISO 8601, "2024-01-15T14:30:00.123-08:00"
+    Iso8601,
+}
+
+impl TimeFormat {
+    /// Render a local time in this format
+    pub fn format<T: TimeZone>(self, time: &DateTime<T>) -> String
+    where
+        T::Offset: std::fmt::Display,
+    {
+        match self {
+            TimeFormat::Maxmind => time.format(MAXMIND_TIME_FORMAT).to_string(),
+            TimeFormat::Iso8601 => time.to_rfc3339_opts(SecondsFormat::Millis, false),
+        }
+    }
+
+    /// Re-render a `current_time` produced in the default format
+    ///
+    /// Values that don't parse are returned unchanged.
+    pub fn convert(self, current_time: &str) -> String {
+        match DateTime::parse_from_str(current_time, MAXMIND_TIME_FORMAT) {
+            Ok(time) => self.format(&time),
+            Err(_) => current_time.to_string(),
+        }
+    }
+}
 
 /// Timezone details including offset and DST information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TimezoneDetails {
     pub name: String,
     pub offset_hours: i32,
+    /// Exact UTC offset in seconds, for zones like Asia/Kolkata (+5:30)
+    pub offset_seconds: i32,
     pub offset_with_dst_hours: i32,
+    /// Abbreviation in effect at the current instant (e.g. "CET" vs "CEST")
+    pub abbreviation: Option<String>,
     pub current_time: String,
     pub current_time_unix: f64,
+    /// Same instant as `current_time_unix`, in integer milliseconds
+    pub current_time_unix_ms: i64,
     pub is_dst: bool,
     pub dst_exists: bool,
     pub dst_savings_hours: i32,
@@ -19,14 +68,23 @@ pub struct TimezoneDetails {
 /// Get detailed timezone information for a given IANA timezone name
 #[must_use]
 pub fn get_timezone_details(tz_name: &str) -> Option<TimezoneDetails> {
+    get_timezone_details_at(tz_name, Utc::now())
+}
+
+/// Get timezone information as of a specific instant
+///
+/// `current_time` and DST state describe `now_utc`, which may be in the past
+/// or future (e.g. "will Berlin be in DST on 2025-07-01").
+#[must_use]
+pub fn get_timezone_details_at(tz_name: &str, now_utc: DateTime<Utc>) -> Option<TimezoneDetails> {
     let tz: Tz = tz_name.parse().ok()?;
-    let now_utc = Utc::now();
     let now_local = now_utc.with_timezone(&tz);
 
     // Get the current offset
     let offset = now_local.offset();
     let offset_secs = offset.fix().local_minus_utc();
     let offset_hours = offset_secs / 3600;
+    let abbreviation = offset.abbreviation().map(String::from);
 
     // Check if DST exists for this timezone by comparing winter and summer
     let (is_dst, dst_exists, dst_savings) = check_dst(&tz, &now_utc);
@@ -34,21 +92,73 @@ pub fn get_timezone_details(tz_name: &str) -> Option<TimezoneDetails> {
     // Calculate offset with DST (current offset already includes DST if active)
     let offset_with_dst_hours = offset_hours;
 
-    // Format current time
-    let current_time = now_local.format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
-    let current_time_unix =
-        now_utc.timestamp() as f64 + (now_utc.timestamp_subsec_millis() as f64 / 1000.0);
-
-    Some(TimezoneDetails {
+    let mut details = TimezoneDetails {
         name: tz_name.to_string(),
         offset_hours,
+        offset_seconds: offset_secs,
         offset_with_dst_hours,
-        current_time,
-        current_time_unix,
+        abbreviation,
+        current_time: String::new(),
+        current_time_unix: 0.0,
+        current_time_unix_ms: 0,
         is_dst,
         dst_exists,
         dst_savings_hours: dst_savings,
-    })
+    };
+    details.set_time(now_utc);
+    Some(details)
+}
+
+impl TimezoneDetails {
+    /// Point the time fields at `now_utc`, keeping the zone's offset
+    fn set_time(&mut self, now_utc: DateTime<Utc>) {
+        let offset = FixedOffset::east_opt(self.offset_seconds).unwrap_or(Utc.fix());
+        self.current_time = TimeFormat::Maxmind.format(&now_utc.with_timezone(&offset));
+        self.current_time_unix =
+            now_utc.timestamp() as f64 + (now_utc.timestamp_subsec_millis() as f64 / 1000.0);
+        self.current_time_unix_ms = now_utc.timestamp_millis();
+    }
+}
+
+/// Zone details memoized by zone name and UTC minute
+///
+/// The offset, abbreviation and DST state only change on transitions, which
+/// the tz database places on whole minutes from 1973 on, so every request
+/// within the same minute can share them. Entries outlive their minute only
+/// until the TTL.
+static ZONE_DETAILS: LazyLock<Cache<(String, i64), TimezoneDetails>> = LazyLock::new(|| {
+    Cache::builder()
+        .max_capacity(2048)
+        .time_to_live(Duration::from_secs(60))
+        .build()
+});
+
+/// Start of 1973 UTC; earlier transitions can fall mid-minute (local mean
+/// time offsets, Monrovia's switch to GMT in January 1972)
+const MINUTE_ALIGNED_SINCE: i64 = 94_694_400;
+
+/// Like [`get_timezone_details_at`], reusing the zone's offset and DST state
+/// computed earlier in the same minute
+///
+/// Only the time fields are rebuilt per call, which keeps `/v1/timezone` from
+/// redoing the DST probes for every request under load. Instants before 1973
+/// are always computed directly.
+#[must_use]
+pub fn memoized_timezone_details_at(
+    tz_name: &str,
+    now_utc: DateTime<Utc>,
+) -> Option<TimezoneDetails> {
+    if now_utc.timestamp() < MINUTE_ALIGNED_SINCE {
+        return get_timezone_details_at(tz_name, now_utc);
+    }
+    let key = (tz_name.to_string(), now_utc.timestamp().div_euclid(60));
+    if let Some(mut details) = ZONE_DETAILS.get(&key) {
+        details.set_time(now_utc);
+        return Some(details);
+    }
+    let details = get_timezone_details_at(tz_name, now_utc)?;
+    ZONE_DETAILS.insert(key, details.clone());
+    Some(details)
 }
 
 /// Check if DST exists and is currently active for a timezone
@@ -95,6 +205,26 @@ pub fn get_timezone_offset(tz_name: &str) -> Option<i32> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_time_format_iso8601() {
+        // 2024-01-15 22:30:00.123 UTC
+        let at = DateTime::from_timestamp_millis(1_705_357_800_123).unwrap();
+        let details = get_timezone_details_at("America/Los_Angeles", at).unwrap();
+        assert_eq!(details.current_time, "2024-01-15 14:30:00.123-0800");
+        assert_eq!(
+            TimeFormat::Iso8601.convert(&details.current_time),
+            "2024-01-15T14:30:00.123-08:00"
+        );
+        assert_eq!(
+            TimeFormat::Maxmind.convert(&details.current_time),
+            details.current_time
+        );
+        assert_eq!(
+            TimeFormat::Iso8601.format(&at.with_timezone(&chrono_tz::Asia::Kolkata)),
+            "2024-01-16T04:00:00.123+05:30"
+        );
+    }
+
     #[test]
     fn test_get_timezone_details_new_york() {
         let details = get_timezone_details("America/New_York").unwrap();
@@ -150,5 +280,98 @@ mod tests {
         let details = get_timezone_details("Europe/Stockholm").unwrap();
         assert!(!details.current_time.is_empty());
         assert!(details.current_time_unix > 0.0);
+        assert_eq!(
+            details.current_time_unix_ms,
+            (details.current_time_unix * 1000.0).round() as i64
+        );
+    }
+
+    #[test]
+    fn test_timezone_abbreviation_winter() {
+        let january = Utc.with_ymd_and_hms(2024, 1, 15, 12, 0, 0).unwrap();
+        let details = get_timezone_details_at("Europe/Berlin", january).unwrap();
+        assert_eq!(details.abbreviation.as_deref(), Some("CET"));
+        assert!(!details.is_dst);
+    }
+
+    #[test]
+    fn test_timezone_abbreviation_dst_active() {
+        let july = Utc.with_ymd_and_hms(2024, 7, 15, 12, 0, 0).unwrap();
+        let details = get_timezone_details_at("Europe/Berlin", july).unwrap();
+        assert_eq!(details.abbreviation.as_deref(), Some("CEST"));
+        assert!(details.is_dst);
+
+        let details = get_timezone_details_at("America/Los_Angeles", july).unwrap();
+        assert_eq!(details.abbreviation.as_deref(), Some("PDT"));
+    }
+
+    #[test]
+    fn test_memoized_timezone_details_consistent() {
+        // Berlin springs forward at 01:00 UTC, so the last second of the
+        // previous minute must not share memoized details with the next one
+        let transition = Utc.with_ymd_and_hms(2025, 3, 30, 1, 0, 0).unwrap();
+        let instants = [
+            transition - chrono::Duration::seconds(1),
+            transition,
+            transition + chrono::Duration::milliseconds(59_999),
+            Utc.with_ymd_and_hms(2024, 1, 15, 22, 30, 0).unwrap(),
+        ];
+        let zones = [
+            "Europe/Berlin",
+            "America/Los_Angeles",
+            "Asia/Kolkata",
+            "UTC",
+        ];
+
+        for i in 0..10_000 {
+            let zone = zones[i % zones.len()];
+            let at = instants[i / zones.len() % instants.len()]
+                + chrono::Duration::milliseconds((i % 7) as i64);
+            assert_eq!(
+                memoized_timezone_details_at(zone, at),
+                get_timezone_details_at(zone, at),
+                "{zone} at {at}"
+            );
+        }
+
+        let before = memoized_timezone_details_at("Europe/Berlin", instants[0]).unwrap();
+        let after = memoized_timezone_details_at("Europe/Berlin", instants[1]).unwrap();
+        assert_eq!(before.abbreviation.as_deref(), Some("CET"));
+        assert_eq!(after.abbreviation.as_deref(), Some("CEST"));
+        assert!(memoized_timezone_details_at("Not/A/Timezone", instants[0]).is_none());
+    }
+
+    #[test]
+    fn test_memoized_timezone_details_sub_minute_transitions() {
+        // Monrovia moved from -0:43:08 to -0:44:30 at 00:43:08 UTC and from
+        // -0:44:30 to GMT at 00:44:30 UTC, both mid-minute
+        let cases = [
+            (
+                "Africa/Monrovia",
+                Utc.with_ymd_and_hms(1972, 1, 7, 0, 44, 30),
+            ),
+            (
+                "Africa/Monrovia",
+                Utc.with_ymd_and_hms(1919, 3, 1, 0, 43, 8),
+            ),
+        ];
+        for (zone, transition) in cases {
+            let transition = transition.unwrap();
+            let before = transition - chrono::Duration::seconds(1);
+            let memo_before = memoized_timezone_details_at(zone, before).unwrap();
+            let memo_after = memoized_timezone_details_at(zone, transition).unwrap();
+            assert_eq!(Some(memo_before), get_timezone_details_at(zone, before));
+            assert_eq!(
+                Some(memo_after.clone()),
+                get_timezone_details_at(zone, transition)
+            );
+            assert_ne!(
+                get_timezone_details_at(zone, before)
+                    .unwrap()
+                    .offset_seconds,
+                memo_after.offset_seconds,
+                "{zone}"
+            );
+        }
     }
 }
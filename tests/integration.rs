@@ -5,15 +5,26 @@
 use std::sync::Arc;
 use std::time::Duration;
 
-use axum::{routing::get, Router};
+use axum::{
+    http::HeaderValue,
+    routing::{delete, get, post},
+    Router,
+};
 use tokio::net::TcpListener;
 
 use ipgeolocation::cache::{CacheConfig, GeoCache};
 use ipgeolocation::geoip::mock::MockGeoIpReader;
+use ipgeolocation::geoip::OverrideGeoIpReader;
 use ipgeolocation::handlers::{
-    health_handler, ipgeo_full_handler, ipgeo_handler, llms_txt_handler, openapi_handler,
-    root_handler, sitemap_handler, timezone_full_handler, timezone_handler,
-    wellknown_ai_plugin_handler, wellknown_openapi_handler, ApiDoc, AppState,
+    admin_all_handler, admin_cache_flush_handler, admin_cache_invalidate_handler,
+    admin_raw_handler, admin_reload_handler, countries_handler, flag_handler,
+    health_database_handler, health_handler, health_ready_handler, ipgeo_full_handler,
+    ipgeo_handler, llms_txt_handler, metrics_handler, openapi_handler, proto_definition_handler,
+    reject_duplicate_params, reject_unknown_params, robots_txt_handler, root_handler,
+    schema_ipgeo_handler, schema_timezone_handler, self_stream_handler, sitemap_handler,
+    timezone_batch_handler, timezone_compare_handler, timezone_full_handler, timezone_handler,
+    timezones_handler, version_handler, wellknown_ai_plugin_handler, wellknown_openapi_handler,
+    ApiDoc, AppState,
 };
 use ipgeolocation::models::GeoData;
 use ipgeolocation::proto::geolocation;
@@ -47,10 +58,141 @@ async fn test_health_endpoint() {
     assert_eq!(response.text().await.unwrap(), "OK");
 }
 
+/// Test that the security headers layer is applied to /health
+#[tokio::test]
+async fn test_security_headers() {
+    let app = Router::new()
+        .route("/health", get(health_handler))
+        .layer(ipgeolocation::security_headers_layer());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let response = reqwest::Client::new()
+        .get(format!("http://{}/health", addr))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    let headers = response.headers();
+    assert_eq!(headers.get("x-content-type-options").unwrap(), "nosniff");
+    assert_eq!(headers.get("x-frame-options").unwrap(), "DENY");
+    assert_eq!(headers.get("referrer-policy").unwrap(), "no-referrer");
+}
+
+/// Test the Alt-Svc header advertising HTTP/3 on the configured port
+#[tokio::test]
+async fn test_alt_svc_header() {
+    let app = Router::new()
+        .route("/health", get(health_handler))
+        .layer(ipgeolocation::alt_svc_layer(8443));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let response = reqwest::Client::new()
+        .get(format!("http://{}/health", addr))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers().get("alt-svc").unwrap(),
+        "h3=\":8443\"; ma=86400"
+    );
+}
+
+/// Test the .proto definition is served for client stub generation
+#[tokio::test]
+async fn test_proto_definition_endpoint() {
+    let app = Router::new().route("/proto/geolocation.proto", get(proto_definition_handler));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let response = reqwest::Client::new()
+        .get(format!("http://{}/proto/geolocation.proto", addr))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    let body = response.text().await.unwrap();
+    assert!(body.contains("package geolocation;"));
+    assert!(body.contains("message IpGeoResponseFull {"));
+}
+
+/// Test the JSON Schema documents for REST responses
+#[tokio::test]
+async fn test_schema_endpoints() {
+    let app = Router::new()
+        .route("/schema/ipgeo", get(schema_ipgeo_handler))
+        .route("/schema/timezone", get(schema_timezone_handler));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/schema/ipgeo", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "application/schema+json"
+    );
+    let json: serde_json::Value = response.json().await.unwrap();
+    let location = &json["definitions"]["IpGeoResponseFull"]["properties"]["location"];
+    assert_eq!(location["type"], "object");
+    assert!(location["properties"].get("country_code2").is_some());
+
+    let json: serde_json::Value = client
+        .get(format!("http://{}/schema/timezone", addr))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(json["definitions"]["TimezoneResponseFull"]["properties"]
+        .get("timezone")
+        .is_some());
+}
+
 /// Test timezone endpoint with Stockholm coordinates
 #[tokio::test]
 async fn test_timezone_stockholm() {
-    let app = Router::new().route("/timezone", get(timezone_handler));
+    let app = Router::new()
+        .route("/timezone", get(timezone_handler))
+        .with_state(create_minimal_test_state());
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -80,7 +222,9 @@ async fn test_timezone_stockholm() {
 /// Test timezone endpoint with New York coordinates
 #[tokio::test]
 async fn test_timezone_new_york() {
-    let app = Router::new().route("/timezone", get(timezone_handler));
+    let app = Router::new()
+        .route("/timezone", get(timezone_handler))
+        .with_state(create_minimal_test_state());
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -110,7 +254,9 @@ async fn test_timezone_new_york() {
 /// Test timezone endpoint with Tokyo coordinates
 #[tokio::test]
 async fn test_timezone_tokyo() {
-    let app = Router::new().route("/timezone", get(timezone_handler));
+    let app = Router::new()
+        .route("/timezone", get(timezone_handler))
+        .with_state(create_minimal_test_state());
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -140,7 +286,9 @@ async fn test_timezone_tokyo() {
 /// Test Cache-Control headers are present
 #[tokio::test]
 async fn test_cache_control_headers() {
-    let app = Router::new().route("/timezone", get(timezone_handler));
+    let app = Router::new()
+        .route("/timezone", get(timezone_handler))
+        .with_state(create_minimal_test_state());
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -172,22 +320,22 @@ async fn test_cache_control_headers() {
 /// Helper to create test app state with mock GeoIP reader
 fn create_test_state(mock: MockGeoIpReader) -> AppState {
     let cache = GeoCache::new(CacheConfig::default());
-    AppState {
-        geoip: Arc::new(mock),
-        cache: Arc::new(cache),
-        base_url: "https://test.example.com".to_string(),
-    }
+    AppState::new(
+        Arc::new(mock),
+        Arc::new(cache),
+        "https://test.example.com".to_string(),
+    )
 }
 
 /// Create a minimal test state for handlers that only need base_url
 fn create_minimal_test_state() -> AppState {
     let mock = MockGeoIpReader::new();
     let cache = GeoCache::new(CacheConfig::default());
-    AppState {
-        geoip: Arc::new(mock),
-        cache: Arc::new(cache),
-        base_url: "https://test.example.com".to_string(),
-    }
+    AppState::new(
+        Arc::new(mock),
+        Arc::new(cache),
+        "https://test.example.com".to_string(),
+    )
 }
 
 /// Test ipgeo endpoint with valid IP
@@ -205,6 +353,7 @@ async fn test_ipgeo_valid_ip() {
             state_code: Some("CA".to_string()),
             postal_code: Some("94043".to_string()),
             geoname_id: Some(5375480),
+            ..Default::default()
         }),
     );
 
@@ -287,6 +436,7 @@ async fn test_ipgeo_caching() {
             state_code: Some("ENG".to_string()),
             postal_code: None,
             geoname_id: Some(2643743),
+            ..Default::default()
         }),
     );
 
@@ -342,6 +492,7 @@ async fn test_ipgeo_cache_control_headers() {
             state_code: None,
             postal_code: None,
             geoname_id: None,
+            ..Default::default()
         }),
     );
 
@@ -374,24 +525,16 @@ async fn test_ipgeo_cache_control_headers() {
     );
 }
 
-/// Test ipgeo endpoint with missing coordinates (no timezone can be determined)
+/// Test X-Cache reports MISS on the first lookup of an IP and HIT afterwards
 #[tokio::test]
-async fn test_ipgeo_no_coordinates() {
+async fn test_ipgeo_x_cache_header() {
     let mock = MockGeoIpReader::new().with_response(
-        "192.0.2.1",
+        "8.8.8.8",
         Ok(GeoData {
-            latitude: None, // No coordinates
-            longitude: None,
-            city: Some("Unknown".to_string()),
-            country_name: Some("Reserved".to_string()),
-            country_code: Some("XX".to_string()),
-            state_prov: None,
-            state_code: None,
-            postal_code: None,
-            geoname_id: None,
+            city: Some("Mountain View".to_string()),
+            ..Default::default()
         }),
     );
-
     let state = create_test_state(mock);
     let app = Router::new()
         .route("/ipgeo", get(ipgeo_handler))
@@ -407,40 +550,45 @@ async fn test_ipgeo_no_coordinates() {
     tokio::time::sleep(Duration::from_millis(100)).await;
 
     let client = reqwest::Client::new();
+    let x_cache = |response: &reqwest::Response| {
+        response
+            .headers()
+            .get("x-cache")
+            .map(|v| v.to_str().unwrap().to_string())
+    };
+
+    let url = format!("http://{}/ipgeo?ip=8.8.8.8", addr);
+    let response = client.get(&url).send().await.unwrap();
+    assert_eq!(x_cache(&response).as_deref(), Some("MISS"));
+    let response = client.get(&url).send().await.unwrap();
+    assert_eq!(x_cache(&response).as_deref(), Some("HIT"));
+
+    // Protobuf and full responses bypass the cache entirely
     let response = client
-        .get(format!("http://{}/ipgeo?apiKey=test&ip=192.0.2.1", addr))
+        .get(&url)
+        .header("Accept", "application/x-protobuf")
         .send()
         .await
         .unwrap();
-
-    assert_eq!(response.status(), 200);
-
-    let json: serde_json::Value = response.json().await.unwrap();
-    // With no coordinates, timezone should be empty
-    assert_eq!(json["time_zone"]["name"], "");
-    // But city and country should still be present
-    assert_eq!(json["city"], "Unknown");
-    assert_eq!(json["country_name"], "Reserved");
+    assert_eq!(x_cache(&response), None);
+    let response = client
+        .get(format!("{}&fields=*", url))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(x_cache(&response), None);
 }
 
-/// Test ipgeo endpoint with partial coordinates (only latitude)
+/// Test that ip=self on /ipgeo looks up the caller's forwarded address
 #[tokio::test]
-async fn test_ipgeo_partial_coordinates() {
+async fn test_ipgeo_ip_self() {
     let mock = MockGeoIpReader::new().with_response(
-        "198.51.100.1",
+        "8.8.8.8",
         Ok(GeoData {
-            latitude: Some(40.0), // Only latitude
-            longitude: None,      // No longitude
-            city: Some("Partial".to_string()),
-            country_name: Some("Test".to_string()),
-            country_code: Some("TE".to_string()),
-            state_prov: None,
-            state_code: None,
-            postal_code: None,
-            geoname_id: None,
+            city: Some("Mountain View".to_string()),
+            ..Default::default()
         }),
     );
-
     let state = create_test_state(mock);
     let app = Router::new()
         .route("/ipgeo", get(ipgeo_handler))
@@ -450,47 +598,65 @@ async fn test_ipgeo_partial_coordinates() {
     let addr = listener.local_addr().unwrap();
 
     tokio::spawn(async move {
-        axum::serve(listener, app).await.unwrap();
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .unwrap();
     });
 
     tokio::time::sleep(Duration::from_millis(100)).await;
 
     let client = reqwest::Client::new();
     let response = client
-        .get(format!("http://{}/ipgeo?apiKey=test&ip=198.51.100.1", addr))
+        .get(format!("http://{}/ipgeo?ip=self", addr))
+        .header("X-Forwarded-For", "8.8.8.8, 10.0.0.1")
         .send()
         .await
         .unwrap();
-
     assert_eq!(response.status(), 200);
+    // Shared caches must not hand one caller's location to the next
+    assert_eq!(response.headers()["cache-control"], "private, no-store");
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["city"], "Mountain View");
+
+    // Explicit IPs stay publicly cacheable
+    let response = client
+        .get(format!("http://{}/ipgeo?ip=8.8.8.8", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.headers()["cache-control"],
+        "public, max-age=1209600"
+    );
 
+    // Without proxy headers the connection address (loopback) is used
+    let response = client
+        .get(format!("http://{}/ipgeo?ip=self&reject_private=true", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 422);
     let json: serde_json::Value = response.json().await.unwrap();
-    // With partial coordinates, timezone should be empty
-    assert_eq!(json["time_zone"]["name"], "");
+    assert_eq!(
+        json["error"],
+        "Private/loopback IP address not supported: 127.0.0.1"
+    );
 }
 
-// ============================================================================
-// V1 API Tests (Extended Format)
-// ============================================================================
-
-/// Test v1/ipgeo endpoint returns full format
+/// Test that /v1/ipgeo requests differing only in cache-busting params share a cache entry
 #[tokio::test]
-async fn test_v1_ipgeo_full_format() {
+async fn test_v1_ipgeo_cache_ignores_cache_busting() {
     let mock = MockGeoIpReader::new().with_response(
         "8.8.8.8",
         Ok(GeoData {
-            latitude: Some(37.751),
-            longitude: Some(-97.822),
             city: Some("Mountain View".to_string()),
-            country_name: Some("United States".to_string()),
-            country_code: Some("US".to_string()),
-            state_prov: Some("California".to_string()),
-            state_code: Some("CA".to_string()),
-            postal_code: Some("94043".to_string()),
-            geoname_id: Some(5375480),
+            time_zone: Some("America/Los_Angeles".to_string()),
+            ..Default::default()
         }),
     );
-
     let state = create_test_state(mock);
     let app = Router::new()
         .route("/v1/ipgeo", get(ipgeo_full_handler))
@@ -506,50 +672,104 @@ async fn test_v1_ipgeo_full_format() {
     tokio::time::sleep(Duration::from_millis(100)).await;
 
     let client = reqwest::Client::new();
-    let response = client
-        .get(format!("http://{}/v1/ipgeo?apiKey=test&ip=8.8.8.8", addr))
-        .send()
-        .await
-        .unwrap();
+    let get = |query: &str| {
+        client
+            .get(format!("http://{}/v1/ipgeo?ip=8.8.8.8{}", addr, query))
+            .send()
+    };
+    let x_cache = |response: &reqwest::Response| {
+        response
+            .headers()
+            .get("x-cache")
+            .map(|v| v.to_str().unwrap().to_string())
+    };
+
+    let response = get("&_=1").await.unwrap();
+    assert_eq!(x_cache(&response).as_deref(), Some("MISS"));
+    let response = get("&_=2&pretty=true").await.unwrap();
+    assert_eq!(x_cache(&response).as_deref(), Some("HIT"));
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["location"]["city"], "Mountain View");
+    assert!(json["time_zone"]["current_time"].is_string());
 
-    assert_eq!(response.status(), 200);
+    // Parameters that change the response get their own entry
+    let response = get("&_=3&no_centroid=true").await.unwrap();
+    assert_eq!(x_cache(&response).as_deref(), Some("MISS"));
+}
 
-    let json: serde_json::Value = response.json().await.unwrap();
+/// Test that handlers resolve timezones through the state's provider
+#[tokio::test]
+async fn test_timezone_provider_from_state() {
+    use ipgeolocation::timezone::mock::MockTimezoneProvider;
 
-    // Check IP is returned
-    assert_eq!(json["ip"], "8.8.8.8");
+    let mock = MockGeoIpReader::new().with_response(
+        "8.8.8.8",
+        Ok(GeoData {
+            latitude: Some(37.751),
+            longitude: Some(-97.822),
+            ..Default::default()
+        }),
+    );
+    let state = AppState {
+        timezones: Arc::new(MockTimezoneProvider::new("Pacific/Chatham")),
+        ..create_test_state(mock)
+    };
+    let app = Router::new()
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .route("/timezone", get(timezone_handler))
+        .route("/v1/timezone", get(timezone_full_handler))
+        .with_state(state);
 
-    // Check location object
-    assert_eq!(json["location"]["country_code2"], "US");
-    assert_eq!(json["location"]["country_code3"], "USA");
-    assert_eq!(json["location"]["country_name"], "United States");
-    assert_eq!(json["location"]["city"], "Mountain View");
-    assert_eq!(json["location"]["state_prov"], "California");
-    assert_eq!(json["location"]["continent_code"], "NA");
-    assert_eq!(json["location"]["continent_name"], "North America");
-    assert_eq!(json["location"]["is_eu"], false);
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
 
-    // Check country_metadata object
-    assert_eq!(json["country_metadata"]["calling_code"], "+1");
-    assert_eq!(json["country_metadata"]["tld"], ".us");
-    assert!(json["country_metadata"]["languages"].is_array());
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
 
-    // Check currency object
-    assert_eq!(json["currency"]["code"], "USD");
-    assert_eq!(json["currency"]["name"], "US Dollar");
-    assert_eq!(json["currency"]["symbol"], "$");
+    tokio::time::sleep(Duration::from_millis(100)).await;
 
-    // Check time_zone object
-    assert!(json["time_zone"]["name"].is_string());
-    assert!(json["time_zone"]["offset"].is_number());
-    assert!(json["time_zone"]["current_time"].is_string());
-    assert!(json["time_zone"]["current_time_unix"].is_number());
+    let client = reqwest::Client::new();
+    let get_json = |path: &str| {
+        let request = client.get(format!("http://{}{}", addr, path)).send();
+        async move {
+            request
+                .await
+                .unwrap()
+                .json::<serde_json::Value>()
+                .await
+                .unwrap()
+        }
+    };
+
+    let json = get_json("/v1/ipgeo?ip=8.8.8.8").await;
+    assert_eq!(json["time_zone"]["name"], "Pacific/Chatham");
+    assert_eq!(json["time_zone"]["source"], "coordinates");
+
+    let json = get_json("/timezone?lat=59.3295&long=18.0695").await;
+    assert_eq!(json["timezone"], "Pacific/Chatham");
+
+    let json = get_json("/v1/timezone?lat=59.3295&long=18.0695&candidates=true").await;
+    assert_eq!(json["timezone"], "Pacific/Chatham");
+    assert_eq!(json["candidates"], serde_json::json!(["Pacific/Chatham"]));
 }
 
-/// Test v1/timezone endpoint returns full details
+/// Test that name_source=table takes country_name from the embedded table
 #[tokio::test]
-async fn test_v1_timezone_full_format() {
-    let app = Router::new().route("/v1/timezone", get(timezone_full_handler));
+async fn test_v1_ipgeo_name_source() {
+    let mock = MockGeoIpReader::new().with_response(
+        "8.8.8.8",
+        Ok(GeoData {
+            country_code: Some("US".to_string()),
+            country_name: Some("USA".to_string()),
+            ..Default::default()
+        }),
+    );
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .route("/ipgeo", get(ipgeo_handler))
+        .with_state(state);
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -561,32 +781,50 @@ async fn test_v1_timezone_full_format() {
     tokio::time::sleep(Duration::from_millis(100)).await;
 
     let client = reqwest::Client::new();
-    let response = client
-        .get(format!(
-            "http://{}/v1/timezone?apiKey=test&lat=59.329504&long=18.069532",
-            addr
-        ))
-        .send()
-        .await
-        .unwrap();
-
-    assert_eq!(response.status(), 200);
-
-    let json: serde_json::Value = response.json().await.unwrap();
-
-    // Check timezone details
-    assert_eq!(json["timezone"], "Europe/Stockholm");
-    assert!(json["offset"].is_number());
-    assert!(json["current_time"].is_string());
-    assert!(json["current_time_unix"].is_number());
-    assert!(json["is_dst"].is_boolean());
-    assert!(json["dst_exists"].is_boolean());
+    let country_name = |path: &str| {
+        let request = client.get(format!("http://{}{}", addr, path)).send();
+        async move {
+            let json: serde_json::Value = request.await.unwrap().json().await.unwrap();
+            json["location"]["country_name"].clone()
+        }
+    };
+
+    assert_eq!(country_name("/v1/ipgeo?ip=8.8.8.8").await, "USA");
+    assert_eq!(
+        country_name("/v1/ipgeo?ip=8.8.8.8&name_source=database").await,
+        "USA"
+    );
+    // Served from the cache entry above, still with the table name
+    assert_eq!(
+        country_name("/v1/ipgeo?ip=8.8.8.8&name_source=table").await,
+        "United States"
+    );
+    assert_eq!(
+        country_name("/ipgeo?ip=8.8.8.8&fields=*&name_source=table").await,
+        "United States"
+    );
 }
 
-/// Test ipgeo endpoint with invalid IP address returns 400
+/// Test ipgeo endpoint with comma-separated IPs returns an array, a single IP an object
 #[tokio::test]
-async fn test_ipgeo_invalid_ip() {
-    let mock = MockGeoIpReader::new();
+async fn test_ipgeo_multiple_ips() {
+    let mock = MockGeoIpReader::new()
+        .with_response(
+            "8.8.8.8",
+            Ok(GeoData {
+                city: Some("Mountain View".to_string()),
+                country_code: Some("US".to_string()),
+                ..Default::default()
+            }),
+        )
+        .with_response(
+            "1.1.1.1",
+            Ok(GeoData {
+                city: Some("Sydney".to_string()),
+                country_code: Some("AU".to_string()),
+                ..Default::default()
+            }),
+        );
 
     let state = create_test_state(mock);
     let app = Router::new()
@@ -603,57 +841,59 @@ async fn test_ipgeo_invalid_ip() {
     tokio::time::sleep(Duration::from_millis(100)).await;
 
     let client = reqwest::Client::new();
+
     let response = client
-        .get(format!("http://{}/ipgeo?apiKey=test&ip=not-an-ip", addr))
+        .get(format!(
+            "http://{}/ipgeo?ip=8.8.8.8,not-an-ip,1.1.1.1",
+            addr
+        ))
         .send()
         .await
         .unwrap();
-
-    assert_eq!(response.status(), 400);
+    assert_eq!(response.status(), 200);
 
     let json: serde_json::Value = response.json().await.unwrap();
-    assert_eq!(json["code"], "INVALID_IP");
-    assert!(json["error"]
-        .as_str()
+    let results = json.as_array().expect("array for multiple IPs");
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0]["city"], "Mountain View");
+    assert_eq!(results[1]["ip"], "not-an-ip");
+    assert_eq!(results[1]["code"], "INVALID_IP");
+    assert_eq!(results[2]["city"], "Sydney");
+
+    // A single IP keeps the bare object shape
+    let json: serde_json::Value = client
+        .get(format!("http://{}/ipgeo?ip=8.8.8.8", addr))
+        .send()
+        .await
         .unwrap()
-        .contains("Invalid IP address"));
-}
-
-/// Test timezone endpoint with invalid latitude returns 400
-#[tokio::test]
-async fn test_timezone_invalid_latitude() {
-    let app = Router::new().route("/timezone", get(timezone_handler));
-
-    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
-    let addr = listener.local_addr().unwrap();
-
-    tokio::spawn(async move {
-        axum::serve(listener, app).await.unwrap();
-    });
-
-    tokio::time::sleep(Duration::from_millis(100)).await;
+        .json()
+        .await
+        .unwrap();
+    assert!(json.is_object());
+    assert_eq!(json["city"], "Mountain View");
 
-    let client = reqwest::Client::new();
+    // More IPs than the bulk limit are rejected
+    let too_many = vec!["8.8.8.8"; 101].join(",");
     let response = client
-        .get(format!(
-            "http://{}/timezone?apiKey=test&lat=91.0&long=18.0",
-            addr
-        ))
+        .get(format!("http://{}/ipgeo?ip={}", addr, too_many))
         .send()
         .await
         .unwrap();
-
     assert_eq!(response.status(), 400);
-
     let json: serde_json::Value = response.json().await.unwrap();
-    assert_eq!(json["code"], "INVALID_LATITUDE");
-    assert!(json["error"].as_str().unwrap().contains("Latitude"));
+    assert_eq!(json["code"], "TOO_MANY_IPS");
 }
 
-/// Test timezone endpoint with invalid longitude returns 400
+/// Test that an oversized ip value is rejected before it is split or parsed
 #[tokio::test]
-async fn test_timezone_invalid_longitude() {
-    let app = Router::new().route("/timezone", get(timezone_handler));
+async fn test_ipgeo_query_too_long() {
+    let state = AppState {
+        max_query_len: 64,
+        ..create_test_state(MockGeoIpReader::new())
+    };
+    let app = Router::new()
+        .route("/ipgeo", get(ipgeo_handler))
+        .with_state(state);
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -664,27 +904,42 @@ async fn test_timezone_invalid_longitude() {
 
     tokio::time::sleep(Duration::from_millis(100)).await;
 
-    let client = reqwest::Client::new();
-    let response = client
-        .get(format!(
-            "http://{}/timezone?apiKey=test&lat=59.0&long=181.0",
-            addr
-        ))
+    let response = reqwest::Client::new()
+        .get(format!("http://{}/ipgeo?ip={}", addr, "1".repeat(65)))
         .send()
         .await
         .unwrap();
-
     assert_eq!(response.status(), 400);
-
     let json: serde_json::Value = response.json().await.unwrap();
-    assert_eq!(json["code"], "INVALID_LONGITUDE");
-    assert!(json["error"].as_str().unwrap().contains("Longitude"));
+    assert_eq!(json["code"], "QUERY_TOO_LONG");
+    assert_eq!(json["field"], "ip");
 }
 
-/// Test v1/timezone with invalid coordinates returns 400
+/// Test that geolocation responses carry the data attribution header
 #[tokio::test]
-async fn test_v1_timezone_invalid_coords() {
-    let app = Router::new().route("/v1/timezone", get(timezone_full_handler));
+async fn test_data_attribution_header() {
+    let mock = MockGeoIpReader::new().with_response(
+        "8.8.8.8",
+        Ok(GeoData {
+            city: Some("Mountain View".to_string()),
+            ..Default::default()
+        }),
+    );
+
+    let state = create_test_state(mock);
+    let layers = ipgeolocation::ResponseLayers {
+        data_attribution: Some(HeaderValue::from_static(
+            ipgeolocation::DEFAULT_DATA_ATTRIBUTION,
+        )),
+        ..Default::default()
+    };
+    let app = layers.attribute(
+        Router::new()
+            .route("/ipgeo", get(ipgeo_handler))
+            .route("/v1/flag", get(flag_handler))
+            .route("/timezone", get(timezone_handler))
+            .with_state(state),
+    );
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -697,27 +952,39 @@ async fn test_v1_timezone_invalid_coords() {
 
     let client = reqwest::Client::new();
     let response = client
-        .get(format!(
-            "http://{}/v1/timezone?apiKey=test&lat=-95.0&long=18.0",
-            addr
-        ))
+        .get(format!("http://{}/ipgeo?ip=8.8.8.8", addr))
         .send()
         .await
         .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers().get("x-data-attribution").unwrap(),
+        "GeoLite2 data by MaxMind (https://www.maxmind.com)"
+    );
 
-    assert_eq!(response.status(), 400);
+    // The flag's country is resolved from the IP, so it is attributed too
+    let response = client
+        .get(format!("http://{}/v1/flag?ip=8.8.8.8", addr))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.headers().get("x-data-attribution").is_some());
 
-    let json: serde_json::Value = response.json().await.unwrap();
-    assert_eq!(json["code"], "INVALID_LATITUDE");
+    // Non-geolocation endpoints don't carry it
+    let response = client
+        .get(format!("http://{}/timezone?lat=59.33&long=18.07", addr))
+        .send()
+        .await
+        .unwrap();
+    assert!(response.headers().get("x-data-attribution").is_none());
 }
 
-/// Test v1/ipgeo with invalid IP returns 400
+/// Test that reject_private turns private IPs into 422 PRIVATE_IP
 #[tokio::test]
-async fn test_v1_ipgeo_invalid_ip() {
-    let mock = MockGeoIpReader::new();
-
-    let state = create_test_state(mock);
+async fn test_ipgeo_reject_private() {
+    let state = create_test_state(MockGeoIpReader::new());
     let app = Router::new()
+        .route("/ipgeo", get(ipgeo_handler))
         .route("/v1/ipgeo", get(ipgeo_full_handler))
         .with_state(state);
 
@@ -731,39 +998,62 @@ async fn test_v1_ipgeo_invalid_ip() {
     tokio::time::sleep(Duration::from_millis(100)).await;
 
     let client = reqwest::Client::new();
+
+    // Without the flag, private IPs get an empty response as before
     let response = client
-        .get(format!("http://{}/v1/ipgeo?apiKey=test&ip=invalid", addr))
+        .get(format!("http://{}/ipgeo?ip=192.168.1.1", addr))
         .send()
         .await
         .unwrap();
+    assert_eq!(response.status(), 200);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["city"], "");
 
-    assert_eq!(response.status(), 400);
+    for path in ["ipgeo", "v1/ipgeo"] {
+        let response = client
+            .get(format!(
+                "http://{}/{}?ip=192.168.1.1&reject_private=true",
+                addr, path
+            ))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 422);
+        let json: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(json["code"], "PRIVATE_IP");
+        assert_eq!(json["field"], "ip");
+    }
 
-    let json: serde_json::Value = response.json().await.unwrap();
-    assert_eq!(json["code"], "INVALID_IP");
+    // In a multi-IP request only the private entry is rejected
+    let json: serde_json::Value = client
+        .get(format!(
+            "http://{}/ipgeo?ip=192.168.1.1,8.8.8.8&reject_private=true",
+            addr
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(json[0]["code"], "PRIVATE_IP");
+    assert!(json[1].get("code").is_none());
 }
 
-/// Test v1/ipgeo with EU country (check is_eu flag)
+/// Test that a `fields` list other than `*`/`location` returns a sparse fieldset
 #[tokio::test]
-async fn test_v1_ipgeo_eu_country() {
+async fn test_ipgeo_sparse_fields() {
     let mock = MockGeoIpReader::new().with_response(
-        "1.2.3.4",
+        "8.8.8.8",
         Ok(GeoData {
-            latitude: Some(52.52),
-            longitude: Some(13.405),
-            city: Some("Berlin".to_string()),
-            country_name: Some("Germany".to_string()),
-            country_code: Some("DE".to_string()),
-            state_prov: Some("Berlin".to_string()),
-            state_code: Some("BE".to_string()),
-            postal_code: Some("10115".to_string()),
-            geoname_id: Some(2950159),
+            city: Some("Mountain View".to_string()),
+            country_name: Some("United States".to_string()),
+            ..Default::default()
         }),
     );
-
     let state = create_test_state(mock);
     let app = Router::new()
-        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .route("/ipgeo", get(ipgeo_handler))
         .with_state(state);
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -776,316 +1066,400 @@ async fn test_v1_ipgeo_eu_country() {
     tokio::time::sleep(Duration::from_millis(100)).await;
 
     let client = reqwest::Client::new();
+
     let response = client
-        .get(format!("http://{}/v1/ipgeo?apiKey=test&ip=1.2.3.4", addr))
+        .get(format!(
+            "http://{}/ipgeo?ip=8.8.8.8&fields=city,country_name",
+            addr
+        ))
         .send()
         .await
         .unwrap();
-
     assert_eq!(response.status(), 200);
-
     let json: serde_json::Value = response.json().await.unwrap();
-
-    // Germany is in EU
-    assert_eq!(json["location"]["is_eu"], true);
-    assert_eq!(json["location"]["country_code2"], "DE");
-    assert_eq!(json["currency"]["code"], "EUR");
-    assert_eq!(json["currency"]["symbol"], "€");
-}
-
-// ============================================================================
-// Root Endpoint Tests (/ - client IP geolocation)
-// ============================================================================
-
-/// Test root endpoint returns geolocation for direct connection IP
-#[tokio::test]
-async fn test_root_endpoint_direct_ip() {
-    // The mock will receive 127.0.0.1 as the client IP from direct connection
-    let mock = MockGeoIpReader::new().with_response(
-        "127.0.0.1",
-        Ok(GeoData {
-            latitude: Some(37.751),
-            longitude: Some(-97.822),
-            city: Some("Test City".to_string()),
-            country_name: Some("United States".to_string()),
-            country_code: Some("US".to_string()),
-            state_prov: None,
-            state_code: None,
-            postal_code: None,
-            geoname_id: None,
-        }),
+    assert_eq!(
+        json,
+        serde_json::json!({"city": "Mountain View", "country_name": "United States"})
     );
 
-    let state = create_test_state(mock);
-    let app = Router::new()
-        .route("/", get(root_handler))
-        .with_state(state);
-
-    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
-    let addr = listener.local_addr().unwrap();
-
-    tokio::spawn(async move {
-        axum::serve(
-            listener,
-            app.into_make_service_with_connect_info::<SocketAddr>(),
-        )
+    // `location` in the list still selects the full format
+    let json: serde_json::Value = client
+        .get(format!(
+            "http://{}/ipgeo?ip=8.8.8.8&fields=location,city",
+            addr
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
         .await
         .unwrap();
-    });
-
-    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert_eq!(json["location"]["city"], "Mountain View");
 
-    let client = reqwest::Client::new();
     let response = client
-        .get(format!("http://{}/", addr))
+        .get(format!(
+            "http://{}/ipgeo?ip=8.8.8.8&fields=city,bogus",
+            addr
+        ))
         .send()
         .await
         .unwrap();
-
-    assert_eq!(response.status(), 200);
-
+    assert_eq!(response.status(), 400);
     let json: serde_json::Value = response.json().await.unwrap();
-    assert_eq!(json["city"], "Test City");
-    assert_eq!(json["country_name"], "United States");
-    assert_eq!(json["languages"], "en-US,en");
+    assert_eq!(json["code"], "INVALID_FIELDS");
+    assert_eq!(json["field"], "fields");
 }
 
-/// Test root endpoint with X-Forwarded-For header
+/// Test that repeating a query parameter is rejected with DUPLICATE_PARAM
 #[tokio::test]
-async fn test_root_endpoint_x_forwarded_for() {
-    // The mock should receive the IP from X-Forwarded-For header
+async fn test_duplicate_query_params_rejected() {
     let mock = MockGeoIpReader::new().with_response(
-        "203.0.113.1",
+        "8.8.8.8",
         Ok(GeoData {
-            latitude: Some(51.5074),
-            longitude: Some(-0.1278),
-            city: Some("London".to_string()),
-            country_name: Some("United Kingdom".to_string()),
-            country_code: Some("GB".to_string()),
-            state_prov: None,
-            state_code: None,
-            postal_code: None,
-            geoname_id: None,
+            city: Some("Mountain View".to_string()),
+            ..Default::default()
         }),
     );
-
     let state = create_test_state(mock);
     let app = Router::new()
-        .route("/", get(root_handler))
-        .with_state(state);
+        .route("/ipgeo", get(ipgeo_handler))
+        .with_state(state)
+        .layer(axum::middleware::from_fn(reject_duplicate_params));
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
 
     tokio::spawn(async move {
-        axum::serve(
-            listener,
-            app.into_make_service_with_connect_info::<SocketAddr>(),
-        )
-        .await
-        .unwrap();
+        axum::serve(listener, app).await.unwrap();
     });
 
     tokio::time::sleep(Duration::from_millis(100)).await;
 
     let client = reqwest::Client::new();
     let response = client
-        .get(format!("http://{}/", addr))
-        .header("X-Forwarded-For", "203.0.113.1, 10.0.0.1")
+        .get(format!("http://{}/ipgeo?ip=8.8.8.8&ip=1.1.1.1", addr))
         .send()
         .await
         .unwrap();
-
-    assert_eq!(response.status(), 200);
-
+    assert_eq!(response.status(), 400);
     let json: serde_json::Value = response.json().await.unwrap();
-    assert_eq!(json["city"], "London");
-    assert_eq!(json["country_name"], "United Kingdom");
+    assert_eq!(json["code"], "DUPLICATE_PARAM");
+    assert_eq!(json["error"], "Duplicate query parameter: ip");
+
+    let response = client
+        .get(format!("http://{}/ipgeo?ip=8.8.8.8", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
 }
 
-/// Test root endpoint with X-Real-IP header
+/// Test that STRICT_QUERY rejects unknown query parameters with UNKNOWN_PARAM
 #[tokio::test]
-async fn test_root_endpoint_x_real_ip() {
-    let mock = MockGeoIpReader::new().with_response(
-        "198.51.100.1",
-        Ok(GeoData {
-            latitude: Some(35.6762),
-            longitude: Some(139.6503),
-            city: Some("Tokyo".to_string()),
-            country_name: Some("Japan".to_string()),
-            country_code: Some("JP".to_string()),
-            state_prov: None,
-            state_code: None,
-            postal_code: None,
-            geoname_id: None,
-        }),
-    );
+async fn test_strict_query_rejects_unknown_params() {
+    let mock = || {
+        MockGeoIpReader::new().with_response(
+            "8.8.8.8",
+            Ok(GeoData {
+                city: Some("Mountain View".to_string()),
+                ..Default::default()
+            }),
+        )
+    };
+    let strict = AppState {
+        strict_query: true,
+        ..create_test_state(mock())
+    };
+    let app = |state: AppState| {
+        Router::new()
+            .route("/ipgeo", get(ipgeo_handler))
+            .with_state(state.clone())
+            .layer(axum::middleware::from_fn_with_state(
+                state,
+                reject_unknown_params,
+            ))
+    };
+    let strict_app = app(strict);
+    let lenient_app = app(create_test_state(mock()));
+
+    let strict_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let strict_addr = strict_listener.local_addr().unwrap();
+    let lenient_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let lenient_addr = lenient_listener.local_addr().unwrap();
 
-    let state = create_test_state(mock);
+    tokio::spawn(async move {
+        axum::serve(strict_listener, strict_app).await.unwrap();
+    });
+    tokio::spawn(async move {
+        axum::serve(lenient_listener, lenient_app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!(
+            "http://{}/ipgeo?ip=8.8.8.8&adress=1.1.1.1",
+            strict_addr
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["code"], "UNKNOWN_PARAM");
+    assert_eq!(json["error"], "Unknown query parameter: adress");
+
+    // The `_` cache-buster is not a typo
+    let response = client
+        .get(format!(
+            "http://{}/ipgeo?ip=8.8.8.8&pretty=true&_=1712345678",
+            strict_addr
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    // Lenient by default
+    let response = client
+        .get(format!(
+            "http://{}/ipgeo?ip=8.8.8.8&adress=1.1.1.1",
+            lenient_addr
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+}
+
+/// Test that GEOIP_OVERRIDES_PATH entries locate private addresses end to end
+#[tokio::test]
+async fn test_ipgeo_override_private_range() {
+    let overrides = OverrideGeoIpReader::from_json(
+        r#"{
+            "10.1.0.0/16": {"city": "Stockholm Office", "country_code": "SE"},
+            "2001:db8::1": {"city": "Lab"}
+        }"#,
+        Arc::new(MockGeoIpReader::new()),
+    )
+    .unwrap();
+    let state = AppState::new(
+        Arc::new(overrides),
+        Arc::new(GeoCache::new(CacheConfig::default())),
+        "https://test.example.com".to_string(),
+    );
     let app = Router::new()
-        .route("/", get(root_handler))
+        .route("/ipgeo", get(ipgeo_handler))
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
         .with_state(state);
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
 
     tokio::spawn(async move {
-        axum::serve(
-            listener,
-            app.into_make_service_with_connect_info::<SocketAddr>(),
-        )
-        .await
-        .unwrap();
+        axum::serve(listener, app).await.unwrap();
     });
 
     tokio::time::sleep(Duration::from_millis(100)).await;
 
     let client = reqwest::Client::new();
-    let response = client
-        .get(format!("http://{}/", addr))
-        .header("X-Real-IP", "198.51.100.1")
-        .send()
+    let get = |path: &str| client.get(format!("http://{}{}", addr, path)).send();
+
+    let json: serde_json::Value = get("/ipgeo?ip=10.1.2.3")
+        .await
+        .unwrap()
+        .json()
         .await
         .unwrap();
+    assert_eq!(json["city"], "Stockholm Office");
 
+    // Overridden ranges are not rejected as private either
+    let response = get("/v1/ipgeo?ip=2001:db8::1&reject_private=true")
+        .await
+        .unwrap();
     assert_eq!(response.status(), 200);
-
     let json: serde_json::Value = response.json().await.unwrap();
-    assert_eq!(json["city"], "Tokyo");
-    assert_eq!(json["country_name"], "Japan");
+    assert_eq!(json["location"]["city"], "Lab");
+
+    // Private addresses outside the table stay empty
+    let json: serde_json::Value = get("/ipgeo?ip=10.2.0.1")
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(json["city"], "");
 }
 
-/// Test root endpoint with CF-Connecting-IP header (Cloudflare)
+/// Test that `fields=both` returns the simple and full responses together
 #[tokio::test]
-async fn test_root_endpoint_cf_connecting_ip() {
+async fn test_ipgeo_fields_both() {
     let mock = MockGeoIpReader::new().with_response(
-        "192.0.2.1",
+        "8.8.8.8",
         Ok(GeoData {
-            latitude: Some(52.52),
-            longitude: Some(13.405),
-            city: Some("Berlin".to_string()),
-            country_name: Some("Germany".to_string()),
-            country_code: Some("DE".to_string()),
-            state_prov: None,
-            state_code: None,
-            postal_code: None,
-            geoname_id: None,
+            city: Some("Mountain View".to_string()),
+            country_name: Some("United States".to_string()),
+            country_code: Some("US".to_string()),
+            time_zone: Some("America/Los_Angeles".to_string()),
+            ..Default::default()
         }),
     );
-
     let state = create_test_state(mock);
     let app = Router::new()
-        .route("/", get(root_handler))
+        .route("/ipgeo", get(ipgeo_handler))
         .with_state(state);
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
 
     tokio::spawn(async move {
-        axum::serve(
-            listener,
-            app.into_make_service_with_connect_info::<SocketAddr>(),
-        )
-        .await
-        .unwrap();
+        axum::serve(listener, app).await.unwrap();
     });
 
     tokio::time::sleep(Duration::from_millis(100)).await;
 
     let client = reqwest::Client::new();
     let response = client
-        .get(format!("http://{}/", addr))
-        .header("CF-Connecting-IP", "192.0.2.1")
+        .get(format!("http://{}/ipgeo?ip=8.8.8.8&fields=both", addr))
         .send()
         .await
         .unwrap();
-
     assert_eq!(response.status(), 200);
+    // The full half embeds the current time, so it is only cached briefly
+    assert_eq!(response.headers()["cache-control"], "public, max-age=60");
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert!(json["simple"].is_object());
+    assert!(json["full"].is_object());
+    assert_eq!(json["simple"]["country_name"], "United States");
+    assert_eq!(
+        json["simple"]["country_name"],
+        json["full"]["location"]["country_name"]
+    );
+    assert_eq!(json["full"]["ip"], "8.8.8.8");
+    assert!(json["full"]["time_zone"]["current_time"].is_string());
 
+    // live_time=false drops the current time and restores long-term caching
+    let response = client
+        .get(format!(
+            "http://{}/ipgeo?ip=8.8.8.8&fields=both&live_time=false",
+            addr
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.headers()["cache-control"],
+        "public, max-age=1209600"
+    );
     let json: serde_json::Value = response.json().await.unwrap();
-    assert_eq!(json["city"], "Berlin");
-    assert_eq!(json["country_name"], "Germany");
+    assert!(json["full"]["time_zone"].get("current_time").is_none());
+
+    // The full format alone follows the same rule
+    let response = client
+        .get(format!("http://{}/ipgeo?ip=8.8.8.8&fields=*", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["cache-control"], "public, max-age=60");
 }
 
-/// Test root endpoint Cache-Control headers
+/// Test nulls=true reports unknown city/country as null instead of ""
 #[tokio::test]
-async fn test_root_endpoint_cache_control_headers() {
+async fn test_ipgeo_nulls() {
     let mock = MockGeoIpReader::new().with_response(
-        "127.0.0.1",
+        "8.8.8.8",
         Ok(GeoData {
-            latitude: Some(40.0),
-            longitude: Some(-74.0),
-            city: Some("Test".to_string()),
-            country_name: Some("Test Country".to_string()),
-            country_code: Some("TC".to_string()),
-            state_prov: None,
-            state_code: None,
-            postal_code: None,
-            geoname_id: None,
+            latitude: Some(37.751),
+            longitude: Some(-97.822),
+            ..Default::default()
         }),
     );
-
     let state = create_test_state(mock);
     let app = Router::new()
-        .route("/", get(root_handler))
+        .route("/ipgeo", get(ipgeo_handler))
         .with_state(state);
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
 
     tokio::spawn(async move {
-        axum::serve(
-            listener,
-            app.into_make_service_with_connect_info::<SocketAddr>(),
-        )
-        .await
-        .unwrap();
+        axum::serve(listener, app).await.unwrap();
     });
 
     tokio::time::sleep(Duration::from_millis(100)).await;
 
     let client = reqwest::Client::new();
+
+    // Empty strings by default
     let response = client
-        .get(format!("http://{}/", addr))
+        .get(format!("http://{}/ipgeo?ip=8.8.8.8", addr))
         .send()
         .await
         .unwrap();
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["city"], "");
+    assert_eq!(json["country_name"], "");
 
-    let cache_control = response.headers().get("cache-control");
-    assert!(cache_control.is_some());
-    assert_eq!(
-        cache_control.unwrap().to_str().unwrap(),
-        "public, max-age=1209600"
-    );
+    // The cached response honours the flag too
+    let response = client
+        .get(format!("http://{}/ipgeo?ip=8.8.8.8&nulls=true", addr))
+        .send()
+        .await
+        .unwrap();
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert!(json["city"].is_null());
+    assert!(json["country_name"].is_null());
+    assert_eq!(json["latitude"], 37.751);
+
+    let response = client
+        .get(format!(
+            "http://{}/ipgeo?ip=8.8.8.8,1.1.1.1&nulls=true",
+            addr
+        ))
+        .send()
+        .await
+        .unwrap();
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert!(json[0]["city"].is_null());
 }
 
-/// Test root endpoint with unknown IP returns empty response
+/// Test ipgeo endpoint with missing coordinates (no timezone can be determined)
 #[tokio::test]
-async fn test_root_endpoint_unknown_ip() {
-    let mock = MockGeoIpReader::new(); // No responses configured
+async fn test_ipgeo_no_coordinates() {
+    let mock = MockGeoIpReader::new().with_response(
+        "1.0.0.1",
+        Ok(GeoData {
+            latitude: None, // No coordinates
+            longitude: None,
+            city: Some("Unknown".to_string()),
+            country_name: Some("Reserved".to_string()),
+            country_code: Some("XX".to_string()),
+            state_prov: None,
+            state_code: None,
+            postal_code: None,
+            geoname_id: None,
+            ..Default::default()
+        }),
+    );
 
     let state = create_test_state(mock);
     let app = Router::new()
-        .route("/", get(root_handler))
+        .route("/ipgeo", get(ipgeo_handler))
         .with_state(state);
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
 
     tokio::spawn(async move {
-        axum::serve(
-            listener,
-            app.into_make_service_with_connect_info::<SocketAddr>(),
-        )
-        .await
-        .unwrap();
+        axum::serve(listener, app).await.unwrap();
     });
 
     tokio::time::sleep(Duration::from_millis(100)).await;
 
     let client = reqwest::Client::new();
     let response = client
-        .get(format!("http://{}/", addr))
+        .get(format!("http://{}/ipgeo?apiKey=test&ip=1.0.0.1", addr))
         .send()
         .await
         .unwrap();
@@ -1093,21 +1467,35 @@ async fn test_root_endpoint_unknown_ip() {
     assert_eq!(response.status(), 200);
 
     let json: serde_json::Value = response.json().await.unwrap();
-    assert_eq!(json["city"], "");
-    assert_eq!(json["country_name"], "");
-    assert_eq!(json["languages"], "");
+    // With no coordinates, timezone should be empty
+    assert_eq!(json["time_zone"]["name"], "");
+    // But city and country should still be present
+    assert_eq!(json["city"], "Unknown");
+    assert_eq!(json["country_name"], "Reserved");
 }
 
-// ============================================================================
-// LLM/Agent Documentation Endpoint Tests
-// ============================================================================
-
-/// Test OpenAPI specification endpoint returns valid YAML
+/// Test ipgeo endpoint with partial coordinates (only latitude)
 #[tokio::test]
-async fn test_openapi_endpoint() {
-    let state = create_minimal_test_state();
+async fn test_ipgeo_partial_coordinates() {
+    let mock = MockGeoIpReader::new().with_response(
+        "9.9.9.9",
+        Ok(GeoData {
+            latitude: Some(40.0), // Only latitude
+            longitude: None,      // No longitude
+            city: Some("Partial".to_string()),
+            country_name: Some("Test".to_string()),
+            country_code: Some("TE".to_string()),
+            state_prov: None,
+            state_code: None,
+            postal_code: None,
+            geoname_id: None,
+            ..Default::default()
+        }),
+    );
+
+    let state = create_test_state(mock);
     let app = Router::new()
-        .route("/openapi.yaml", get(openapi_handler))
+        .route("/ipgeo", get(ipgeo_handler))
         .with_state(state);
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -1121,39 +1509,44 @@ async fn test_openapi_endpoint() {
 
     let client = reqwest::Client::new();
     let response = client
-        .get(format!("http://{}/openapi.yaml", addr))
+        .get(format!("http://{}/ipgeo?apiKey=test&ip=9.9.9.9", addr))
         .send()
         .await
         .unwrap();
 
     assert_eq!(response.status(), 200);
 
-    // Check content type
-    let content_type = response.headers().get("content-type");
-    assert!(content_type.is_some());
-    assert!(content_type
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .contains("application/yaml"));
-
-    // Check content contains expected OpenAPI structure
-    let body = response.text().await.unwrap();
-    assert!(body.contains("openapi: 3.1"));
-    assert!(body.contains("IP Geolocation API"));
-    assert!(body.contains("/ipgeo"));
-    assert!(body.contains("/timezone"));
-    assert!(body.contains("/v1/ipgeo"));
-    assert!(body.contains("/v1/timezone"));
-    assert!(body.contains("/health"));
+    let json: serde_json::Value = response.json().await.unwrap();
+    // With partial coordinates, timezone should be empty
+    assert_eq!(json["time_zone"]["name"], "");
 }
 
-/// Test OpenAPI specification is valid YAML
+// ============================================================================
+// V1 API Tests (Extended Format)
+// ============================================================================
+
+/// Test v1/ipgeo endpoint returns full format
 #[tokio::test]
-async fn test_openapi_valid_yaml() {
-    let state = create_minimal_test_state();
+async fn test_v1_ipgeo_full_format() {
+    let mock = MockGeoIpReader::new().with_response(
+        "8.8.8.8",
+        Ok(GeoData {
+            latitude: Some(37.751),
+            longitude: Some(-97.822),
+            city: Some("Mountain View".to_string()),
+            country_name: Some("United States".to_string()),
+            country_code: Some("US".to_string()),
+            state_prov: Some("California".to_string()),
+            state_code: Some("CA".to_string()),
+            postal_code: Some("94043".to_string()),
+            geoname_id: Some(5375480),
+            ..Default::default()
+        }),
+    );
+
+    let state = create_test_state(mock);
     let app = Router::new()
-        .route("/openapi.yaml", get(openapi_handler))
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
         .with_state(state);
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -1167,50 +1560,72 @@ async fn test_openapi_valid_yaml() {
 
     let client = reqwest::Client::new();
     let response = client
-        .get(format!("http://{}/openapi.yaml", addr))
+        .get(format!("http://{}/v1/ipgeo?apiKey=test&ip=8.8.8.8", addr))
         .send()
         .await
         .unwrap();
 
-    let body = response.text().await.unwrap();
+    assert_eq!(response.status(), 200);
 
-    // Parse as YAML to validate syntax using yaml-rust2
-    let yaml_docs = yaml_rust2::YamlLoader::load_from_str(&body);
-    assert!(yaml_docs.is_ok(), "OpenAPI spec should be valid YAML");
+    let json: serde_json::Value = response.json().await.unwrap();
 
-    let docs = yaml_docs.unwrap();
-    assert!(!docs.is_empty(), "Should have at least one YAML document");
+    // Check IP is returned
+    assert_eq!(json["ip"], "8.8.8.8");
 
-    let yaml = &docs[0];
-    assert_eq!(yaml["openapi"].as_str(), Some("3.1.0"));
-    assert!(!yaml["paths"].is_badvalue());
-    assert!(!yaml["components"]["schemas"].is_badvalue());
-}
+    // Check location object
+    assert_eq!(json["location"]["country_code2"], "US");
+    assert_eq!(json["location"]["country_code3"], "USA");
+    assert_eq!(json["location"]["country_name"], "United States");
+    assert_eq!(json["location"]["city"], "Mountain View");
+    assert_eq!(json["location"]["state_prov"], "California");
+    assert_eq!(json["location"]["continent_code"], "NA");
+    assert_eq!(json["location"]["continent_name"], "North America");
+    assert_eq!(json["location"]["is_eu"], false);
 
-/// Test OpenAPI spec is generated from code (not static file)
-#[tokio::test]
-async fn test_openapi_generated_from_code() {
-    // Verify ApiDoc generates valid spec
-    let spec = ApiDoc::openapi();
-    let yaml = spec.to_yaml().unwrap();
+    // Check country_metadata object
+    assert_eq!(json["country_metadata"]["calling_code"], "+1");
+    assert_eq!(json["country_metadata"]["tld"], ".us");
+    assert!(json["country_metadata"]["languages"].is_array());
 
-    // Should contain all our endpoints
-    assert!(yaml.contains("/ipgeo"));
-    assert!(yaml.contains("/timezone"));
-    assert!(yaml.contains("/v1/ipgeo"));
-    assert!(yaml.contains("/v1/timezone"));
-    assert!(yaml.contains("/health"));
+    // Check currency object
+    assert_eq!(json["currency"]["code"], "USD");
+    assert_eq!(json["currency"]["name"], "US Dollar");
+    assert_eq!(json["currency"]["symbol"], "$");
 
-    // Should contain our schemas
-    assert!(yaml.contains("IpGeoResponse"));
-    assert!(yaml.contains("TimezoneResponse"));
-    assert!(yaml.contains("ApiErrorResponse"));
+    // Check time_zone object
+    assert!(json["time_zone"]["name"].is_string());
+    assert!(json["time_zone"]["offset"].is_number());
+    assert!(json["time_zone"]["current_time"].is_string());
+    assert!(json["time_zone"]["current_time_unix"].is_number());
 }
 
-/// Test LLM documentation endpoint returns valid content
+/// Test v1/ipgeo includes the country and state GeoNames IDs, omitting absent ones
 #[tokio::test]
-async fn test_llms_txt_endpoint() {
-    let app = Router::new().route("/llms.txt", get(llms_txt_handler));
+async fn test_v1_ipgeo_geoname_hierarchy() {
+    let mock = MockGeoIpReader::new()
+        .with_response(
+            "8.8.8.8",
+            Ok(GeoData {
+                country_code: Some("US".to_string()),
+                geoname_id: Some(5375480),
+                country_geoname_id: Some(6252001),
+                state_geoname_id: Some(5332921),
+                ..Default::default()
+            }),
+        )
+        .with_response(
+            "1.1.1.1",
+            Ok(GeoData {
+                country_code: Some("AU".to_string()),
+                country_geoname_id: Some(2077456),
+                ..Default::default()
+            }),
+        );
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .with_state(state);
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -1222,36 +1637,48 @@ async fn test_llms_txt_endpoint() {
     tokio::time::sleep(Duration::from_millis(100)).await;
 
     let client = reqwest::Client::new();
-    let response = client
-        .get(format!("http://{}/llms.txt", addr))
+    let json: serde_json::Value = client
+        .get(format!("http://{}/v1/ipgeo?ip=8.8.8.8", addr))
         .send()
         .await
+        .unwrap()
+        .json()
+        .await
         .unwrap();
+    assert_eq!(json["location"]["geoname_id"], "5375480");
+    assert_eq!(json["location"]["country_geoname_id"], "6252001");
+    assert_eq!(json["location"]["state_geoname_id"], "5332921");
 
-    assert_eq!(response.status(), 200);
-
-    // Check content type
-    let content_type = response.headers().get("content-type");
-    assert!(content_type.is_some());
-    assert!(content_type
-        .unwrap()
-        .to_str()
+    let json: serde_json::Value = client
+        .get(format!("http://{}/v1/ipgeo?ip=1.1.1.1", addr))
+        .send()
+        .await
         .unwrap()
-        .contains("text/plain"));
-
-    // Check content contains expected documentation
-    let body = response.text().await.unwrap();
-    assert!(body.contains("IP Geolocation API"));
-    assert!(body.contains("/ipgeo"));
-    assert!(body.contains("/timezone"));
-    assert!(body.contains("openapi.yaml"));
-    assert!(body.contains("curl"));
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(json["location"]["country_geoname_id"], "2077456");
+    assert!(json["location"].get("state_geoname_id").is_none());
+    assert!(json["location"].get("geoname_id").is_none());
 }
 
-/// Test LLM documentation contains all required sections
+/// Test v1/ipgeo with live_time=false omits the current time and is cacheable long-term
 #[tokio::test]
-async fn test_llms_txt_content_structure() {
-    let app = Router::new().route("/llms.txt", get(llms_txt_handler));
+async fn test_v1_ipgeo_without_live_time() {
+    let mock = MockGeoIpReader::new().with_response(
+        "8.8.8.8",
+        Ok(GeoData {
+            latitude: Some(37.751),
+            longitude: Some(-97.822),
+            country_code: Some("US".to_string()),
+            ..Default::default()
+        }),
+    );
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .with_state(state);
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -1263,30 +1690,45 @@ async fn test_llms_txt_content_structure() {
     tokio::time::sleep(Duration::from_millis(100)).await;
 
     let client = reqwest::Client::new();
+
+    // Default: live time included, short cache lifetime
     let response = client
-        .get(format!("http://{}/llms.txt", addr))
+        .get(format!("http://{}/v1/ipgeo?ip=8.8.8.8", addr))
         .send()
         .await
         .unwrap();
+    assert_eq!(
+        response.headers().get("cache-control").unwrap(),
+        "public, max-age=60"
+    );
 
-    let body = response.text().await.unwrap();
+    let response = client
+        .get(format!(
+            "http://{}/v1/ipgeo?ip=8.8.8.8&live_time=false",
+            addr
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.headers().get("cache-control").unwrap(),
+        "public, max-age=1209600"
+    );
 
-    // Check required sections
-    assert!(body.contains("What This API Does"));
-    assert!(body.contains("When to Use This API"));
-    assert!(body.contains("Quick Reference"));
-    assert!(body.contains("Examples"));
-    assert!(body.contains("Error Handling"));
-    assert!(body.contains("API Discovery"));
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert!(json["time_zone"]["name"].is_string());
+    assert!(json["time_zone"]["offset"].is_number());
+    assert!(json["time_zone"]["is_dst"].is_boolean());
+    assert!(json["time_zone"].get("current_time").is_none());
+    assert!(json["time_zone"].get("current_time_unix").is_none());
 }
 
-/// Test sitemap.xml endpoint returns valid XML
+/// Test POST /v1/timezone/batch resolves points in order with per-point errors
 #[tokio::test]
-async fn test_sitemap_endpoint() {
-    let state = create_minimal_test_state();
+async fn test_v1_timezone_batch() {
     let app = Router::new()
-        .route("/sitemap.xml", get(sitemap_handler))
-        .with_state(state);
+        .route("/v1/timezone/batch", post(timezone_batch_handler))
+        .with_state(create_minimal_test_state());
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -1299,40 +1741,51 @@ async fn test_sitemap_endpoint() {
 
     let client = reqwest::Client::new();
     let response = client
-        .get(format!("http://{}/sitemap.xml", addr))
+        .post(format!("http://{}/v1/timezone/batch", addr))
+        .json(&serde_json::json!({
+            "points": [
+                {"lat": 59.329504, "lon": 18.069532},
+                {"lat": 95.0, "lon": 0.0},
+                {"lat": 40.7128, "lon": -74.006},
+                {"lat": 0.0, "lon": 200.0}
+            ]
+        }))
         .send()
         .await
         .unwrap();
-
     assert_eq!(response.status(), 200);
 
-    // Check content type
-    let content_type = response.headers().get("content-type");
-    assert!(content_type.is_some());
-    assert!(content_type
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .contains("application/xml"));
-
-    // Check XML content
-    let body = response.text().await.unwrap();
-    assert!(body.contains("<?xml version"));
-    assert!(body.contains("<urlset"));
-    assert!(body.contains("test.example.com")); // Uses base_url from state
-    assert!(body.contains("/ipgeo"));
-    assert!(body.contains("/timezone"));
-    assert!(body.contains("/openapi.yaml"));
-    assert!(body.contains("/llms.txt"));
+    let json: serde_json::Value = response.json().await.unwrap();
+    let results = json.as_array().unwrap();
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0]["timezone"], "Europe/Stockholm");
+    assert!(results[0]["current_time"].is_string());
+    assert_eq!(results[1]["code"], "INVALID_LATITUDE");
+    assert_eq!(results[1]["lat"], 95.0);
+    assert_eq!(results[2]["timezone"], "America/New_York");
+    assert_eq!(results[3]["code"], "INVALID_LONGITUDE");
+
+    // More points than the bulk cap are rejected outright
+    let points: Vec<serde_json::Value> = (0..101)
+        .map(|_| serde_json::json!({"lat": 0.0, "lon": 0.0}))
+        .collect();
+    let response = client
+        .post(format!("http://{}/v1/timezone/batch", addr))
+        .json(&serde_json::json!({ "points": points }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["code"], "TOO_MANY_POINTS");
 }
 
-/// Test .well-known/openapi.yaml endpoint
+/// Test /v1/timezone/compare reports differences against the first zone
 #[tokio::test]
-async fn test_wellknown_openapi_endpoint() {
-    let state = create_minimal_test_state();
+async fn test_v1_timezone_compare() {
     let app = Router::new()
-        .route("/.well-known/openapi.yaml", get(wellknown_openapi_handler))
-        .with_state(state);
+        .route("/v1/timezone/compare", get(timezone_compare_handler))
+        .with_state(create_minimal_test_state());
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -1343,39 +1796,59 @@ async fn test_wellknown_openapi_endpoint() {
 
     tokio::time::sleep(Duration::from_millis(100)).await;
 
+    // Zones without DST, so the differences are stable year-round
     let client = reqwest::Client::new();
     let response = client
-        .get(format!("http://{}/.well-known/openapi.yaml", addr))
+        .get(format!(
+            "http://{}/v1/timezone/compare?zones=Asia/Tokyo,Asia/Kolkata,Etc/UTC",
+            addr
+        ))
         .send()
         .await
         .unwrap();
-
     assert_eq!(response.status(), 200);
 
-    // Check content type
-    let content_type = response.headers().get("content-type");
-    assert!(content_type.is_some());
-    assert!(content_type
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .contains("application/yaml"));
-
-    // Check it's the same as /openapi.yaml
-    let body = response.text().await.unwrap();
-    assert!(body.contains("openapi: 3.1"));
-    assert!(body.contains("IP Geolocation API"));
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["reference"], "Asia/Tokyo");
+    let zones = json["zones"].as_array().unwrap();
+    assert_eq!(zones.len(), 3);
+    assert_eq!(zones[0]["offset"], 9.0);
+    assert_eq!(zones[0]["difference"], 0.0);
+    assert_eq!(zones[1]["timezone"], "Asia/Kolkata");
+    assert_eq!(zones[1]["offset"], 5.5);
+    assert_eq!(zones[1]["difference"], -3.5);
+    assert_eq!(zones[2]["offset"], 0.0);
+    assert_eq!(zones[2]["difference"], -9.0);
+
+    // Any unknown zone fails the whole request
+    let response = client
+        .get(format!(
+            "http://{}/v1/timezone/compare?zones=Asia/Tokyo,Mars/Olympus",
+            addr
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["code"], "INVALID_TIMEZONE");
+    assert_eq!(json["field"], "zones");
 }
 
-/// Test .well-known/ai-plugin.json endpoint
+/// Test pretty=true indents JSON while the default output stays compact
 #[tokio::test]
-async fn test_wellknown_ai_plugin_endpoint() {
-    let state = create_minimal_test_state();
+async fn test_pretty_json() {
+    let mock = MockGeoIpReader::new().with_response(
+        "8.8.8.8",
+        Ok(GeoData {
+            latitude: Some(37.751),
+            ..Default::default()
+        }),
+    );
+
+    let state = create_test_state(mock);
     let app = Router::new()
-        .route(
-            "/.well-known/ai-plugin.json",
-            get(wellknown_ai_plugin_handler),
-        )
+        .route("/ipgeo", get(ipgeo_handler))
         .with_state(state);
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -1388,43 +1861,59 @@ async fn test_wellknown_ai_plugin_endpoint() {
     tokio::time::sleep(Duration::from_millis(100)).await;
 
     let client = reqwest::Client::new();
-    let response = client
-        .get(format!("http://{}/.well-known/ai-plugin.json", addr))
-        .send()
+
+    let compact = client
+        .get(format!("http://{}/ipgeo?ip=8.8.8.8", addr))
+        .send()
+        .await
+        .unwrap()
+        .text()
         .await
         .unwrap();
+    assert!(!compact.contains('\n'));
 
-    assert_eq!(response.status(), 200);
-
-    // Check content type
-    let content_type = response.headers().get("content-type");
-    assert!(content_type.is_some());
-    assert!(content_type
-        .unwrap()
-        .to_str()
+    let pretty = client
+        .get(format!("http://{}/ipgeo?ip=8.8.8.8&pretty=true", addr))
+        .send()
+        .await
         .unwrap()
-        .contains("application/json"));
+        .text()
+        .await
+        .unwrap();
+    assert!(pretty.contains("\n  \"latitude\": 37.751"));
 
-    // Check JSON structure
-    let json: serde_json::Value = response.json().await.unwrap();
-    assert_eq!(json["schema_version"], "v1");
-    assert_eq!(json["name_for_model"], "ip_geolocation");
-    assert!(!json["description_for_model"].as_str().unwrap().is_empty());
-    assert_eq!(json["api"]["type"], "openapi");
-    assert!(json["api"]["url"]
-        .as_str()
+    // Same document either way
+    let compact: serde_json::Value = serde_json::from_str(&compact).unwrap();
+    let pretty: serde_json::Value = serde_json::from_str(&pretty).unwrap();
+    assert_eq!(compact, pretty);
+
+    // Errors honour the flag too
+    let error = client
+        .get(format!("http://{}/ipgeo?ip=invalid&pretty=true", addr))
+        .send()
+        .await
         .unwrap()
-        .contains("openapi.yaml"));
+        .text()
+        .await
+        .unwrap();
+    assert!(error.contains("\n  \"code\": \"INVALID_IP\""));
 }
 
-// ============================================================================
-// Protobuf Response Tests
-// ============================================================================
-
-/// Test timezone endpoint with protobuf Accept header
+/// Test v1/flag endpoint returns the flag emoji and SVG path
 #[tokio::test]
-async fn test_timezone_protobuf_response() {
-    let app = Router::new().route("/timezone", get(timezone_handler));
+async fn test_v1_flag() {
+    let mock = MockGeoIpReader::new().with_response(
+        "8.8.8.8",
+        Ok(GeoData {
+            country_code: Some("US".to_string()),
+            ..Default::default()
+        }),
+    );
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/v1/flag", get(flag_handler))
+        .with_state(state);
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -1437,54 +1926,37 @@ async fn test_timezone_protobuf_response() {
 
     let client = reqwest::Client::new();
     let response = client
-        .get(format!(
-            "http://{}/timezone?lat=59.329504&long=18.069532",
-            addr
-        ))
-        .header("Accept", "application/x-protobuf")
+        .get(format!("http://{}/v1/flag?ip=8.8.8.8", addr))
         .send()
         .await
         .unwrap();
-
     assert_eq!(response.status(), 200);
 
-    // Check content type is protobuf
-    let content_type = response.headers().get("content-type");
-    assert!(content_type.is_some());
-    assert!(content_type
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .contains("application/x-protobuf"));
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["country_code"], "US");
+    assert_eq!(json["emoji"], "🇺🇸");
+    assert_eq!(json["svg"], "/static/flags/us.svg");
 
-    // Decode protobuf response
-    let bytes = response.bytes().await.unwrap();
-    let proto = geolocation::TimezoneResponse::decode(bytes).unwrap();
-    assert_eq!(proto.timezone, "Europe/Stockholm");
+    // Unknown IPs return empty fields
+    let json: serde_json::Value = client
+        .get(format!("http://{}/v1/flag?ip=10.0.0.1", addr))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(json["country_code"], "");
+    assert_eq!(json["emoji"], "");
+    assert_eq!(json["svg"], "");
 }
 
-/// Test ipgeo endpoint with protobuf Accept header
+/// Test v1/timezone endpoint returns full details
 #[tokio::test]
-async fn test_ipgeo_protobuf_response() {
-    let mock = MockGeoIpReader::new().with_response(
-        "8.8.8.8",
-        Ok(GeoData {
-            latitude: Some(37.751),
-            longitude: Some(-97.822),
-            city: Some("Mountain View".to_string()),
-            country_name: Some("United States".to_string()),
-            country_code: Some("US".to_string()),
-            state_prov: Some("California".to_string()),
-            state_code: Some("CA".to_string()),
-            postal_code: Some("94043".to_string()),
-            geoname_id: Some(5375480),
-        }),
-    );
-
-    let state = create_test_state(mock);
+async fn test_v1_timezone_full_format() {
     let app = Router::new()
-        .route("/ipgeo", get(ipgeo_handler))
-        .with_state(state);
+        .route("/v1/timezone", get(timezone_full_handler))
+        .with_state(create_minimal_test_state());
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -1497,52 +1969,54 @@ async fn test_ipgeo_protobuf_response() {
 
     let client = reqwest::Client::new();
     let response = client
-        .get(format!("http://{}/ipgeo?ip=8.8.8.8", addr))
-        .header("Accept", "application/x-protobuf")
+        .get(format!(
+            "http://{}/v1/timezone?apiKey=test&lat=59.329504&long=18.069532",
+            addr
+        ))
         .send()
         .await
         .unwrap();
 
     assert_eq!(response.status(), 200);
 
-    // Check content type is protobuf
-    let content_type = response.headers().get("content-type");
-    assert!(content_type.is_some());
-    assert!(content_type
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .contains("application/x-protobuf"));
+    let json: serde_json::Value = response.json().await.unwrap();
 
-    // Decode protobuf response
-    let bytes = response.bytes().await.unwrap();
-    let proto = geolocation::IpGeoResponse::decode(bytes).unwrap();
-    assert_eq!(proto.city, "Mountain View");
-    assert_eq!(proto.country_name, "United States");
-    assert!(proto.latitude.is_some());
+    // Check timezone details
+    assert_eq!(json["timezone"], "Europe/Stockholm");
+    assert!(json["offset"].is_number());
+    assert!(json["current_time"].is_string());
+    assert!(json["current_time_unix"].is_number());
+    let unix = json["current_time_unix"].as_f64().unwrap();
+    assert_eq!(
+        json["current_time_unix_ms"].as_i64().unwrap(),
+        (unix * 1000.0).round() as i64
+    );
+    assert!(json["is_dst"].is_boolean());
+    assert!(json["dst_exists"].is_boolean());
+    assert_eq!(json["source"], "coordinates");
+
+    // Open-ocean coordinates get a flagged nautical zone
+    let json: serde_json::Value = client
+        .get(format!("http://{}/v1/timezone?lat=0&long=-30", addr))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(json["timezone"], "Etc/GMT+2");
+    assert_eq!(json["source"], "nautical");
+    assert_eq!(json["offset"], -2);
 }
 
-/// Test v1/ipgeo endpoint with protobuf Accept header
+/// Test ipgeo endpoint with invalid IP address returns 400
 #[tokio::test]
-async fn test_v1_ipgeo_protobuf_response() {
-    let mock = MockGeoIpReader::new().with_response(
-        "1.1.1.1",
-        Ok(GeoData {
-            latitude: Some(51.5074),
-            longitude: Some(-0.1278),
-            city: Some("London".to_string()),
-            country_name: Some("United Kingdom".to_string()),
-            country_code: Some("GB".to_string()),
-            state_prov: None,
-            state_code: None,
-            postal_code: None,
-            geoname_id: None,
-        }),
-    );
+async fn test_ipgeo_invalid_ip() {
+    let mock = MockGeoIpReader::new();
 
     let state = create_test_state(mock);
     let app = Router::new()
-        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .route("/ipgeo", get(ipgeo_handler))
         .with_state(state);
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
@@ -1556,41 +2030,27 @@ async fn test_v1_ipgeo_protobuf_response() {
 
     let client = reqwest::Client::new();
     let response = client
-        .get(format!("http://{}/v1/ipgeo?ip=1.1.1.1", addr))
-        .header("Accept", "application/x-protobuf")
+        .get(format!("http://{}/ipgeo?apiKey=test&ip=not-an-ip", addr))
         .send()
         .await
         .unwrap();
 
-    assert_eq!(response.status(), 200);
+    assert_eq!(response.status(), 400);
 
-    // Check content type is protobuf
-    let content_type = response.headers().get("content-type");
-    assert!(content_type.is_some());
-    assert!(content_type
-        .unwrap()
-        .to_str()
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["code"], "INVALID_IP");
+    assert!(json["error"]
+        .as_str()
         .unwrap()
-        .contains("application/x-protobuf"));
-
-    // Decode protobuf response
-    let bytes = response.bytes().await.unwrap();
-    let proto = geolocation::IpGeoResponseFull::decode(bytes).unwrap();
-    assert_eq!(proto.ip, Some("1.1.1.1".to_string()));
-    assert!(proto.location.is_some());
-    let location = proto.location.unwrap();
-    assert_eq!(location.city, Some("London".to_string()));
+        .contains("Invalid IP address"));
 }
 
-/// Test error response in protobuf format
+/// Test v1/timezone `at` parameter reports DST for the given instant
 #[tokio::test]
-async fn test_error_protobuf_response() {
-    let mock = MockGeoIpReader::new();
-
-    let state = create_test_state(mock);
+async fn test_v1_timezone_at() {
     let app = Router::new()
-        .route("/ipgeo", get(ipgeo_handler))
-        .with_state(state);
+        .route("/v1/timezone", get(timezone_full_handler))
+        .with_state(create_minimal_test_state());
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -1602,38 +2062,54 @@ async fn test_error_protobuf_response() {
     tokio::time::sleep(Duration::from_millis(100)).await;
 
     let client = reqwest::Client::new();
-    let response = client
-        .get(format!("http://{}/ipgeo?ip=invalid-ip", addr))
-        .header("Accept", "application/x-protobuf")
-        .send()
+    let berlin_at = |at: &str| {
+        client
+            .get(format!(
+                "http://{}/v1/timezone?lat=52.52&long=13.405&at={}",
+                addr, at
+            ))
+            .send()
+    };
+
+    // 2025-07-01 12:00 UTC: summer time
+    let json: serde_json::Value = berlin_at("1751371200").await.unwrap().json().await.unwrap();
+    assert_eq!(json["timezone"], "Europe/Berlin");
+    assert_eq!(json["is_dst"], true);
+    assert_eq!(json["offset_with_dst"], 2);
+    assert_eq!(json["abbreviation"], "CEST");
+    assert_eq!(json["current_time"], "2025-07-01 14:00:00.000+0200");
+    assert_eq!(json["current_time_unix"], 1751371200.0);
+    assert_eq!(json["current_time_unix_ms"], 1751371200000_i64);
+
+    // Same instant as RFC 3339
+    let json: serde_json::Value = berlin_at("1751371200&time_format=iso8601")
+        .await
+        .unwrap()
+        .json()
         .await
         .unwrap();
+    assert_eq!(json["current_time"], "2025-07-01T14:00:00.000+02:00");
 
-    assert_eq!(response.status(), 400);
-
-    // Check content type is protobuf
-    let content_type = response.headers().get("content-type");
-    assert!(content_type.is_some());
-    assert!(content_type
-        .unwrap()
-        .to_str()
-        .unwrap()
-        .contains("application/x-protobuf"));
+    // 2025-01-15 12:00 UTC: standard time
+    let json: serde_json::Value = berlin_at("1736942400").await.unwrap().json().await.unwrap();
+    assert_eq!(json["is_dst"], false);
+    assert_eq!(json["offset_with_dst"], 1);
+    assert_eq!(json["abbreviation"], "CET");
 
-    // Decode protobuf error response
-    let bytes = response.bytes().await.unwrap();
-    let proto = geolocation::ApiError::decode(bytes).unwrap();
-    assert_eq!(proto.code, "INVALID_IP");
-    assert!(proto.error.contains("Invalid IP address"));
+    // Out-of-range timestamps are rejected
+    let response = berlin_at("99999999999").await.unwrap();
+    assert_eq!(response.status(), 400);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["code"], "INVALID_TIMESTAMP");
+    assert_eq!(json["field"], "at");
 }
 
-/// The shared CORS policy allows any origin to read the API from a browser.
-/// Guards the header a static browser app depends on to read responses.
+/// Test v1/timezone `candidates` lists neighbouring zones near a border
 #[tokio::test]
-async fn test_cors_allows_any_origin() {
+async fn test_v1_timezone_candidates() {
     let app = Router::new()
-        .route("/health", get(health_handler))
-        .layer(ipgeolocation::cors_layer());
+        .route("/v1/timezone", get(timezone_full_handler))
+        .with_state(create_minimal_test_state());
 
     let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
     let addr = listener.local_addr().unwrap();
@@ -1646,47 +2122,3593 @@ async fn test_cors_allows_any_origin() {
 
     let client = reqwest::Client::new();
 
-    // A simple cross-origin GET must carry Access-Control-Allow-Origin so the
-    // browser lets the calling script read the body.
-    let response = client
-        .get(format!("http://{}/health", addr))
-        .header("Origin", "https://example.com")
+    // El Paso, on the border with Ciudad Juarez
+    let json: serde_json::Value = client
+        .get(format!(
+            "http://{}/v1/timezone?lat=31.7587&long=-106.4869&candidates=true",
+            addr
+        ))
         .send()
         .await
+        .unwrap()
+        .json()
+        .await
         .unwrap();
-    assert_eq!(response.status(), 200);
-    assert_eq!(
-        response
-            .headers()
-            .get("access-control-allow-origin")
-            .unwrap()
-            .to_str()
-            .unwrap(),
-        "*"
-    );
-
-    // A CORS preflight (OPTIONS) is answered by the layer and advertises GET.
-    let preflight = client
-        .request(reqwest::Method::OPTIONS, format!("http://{}/health", addr))
-        .header("Origin", "https://example.com")
-        .header("Access-Control-Request-Method", "GET")
+    assert_eq!(json["timezone"], "America/Denver");
+    let candidates = json["candidates"].as_array().unwrap();
+    assert!(candidates.len() > 1, "{candidates:?}");
+    assert_eq!(candidates[0], "America/Denver");
+    assert!(candidates.contains(&serde_json::json!("America/Ciudad_Juarez")));
+
+    // Only on request
+    let json: serde_json::Value = client
+        .get(format!(
+            "http://{}/v1/timezone?lat=31.7587&long=-106.4869",
+            addr
+        ))
         .send()
         .await
-        .unwrap();
-    assert_eq!(
-        preflight
-            .headers()
-            .get("access-control-allow-origin")
-            .unwrap()
-            .to_str()
-            .unwrap(),
-        "*"
-    );
-    let allow_methods = preflight
-        .headers()
-        .get("access-control-allow-methods")
         .unwrap()
-        .to_str()
+        .json()
+        .await
         .unwrap();
-    assert!(allow_methods.contains("GET"));
+    assert!(json.get("candidates").is_none());
+}
+
+/// Test timezone endpoint with invalid latitude returns 400
+#[tokio::test]
+async fn test_timezone_invalid_latitude() {
+    let app = Router::new()
+        .route("/timezone", get(timezone_handler))
+        .with_state(create_minimal_test_state());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!(
+            "http://{}/timezone?apiKey=test&lat=91.0&long=18.0",
+            addr
+        ))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 400);
+
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["code"], "INVALID_LATITUDE");
+    assert!(json["error"].as_str().unwrap().contains("Latitude"));
+}
+
+/// Test NaN and infinite coordinates (which f64 parsing accepts) return 400
+#[tokio::test]
+async fn test_timezone_non_finite_coordinates() {
+    let app = Router::new()
+        .route("/timezone", get(timezone_handler))
+        .with_state(create_minimal_test_state());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    for (query, code) in [
+        ("lat=NaN&long=18.0", "INVALID_LATITUDE"),
+        ("lat=inf&long=18.0", "INVALID_LATITUDE"),
+        ("lat=59.0&long=-infinity", "INVALID_LONGITUDE"),
+    ] {
+        let response = client
+            .get(format!("http://{}/timezone?{}", addr, query))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 400, "{query}");
+        let json: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(json["code"], code, "{query}");
+        assert!(json["error"].as_str().unwrap().contains("finite"));
+    }
+}
+
+/// Test timezone endpoint with invalid longitude returns 400
+#[tokio::test]
+async fn test_timezone_invalid_longitude() {
+    let app = Router::new()
+        .route("/timezone", get(timezone_handler))
+        .with_state(create_minimal_test_state());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!(
+            "http://{}/timezone?apiKey=test&lat=59.0&long=181.0",
+            addr
+        ))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 400);
+
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["code"], "INVALID_LONGITUDE");
+    assert!(json["error"].as_str().unwrap().contains("Longitude"));
+}
+
+/// Test v1/timezone with invalid coordinates returns 400
+#[tokio::test]
+async fn test_v1_timezone_invalid_coords() {
+    let app = Router::new()
+        .route("/v1/timezone", get(timezone_full_handler))
+        .with_state(create_minimal_test_state());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!(
+            "http://{}/v1/timezone?apiKey=test&lat=-95.0&long=18.0",
+            addr
+        ))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 400);
+
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["code"], "INVALID_LATITUDE");
+}
+
+/// Test v1/ipgeo with invalid IP returns 400
+#[tokio::test]
+async fn test_v1_ipgeo_invalid_ip() {
+    let mock = MockGeoIpReader::new();
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/v1/ipgeo?apiKey=test&ip=invalid", addr))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 400);
+
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["code"], "INVALID_IP");
+}
+
+/// Test v1/ipgeo with EU country (check is_eu flag)
+#[tokio::test]
+async fn test_v1_ipgeo_eu_country() {
+    let mock = MockGeoIpReader::new().with_response(
+        "1.2.3.4",
+        Ok(GeoData {
+            latitude: Some(52.52),
+            longitude: Some(13.405),
+            city: Some("Berlin".to_string()),
+            country_name: Some("Germany".to_string()),
+            country_code: Some("DE".to_string()),
+            state_prov: Some("Berlin".to_string()),
+            state_code: Some("BE".to_string()),
+            postal_code: Some("10115".to_string()),
+            geoname_id: Some(2950159),
+            ..Default::default()
+        }),
+    );
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/v1/ipgeo?apiKey=test&ip=1.2.3.4", addr))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    let json: serde_json::Value = response.json().await.unwrap();
+
+    // Germany is in EU
+    assert_eq!(json["location"]["is_eu"], true);
+    assert_eq!(json["location"]["country_code2"], "DE");
+    assert_eq!(json["currency"]["code"], "EUR");
+    assert_eq!(json["currency"]["symbol"], "€");
+}
+
+// ============================================================================
+// Root Endpoint Tests (/ - client IP geolocation)
+// ============================================================================
+
+/// Test root endpoint uses the direct connection IP, which for loopback is
+/// reserved and answered empty without a lookup
+#[tokio::test]
+async fn test_root_endpoint_direct_ip() {
+    // Data the handler must not reach: 127.0.0.1 is a reserved address
+    let mock = MockGeoIpReader::new().with_response(
+        "127.0.0.1",
+        Ok(GeoData {
+            latitude: Some(37.751),
+            longitude: Some(-97.822),
+            city: Some("Test City".to_string()),
+            country_name: Some("United States".to_string()),
+            country_code: Some("US".to_string()),
+            state_prov: None,
+            state_code: None,
+            postal_code: None,
+            geoname_id: None,
+            ..Default::default()
+        }),
+    );
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/", get(root_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/", addr))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["city"], "");
+    assert_eq!(json["country_name"], "");
+    assert_eq!(json["languages"], "");
+}
+
+/// Test root endpoint with X-Forwarded-For header
+#[tokio::test]
+async fn test_root_endpoint_x_forwarded_for() {
+    // The mock should receive the IP from X-Forwarded-For header
+    let mock = MockGeoIpReader::new().with_response(
+        "81.2.69.142",
+        Ok(GeoData {
+            latitude: Some(51.5074),
+            longitude: Some(-0.1278),
+            city: Some("London".to_string()),
+            country_name: Some("United Kingdom".to_string()),
+            country_code: Some("GB".to_string()),
+            state_prov: None,
+            state_code: None,
+            postal_code: None,
+            geoname_id: None,
+            ..Default::default()
+        }),
+    );
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/", get(root_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/", addr))
+        .header("X-Forwarded-For", "81.2.69.142, 10.0.0.1")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["city"], "London");
+    assert_eq!(json["country_name"], "United Kingdom");
+}
+
+/// Test root endpoint with X-Real-IP header
+#[tokio::test]
+async fn test_root_endpoint_x_real_ip() {
+    let mock = MockGeoIpReader::new().with_response(
+        "9.9.9.9",
+        Ok(GeoData {
+            latitude: Some(35.6762),
+            longitude: Some(139.6503),
+            city: Some("Tokyo".to_string()),
+            country_name: Some("Japan".to_string()),
+            country_code: Some("JP".to_string()),
+            state_prov: None,
+            state_code: None,
+            postal_code: None,
+            geoname_id: None,
+            ..Default::default()
+        }),
+    );
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/", get(root_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/", addr))
+        .header("X-Real-IP", "9.9.9.9")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["city"], "Tokyo");
+    assert_eq!(json["country_name"], "Japan");
+}
+
+/// Test root endpoint with CF-Connecting-IP header (Cloudflare)
+#[tokio::test]
+async fn test_root_endpoint_cf_connecting_ip() {
+    let mock = MockGeoIpReader::new().with_response(
+        "1.0.0.1",
+        Ok(GeoData {
+            latitude: Some(52.52),
+            longitude: Some(13.405),
+            city: Some("Berlin".to_string()),
+            country_name: Some("Germany".to_string()),
+            country_code: Some("DE".to_string()),
+            state_prov: None,
+            state_code: None,
+            postal_code: None,
+            geoname_id: None,
+            ..Default::default()
+        }),
+    );
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/", get(root_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/", addr))
+        .header("CF-Connecting-IP", "1.0.0.1")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["city"], "Berlin");
+    assert_eq!(json["country_name"], "Germany");
+}
+
+/// Test root endpoint Cache-Control headers
+#[tokio::test]
+async fn test_root_endpoint_cache_control_headers() {
+    let mock = MockGeoIpReader::new().with_response(
+        "8.8.4.4",
+        Ok(GeoData {
+            latitude: Some(40.0),
+            longitude: Some(-74.0),
+            city: Some("Test".to_string()),
+            country_name: Some("Test Country".to_string()),
+            country_code: Some("TC".to_string()),
+            state_prov: None,
+            state_code: None,
+            postal_code: None,
+            geoname_id: None,
+            ..Default::default()
+        }),
+    );
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/", get(root_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/", addr))
+        .header("X-Forwarded-For", "8.8.4.4")
+        .send()
+        .await
+        .unwrap();
+
+    let cache_control = response.headers().get("cache-control");
+    assert!(cache_control.is_some());
+    assert_eq!(
+        cache_control.unwrap().to_str().unwrap(),
+        "public, max-age=1209600"
+    );
+}
+
+/// Test root endpoint with unknown IP returns empty response
+#[tokio::test]
+async fn test_root_endpoint_unknown_ip() {
+    let mock = MockGeoIpReader::new(); // No responses configured
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/", get(root_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/", addr))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["city"], "");
+    assert_eq!(json["country_name"], "");
+    assert_eq!(json["languages"], "");
+}
+
+/// Test /v1/self/stream sends the client's location as the first SSE event
+#[tokio::test]
+async fn test_self_stream_initial_event() {
+    let mock = MockGeoIpReader::new().with_response(
+        "8.8.8.8",
+        Ok(GeoData {
+            city: Some("Mountain View".to_string()),
+            country_code: Some("US".to_string()),
+            ..Default::default()
+        }),
+    );
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/v1/self/stream", get(self_stream_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let mut response = client
+        .get(format!("http://{}/v1/self/stream", addr))
+        .header("X-Forwarded-For", "8.8.8.8")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/event-stream"
+    );
+
+    // Read until the first complete event; the stream itself never ends
+    let mut received = String::new();
+    while !received.contains("\n\n") {
+        let chunk = tokio::time::timeout(Duration::from_secs(5), response.chunk())
+            .await
+            .expect("initial event within timeout")
+            .unwrap()
+            .expect("stream still open");
+        received.push_str(std::str::from_utf8(&chunk).unwrap());
+    }
+
+    assert!(received.starts_with("event: location\n"));
+    let data = received
+        .lines()
+        .find_map(|line| line.strip_prefix("data: "))
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_str(data).unwrap();
+    assert_eq!(json["ip"], "8.8.8.8");
+    assert_eq!(json["location"]["city"], "Mountain View");
+}
+
+/// Test the unspecified address is answered empty without querying the database
+#[tokio::test]
+async fn test_unspecified_address_skips_lookup() {
+    // Data the handlers must never reach for the zero address
+    let geo_data = GeoData {
+        city: Some("Nowhere".to_string()),
+        country_code: Some("US".to_string()),
+        ..Default::default()
+    };
+    let mock = MockGeoIpReader::new()
+        .with_response("0.0.0.0", Ok(geo_data.clone()))
+        .with_response("::", Ok(geo_data));
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/", get(root_handler))
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/", addr))
+        .header("X-Forwarded-For", "0.0.0.0")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["city"], "");
+    assert_eq!(json["country_name"], "");
+
+    let response = client
+        .get(format!("http://{}/v1/ipgeo?ip=::", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["ip"], "::");
+    assert!(json.get("location").is_none());
+    assert_eq!(json["network"]["ip_type"], "unspecified");
+}
+
+/// Test bogon addresses (CGNAT, documentation) are answered empty without a lookup
+#[tokio::test]
+async fn test_bogon_addresses_skip_lookup() {
+    let geo_data = GeoData {
+        city: Some("Nowhere".to_string()),
+        country_code: Some("US".to_string()),
+        ..Default::default()
+    };
+    let mock = MockGeoIpReader::new()
+        .with_response("100.64.0.1", Ok(geo_data.clone()))
+        .with_response("192.0.2.1", Ok(geo_data));
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/ipgeo", get(ipgeo_handler))
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/ipgeo?ip=100.64.0.1", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["city"], "");
+    assert_eq!(json["country_name"], "");
+
+    let response = client
+        .get(format!("http://{}/v1/ipgeo?ip=192.0.2.1", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert!(json.get("location").is_none());
+    assert_eq!(json["network"]["ip_type"], "reserved");
+}
+
+// ============================================================================
+// LLM/Agent Documentation Endpoint Tests
+// ============================================================================
+
+/// Test OpenAPI specification endpoint returns valid YAML
+#[tokio::test]
+async fn test_openapi_endpoint() {
+    let state = create_minimal_test_state();
+    let app = Router::new()
+        .route("/openapi.yaml", get(openapi_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/openapi.yaml", addr))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    // Check content type
+    let content_type = response.headers().get("content-type");
+    assert!(content_type.is_some());
+    assert!(content_type
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .contains("application/yaml"));
+
+    // Check content contains expected OpenAPI structure
+    let body = response.text().await.unwrap();
+    assert!(body.contains("openapi: 3.1"));
+    assert!(body.contains("IP Geolocation API"));
+    assert!(body.contains("/ipgeo"));
+    assert!(body.contains("/timezone"));
+    assert!(body.contains("/v1/ipgeo"));
+    assert!(body.contains("/v1/timezone"));
+    assert!(body.contains("/health"));
+}
+
+/// Test OpenAPI specification is valid YAML
+#[tokio::test]
+async fn test_openapi_valid_yaml() {
+    let state = create_minimal_test_state();
+    let app = Router::new()
+        .route("/openapi.yaml", get(openapi_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/openapi.yaml", addr))
+        .send()
+        .await
+        .unwrap();
+
+    let body = response.text().await.unwrap();
+
+    // Parse as YAML to validate syntax using yaml-rust2
+    let yaml_docs = yaml_rust2::YamlLoader::load_from_str(&body);
+    assert!(yaml_docs.is_ok(), "OpenAPI spec should be valid YAML");
+
+    let docs = yaml_docs.unwrap();
+    assert!(!docs.is_empty(), "Should have at least one YAML document");
+
+    let yaml = &docs[0];
+    assert_eq!(yaml["openapi"].as_str(), Some("3.1.0"));
+    assert!(!yaml["paths"].is_badvalue());
+    assert!(!yaml["components"]["schemas"].is_badvalue());
+}
+
+/// Test OpenAPI spec is generated from code (not static file)
+#[tokio::test]
+async fn test_openapi_generated_from_code() {
+    // Verify ApiDoc generates valid spec
+    let spec = ApiDoc::openapi();
+    let yaml = spec.to_yaml().unwrap();
+
+    // Should contain all our endpoints
+    assert!(yaml.contains("/ipgeo"));
+    assert!(yaml.contains("/timezone"));
+    assert!(yaml.contains("/v1/ipgeo"));
+    assert!(yaml.contains("/v1/timezone"));
+    assert!(yaml.contains("/v1/flag"));
+    assert!(yaml.contains("/health"));
+
+    // Should contain our schemas
+    assert!(yaml.contains("IpGeoResponse"));
+    assert!(yaml.contains("TimezoneResponse"));
+    assert!(yaml.contains("ApiErrorResponse"));
+}
+
+/// Test LLM documentation endpoint returns valid content
+#[tokio::test]
+async fn test_llms_txt_endpoint() {
+    let app = Router::new().route("/llms.txt", get(llms_txt_handler));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/llms.txt", addr))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    // Check content type
+    let content_type = response.headers().get("content-type");
+    assert!(content_type.is_some());
+    assert!(content_type
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .contains("text/plain"));
+
+    // Check content contains expected documentation
+    let body = response.text().await.unwrap();
+    assert!(body.contains("IP Geolocation API"));
+    assert!(body.contains("/ipgeo"));
+    assert!(body.contains("/timezone"));
+    assert!(body.contains("openapi.yaml"));
+    assert!(body.contains("curl"));
+}
+
+/// Test llms.txt picks the Spanish variant from Accept-Language
+#[tokio::test]
+async fn test_llms_txt_accept_language() {
+    let app = Router::new().route("/llms.txt", get(llms_txt_handler));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/llms.txt", addr))
+        .header("Accept-Language", "es")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers().get("content-language").unwrap(), "es");
+    assert_eq!(response.headers().get("vary").unwrap(), "Accept-Language");
+    let body = response.text().await.unwrap();
+    assert!(body.contains("API de geolocalización por IP"));
+
+    // Unsupported languages fall back to English
+    let response = client
+        .get(format!("http://{}/llms.txt", addr))
+        .header("Accept-Language", "ja")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers().get("content-language").unwrap(), "en");
+    assert!(response
+        .text()
+        .await
+        .unwrap()
+        .contains("IP Geolocation API"));
+}
+
+/// Test LLM documentation contains all required sections
+#[tokio::test]
+async fn test_llms_txt_content_structure() {
+    let app = Router::new().route("/llms.txt", get(llms_txt_handler));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/llms.txt", addr))
+        .send()
+        .await
+        .unwrap();
+
+    let body = response.text().await.unwrap();
+
+    // Check required sections
+    assert!(body.contains("What This API Does"));
+    assert!(body.contains("When to Use This API"));
+    assert!(body.contains("Quick Reference"));
+    assert!(body.contains("Examples"));
+    assert!(body.contains("Error Handling"));
+    assert!(body.contains("API Discovery"));
+}
+
+/// Test sitemap.xml endpoint returns valid XML
+#[tokio::test]
+async fn test_sitemap_endpoint() {
+    let state = create_minimal_test_state();
+    let app = Router::new()
+        .route("/sitemap.xml", get(sitemap_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/sitemap.xml", addr))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    // Check content type
+    let content_type = response.headers().get("content-type");
+    assert!(content_type.is_some());
+    assert!(content_type
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .contains("application/xml"));
+
+    // Check XML content
+    let body = response.text().await.unwrap();
+    assert!(body.contains("<?xml version"));
+    assert!(body.contains("<urlset"));
+    assert!(body.contains("test.example.com")); // Uses base_url from state
+    assert!(body.contains("/ipgeo"));
+    assert!(body.contains("/timezone"));
+    assert!(body.contains("/openapi.yaml"));
+    assert!(body.contains("/llms.txt"));
+}
+
+/// Test that DETECT_BASE_URL builds discovery URLs from the forwarding headers
+#[tokio::test]
+async fn test_detect_base_url_from_forwarded_headers() {
+    let state = AppState {
+        detect_base_url: true,
+        ..create_minimal_test_state()
+    };
+    let app = Router::new()
+        .route("/openapi.yaml", get(openapi_handler))
+        .route("/sitemap.xml", get(sitemap_handler))
+        .route("/robots.txt", get(robots_txt_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let get = |path: &str, headers: &[(&'static str, &'static str)]| {
+        let mut request = client.get(format!("http://{}{}", addr, path));
+        for (name, value) in headers {
+            request = request.header(*name, *value);
+        }
+        async move { request.send().await.unwrap().text().await.unwrap() }
+    };
+    let forwarded = [
+        ("X-Forwarded-Proto", "https"),
+        ("X-Forwarded-Host", "geo.example.org, proxy.internal"),
+    ];
+
+    let spec = get("/openapi.yaml", &forwarded).await;
+    assert!(spec.contains("url: https://geo.example.org\n"), "{spec}");
+    let sitemap = get("/sitemap.xml", &forwarded).await;
+    assert!(sitemap.contains("<loc>https://geo.example.org/ipgeo</loc>"));
+    let robots = get("/robots.txt", &forwarded).await;
+    assert!(robots.contains("Sitemap: https://geo.example.org/sitemap.xml"));
+
+    // Without X-Forwarded-Host the Host header is used, with BASE_URL's scheme
+    let robots = get("/robots.txt", &[("Host", "other.example.net:8443")]).await;
+    assert!(robots.contains("Sitemap: https://other.example.net:8443/sitemap.xml"));
+
+    // Unusable headers fall back to BASE_URL
+    let robots = get(
+        "/robots.txt",
+        &[
+            ("X-Forwarded-Proto", "gopher"),
+            ("X-Forwarded-Host", "a.example"),
+        ],
+    )
+    .await;
+    assert!(robots.contains("Sitemap: https://test.example.com/sitemap.xml"));
+    let robots = get("/robots.txt", &[("X-Forwarded-Host", "evil.example/<x>")]).await;
+    assert!(robots.contains("Sitemap: https://test.example.com/sitemap.xml"));
+}
+
+/// Test .well-known/openapi.yaml endpoint
+#[tokio::test]
+async fn test_wellknown_openapi_endpoint() {
+    let state = create_minimal_test_state();
+    let app = Router::new()
+        .route("/.well-known/openapi.yaml", get(wellknown_openapi_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/.well-known/openapi.yaml", addr))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    // Check content type
+    let content_type = response.headers().get("content-type");
+    assert!(content_type.is_some());
+    assert!(content_type
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .contains("application/yaml"));
+
+    // Check it's the same as /openapi.yaml
+    let body = response.text().await.unwrap();
+    assert!(body.contains("openapi: 3.1"));
+    assert!(body.contains("IP Geolocation API"));
+}
+
+/// Test that HEAD on both OpenAPI paths reports the spec size without a body
+#[tokio::test]
+async fn test_openapi_head_content_length() {
+    let state = create_minimal_test_state();
+    let app = Router::new()
+        .route("/openapi.yaml", get(openapi_handler))
+        .route("/.well-known/openapi.yaml", get(wellknown_openapi_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    for path in ["/openapi.yaml", "/.well-known/openapi.yaml"] {
+        let url = format!("http://{}{}", addr, path);
+        let response = client.head(&url).send().await.unwrap();
+        assert_eq!(response.status(), 200);
+        let content_length: usize = response.headers()["content-length"]
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(content_length > 0);
+        assert!(response.bytes().await.unwrap().is_empty());
+
+        let spec = client.get(&url).send().await.unwrap().text().await.unwrap();
+        assert_eq!(content_length, spec.len());
+    }
+}
+
+/// Test .well-known/ai-plugin.json endpoint
+#[tokio::test]
+async fn test_wellknown_ai_plugin_endpoint() {
+    let state = create_minimal_test_state();
+    let app = Router::new()
+        .route(
+            "/.well-known/ai-plugin.json",
+            get(wellknown_ai_plugin_handler),
+        )
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/.well-known/ai-plugin.json", addr))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    // Check content type
+    let content_type = response.headers().get("content-type");
+    assert!(content_type.is_some());
+    assert!(content_type
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .contains("application/json"));
+
+    // Check JSON structure
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["schema_version"], "v1");
+    assert_eq!(json["name_for_model"], "ip_geolocation");
+    assert!(!json["description_for_model"].as_str().unwrap().is_empty());
+    assert_eq!(json["api"]["type"], "openapi");
+    assert!(json["api"]["url"]
+        .as_str()
+        .unwrap()
+        .contains("openapi.yaml"));
+}
+
+// ============================================================================
+// Protobuf Response Tests
+// ============================================================================
+
+/// Test timezone endpoint with protobuf Accept header
+#[tokio::test]
+async fn test_timezone_protobuf_response() {
+    let app = Router::new()
+        .route("/timezone", get(timezone_handler))
+        .with_state(create_minimal_test_state());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!(
+            "http://{}/timezone?lat=59.329504&long=18.069532",
+            addr
+        ))
+        .header("Accept", "application/x-protobuf")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    // Check content type is protobuf
+    let content_type = response.headers().get("content-type");
+    assert!(content_type.is_some());
+    assert_eq!(
+        content_type.unwrap(),
+        "application/x-protobuf; proto=geolocation.v1"
+    );
+
+    // Decode protobuf response
+    let bytes = response.bytes().await.unwrap();
+    let proto = geolocation::TimezoneResponse::decode(bytes).unwrap();
+    assert_eq!(proto.timezone, "Europe/Stockholm");
+}
+
+/// Test ipgeo endpoint with protobuf Accept header
+#[tokio::test]
+async fn test_ipgeo_protobuf_response() {
+    let mock = MockGeoIpReader::new().with_response(
+        "8.8.8.8",
+        Ok(GeoData {
+            latitude: Some(37.751),
+            longitude: Some(-97.822),
+            city: Some("Mountain View".to_string()),
+            country_name: Some("United States".to_string()),
+            country_code: Some("US".to_string()),
+            state_prov: Some("California".to_string()),
+            state_code: Some("CA".to_string()),
+            postal_code: Some("94043".to_string()),
+            geoname_id: Some(5375480),
+            ..Default::default()
+        }),
+    );
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/ipgeo", get(ipgeo_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/ipgeo?ip=8.8.8.8", addr))
+        .header("Accept", "application/x-protobuf")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    // Check content type is protobuf
+    let content_type = response.headers().get("content-type");
+    assert!(content_type.is_some());
+    assert!(content_type
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .contains("application/x-protobuf"));
+
+    // Decode protobuf response
+    let bytes = response.bytes().await.unwrap();
+    let proto = geolocation::IpGeoResponse::decode(bytes).unwrap();
+    assert_eq!(proto.city, "Mountain View");
+    assert_eq!(proto.country_name, "United States");
+    assert!(proto.latitude.is_some());
+}
+
+/// Test v1/ipgeo endpoint with protobuf Accept header
+#[tokio::test]
+async fn test_v1_ipgeo_protobuf_response() {
+    let mock = MockGeoIpReader::new().with_response(
+        "1.1.1.1",
+        Ok(GeoData {
+            latitude: Some(51.5074),
+            longitude: Some(-0.1278),
+            city: Some("London".to_string()),
+            country_name: Some("United Kingdom".to_string()),
+            country_code: Some("GB".to_string()),
+            state_prov: None,
+            state_code: None,
+            postal_code: None,
+            geoname_id: None,
+            ..Default::default()
+        }),
+    );
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/v1/ipgeo?ip=1.1.1.1", addr))
+        .header("Accept", "application/x-protobuf")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+
+    // Check content type is protobuf
+    let content_type = response.headers().get("content-type");
+    assert!(content_type.is_some());
+    assert!(content_type
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .contains("application/x-protobuf"));
+
+    // Decode protobuf response
+    let bytes = response.bytes().await.unwrap();
+    let proto = geolocation::IpGeoResponseFull::decode(bytes).unwrap();
+    assert_eq!(proto.ip, Some("1.1.1.1".to_string()));
+    assert!(proto.location.is_some());
+    let location = proto.location.unwrap();
+    assert_eq!(location.city, Some("London".to_string()));
+}
+
+/// Test that the full response carries matching string and numeric coordinates
+#[tokio::test]
+async fn test_v1_ipgeo_numeric_coordinates() {
+    let mock = MockGeoIpReader::new().with_response(
+        "1.1.1.1",
+        Ok(GeoData {
+            latitude: Some(51.5074),
+            longitude: Some(-0.1278),
+            ..Default::default()
+        }),
+    );
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let url = format!("http://{}/v1/ipgeo?ip=1.1.1.1", addr);
+
+    let json: serde_json::Value = client.get(&url).send().await.unwrap().json().await.unwrap();
+    let location = &json["location"];
+    assert_eq!(location["latitude"], "51.50740");
+    assert_eq!(location["longitude"], "-0.12780");
+    assert_eq!(location["latitude_f"], 51.5074);
+    assert_eq!(location["longitude_f"], -0.1278);
+
+    let bytes = client
+        .get(&url)
+        .header("Accept", "application/x-protobuf")
+        .send()
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap();
+    let location = geolocation::IpGeoResponseFull::decode(bytes)
+        .unwrap()
+        .location
+        .unwrap();
+    let latitude: f64 = location.latitude.unwrap().parse().unwrap();
+    let longitude: f64 = location.longitude.unwrap().parse().unwrap();
+    assert_eq!(location.latitude_f, Some(51.5074));
+    assert_eq!(location.longitude_f, Some(-0.1278));
+    assert!((latitude - location.latitude_f.unwrap()).abs() < 1e-5);
+    assert!((longitude - location.longitude_f.unwrap()).abs() < 1e-5);
+}
+
+/// Test error response in protobuf format
+#[tokio::test]
+async fn test_error_protobuf_response() {
+    let mock = MockGeoIpReader::new();
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/ipgeo", get(ipgeo_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/ipgeo?ip=invalid-ip", addr))
+        .header("Accept", "application/x-protobuf")
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 400);
+
+    // Check content type is protobuf
+    let content_type = response.headers().get("content-type");
+    assert!(content_type.is_some());
+    assert!(content_type
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .contains("application/x-protobuf"));
+
+    // Decode protobuf error response
+    let bytes = response.bytes().await.unwrap();
+    let proto = geolocation::ApiError::decode(bytes).unwrap();
+    assert_eq!(proto.code, "INVALID_IP");
+    assert!(proto.error.contains("Invalid IP address"));
+    assert_eq!(proto.field.as_deref(), Some("ip"));
+}
+
+/// Validation errors name the offending parameter in JSON and protobuf
+#[tokio::test]
+async fn test_error_field_name() {
+    let app = Router::new()
+        .route("/v1/timezone", get(timezone_full_handler))
+        .with_state(create_minimal_test_state());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/v1/timezone?lat=95&long=0", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["code"], "INVALID_LATITUDE");
+    assert_eq!(json["field"], "lat");
+
+    let response = client
+        .get(format!("http://{}/v1/timezone?lat=0&long=200", addr))
+        .header("Accept", "application/x-protobuf")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let proto = geolocation::ApiError::decode(response.bytes().await.unwrap()).unwrap();
+    assert_eq!(proto.code, "INVALID_LONGITUDE");
+    assert_eq!(proto.field.as_deref(), Some("long"));
+}
+
+/// The shared CORS policy allows any origin to read the API from a browser.
+/// Guards the header a static browser app depends on to read responses.
+#[tokio::test]
+async fn test_cors_allows_any_origin() {
+    let app = Router::new()
+        .route("/health", get(health_handler))
+        .layer(ipgeolocation::cors_layer());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+
+    // A simple cross-origin GET must carry Access-Control-Allow-Origin so the
+    // browser lets the calling script read the body.
+    let response = client
+        .get(format!("http://{}/health", addr))
+        .header("Origin", "https://example.com")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response
+            .headers()
+            .get("access-control-allow-origin")
+            .unwrap()
+            .to_str()
+            .unwrap(),
+        "*"
+    );
+
+    // A CORS preflight (OPTIONS) is answered by the layer and advertises GET.
+    let preflight = client
+        .request(reqwest::Method::OPTIONS, format!("http://{}/health", addr))
+        .header("Origin", "https://example.com")
+        .header("Access-Control-Request-Method", "GET")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        preflight
+            .headers()
+            .get("access-control-allow-origin")
+            .unwrap()
+            .to_str()
+            .unwrap(),
+        "*"
+    );
+    let allow_methods = preflight
+        .headers()
+        .get("access-control-allow-methods")
+        .unwrap()
+        .to_str()
+        .unwrap();
+    assert!(allow_methods.contains("GET"));
+}
+
+/// Trailing slashes are trimmed before routing, so `/timezone/` is `/timezone`
+#[tokio::test]
+async fn test_trailing_slash_is_normalized() {
+    use tower::Layer;
+
+    let app = Router::new()
+        .route("/timezone", get(timezone_handler))
+        .route("/health", get(health_handler))
+        .with_state(create_minimal_test_state());
+    let app = ipgeolocation::trailing_slash_layer().layer(app);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            axum::ServiceExt::<axum::extract::Request>::into_make_service(app),
+        )
+        .await
+        .unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!(
+            "http://{}/timezone/?lat=59.329504&long=18.069532",
+            addr
+        ))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["timezone"], "Europe/Stockholm");
+
+    let response = client
+        .get(format!("http://{}/health//", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+}
+
+/// Flushing the cache via the admin endpoint forces the next request to re-run the lookup
+#[tokio::test]
+async fn test_admin_cache_flush_forces_relookup() {
+    let mock = MockGeoIpReader::new().with_response(
+        "1.1.1.1",
+        Ok(GeoData {
+            latitude: Some(51.5074),
+            longitude: Some(-0.1278),
+            city: Some("London".to_string()),
+            country_name: Some("United Kingdom".to_string()),
+            country_code: Some("GB".to_string()),
+            state_prov: None,
+            state_code: None,
+            postal_code: None,
+            geoname_id: None,
+            ..Default::default()
+        }),
+    );
+
+    let mut state = create_test_state(mock);
+    state.admin_token = Some("secret".to_string());
+
+    // Seed a stale entry so a cached response is distinguishable from a fresh lookup
+    state.cache.insert(
+        "1.1.1.1".to_string(),
+        ipgeolocation::models::IpGeoResponse {
+            city: Some("Stale".to_string()),
+            ..Default::default()
+        },
+    );
+
+    let app = Router::new()
+        .route("/ipgeo", get(ipgeo_handler))
+        .route("/admin/cache/flush", post(admin_cache_flush_handler))
+        .route("/admin/cache/{ip}", delete(admin_cache_invalidate_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let url = format!("http://{}/ipgeo?ip=1.1.1.1", addr);
+
+    let json: serde_json::Value = client.get(&url).send().await.unwrap().json().await.unwrap();
+    assert_eq!(json["city"], "Stale");
+
+    // Without the token the flush is rejected
+    let response = client
+        .post(format!("http://{}/admin/cache/flush", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 401);
+
+    let response = client
+        .post(format!("http://{}/admin/cache/flush", addr))
+        .bearer_auth("secret")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["evicted"], 1);
+
+    let json: serde_json::Value = client.get(&url).send().await.unwrap().json().await.unwrap();
+    assert_eq!(json["city"], "London");
+
+    // Single-entry invalidation reports whether the IP was cached
+    let response = client
+        .delete(format!("http://{}/admin/cache/1.1.1.1", addr))
+        .bearer_auth("secret")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["invalidated"], true);
+}
+
+/// Without a database (GEOIP_DB_OPTIONAL) geo endpoints answer 503 until a reload succeeds
+#[tokio::test]
+async fn test_optional_database_until_reload() {
+    use ipgeolocation::geoip::{DatabasePaths, NullGeoIpReader, ReloadableGeoIpReader};
+
+    let reloader = Arc::new(ReloadableGeoIpReader::new(
+        Arc::new(NullGeoIpReader),
+        DatabasePaths::default(),
+        Box::new(|_| {
+            Ok(Arc::new(MockGeoIpReader::new().with_response(
+                "8.8.8.8",
+                Ok(GeoData {
+                    city: Some("Mountain View".to_string()),
+                    ..Default::default()
+                }),
+            )))
+        }),
+    ));
+    let state = AppState {
+        admin_token: Some("secret".to_string()),
+        reloader: Some(reloader.clone()),
+        ..AppState::new(
+            reloader,
+            Arc::new(GeoCache::new(CacheConfig::default())),
+            "https://test.example.com".to_string(),
+        )
+    };
+    let app = Router::new()
+        .route("/ipgeo", get(ipgeo_handler))
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .route("/health", get(health_handler))
+        .route("/openapi.yaml", get(openapi_handler))
+        .route("/admin/reload", post(admin_reload_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+
+    for path in ["ipgeo", "v1/ipgeo"] {
+        let response = client
+            .get(format!("http://{}/{}?ip=8.8.8.8", addr, path))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 503);
+        assert_eq!(response.headers().get("cache-control").unwrap(), "no-store");
+        let json: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(json["code"], "DATABASE_NOT_LOADED");
+    }
+
+    // Non-geo endpoints keep working
+    for path in ["health", "openapi.yaml"] {
+        let response = client
+            .get(format!("http://{}/{}", addr, path))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 200);
+    }
+
+    let response = client
+        .post(format!("http://{}/admin/reload", addr))
+        .bearer_auth("secret")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["reloaded"], true);
+
+    let json: serde_json::Value = client
+        .get(format!("http://{}/ipgeo?ip=8.8.8.8", addr))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(json["city"], "Mountain View");
+}
+
+/// The admin raw endpoint returns the undecoded database record
+#[tokio::test]
+async fn test_admin_raw_record() {
+    let mock = MockGeoIpReader::new().with_raw_record(
+        "81.2.69.142",
+        serde_json::json!({
+            "country": { "iso_code": "GB", "is_in_european_union": false },
+            "location": { "time_zone": "Europe/London", "accuracy_radius": 10 },
+            "city": { "names": { "en": "London" } }
+        }),
+    );
+    let mut state = create_test_state(mock);
+    state.admin_token = Some("secret".to_string());
+
+    let app = Router::new()
+        .route("/admin/raw", get(admin_raw_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+
+    // Requires the admin token
+    let response = client
+        .get(format!("http://{}/admin/raw?ip=81.2.69.142", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 401);
+
+    let response = client
+        .get(format!("http://{}/admin/raw?ip=81.2.69.142", addr))
+        .bearer_auth("secret")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["ip"], "81.2.69.142");
+    assert_eq!(json["record"]["country"]["is_in_european_union"], false);
+    assert_eq!(json["record"]["location"]["time_zone"], "Europe/London");
+    assert_eq!(json["record"]["city"]["names"]["en"], "London");
+
+    // IPs missing from the database have a null record
+    let json: serde_json::Value = client
+        .get(format!("http://{}/admin/raw?ip=9.9.9.9", addr))
+        .bearer_auth("secret")
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(json["record"].is_null());
+}
+
+/// The admin all endpoint combines every view of an IP in one object
+#[tokio::test]
+async fn test_admin_all_dump() {
+    let mock = MockGeoIpReader::new()
+        .with_response(
+            "81.2.69.142",
+            Ok(GeoData {
+                city: Some("London".to_string()),
+                country_code: Some("GB".to_string()),
+                latitude: Some(51.5142),
+                longitude: Some(-0.0931),
+                time_zone: Some("Europe/London".to_string()),
+                ..Default::default()
+            }),
+        )
+        .with_raw_record(
+            "81.2.69.142",
+            serde_json::json!({ "city": { "names": { "en": "London" } } }),
+        );
+    let mut state = create_test_state(mock);
+    state.admin_token = Some("secret".to_string());
+
+    let app = Router::new()
+        .route("/admin/all", get(admin_all_handler))
+        .route("/ipgeo", get(ipgeo_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let url = format!("http://{}/admin/all?ip=81.2.69.142", addr);
+
+    let response = client.get(&url).send().await.unwrap();
+    assert_eq!(response.status(), 401);
+
+    // Warm the simple cache only
+    client
+        .get(format!("http://{}/ipgeo?ip=81.2.69.142", addr))
+        .send()
+        .await
+        .unwrap();
+
+    let response = client.get(&url).bearer_auth("secret").send().await.unwrap();
+    assert_eq!(response.status(), 200);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["ip"], "81.2.69.142");
+    assert_eq!(json["simple"]["city"], "London");
+    assert_eq!(json["full"]["location"]["city"], "London");
+    assert_eq!(json["record"]["city"]["names"]["en"], "London");
+    assert_eq!(json["cache"]["simple"], true);
+    assert_eq!(json["cache"]["full"], false);
+    assert_eq!(json["timezone"]["resolved"]["name"], "Europe/London");
+    assert_eq!(json["timezone"]["coordinates"], "Europe/London");
+    assert_eq!(json["timezone"]["database"], "Europe/London");
+}
+
+/// Requests beyond MAX_CONCURRENT_REQUESTS are shed with 503 OVERLOADED
+#[tokio::test]
+async fn test_concurrency_limit_sheds_excess_requests() {
+    use ipgeolocation::overload::{concurrency_limit, shed_overload};
+
+    // A deliberately slow handler keeps the single permit busy
+    let app = Router::new()
+        .route(
+            "/slow",
+            get(|| async {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+                "done"
+            }),
+        )
+        .layer(axum::middleware::from_fn_with_state(
+            concurrency_limit(1),
+            shed_overload,
+        ));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let url = format!("http://{}/slow", addr);
+
+    let first = tokio::spawn(client.get(&url).send());
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let shed = client.get(&url).send().await.unwrap();
+    assert_eq!(shed.status(), 503);
+    assert_eq!(shed.headers().get("retry-after").unwrap(), "1");
+    let json: serde_json::Value = shed.json().await.unwrap();
+    assert_eq!(json["code"], "OVERLOADED");
+
+    // The in-flight request still completes, and its permit is released
+    assert_eq!(first.await.unwrap().unwrap().status(), 200);
+    assert_eq!(client.get(&url).send().await.unwrap().status(), 200);
+}
+
+/// RESPONSE_SIGNING_KEY adds an HMAC-SHA256 X-Signature over the exact body
+#[tokio::test]
+async fn test_response_signing() {
+    use ipgeolocation::signing::{sign_response, signing_key};
+
+    let app = Router::new()
+        .route("/signed", get(|| async { "{\"hello\":\"world\"}" }))
+        .layer(axum::middleware::from_fn_with_state(
+            signing_key("secret"),
+            sign_response,
+        ));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let response = reqwest::get(format!("http://{}/signed", addr))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    // echo -n '{"hello":"world"}' | openssl dgst -sha256 -hmac secret
+    let signature = response.headers().get("x-signature").unwrap().clone();
+    assert_eq!(
+        signature,
+        "2677ad3e7c090b2fa2c0fb13020d66d5420879b8316eb356a2d60fb9073bc778"
+    );
+    assert_eq!(response.text().await.unwrap(), "{\"hello\":\"world\"}");
+}
+
+/// Files under a configured EXTRA_STATIC_MOUNTS directory are served
+#[tokio::test]
+async fn test_extra_static_mount_serves_files() {
+    use ipgeolocation::static_mounts::{parse_static_mounts, with_static_mounts};
+
+    let dir = std::env::temp_dir().join(format!("ipgeo-static-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("hello.txt"), "hello from an extra mount").unwrap();
+
+    let mounts = parse_static_mounts(&format!("/assets={}", dir.display())).unwrap();
+    let app = with_static_mounts(Router::new(), &mounts);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/assets/hello.txt", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.text().await.unwrap(), "hello from an extra mount");
+
+    let response = client
+        .get(format!("http://{}/assets/missing.txt", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 404);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// A `.gz` sibling of a static file is served to clients accepting gzip
+#[tokio::test]
+async fn test_static_precompressed_flag() {
+    use ipgeolocation::static_mounts::serve_static_dir;
+
+    let dir = std::env::temp_dir().join(format!("ipgeo-flags-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("us.svg"), "<svg>plain</svg>").unwrap();
+    std::fs::write(dir.join("us.svg.gz"), b"\x1f\x8b pretend gzip").unwrap();
+    std::fs::write(dir.join("se.svg"), "<svg>no sibling</svg>").unwrap();
+
+    let app: Router = Router::new().nest_service("/static/flags", serve_static_dir(&dir));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let get = |flag: &str, accept_encoding: &str| {
+        client
+            .get(format!("http://{}/static/flags/{}", addr, flag))
+            .header("Accept-Encoding", accept_encoding)
+            .send()
+    };
+
+    let response = get("us.svg", "gzip, deflate").await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-encoding"], "gzip");
+    assert_eq!(response.headers()["content-type"], "image/svg+xml");
+    assert_eq!(
+        response.bytes().await.unwrap().as_ref(),
+        b"\x1f\x8b pretend gzip"
+    );
+
+    // Clients not accepting gzip, and files without a sibling, get the plain file
+    let response = get("us.svg", "identity").await.unwrap();
+    assert!(response.headers().get("content-encoding").is_none());
+    assert_eq!(response.text().await.unwrap(), "<svg>plain</svg>");
+    let response = get("se.svg", "gzip").await.unwrap();
+    assert!(response.headers().get("content-encoding").is_none());
+    assert_eq!(response.text().await.unwrap(), "<svg>no sibling</svg>");
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// Version endpoint reports the crate version and responses carry a Server header
+#[tokio::test]
+async fn test_version_endpoint() {
+    let app = Router::new()
+        .route("/version", get(version_handler))
+        .layer(ipgeolocation::server_header_layer());
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/version", addr))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers().get("server").unwrap(),
+        &format!("ipgeolocation/{}", env!("CARGO_PKG_VERSION"))
+    );
+
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+    assert!(json["build_time"].is_string());
+}
+
+/// Database failures other than "not found" surface as 503 DATABASE_ERROR
+#[tokio::test]
+async fn test_ipgeo_database_error_returns_503() {
+    let mock = MockGeoIpReader::new().with_response(
+        "8.8.8.8",
+        Err(ipgeolocation::geoip::GeoIpError::DatabaseOpen(
+            maxminddb::MaxMindDbError::invalid_database("corrupt search tree"),
+        )),
+    );
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/ipgeo", get(ipgeo_handler))
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    for path in ["ipgeo", "v1/ipgeo"] {
+        let response = client
+            .get(format!("http://{}/{}?ip=8.8.8.8", addr, path))
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), 503);
+        assert_eq!(response.headers().get("cache-control").unwrap(), "no-store");
+        let json: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(json["code"], "DATABASE_ERROR");
+    }
+
+    // Addresses simply missing from the database are still an empty 200
+    let response = client
+        .get(format!("http://{}/ipgeo?ip=1.1.1.1", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+}
+
+/// Database health endpoint reports metadata and the database age
+#[tokio::test]
+async fn test_health_database_endpoint() {
+    let ten_days_ago = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        - 10 * 86_400;
+    let mock = MockGeoIpReader::new().with_metadata(ipgeolocation::geoip::DatabaseMetadata {
+        database_type: "GeoLite2-City".to_string(),
+        build_epoch: ten_days_ago,
+        node_count: 1234,
+        ip_version: 6,
+    });
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/health/database", get(health_database_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/health/database", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["database_type"], "GeoLite2-City");
+    assert_eq!(json["build_epoch"], ten_days_ago);
+    assert_eq!(json["node_count"], 1234);
+    assert_eq!(json["ip_version"], 6);
+    assert_eq!(json["age_days"], 10);
+    assert_eq!(json["stale"], false);
+}
+
+/// Database health flags databases older than MAX_DB_AGE_DAYS as stale
+#[tokio::test]
+async fn test_health_database_stale() {
+    let forty_days_ago = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+        - 40 * 86_400;
+    let mock = MockGeoIpReader::new().with_metadata(ipgeolocation::geoip::DatabaseMetadata {
+        database_type: "GeoLite2-City".to_string(),
+        build_epoch: forty_days_ago,
+        node_count: 1234,
+        ip_version: 6,
+    });
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/health/database", get(health_database_handler))
+        .with_state(state.clone());
+    let lenient_app = Router::new()
+        .route("/health/database", get(health_database_handler))
+        .with_state(AppState {
+            max_db_age_days: 60,
+            ..state
+        });
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let lenient_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let lenient_addr = lenient_listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+    tokio::spawn(async move {
+        axum::serve(lenient_listener, lenient_app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let json: serde_json::Value = client
+        .get(format!("http://{}/health/database", addr))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(json["age_days"], 40);
+    assert_eq!(json["stale"], true);
+
+    // A higher MAX_DB_AGE_DAYS accepts the same database
+    let json: serde_json::Value = client
+        .get(format!("http://{}/health/database", lenient_addr))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(json["stale"], false);
+}
+
+/// /metrics exposes geoip_up and a geoip_build_info gauge labelled with version and DB metadata
+#[tokio::test]
+async fn test_metrics_build_info() {
+    let mock = MockGeoIpReader::new().with_metadata(ipgeolocation::geoip::DatabaseMetadata {
+        database_type: "GeoLite2-City".to_string(),
+        build_epoch: 1_700_000_000,
+        node_count: 1234,
+        ip_version: 6,
+    });
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/metrics", get(metrics_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let response = reqwest::get(format!("http://{}/metrics", addr))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert!(response
+        .headers()
+        .get("content-type")
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .starts_with("text/plain"));
+
+    let body = response.text().await.unwrap();
+    assert!(body.lines().any(|line| line == "geoip_up 1"));
+    let expected = format!(
+        "geoip_build_info{{version=\"{}\",db_type=\"GeoLite2-City\",db_build_epoch=\"1700000000\"}} 1",
+        env!("CARGO_PKG_VERSION")
+    );
+    assert!(body.lines().any(|line| line == expected), "{}", body);
+}
+
+/// Connection type from the optional database appears under network, and is omitted otherwise
+#[tokio::test]
+async fn test_v1_ipgeo_connection_type() {
+    let mock = MockGeoIpReader::new()
+        .with_response(
+            "8.8.8.8",
+            Ok(GeoData {
+                country_code: Some("US".to_string()),
+                connection_type: Some("Cellular".to_string()),
+                ..Default::default()
+            }),
+        )
+        .with_response(
+            "1.1.1.1",
+            Ok(GeoData {
+                country_code: Some("AU".to_string()),
+                ..Default::default()
+            }),
+        );
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let json: serde_json::Value = client
+        .get(format!("http://{}/v1/ipgeo?ip=8.8.8.8", addr))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(json["network"]["connection_type"], "Cellular");
+
+    let json: serde_json::Value = client
+        .get(format!("http://{}/v1/ipgeo?ip=1.1.1.1", addr))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(json.get("network").is_none());
+
+    // Protobuf carries the same field
+    let bytes = client
+        .get(format!("http://{}/v1/ipgeo?ip=8.8.8.8", addr))
+        .header("Accept", "application/x-protobuf")
+        .send()
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap();
+    let proto = geolocation::IpGeoResponseFull::decode(bytes).unwrap();
+    assert_eq!(
+        proto.network.unwrap().connection_type.as_deref(),
+        Some("Cellular")
+    );
+}
+
+/// US records carry their DMA code as location.metro_code, omitted elsewhere
+#[tokio::test]
+async fn test_v1_ipgeo_metro_code() {
+    let mock = MockGeoIpReader::new()
+        .with_response(
+            "8.8.8.8",
+            Ok(GeoData {
+                country_code: Some("US".to_string()),
+                metro_code: Some(807),
+                ..Default::default()
+            }),
+        )
+        .with_response(
+            "1.1.1.1",
+            Ok(GeoData {
+                country_code: Some("AU".to_string()),
+                ..Default::default()
+            }),
+        );
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let json: serde_json::Value = client
+        .get(format!("http://{}/v1/ipgeo?ip=8.8.8.8", addr))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(json["location"]["metro_code"], 807);
+
+    let json: serde_json::Value = client
+        .get(format!("http://{}/v1/ipgeo?ip=1.1.1.1", addr))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(json["location"].get("metro_code").is_none());
+}
+
+/// The matched database network appears under network.network
+#[tokio::test]
+async fn test_v1_ipgeo_network_prefix() {
+    let mock = MockGeoIpReader::new().with_response(
+        "8.8.8.8",
+        Ok(GeoData {
+            country_code: Some("US".to_string()),
+            network: Some("8.8.8.0/24".to_string()),
+            ..Default::default()
+        }),
+    );
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let json: serde_json::Value = client
+        .get(format!("http://{}/v1/ipgeo?ip=8.8.8.8", addr))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(json["network"]["network"], "8.8.8.0/24");
+    assert!(json["network"].get("connection_type").is_none());
+
+    let bytes = client
+        .get(format!("http://{}/v1/ipgeo?ip=8.8.8.8", addr))
+        .header("Accept", "application/x-protobuf")
+        .send()
+        .await
+        .unwrap()
+        .bytes()
+        .await
+        .unwrap();
+    let proto = geolocation::IpGeoResponseFull::decode(bytes).unwrap();
+    assert_eq!(
+        proto.network.unwrap().network.as_deref(),
+        Some("8.8.8.0/24")
+    );
+}
+
+/// Test explain=true adds a _debug object only when ENABLE_EXPLAIN is set
+#[tokio::test]
+async fn test_v1_ipgeo_explain() {
+    let mock = MockGeoIpReader::new()
+        .with_response(
+            "8.8.8.8",
+            Ok(GeoData {
+                country_code: Some("US".to_string()),
+                state_prov: Some("California".to_string()),
+                network: Some("8.8.8.0/24".to_string()),
+                ..Default::default()
+            }),
+        )
+        .with_metadata(ipgeolocation::geoip::DatabaseMetadata {
+            database_type: "GeoLite2-City".to_string(),
+            ..Default::default()
+        });
+
+    let enabled = AppState {
+        enable_explain: true,
+        ..create_test_state(mock)
+    };
+    let disabled = create_test_state(MockGeoIpReader::new());
+    let app = Router::new()
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .with_state(enabled)
+        .nest(
+            "/disabled",
+            Router::new()
+                .route("/v1/ipgeo", get(ipgeo_full_handler))
+                .with_state(disabled),
+        );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let get_json = |path: &str| {
+        let request = client.get(format!("http://{}{}", addr, path));
+        async move {
+            request
+                .send()
+                .await
+                .unwrap()
+                .json::<serde_json::Value>()
+                .await
+                .unwrap()
+        }
+    };
+
+    let json = get_json("/v1/ipgeo?ip=8.8.8.8&explain=true").await;
+    assert_eq!(
+        json["_debug"],
+        serde_json::json!({
+            "network": "8.8.8.0/24",
+            "database_type": "GeoLite2-City",
+            "cached": false,
+            "subdivision_index": 0
+        })
+    );
+    assert_eq!(json["location"]["country_code2"], "US");
+
+    // Not found: no network or subdivision
+    let json = get_json("/v1/ipgeo?ip=1.1.1.1&explain=true").await;
+    assert!(json["_debug"]["network"].is_null());
+    assert!(json["_debug"]["subdivision_index"].is_null());
+
+    // Not requested
+    let json = get_json("/v1/ipgeo?ip=8.8.8.8").await;
+    assert!(json.get("_debug").is_none());
+
+    // Requested but not enabled on the server
+    let json = get_json("/disabled/v1/ipgeo?ip=8.8.8.8&explain=true").await;
+    assert!(json.get("_debug").is_none());
+}
+
+/// Test empty_as_204 answers valid IPs without data with 204 No Content
+#[tokio::test]
+async fn test_ipgeo_empty_as_204() {
+    let mock = MockGeoIpReader::new().with_response(
+        "8.8.8.8",
+        Ok(GeoData {
+            city: Some("Mountain View".to_string()),
+            country_name: Some("United States".to_string()),
+            ..Default::default()
+        }),
+    );
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/ipgeo", get(ipgeo_handler))
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let get = |path: &str| client.get(format!("http://{}{}", addr, path)).send();
+
+    // 9.9.9.9 is not in the mock database; ask twice to cover the cache
+    for x_cache in ["MISS", "HIT"] {
+        let response = get("/ipgeo?ip=9.9.9.9&empty_as_204=true").await.unwrap();
+        assert_eq!(response.status(), 204);
+        assert_eq!(response.headers()["x-cache"], x_cache);
+        assert!(response.text().await.unwrap().is_empty());
+    }
+    let response = get("/v1/ipgeo?ip=9.9.9.9&empty_as_204=true").await.unwrap();
+    assert_eq!(response.status(), 204);
+    assert_eq!(response.headers()["x-cache"], "MISS");
+
+    // Default stays 200 with empty strings
+    let response = get("/ipgeo?ip=9.9.9.9").await.unwrap();
+    assert_eq!(response.status(), 200);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["city"], "");
+
+    // Known IPs are unaffected
+    let response = get("/ipgeo?ip=8.8.8.8&empty_as_204=true").await.unwrap();
+    assert_eq!(response.status(), 200);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["city"], "Mountain View");
+
+    // Invalid IPs are still errors
+    let response = get("/ipgeo?ip=not-an-ip&empty_as_204=true").await.unwrap();
+    assert_eq!(response.status(), 400);
+}
+
+/// Test country-only results fall back to the country centroid
+#[tokio::test]
+async fn test_v1_ipgeo_country_centroid() {
+    let mock = MockGeoIpReader::new().with_response(
+        "8.8.8.8",
+        Ok(GeoData {
+            country_code: Some("DE".to_string()),
+            country_name: Some("Germany".to_string()),
+            ..Default::default()
+        }),
+    );
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let json: serde_json::Value = client
+        .get(format!("http://{}/v1/ipgeo?ip=8.8.8.8", addr))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(json["location"]["coordinate_source"], "country_centroid");
+    assert_eq!(json["location"]["latitude"], "51.16569");
+    assert_eq!(json["location"]["longitude_f"], 10.451526);
+
+    let json: serde_json::Value = client
+        .get(format!(
+            "http://{}/v1/ipgeo?ip=8.8.8.8&no_centroid=true",
+            addr
+        ))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(json["location"].get("coordinate_source").is_none());
+    assert!(json["location"].get("latitude").is_none());
+    assert!(json["location"].get("longitude_f").is_none());
+    assert_eq!(json["location"]["country_code2"], "DE");
+}
+
+/// Test airport=true adds the nearest airport to resolved coordinates
+#[tokio::test]
+async fn test_v1_ipgeo_nearest_airport() {
+    let mock = MockGeoIpReader::new()
+        .with_response(
+            "8.8.8.8",
+            Ok(GeoData {
+                city: Some("Stockholm".to_string()),
+                country_code: Some("SE".to_string()),
+                latitude: Some(59.3293),
+                longitude: Some(18.0686),
+                ..Default::default()
+            }),
+        )
+        .with_response(
+            "1.1.1.1",
+            Ok(GeoData {
+                country_code: Some("DE".to_string()),
+                ..Default::default()
+            }),
+        );
+
+    let state = create_test_state(mock);
+    let app = Router::new()
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let json: serde_json::Value = client
+        .get(format!("http://{}/v1/ipgeo?ip=8.8.8.8&airport=true", addr))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let airport = &json["location"]["nearest_airport"];
+    assert_eq!(airport["iata"], "ARN");
+    let distance = airport["distance_km"].as_f64().unwrap();
+    assert!(distance > 30.0 && distance < 45.0, "{distance}");
+
+    // Only on request
+    let json: serde_json::Value = client
+        .get(format!("http://{}/v1/ipgeo?ip=8.8.8.8", addr))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(json["location"].get("nearest_airport").is_none());
+
+    // Country centroids are too coarse to pick an airport from
+    let json: serde_json::Value = client
+        .get(format!("http://{}/v1/ipgeo?ip=1.1.1.1&airport=true", addr))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(json["location"]["coordinate_source"], "country_centroid");
+    assert!(json["location"].get("nearest_airport").is_none());
+}
+
+/// Test /v1/countries lists the embedded dataset
+#[tokio::test]
+async fn test_v1_countries() {
+    let app = Router::new().route("/v1/countries", get(countries_handler));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/v1/countries", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(
+        response.headers().get("cache-control").unwrap(),
+        "public, max-age=1209600"
+    );
+    let countries: Vec<serde_json::Value> = response.json().await.unwrap();
+    assert!(!countries.is_empty());
+    let germany = countries.iter().find(|c| c["code"] == "DE").unwrap();
+    assert_eq!(germany["name"], "Germany");
+    assert_eq!(germany["is_eu"], true);
+    assert_eq!(germany["currency"], "EUR");
+    assert!(countries.iter().any(|c| c["is_eu"] == false));
+
+    let eu: Vec<serde_json::Value> = client
+        .get(format!("http://{}/v1/countries?eu_only=true", addr))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(eu.iter().any(|c| c["code"] == "DE"));
+    assert!(eu.iter().all(|c| c["is_eu"] == true));
+    assert!(eu.len() < countries.len());
+}
+
+/// Test /v1/timezones lists the IANA zones
+#[tokio::test]
+async fn test_v1_timezones() {
+    let app = Router::new().route("/v1/timezones", get(timezones_handler));
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let zones: Vec<serde_json::Value> = client
+        .get(format!("http://{}/v1/timezones", addr))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert!(zones.len() > 400, "only {} zones", zones.len());
+    let stockholm = zones
+        .iter()
+        .find(|z| z["name"] == "Europe/Stockholm")
+        .unwrap();
+    assert!(stockholm.get("offset").is_none());
+
+    let response = client
+        .get(format!("http://{}/v1/timezones?with_offsets=true", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers().get("cache-control").unwrap(), "no-store");
+    let zones: Vec<serde_json::Value> = response.json().await.unwrap();
+    let kolkata = zones.iter().find(|z| z["name"] == "Asia/Kolkata").unwrap();
+    assert_eq!(kolkata["offset"], 5.5);
+}
+
+/// Test /health/ready reports every subsystem
+#[tokio::test]
+async fn test_health_ready() {
+    let ready = create_test_state(MockGeoIpReader::new());
+    let not_ready = AppState {
+        http3_running: Some(Arc::new(std::sync::atomic::AtomicBool::new(false))),
+        ..AppState::new(
+            Arc::new(ipgeolocation::geoip::NullGeoIpReader),
+            Arc::new(GeoCache::new(CacheConfig::default())),
+            "https://test.example.com".to_string(),
+        )
+    };
+    let app = Router::new()
+        .route("/health/ready", get(health_ready_handler))
+        .with_state(ready)
+        .nest(
+            "/starting",
+            Router::new()
+                .route("/health/ready", get(health_ready_handler))
+                .with_state(not_ready),
+        );
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/health/ready", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({"database": "ok", "cache": "ok", "http3": "disabled"})
+    );
+
+    let response = client
+        .get(format!("http://{}/starting/health/ready", addr))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 503);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({"database": "not_loaded", "cache": "ok", "http3": "down"})
+    );
+}
+
+/// Test browsers get an HTML page on the root endpoint
+#[tokio::test]
+async fn test_root_endpoint_html() {
+    let mock = MockGeoIpReader::new().with_response(
+        "8.8.8.8",
+        Ok(GeoData {
+            latitude: Some(37.751),
+            longitude: Some(-97.822),
+            city: Some("Mountain View".to_string()),
+            country_name: Some("United States".to_string()),
+            country_code: Some("US".to_string()),
+            ..Default::default()
+        }),
+    );
+
+    let state = AppState {
+        root_html: true,
+        ..create_test_state(mock)
+    };
+    let app = Router::new()
+        .route("/", get(root_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let response = client
+        .get(format!("http://{}/", addr))
+        .header("X-Forwarded-For", "8.8.8.8")
+        .header(
+            "Accept",
+            "text/html,application/xhtml+xml,application/xml;q=0.9,*/*;q=0.8",
+        )
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(
+        response.headers().get("content-type").unwrap(),
+        "text/html; charset=utf-8"
+    );
+    assert_eq!(response.headers().get("vary").unwrap(), "Accept");
+    let page = response.text().await.unwrap();
+    assert!(page.contains("<code>8.8.8.8</code>"));
+    assert!(page.contains("Mountain View"));
+    assert!(page.contains("United States"));
+
+    // API clients still get JSON, kept apart from the page in shared caches
+    let response = client
+        .get(format!("http://{}/", addr))
+        .header("X-Forwarded-For", "8.8.8.8")
+        .header("Accept", "*/*")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.headers().get("vary").unwrap(), "Accept");
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["city"], "Mountain View");
+}
+
+/// A reload with new paths swaps every database or none of them
+#[tokio::test]
+async fn test_admin_reload_new_paths_is_atomic() {
+    use ipgeolocation::geoip::{DatabasePaths, ReloadableGeoIpReader};
+
+    // Each "database" answers with the path it was opened from
+    let open = |paths: &DatabasePaths| -> Result<ipgeolocation::geoip::SharedGeoIpReader, String> {
+        for path in [Some(&paths.city), paths.connection_type.as_ref()]
+            .into_iter()
+            .flatten()
+        {
+            if path.contains("missing") {
+                return Err(format!("{path}: not found"));
+            }
+        }
+        Ok(Arc::new(MockGeoIpReader::new().with_response(
+            "8.8.8.8",
+            Ok(GeoData {
+                city: Some(paths.city.clone()),
+                connection_type: paths.connection_type.clone(),
+                ..Default::default()
+            }),
+        )))
+    };
+    let paths = DatabasePaths {
+        city: "city-1.mmdb".to_string(),
+        connection_type: Some("conn-1.mmdb".to_string()),
+        fallback: None,
+    };
+    let reloader = Arc::new(ReloadableGeoIpReader::new(
+        open(&paths).unwrap(),
+        paths,
+        Box::new(open),
+    ));
+    let state = AppState {
+        admin_token: Some("secret".to_string()),
+        reloader: Some(reloader.clone()),
+        ..AppState::new(
+            reloader,
+            Arc::new(GeoCache::new(CacheConfig::default())),
+            "https://test.example.com".to_string(),
+        )
+    };
+    let app = Router::new()
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .route("/admin/reload", post(admin_reload_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let lookup = || async {
+        client
+            .get(format!("http://{}/v1/ipgeo?ip=8.8.8.8", addr))
+            .send()
+            .await
+            .unwrap()
+            .json::<serde_json::Value>()
+            .await
+            .unwrap()
+    };
+
+    // The City path is fine but the Connection-Type one is not: nothing changes
+    let response = client
+        .post(format!("http://{}/admin/reload", addr))
+        .bearer_auth("secret")
+        .json(&serde_json::json!({
+            "city": "city-2.mmdb",
+            "connection_type": "missing.mmdb"
+        }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 503);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["code"], "DATABASE_ERROR");
+    assert!(json["error"].as_str().unwrap().contains("missing.mmdb"));
+
+    let json = lookup().await;
+    assert_eq!(json["location"]["city"], "city-1.mmdb");
+    assert_eq!(json["network"]["connection_type"], "conn-1.mmdb");
+
+    // Valid paths swap together; omitted ones keep their current path
+    let response = client
+        .post(format!("http://{}/admin/reload", addr))
+        .bearer_auth("secret")
+        .json(&serde_json::json!({ "city": "city-2.mmdb" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let json = lookup().await;
+    assert_eq!(json["location"]["city"], "city-2.mmdb");
+    assert_eq!(json["network"]["connection_type"], "conn-1.mmdb");
+}
+
+/// A database reload tells MCP SSE clients the data-source resource changed
+#[tokio::test]
+async fn test_admin_reload_notifies_mcp() {
+    use ipgeolocation::geoip::{DatabasePaths, ReloadableGeoIpReader};
+    use ipgeolocation::mcp::McpState;
+
+    let open = |_: &DatabasePaths| -> Result<ipgeolocation::geoip::SharedGeoIpReader, String> {
+        Ok(Arc::new(MockGeoIpReader::new()))
+    };
+    let paths = DatabasePaths {
+        city: "city.mmdb".to_string(),
+        connection_type: None,
+        fallback: None,
+    };
+    let reloader = Arc::new(ReloadableGeoIpReader::new(
+        open(&paths).unwrap(),
+        paths,
+        Box::new(open),
+    ));
+    let mcp_state = McpState::new(reloader.clone());
+    let mut notifications = mcp_state.notification_tx.subscribe();
+    let state = AppState {
+        admin_token: Some("secret".to_string()),
+        reloader: Some(reloader.clone()),
+        mcp_notifications: Some(mcp_state.notification_tx.clone()),
+        ..AppState::new(
+            reloader,
+            Arc::new(GeoCache::new(CacheConfig::default())),
+            "https://test.example.com".to_string(),
+        )
+    };
+    let app = Router::new()
+        .route("/admin/reload", post(admin_reload_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let response = reqwest::Client::new()
+        .post(format!("http://{}/admin/reload", addr))
+        .bearer_auth("secret")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+
+    let notification = tokio::time::timeout(Duration::from_secs(1), notifications.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(notification.method, "notifications/resources/updated");
+    assert_eq!(notification.params["uri"], "geoip://data-source");
+}
+
+/// Test OMIT_FIELDS drops fields, including both forms of hidden coordinates
+#[tokio::test]
+async fn test_v1_ipgeo_omit_fields() {
+    use ipgeolocation::handlers::{OmitFields, ResponseOptions};
+
+    // Unknown sections and fields are rejected
+    assert!(OmitFields::parse("location.zipcode,postal").is_err());
+    assert!(OmitFields::parse("location.zipcode,location.zip").is_err());
+
+    let mock = MockGeoIpReader::new().with_response(
+        "8.8.8.8",
+        Ok(GeoData {
+            city: Some("Mountain View".to_string()),
+            country_code: Some("US".to_string()),
+            postal_code: Some("94043".to_string()),
+            latitude: Some(37.751),
+            longitude: Some(-97.822),
+            ..Default::default()
+        }),
+    );
+    let state = AppState {
+        response_options: Arc::new(ResponseOptions {
+            omit_fields: OmitFields::parse(
+                "location.zipcode, currency, location.latitude, location.longitude",
+            )
+            .unwrap(),
+            ..Default::default()
+        }),
+        ..create_test_state(mock)
+    };
+    let app = Router::new()
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let json: serde_json::Value =
+        reqwest::get(format!("http://{}/v1/ipgeo?ip=8.8.8.8&airport=true", addr))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+    assert_eq!(json["location"]["city"], "Mountain View");
+    assert!(json["location"].get("zipcode").is_none());
+    assert!(json.get("currency").is_none());
+    assert!(json.get("country_metadata").is_some());
+    // Hidden coordinates are dropped in every form and not used for the airport
+    for field in [
+        "latitude",
+        "longitude",
+        "latitude_f",
+        "longitude_f",
+        "nearest_airport",
+    ] {
+        assert!(json["location"].get(field).is_none(), "{field}: {json}");
+    }
+}
+
+/// Test COORD_ROUND_DP coarsens coordinates on /ipgeo and /v1/ipgeo
+#[tokio::test]
+async fn test_ipgeo_coord_round_dp() {
+    use ipgeolocation::handlers::ResponseOptions;
+
+    let mock = MockGeoIpReader::new().with_response(
+        "8.8.8.8",
+        Ok(GeoData {
+            latitude: Some(37.751),
+            longitude: Some(-97.822),
+            country_code: Some("US".to_string()),
+            ..Default::default()
+        }),
+    );
+    let state = AppState {
+        response_options: Arc::new(ResponseOptions {
+            coord_round_dp: Some(1),
+            ..Default::default()
+        }),
+        ..create_test_state(mock)
+    };
+    let app = Router::new()
+        .route("/ipgeo", get(ipgeo_handler))
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let json: serde_json::Value = reqwest::get(format!("http://{}/ipgeo?ip=8.8.8.8", addr))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(json["latitude"], 37.8);
+    assert_eq!(json["longitude"], -97.8);
+
+    let json: serde_json::Value = reqwest::get(format!("http://{}/v1/ipgeo?ip=8.8.8.8", addr))
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(json["location"]["latitude_f"], 37.8);
+    assert_eq!(json["location"]["longitude"], "-97.80000");
+}
+
+/// Test LANGUAGE_OVERRIDES replace the built-in languages per country
+#[tokio::test]
+async fn test_ipgeo_language_overrides() {
+    use ipgeolocation::handlers::ResponseOptions;
+    use ipgeolocation::languages::parse_language_overrides;
+
+    let mock = MockGeoIpReader::new()
+        .with_response(
+            "8.8.8.8",
+            Ok(GeoData {
+                country_code: Some("US".to_string()),
+                ..Default::default()
+            }),
+        )
+        .with_response(
+            "1.2.3.4",
+            Ok(GeoData {
+                country_code: Some("SE".to_string()),
+                ..Default::default()
+            }),
+        );
+    let state = AppState {
+        response_options: Arc::new(ResponseOptions {
+            language_overrides: parse_language_overrides("us=es-US,es; GB=en").unwrap(),
+            ..Default::default()
+        }),
+        ..create_test_state(mock)
+    };
+    let app = Router::new()
+        .route("/ipgeo", get(ipgeo_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let languages = |ip: &'static str| async move {
+        let json: serde_json::Value = reqwest::get(format!("http://{}/ipgeo?ip={}", addr, ip))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        json["languages"].clone()
+    };
+    assert_eq!(languages("8.8.8.8").await, "es-US,es");
+    // Countries without an override keep the built-in languages
+    assert_eq!(languages("1.2.3.4").await, "sv-SE,sv");
+}
+
+/// Test PATH_PREFIX nests the routes and prefixes flag paths and public URLs
+#[tokio::test]
+async fn test_path_prefix_nests_routes_and_urls() {
+    use ipgeolocation::handlers::ResponseOptions;
+    use ipgeolocation::{nest_under_prefix, parse_path_prefix};
+
+    assert!(parse_path_prefix("/geo ip").is_err());
+    assert!(parse_path_prefix("/geo//ip").is_err());
+    assert_eq!(parse_path_prefix("/").unwrap(), "");
+    let path_prefix = parse_path_prefix("geoip/").unwrap();
+    assert_eq!(path_prefix, "/geoip");
+
+    let mock = MockGeoIpReader::new()
+        .with_response(
+            "8.8.8.8",
+            Ok(GeoData {
+                country_code: Some("US".to_string()),
+                ..Default::default()
+            }),
+        )
+        .with_response(
+            "1.2.3.4",
+            Ok(GeoData {
+                country_code: Some("ZZ".to_string()),
+                ..Default::default()
+            }),
+        );
+    let state = AppState {
+        response_options: Arc::new(ResponseOptions {
+            path_prefix: path_prefix.clone(),
+            default_flag: "xx".to_string(),
+            ..Default::default()
+        }),
+        ..AppState::new(
+            Arc::new(mock),
+            Arc::new(GeoCache::new(CacheConfig::default())),
+            "https://example.com".to_string(),
+        )
+    };
+    let app = Router::new()
+        .route("/health", get(health_handler))
+        .route("/v1/ipgeo", get(ipgeo_full_handler))
+        .route("/openapi.yaml", get(openapi_handler))
+        .route("/sitemap.xml", get(sitemap_handler))
+        .with_state(state);
+    let app = nest_under_prefix(app, &path_prefix);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let client = reqwest::Client::new();
+    let get = |path: &str| client.get(format!("http://{}{}", addr, path)).send();
+
+    // Routes only exist under the prefix
+    let response = get("/geoip/health").await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(get("/health").await.unwrap().status(), 404);
+
+    // Flag paths, the OpenAPI server URL and the sitemap include it
+    let json: serde_json::Value = get("/geoip/v1/ipgeo?ip=8.8.8.8")
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(
+        json["location"]["country_flag"],
+        "/geoip/static/flags/us.svg"
+    );
+
+    // Codes without a flag get DEFAULT_FLAG
+    let json: serde_json::Value = get("/geoip/v1/ipgeo?ip=1.2.3.4")
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(
+        json["location"]["country_flag"],
+        "/geoip/static/flags/xx.svg"
+    );
+
+    let spec = get("/geoip/openapi.yaml")
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(spec.contains("url: https://example.com/geoip"), "{spec}");
+
+    let sitemap = get("/geoip/sitemap.xml")
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(sitemap.contains("<loc>https://example.com/geoip/v1/ipgeo</loc>"));
+}
+
+/// Post one JSON-RPC request to an MCP server
+async fn mcp_call(addr: SocketAddr, request: serde_json::Value) -> serde_json::Value {
+    reqwest::Client::new()
+        .post(format!("http://{}/mcp", addr))
+        .json(&request)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+/// Serve `mcp_jsonrpc_handler` with `state`, returning its address
+async fn spawn_mcp_server(state: ipgeolocation::mcp::McpState) -> SocketAddr {
+    let app = Router::new()
+        .route("/mcp", post(ipgeolocation::mcp::mcp_jsonrpc_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(
+            listener,
+            app.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    addr
+}
+
+/// Test MCP_DISABLE_TOOLS hides tools from tools/list and rejects calls to them
+#[tokio::test]
+async fn test_mcp_disabled_tool_hidden_and_rejected() {
+    use ipgeolocation::mcp::{parse_disabled_tools, McpState};
+
+    // Unknown names are rejected
+    assert!(parse_disabled_tools("geoip_lookup,no_such_tool").is_err());
+
+    let state = McpState::new(Arc::new(MockGeoIpReader::new()))
+        .with_disabled_tools(parse_disabled_tools("geoip_bulk_lookup").unwrap());
+    assert!(!state.is_tool_enabled("geoip_bulk_lookup"));
+    let addr = spawn_mcp_server(state).await;
+
+    let json = mcp_call(
+        addr,
+        serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}),
+    )
+    .await;
+    let names: Vec<&str> = json["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|tool| tool["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names.len(), 5);
+    assert!(!names.contains(&"geoip_bulk_lookup"));
+    assert!(names.contains(&"geoip_lookup"));
+
+    let json = mcp_call(
+        addr,
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "tools/call",
+            "params": {"name": "geoip_bulk_lookup", "arguments": {"ips": ["8.8.8.8"]}}
+        }),
+    )
+    .await;
+    assert_eq!(json["error"]["code"], -32601);
+    assert!(json["result"].is_null());
+}
+
+/// Test BULK_MAX_IPS caps the REST batch endpoints and the MCP bulk tools
+#[tokio::test]
+async fn test_bulk_max_ips_caps_rest_and_mcp() {
+    use ipgeolocation::mcp::{BulkLimits, McpState};
+
+    let state = AppState {
+        bulk_max_ips: 2,
+        ..create_minimal_test_state()
+    };
+    let app = Router::new()
+        .route("/v1/timezone/batch", post(timezone_batch_handler))
+        .with_state(state);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    tokio::spawn(async move {
+        axum::serve(listener, app).await.unwrap();
+    });
+
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let point = serde_json::json!({"lat": 59.33, "lon": 18.07});
+    let response = reqwest::Client::new()
+        .post(format!("http://{}/v1/timezone/batch", addr))
+        .json(&serde_json::json!({"points": [point, point, point]}))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 400);
+    let json: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(json["code"], "TOO_MANY_POINTS");
+
+    let state =
+        McpState::new(Arc::new(MockGeoIpReader::new())).with_bulk_limits(BulkLimits::new(2, 1));
+    let addr = spawn_mcp_server(state).await;
+
+    // The cap is advertised in the tool schema and the limits resource
+    let json = mcp_call(
+        addr,
+        serde_json::json!({"jsonrpc": "2.0", "id": 1, "method": "tools/list"}),
+    )
+    .await;
+    let bulk_tool = json["result"]["tools"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|tool| tool["name"] == "geoip_bulk_lookup")
+        .unwrap()
+        .clone();
+    assert_eq!(bulk_tool["inputSchema"]["properties"]["ips"]["maxItems"], 2);
+
+    let json = mcp_call(
+        addr,
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "resources/read",
+            "params": {"uri": "geoip://limits"}
+        }),
+    )
+    .await;
+    let text = json["result"]["contents"][0]["text"].as_str().unwrap();
+    let limits: serde_json::Value = serde_json::from_str(text).unwrap();
+    assert_eq!(limits["limits"]["bulk_lookup"]["max_ips_per_request"], 2);
+
+    let json = mcp_call(
+        addr,
+        serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "tools/call",
+            "params": {
+                "name": "geoip_bulk_lookup",
+                "arguments": {"ips": ["8.8.8.8", "1.1.1.1", "9.9.9.9"]}
+            }
+        }),
+    )
+    .await;
+    assert_eq!(json["result"]["isError"], true);
+    assert!(json["result"]["content"][0]["text"]
+        .as_str()
+        .unwrap()
+        .contains("BULK_LIMIT_EXCEEDED"));
 }